@@ -1,16 +1,23 @@
 use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 
-use axum::extract::{Path, State};
+use axum::extract::{ConnectInfo, Multipart, Path, Query, State};
 use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Response};
 use axum::routing::{delete, get, post, put};
 use axum::{Json, Router};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use futures_util::stream::{unfold, Stream};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header as JwtHeader, Validation};
+use sea_orm::DbErr;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256};
 use time::OffsetDateTime;
-use tokio::sync::watch;
+use tokio::sync::{broadcast, watch};
 
 use gproxy_core::{AuthKeyEntry, AuthSnapshot, MemoryAuth, UserEntry};
 use gproxy_provider_core::{
@@ -18,14 +25,43 @@ use gproxy_provider_core::{
 };
 use gproxy_provider_impl::{BaseCredential, ProviderRegistry};
 use gproxy_storage::{
-    entities, AdminCredentialInput, AdminDisallowInput, AdminKeyInput, AdminProviderInput,
-    AdminUserInput, TrafficStorage,
+    entities, AdminAuditInput, AdminCredentialInput, AdminDisallowInput,
+    AdminInstructionRuleInput, AdminInstructionTemplateInput, AdminKeyInput, AdminProviderInput,
+    AdminUserInput, ConfigEvent, TrafficStorage, TrafficStore,
 };
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
 
 use crate::cli::GlobalConfig;
 use crate::dsn::ensure_sqlite_dsn;
+use crate::instructions_store;
+use crate::model_config::ModelTable;
+use crate::routes_config::RouteTable;
 use crate::snapshot;
 
+/// Named change notifications published over `AdminState::events` so the
+/// `GET /admin/events` SSE stream can push updates instead of clients
+/// polling `list_*`/`stats`.
+#[derive(Debug, Clone)]
+enum AdminEvent {
+    ProviderUpdated,
+    CredentialUpdated,
+    DisallowChanged,
+    AuthReloaded,
+}
+
+impl AdminEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            AdminEvent::ProviderUpdated => "provider.updated",
+            AdminEvent::CredentialUpdated => "credential.updated",
+            AdminEvent::DisallowChanged => "disallow.changed",
+            AdminEvent::AuthReloaded => "auth.reloaded",
+        }
+    }
+}
+
 #[derive(Clone)]
 struct AdminState {
     storage: Arc<RwLock<TrafficStorage>>,
@@ -35,8 +71,51 @@ struct AdminState {
     auth: Arc<MemoryAuth>,
     provider_ids: Arc<RwLock<HashMap<String, i64>>>,
     provider_names: Arc<RwLock<HashMap<i64, String>>>,
+    routes: RouteTable,
+    routes_config_path: std::path::PathBuf,
+    models: ModelTable,
+    models_config_path: std::path::PathBuf,
+    events: broadcast::Sender<AdminEvent>,
+    oidc: Arc<tokio::sync::RwLock<OidcRuntime>>,
+    metrics: Arc<AdminMetrics>,
+}
+
+/// Counters exposed by `GET /metrics` alongside the per-provider pool gauges
+/// from [`collect_stats`]. `Relaxed` ordering is enough here: these are
+/// monotonically-incrementing scrape counters, not synchronization points.
+#[derive(Default)]
+struct AdminMetrics {
+    keys_upserted: std::sync::atomic::AtomicU64,
+    keys_deleted: std::sync::atomic::AtomicU64,
+    snapshots_reloaded: std::sync::atomic::AtomicU64,
+    disallow_cooldown: std::sync::atomic::AtomicU64,
+    disallow_transient: std::sync::atomic::AtomicU64,
+    disallow_dead: std::sync::atomic::AtomicU64,
 }
 
+impl AdminMetrics {
+    fn record_disallow(&self, level: &str) {
+        let counter = match level {
+            "cooldown" => &self.disallow_cooldown,
+            "transient" => &self.disallow_transient,
+            "dead" => &self.disallow_dead,
+            _ => return,
+        };
+        counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+/// Live OIDC configuration plus its JWKS cache, held separately from
+/// `AdminState.config` because refreshing the cache needs a lock that can be
+/// held across an `.await` (`tokio::sync::RwLock`), unlike the plain
+/// `std::sync::RwLock` used for the rest of `AdminState`.
+#[derive(Default)]
+struct OidcRuntime {
+    config: Option<crate::oidc::OidcConfig>,
+    cache: crate::oidc::JwksCache,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn admin_router(
     config: Arc<RwLock<GlobalConfig>>,
     storage: TrafficStorage,
@@ -45,6 +124,11 @@ pub(crate) fn admin_router(
     auth: Arc<MemoryAuth>,
     provider_ids: HashMap<String, i64>,
     provider_names: HashMap<i64, String>,
+    routes: RouteTable,
+    routes_config_path: std::path::PathBuf,
+    models: ModelTable,
+    models_config_path: std::path::PathBuf,
+    config_events: Option<broadcast::Receiver<ConfigEvent>>,
 ) -> Router {
     let state = AdminState {
         storage: Arc::new(RwLock::new(storage)),
@@ -54,9 +138,22 @@ pub(crate) fn admin_router(
         auth,
         provider_ids: Arc::new(RwLock::new(provider_ids)),
         provider_names: Arc::new(RwLock::new(provider_names)),
+        routes,
+        routes_config_path,
+        models,
+        models_config_path,
+        events: broadcast::channel(64).0,
+        oidc: Arc::new(tokio::sync::RwLock::new(OidcRuntime::default())),
+        metrics: Arc::new(AdminMetrics::default()),
     };
 
+    if let Some(config_events) = config_events {
+        tokio::spawn(auto_reload_loop(state.clone(), config_events));
+    }
+
     Router::new()
+        .route("/admin/login", post(login))
+        .route("/admin/logout", post(logout))
         .route("/admin/health", get(admin_health))
         .route("/admin/config", get(get_config).put(put_config))
         .route(
@@ -82,11 +179,134 @@ pub(crate) fn admin_router(
         .route("/admin/keys", get(list_keys).post(create_key))
         .route("/admin/keys/{id}", delete(delete_key))
         .route("/admin/keys/{id}/disable", put(disable_key))
+        .route(
+            "/admin/instructions/templates",
+            get(list_instruction_templates).post(create_instruction_template),
+        )
+        .route(
+            "/admin/instructions/templates/{id}",
+            delete(delete_instruction_template),
+        )
+        .route(
+            "/admin/instructions/rules",
+            get(list_instruction_rules).post(create_instruction_rule),
+        )
+        .route(
+            "/admin/instructions/rules/{id}",
+            delete(delete_instruction_rule),
+        )
         .route("/admin/reload", post(reload_snapshot))
+        .route("/admin/routes/reload", post(reload_routes))
+        .route("/admin/models/reload", post(reload_models))
+        .route("/admin/export", get(admin_export))
+        .route("/admin/import", post(admin_import))
+        .route("/admin/batch", post(admin_batch))
+        .route("/admin/audit", get(admin_audit))
         .route("/admin/stats", get(stats))
+        .route("/metrics", get(metrics_handler))
+        .route("/admin/events", get(admin_events))
+        .route("/admin/openapi.json", get(admin_openapi))
+        .merge(SwaggerUi::new("/admin/swagger-ui").url("/admin/openapi.json", ApiDoc::openapi()))
         .with_state(state)
 }
 
+/// Registers the `x-admin-key` / `Authorization: Bearer <key>` header that
+/// [`require_admin`] accepts as the `admin_key` security scheme referenced by
+/// every `#[utoipa::path]` below.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi
+            .components
+            .get_or_insert_with(utoipa::openapi::Components::default);
+        components.add_security_scheme(
+            "admin_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-admin-key"))),
+        );
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        login,
+        logout,
+        admin_health,
+        get_config,
+        put_config,
+        list_providers,
+        create_provider,
+        update_provider,
+        delete_provider,
+        list_credentials,
+        create_credential,
+        update_credential,
+        delete_credential,
+        list_disallow,
+        create_disallow,
+        delete_disallow,
+        list_users,
+        create_user,
+        delete_user,
+        list_keys,
+        create_key,
+        delete_key,
+        disable_key,
+        list_instruction_templates,
+        create_instruction_template,
+        delete_instruction_template,
+        list_instruction_rules,
+        create_instruction_rule,
+        delete_instruction_rule,
+        reload_snapshot,
+        reload_routes,
+        reload_models,
+        admin_export,
+        admin_import,
+        admin_batch,
+        admin_audit,
+        stats,
+        admin_events,
+    ),
+    components(schemas(
+        LoginPayload,
+        LoginResponse,
+        ProviderPayload,
+        CredentialPayload,
+        DisallowPayload,
+        UserPayload,
+        KeyPayload,
+        InstructionTemplatePayload,
+        InstructionRulePayload,
+        ProviderPoolStats,
+        ExportDocument,
+        ImportSummary,
+        BatchRequest,
+        BatchOperation,
+        BatchOpResult,
+        AuditEntry,
+    )),
+    tags(
+        (name = "admin", description = "health, config, reload and stats endpoints"),
+        (name = "providers", description = "provider CRUD"),
+        (name = "credentials", description = "credential CRUD"),
+        (name = "disallow", description = "per-credential disallow rules"),
+        (name = "users", description = "user CRUD"),
+        (name = "keys", description = "api key CRUD"),
+        (name = "instructions", description = "Codex instruction template/rule CRUD"),
+    ),
+    modifiers(&SecurityAddon)
+)]
+struct ApiDoc;
+
+async fn admin_openapi(State(state): State<AdminState>, headers: HeaderMap) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+    Json(ApiDoc::openapi()).into_response()
+}
+
 #[allow(clippy::result_large_err)]
 impl AdminState {
     fn storage(&self) -> Result<TrafficStorage, Response> {
@@ -154,11 +374,155 @@ impl AdminState {
     }
 }
 
+/// How long a session issued by `POST /admin/login` stays valid before the
+/// caller has to log in again.
+const ADMIN_SESSION_TTL_SECS: i64 = 12 * 60 * 60;
+const ADMIN_SESSION_COOKIE: &str = "admin_session";
+
+/// Claims of a signed admin session token. `key_fingerprint` pins the token
+/// to the `admin_key` that was active when it was issued: rotating
+/// `admin_key` through `PUT /admin/config` changes the fingerprint, which
+/// makes every outstanding session invalid without tracking a separate
+/// revocation list.
+#[derive(Debug, Serialize, Deserialize)]
+struct AdminSessionClaims {
+    iat: i64,
+    exp: i64,
+    key_fingerprint: String,
+}
+
+fn admin_key_fingerprint(admin_key: &str) -> String {
+    format!("{:x}", Sha256::digest(admin_key.as_bytes()))
+}
+
+/// Derives the HS256 signing secret from `admin_key` rather than storing a
+/// separate session secret, so there's nothing new to configure or persist:
+/// whoever can prove they know `admin_key` can already do anything a session
+/// token would let them do.
+fn admin_session_secret(admin_key: &str) -> Vec<u8> {
+    Sha256::digest(format!("gproxy-admin-session:{admin_key}").as_bytes()).to_vec()
+}
+
+fn issue_admin_session(admin_key: &str) -> Result<(String, i64), jsonwebtoken::errors::Error> {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let exp = now + ADMIN_SESSION_TTL_SECS;
+    let claims = AdminSessionClaims {
+        iat: now,
+        exp,
+        key_fingerprint: admin_key_fingerprint(admin_key),
+    };
+    let token = jsonwebtoken::encode(
+        &JwtHeader::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(&admin_session_secret(admin_key)),
+    )?;
+    Ok((token, exp))
+}
+
+fn verify_admin_session(token: &str, admin_key: &str) -> bool {
+    let validation = Validation::new(Algorithm::HS256);
+    let decoded = jsonwebtoken::decode::<AdminSessionClaims>(
+        token,
+        &DecodingKey::from_secret(&admin_session_secret(admin_key)),
+        &validation,
+    );
+    match decoded {
+        Ok(data) => data.claims.key_fingerprint == admin_key_fingerprint(admin_key),
+        Err(_) => false,
+    }
+}
+
+fn session_token_from_headers(headers: &HeaderMap) -> Option<String> {
+    let cookie_header = header_value(headers, "cookie")?;
+    cookie_header.split(';').find_map(|part| {
+        part.trim()
+            .strip_prefix(&format!("{ADMIN_SESSION_COOKIE}="))
+            .map(|value| value.to_string())
+    })
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+struct LoginPayload {
+    admin_key: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct LoginResponse {
+    token: String,
+    expires_at: i64,
+}
+
+/// Exchanges `admin_key` for a short-lived signed session so a browser-based
+/// admin UI can hold a cookie instead of the raw key. `require_admin` still
+/// accepts `admin_key` directly (as `x-admin-key` or a bearer token) for
+/// scripts and backward compatibility.
+#[utoipa::path(
+    post,
+    path = "/admin/login",
+    tag = "admin",
+    request_body = LoginPayload,
+    responses(
+        (status = 200, description = "session issued", body = LoginResponse),
+        (status = 401, description = "invalid admin key"),
+        (status = 500, description = "internal error"),
+    )
+)]
+async fn login(
+    State(state): State<AdminState>,
+    jar: CookieJar,
+    Json(payload): Json<LoginPayload>,
+) -> Response {
+    let admin_key = match state.admin_key() {
+        Ok(key) => key,
+        Err(resp) => return resp,
+    };
+    if payload.admin_key != admin_key {
+        return (StatusCode::UNAUTHORIZED, "invalid admin key").into_response();
+    }
+
+    let (token, expires_at) = match issue_admin_session(&admin_key) {
+        Ok(session) => session,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+
+    let cookie = Cookie::build((ADMIN_SESSION_COOKIE, token.clone()))
+        .http_only(true)
+        .same_site(SameSite::Strict)
+        .path("/admin")
+        .max_age(time::Duration::seconds(ADMIN_SESSION_TTL_SECS))
+        .build();
+    let jar = jar.add(cookie);
+
+    (jar, Json(LoginResponse { token, expires_at })).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/logout",
+    tag = "admin",
+    responses((status = 200, description = "session cookie cleared"))
+)]
+async fn logout(jar: CookieJar) -> Response {
+    let jar = jar.remove(Cookie::from(ADMIN_SESSION_COOKIE));
+    (jar, Json(json!({ "status": "ok" }))).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/health",
+    tag = "admin",
+    responses(
+        (status = 200, description = "storage reachable"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 503, description = "storage unreachable"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn admin_health(
     State(state): State<AdminState>,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -177,11 +541,23 @@ async fn admin_health(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/config",
+    tag = "admin",
+    responses(
+        (status = 200, description = "current global config"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 404, description = "global config not set"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn get_config(
     State(state): State<AdminState>,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -202,12 +578,27 @@ async fn get_config(
     }
 }
 
+/// Request/response bodies are left as generic JSON in the spec: `GlobalConfig`
+/// is defined in `apps/gproxy/src/cli.rs`, which isn't part of this crate's
+/// schema derives, so it can't carry a `ToSchema` impl here.
+#[utoipa::path(
+    put,
+    path = "/admin/config",
+    tag = "admin",
+    responses(
+        (status = 200, description = "config applied"),
+        (status = 400, description = "invalid dsn or config"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error applying config"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn put_config(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Json(payload): Json<GlobalConfig>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -290,6 +681,16 @@ async fn put_config(
     };
     apply_snapshot(&state, &snapshot);
 
+    record_audit(
+        &effective_storage,
+        &headers,
+        "/admin/config",
+        "config",
+        None,
+        &redact_audit_payload(&config_json),
+    )
+    .await;
+
     if dsn_changed && let Err(resp) = state.set_storage(effective_storage) {
         return resp;
     }
@@ -317,7 +718,7 @@ async fn put_config(
     .into_response()
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 struct ProviderPayload {
     id: Option<i64>,
     name: String,
@@ -325,11 +726,22 @@ struct ProviderPayload {
     enabled: bool,
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/providers",
+    tag = "providers",
+    responses(
+        (status = 200, description = "list of providers"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn list_providers(
     State(state): State<AdminState>,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -347,12 +759,24 @@ async fn list_providers(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/admin/providers",
+    tag = "providers",
+    request_body = ProviderPayload,
+    responses(
+        (status = 200, description = "provider created"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn create_provider(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Json(payload): Json<ProviderPayload>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -372,20 +796,44 @@ async fn create_provider(
     match storage.upsert_provider(input).await {
         Ok(id) => {
             insert_provider_map(&state, id, name.clone());
-            let _ = refresh_provider_pool(&state, &storage, Some(id)).await;
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/providers",
+                "provider",
+                Some(id),
+                &json!({ "name": name, "enabled": payload.enabled }),
+            )
+            .await;
+            let _ =
+                refresh_provider_pool(&state, &storage, Some(id), AdminEvent::ProviderUpdated)
+                    .await;
             Json(json!({ "status": "ok" })).into_response()
         }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/admin/providers/{id}",
+    tag = "providers",
+    params(("id" = i64, Path, description = "provider id")),
+    request_body = ProviderPayload,
+    responses(
+        (status = 200, description = "provider updated"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn update_provider(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Path(id): Path<i64>,
     Json(payload): Json<ProviderPayload>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -404,8 +852,20 @@ async fn update_provider(
 
     match storage.upsert_provider(input).await {
         Ok(id) => {
-            update_provider_map(&state, id, name);
-            if let Err(resp) = refresh_provider_pool(&state, &storage, Some(id)).await {
+            update_provider_map(&state, id, name.clone());
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/providers/{id}",
+                "provider",
+                Some(id),
+                &json!({ "name": name, "enabled": payload.enabled }),
+            )
+            .await;
+            if let Err(resp) =
+                refresh_provider_pool(&state, &storage, Some(id), AdminEvent::ProviderUpdated)
+                    .await
+            {
                 return resp;
             }
             Json(json!({ "status": "ok" })).into_response()
@@ -414,12 +874,25 @@ async fn update_provider(
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/admin/providers/{id}",
+    tag = "providers",
+    params(("id" = i64, Path, description = "provider id")),
+    responses(
+        (status = 200, description = "provider deleted"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 404, description = "provider not found"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn delete_provider(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Path(id): Path<i64>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -443,13 +916,23 @@ async fn delete_provider(
         Ok(_) => {
             clear_provider_pool(&state, &name);
             remove_provider_map(&state, id);
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/providers/{id}",
+                "provider",
+                Some(id),
+                &json!({ "name": name }),
+            )
+            .await;
+            let _ = state.events.send(AdminEvent::ProviderUpdated);
             Json(json!({ "status": "ok" })).into_response()
         }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 struct CredentialPayload {
     id: Option<i64>,
     provider_id: Option<i64>,
@@ -461,11 +944,22 @@ struct CredentialPayload {
     enabled: bool,
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/credentials",
+    tag = "credentials",
+    responses(
+        (status = 200, description = "list of credentials"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn list_credentials(
     State(state): State<AdminState>,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -483,12 +977,25 @@ async fn list_credentials(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/admin/credentials",
+    tag = "credentials",
+    request_body = CredentialPayload,
+    responses(
+        (status = 200, description = "credential created"),
+        (status = 400, description = "provider_id/provider_name did not resolve"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn create_credential(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Json(payload): Json<CredentialPayload>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -512,21 +1019,56 @@ async fn create_credential(
     };
 
     match storage.upsert_credential(input).await {
-        Ok(_) => match refresh_provider_pool(&state, &storage, Some(provider_id)).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(id) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/credentials",
+                "credential",
+                Some(id),
+                &redact_audit_payload(&json!({
+                    "provider_id": provider_id,
+                    "secret": "present",
+                })),
+            )
+            .await;
+            match refresh_provider_pool(
+                &state,
+                &storage,
+                Some(provider_id),
+                AdminEvent::CredentialUpdated,
+            )
+            .await
+            {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/admin/credentials/{id}",
+    tag = "credentials",
+    params(("id" = i64, Path, description = "credential id")),
+    request_body = CredentialPayload,
+    responses(
+        (status = 200, description = "credential updated"),
+        (status = 400, description = "provider_id/provider_name did not resolve"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn update_credential(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Path(id): Path<i64>,
     Json(payload): Json<CredentialPayload>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -550,20 +1092,54 @@ async fn update_credential(
     };
 
     match storage.upsert_credential(input).await {
-        Ok(_) => match refresh_provider_pool(&state, &storage, Some(provider_id)).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(_) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/credentials/{id}",
+                "credential",
+                Some(id),
+                &redact_audit_payload(&json!({
+                    "provider_id": provider_id,
+                    "secret": "present",
+                })),
+            )
+            .await;
+            match refresh_provider_pool(
+                &state,
+                &storage,
+                Some(provider_id),
+                AdminEvent::CredentialUpdated,
+            )
+            .await
+            {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/admin/credentials/{id}",
+    tag = "credentials",
+    params(("id" = i64, Path, description = "credential id")),
+    responses(
+        (status = 200, description = "credential deleted"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 404, description = "credential not found"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn delete_credential(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Path(id): Path<i64>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -584,15 +1160,33 @@ async fn delete_credential(
     };
 
     match storage.delete_credential(id).await {
-        Ok(_) => match refresh_provider_pool(&state, &storage, Some(provider_id)).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(_) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/credentials/{id}",
+                "credential",
+                Some(id),
+                &json!({ "provider_id": provider_id }),
+            )
+            .await;
+            match refresh_provider_pool(
+                &state,
+                &storage,
+                Some(provider_id),
+                AdminEvent::CredentialUpdated,
+            )
+            .await
+            {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 struct DisallowPayload {
     credential_id: i64,
     scope_kind: String,
@@ -602,11 +1196,22 @@ struct DisallowPayload {
     reason: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/disallow",
+    tag = "disallow",
+    responses(
+        (status = 200, description = "list of disallow entries"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn list_disallow(
     State(state): State<AdminState>,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -624,12 +1229,25 @@ async fn list_disallow(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/admin/disallow",
+    tag = "disallow",
+    request_body = DisallowPayload,
+    responses(
+        (status = 200, description = "disallow entry created"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 404, description = "credential not found"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn create_disallow(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Json(payload): Json<DisallowPayload>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -656,20 +1274,52 @@ async fn create_disallow(
     };
 
     match storage.upsert_disallow(input).await {
-        Ok(_) => match refresh_provider_pool(&state, &storage, Some(provider_id)).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(_) => {
+            state.metrics.record_disallow(payload.level.as_str());
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/disallow",
+                "disallow",
+                Some(payload.credential_id),
+                &json!({ "credential_id": payload.credential_id }),
+            )
+            .await;
+            match refresh_provider_pool(
+                &state,
+                &storage,
+                Some(provider_id),
+                AdminEvent::DisallowChanged,
+            )
+            .await
+            {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/admin/disallow/{id}",
+    tag = "disallow",
+    params(("id" = i64, Path, description = "disallow entry id")),
+    responses(
+        (status = 200, description = "disallow entry deleted"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 404, description = "disallow entry not found"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn delete_disallow(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Path(id): Path<i64>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -694,25 +1344,54 @@ async fn delete_disallow(
     };
 
     match storage.delete_disallow(id).await {
-        Ok(_) => match refresh_provider_pool(&state, &storage, Some(provider_id)).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(_) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/disallow/{id}",
+                "disallow",
+                Some(credential_id),
+                &json!({ "credential_id": credential_id }),
+            )
+            .await;
+            match refresh_provider_pool(
+                &state,
+                &storage,
+                Some(provider_id),
+                AdminEvent::DisallowChanged,
+            )
+            .await
+            {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 struct UserPayload {
     id: Option<i64>,
     name: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/users",
+    tag = "users",
+    responses(
+        (status = 200, description = "list of users"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn list_users(
     State(state): State<AdminState>,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -730,12 +1409,24 @@ async fn list_users(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/admin/users",
+    tag = "users",
+    request_body = UserPayload,
+    responses(
+        (status = 200, description = "user created"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn create_user(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Json(payload): Json<UserPayload>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -750,20 +1441,35 @@ async fn create_user(
     };
 
     match storage.upsert_user(input).await {
-        Ok(_) => match refresh_auth(&state, &storage).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(id) => {
+            record_audit(&storage, &headers, "/admin/users", "user", Some(id), &json!({})).await;
+            match refresh_auth(&state, &storage).await {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/admin/users/{id}",
+    tag = "users",
+    params(("id" = i64, Path, description = "user id")),
+    responses(
+        (status = 200, description = "user deleted"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn delete_user(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Path(id): Path<i64>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -773,15 +1479,26 @@ async fn delete_user(
     };
 
     match storage.delete_user(id).await {
-        Ok(_) => match refresh_auth(&state, &storage).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(_) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/users/{id}",
+                "user",
+                Some(id),
+                &json!({}),
+            )
+            .await;
+            match refresh_auth(&state, &storage).await {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 struct KeyPayload {
     id: Option<i64>,
     user_id: i64,
@@ -790,11 +1507,22 @@ struct KeyPayload {
     enabled: Option<bool>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/keys",
+    tag = "keys",
+    responses(
+        (status = 200, description = "list of api keys"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn list_keys(
     State(state): State<AdminState>,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -812,12 +1540,24 @@ async fn list_keys(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/admin/keys",
+    tag = "keys",
+    request_body = KeyPayload,
+    responses(
+        (status = 200, description = "api key created"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn create_key(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Json(payload): Json<KeyPayload>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -835,20 +1575,47 @@ async fn create_key(
     };
 
     match storage.upsert_key(input).await {
-        Ok(_) => match refresh_auth(&state, &storage).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(id) => {
+            state
+                .metrics
+                .keys_upserted
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/keys",
+                "key",
+                Some(id),
+                &json!({ "key_value": "present" }),
+            )
+            .await;
+            match refresh_auth(&state, &storage).await {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/admin/keys/{id}",
+    tag = "keys",
+    params(("id" = i64, Path, description = "api key id")),
+    responses(
+        (status = 200, description = "api key deleted"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn delete_key(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Path(id): Path<i64>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -858,20 +1625,40 @@ async fn delete_key(
     };
 
     match storage.delete_key(id).await {
-        Ok(_) => match refresh_auth(&state, &storage).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(_) => {
+            state
+                .metrics
+                .keys_deleted
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            record_audit(&storage, &headers, "/admin/keys/{id}", "key", Some(id), &json!({}))
+                .await;
+            match refresh_auth(&state, &storage).await {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/admin/keys/{id}/disable",
+    tag = "keys",
+    params(("id" = i64, Path, description = "api key id")),
+    responses(
+        (status = 200, description = "api key disabled"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
 async fn disable_key(
     State(state): State<AdminState>,
     headers: HeaderMap,
     Path(id): Path<i64>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -881,19 +1668,48 @@ async fn disable_key(
     };
 
     match storage.set_key_enabled(id, false).await {
-        Ok(_) => match refresh_auth(&state, &storage).await {
-            Ok(_) => Json(json!({ "status": "ok" })).into_response(),
-            Err(resp) => resp,
-        },
+        Ok(_) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/keys/{id}/disable",
+                "key",
+                Some(id),
+                &json!({ "enabled": false }),
+            )
+            .await;
+            match refresh_auth(&state, &storage).await {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(resp) => resp,
+            }
+        }
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
-async fn reload_snapshot(
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+struct InstructionTemplatePayload {
+    id: Option<i64>,
+    template_id: String,
+    body: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/instructions/templates",
+    tag = "instructions",
+    responses(
+        (status = 200, description = "list of instruction templates"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn list_instruction_templates(
     State(state): State<AdminState>,
     headers: HeaderMap,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
@@ -902,109 +1718,1320 @@ async fn reload_snapshot(
         Err(resp) => return resp,
     };
 
-    let snapshot = match storage.load_snapshot().await {
-        Ok(snapshot) => snapshot,
-        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    match storage.list_instruction_templates().await {
+        Ok(items) => {
+            let data: Vec<JsonValue> = items.into_iter().map(instruction_template_to_json).collect();
+            Json(json!(data)).into_response()
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/instructions/templates",
+    tag = "instructions",
+    request_body = InstructionTemplatePayload,
+    responses(
+        (status = 200, description = "instruction template created or updated"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn create_instruction_template(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Json(payload): Json<InstructionTemplatePayload>,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
     };
-    apply_snapshot(&state, &snapshot);
 
-    Json(json!({ "status": "ok" })).into_response()
-}
+    let input = AdminInstructionTemplateInput {
+        id: payload.id,
+        template_id: payload.template_id.clone(),
+        body: payload.body,
+    };
 
-#[derive(Serialize)]
-struct ProviderPoolStats {
-    name: String,
-    credentials_total: usize,
-    credentials_enabled: usize,
-    disallow: usize,
+    match storage.upsert_instruction_template(input).await {
+        Ok(id) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/instructions/templates",
+                "instruction_template",
+                Some(id),
+                &json!({ "template_id": payload.template_id }),
+            )
+            .await;
+            match instructions_store::reload_instruction_table(&storage).await {
+                Ok(_) => Json(json!({ "status": "ok", "id": id })).into_response(),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            }
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
 }
 
-async fn stats(
+#[utoipa::path(
+    delete,
+    path = "/admin/instructions/templates/{id}",
+    tag = "instructions",
+    params(("id" = i64, Path, description = "instruction template id")),
+    responses(
+        (status = 200, description = "instruction template deleted"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn delete_instruction_template(
     State(state): State<AdminState>,
     headers: HeaderMap,
+    Path(id): Path<i64>,
 ) -> Response {
-    if let Err(resp) = require_admin(&state, &headers) {
+    if let Err(resp) = require_admin(&state, &headers).await {
         return resp;
     }
 
-    let stats = collect_stats(&state);
-    Json(json!({ "providers": stats })).into_response()
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
+    };
+
+    match storage.delete_instruction_template(id).await {
+        Ok(_) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/instructions/templates/{id}",
+                "instruction_template",
+                Some(id),
+                &json!({}),
+            )
+            .await;
+            match instructions_store::reload_instruction_table(&storage).await {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            }
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
 }
 
-fn collect_stats(state: &AdminState) -> Vec<ProviderPoolStats> {
-    let mut out = Vec::new();
-    collect_one(&mut out, "openai", state.registry.openai().pool().snapshot());
-    collect_one(&mut out, "claude", state.registry.claude().pool().snapshot());
-    collect_one(
-        &mut out,
-        "aistudio",
-        state.registry.aistudio().pool().snapshot(),
-    );
-    collect_one(
-        &mut out,
-        "vertexexpress",
-        state.registry.vertexexpress().pool().snapshot(),
-    );
-    collect_one(&mut out, "vertex", state.registry.vertex().pool().snapshot());
-    collect_one(
-        &mut out,
-        "geminicli",
-        state.registry.geminicli().pool().snapshot(),
-    );
-    collect_one(
-        &mut out,
-        "claudecode",
-        state.registry.claudecode().pool().snapshot(),
-    );
-    collect_one(&mut out, "codex", state.registry.codex().pool().snapshot());
-    collect_one(
-        &mut out,
-        "antigravity",
-        state.registry.antigravity().pool().snapshot(),
-    );
-    collect_one(
-        &mut out,
-        "nvidia",
-        state.registry.nvidia().pool().snapshot(),
-    );
-    collect_one(
-        &mut out,
-        "deepseek",
-        state.registry.deepseek().pool().snapshot(),
-    );
-    out
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+struct InstructionRulePayload {
+    id: Option<i64>,
+    position: i32,
+    model_glob: String,
+    template_id: String,
+    personality: Option<String>,
 }
 
-fn apply_snapshot(state: &AdminState, snapshot: &gproxy_storage::StorageSnapshot) {
-    let auth_snapshot = snapshot::build_auth_snapshot(snapshot);
-    state.auth.replace_snapshot(auth_snapshot);
-    let pools = snapshot::build_provider_pools(snapshot);
-    state.registry.apply_pools(pools);
-    let provider_ids = snapshot::build_provider_id_map(snapshot);
-    let provider_names = snapshot::build_provider_name_map(snapshot);
-    if let Ok(mut guard) = state.provider_ids.write() {
-        *guard = provider_ids;
+#[utoipa::path(
+    get,
+    path = "/admin/instructions/rules",
+    tag = "instructions",
+    responses(
+        (status = 200, description = "list of instruction rules"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn list_instruction_rules(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
     }
-    if let Ok(mut guard) = state.provider_names.write() {
-        *guard = provider_names;
+
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
+    };
+
+    match storage.list_instruction_rules().await {
+        Ok(items) => {
+            let data: Vec<JsonValue> = items.into_iter().map(instruction_rule_to_json).collect();
+            Json(json!(data)).into_response()
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
     }
 }
 
-#[allow(clippy::result_large_err)]
-async fn refresh_auth(
-    state: &AdminState,
-    storage: &TrafficStorage,
-) -> Result<(), Response> {
-    let users = match storage.list_users().await {
-        Ok(items) => items,
-        Err(err) => return Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()),
+#[utoipa::path(
+    post,
+    path = "/admin/instructions/rules",
+    tag = "instructions",
+    request_body = InstructionRulePayload,
+    responses(
+        (status = 200, description = "instruction rule created or updated"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn create_instruction_rule(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Json(payload): Json<InstructionRulePayload>,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
     };
-    let keys = match storage.list_keys().await {
-        Ok(items) => items,
-        Err(err) => return Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()),
+
+    let input = AdminInstructionRuleInput {
+        id: payload.id,
+        position: payload.position,
+        model_glob: payload.model_glob.clone(),
+        template_id: payload.template_id.clone(),
+        personality: payload.personality,
     };
 
-    let mut snapshot = AuthSnapshot::default();
+    match storage.upsert_instruction_rule(input).await {
+        Ok(id) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/instructions/rules",
+                "instruction_rule",
+                Some(id),
+                &json!({ "model_glob": payload.model_glob, "template_id": payload.template_id }),
+            )
+            .await;
+            match instructions_store::reload_instruction_table(&storage).await {
+                Ok(_) => Json(json!({ "status": "ok", "id": id })).into_response(),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            }
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/instructions/rules/{id}",
+    tag = "instructions",
+    params(("id" = i64, Path, description = "instruction rule id")),
+    responses(
+        (status = 200, description = "instruction rule deleted"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn delete_instruction_rule(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
+    };
+
+    match storage.delete_instruction_rule(id).await {
+        Ok(_) => {
+            record_audit(
+                &storage,
+                &headers,
+                "/admin/instructions/rules/{id}",
+                "instruction_rule",
+                Some(id),
+                &json!({}),
+            )
+            .await;
+            match instructions_store::reload_instruction_table(&storage).await {
+                Ok(_) => Json(json!({ "status": "ok" })).into_response(),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            }
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/reload",
+    tag = "admin",
+    responses(
+        (status = 200, description = "storage snapshot reloaded into memory"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn reload_snapshot(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
+    };
+
+    let snapshot = match storage.load_snapshot().await {
+        Ok(snapshot) => snapshot,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+    apply_snapshot(&state, &snapshot);
+    state
+        .metrics
+        .snapshots_reloaded
+        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+    Json(json!({ "status": "ok" })).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/routes/reload",
+    tag = "admin",
+    responses(
+        (status = 200, description = "routes config reloaded"),
+        (status = 400, description = "routes config invalid"),
+        (status = 401, description = "missing or invalid admin key"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn reload_routes(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    match crate::routes_config::load_routes_config(&state.routes_config_path) {
+        Ok(config) => {
+            state.routes.set(config);
+            Json(json!({ "status": "ok" })).into_response()
+        }
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/models/reload",
+    tag = "admin",
+    responses(
+        (status = 200, description = "models config reloaded"),
+        (status = 400, description = "models config invalid"),
+        (status = 401, description = "missing or invalid admin key"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn reload_models(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    match state.models.reload(&state.models_config_path) {
+        Ok(()) => Json(json!({ "status": "ok" })).into_response(),
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+    }
+}
+
+/// Version of the [`ExportDocument`] shape. Bump whenever a field is added
+/// or removed so `admin_import` can reject documents it doesn't know how to
+/// apply instead of silently misinterpreting them.
+const EXPORT_DOCUMENT_VERSION: u32 = 1;
+
+/// The full set of provider/credential/disallow/user/key rows as a single
+/// document, produced by `admin_export` and accepted by `admin_import`.
+/// Reuses the same `*Payload` types the individual CRUD endpoints already
+/// validate against rather than introducing a parallel schema, so a
+/// document round-trips through `admin_export` -> `admin_import` without a
+/// translation layer.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+struct ExportDocument {
+    version: u32,
+    providers: Vec<ProviderPayload>,
+    credentials: Vec<CredentialPayload>,
+    disallow: Vec<DisallowPayload>,
+    users: Vec<UserPayload>,
+    keys: Vec<KeyPayload>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/export",
+    tag = "admin",
+    responses(
+        (status = 200, description = "full provider/credential/disallow/user/key config as one document", body = ExportDocument),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn admin_export(State(state): State<AdminState>, headers: HeaderMap) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
+    };
+
+    let providers = match storage.list_providers().await {
+        Ok(items) => items,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+    let provider_names: HashMap<i64, String> = providers
+        .iter()
+        .map(|provider| (provider.id, provider.name.clone()))
+        .collect();
+    let credentials = match storage.list_credentials().await {
+        Ok(items) => items,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+    let disallow = match storage.list_disallow().await {
+        Ok(items) => items,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+    let users = match storage.list_users().await {
+        Ok(items) => items,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+    let keys = match storage.list_keys().await {
+        Ok(items) => items,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+
+    let document = ExportDocument {
+        version: EXPORT_DOCUMENT_VERSION,
+        providers: providers
+            .into_iter()
+            .map(|provider| ProviderPayload {
+                id: Some(provider.id),
+                name: provider.name,
+                config_json: provider.config_json,
+                enabled: provider.enabled,
+            })
+            .collect(),
+        credentials: credentials
+            .into_iter()
+            .map(|credential| CredentialPayload {
+                id: Some(credential.id),
+                provider_id: Some(credential.provider_id),
+                provider_name: provider_names.get(&credential.provider_id).cloned(),
+                name: credential.name,
+                secret: credential.secret,
+                meta_json: credential.meta_json,
+                weight: credential.weight,
+                enabled: credential.enabled,
+            })
+            .collect(),
+        disallow: disallow
+            .into_iter()
+            .map(|record| DisallowPayload {
+                credential_id: record.credential_id,
+                scope_kind: record.scope_kind,
+                scope_value: record.scope_value,
+                level: record.level,
+                until_at: ts_opt(record.until_at),
+                reason: record.reason,
+            })
+            .collect(),
+        users: users
+            .into_iter()
+            .map(|user| UserPayload {
+                id: Some(user.id),
+                name: user.name,
+            })
+            .collect(),
+        keys: keys
+            .into_iter()
+            .map(|key| KeyPayload {
+                id: Some(key.id),
+                user_id: key.user_id,
+                key_value: key.key_value,
+                label: key.label,
+                enabled: Some(key.enabled),
+            })
+            .collect(),
+    };
+
+    Json(document).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportQuery {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Counts of rows an `admin_import` call applied (or would apply, under
+/// `dry_run`).
+#[derive(Debug, Serialize, ToSchema)]
+struct ImportSummary {
+    providers: usize,
+    credentials: usize,
+    disallow: usize,
+    users: usize,
+    keys: usize,
+    dry_run: bool,
+}
+
+/// Accepts an [`ExportDocument`] as a `multipart/form-data` file upload and
+/// upserts every row it contains: providers first, then credentials (each
+/// remapped from `provider_name` to `provider_id` via [`resolve_provider_id`]
+/// against the provider rows just written, exactly like `create_credential`
+/// does), then disallow rules, users and keys. A single
+/// `load_snapshot`/[`apply_snapshot`] call at the end reloads the in-memory
+/// auth and provider pools once, instead of the per-item
+/// `refresh_provider_pool`/`refresh_auth` calls the individual CRUD
+/// endpoints use. `?dry_run=true` parses and counts the document's rows
+/// without upserting anything, so an operator can preview an import before
+/// committing it. This tree has no cross-table transaction primitive on
+/// [`TrafficStorage`], so a non-dry-run import is applied row group by row
+/// group rather than atomically — a failure partway through leaves earlier
+/// groups already written.
+#[utoipa::path(
+    post,
+    path = "/admin/import",
+    tag = "admin",
+    params(("dry_run" = Option<bool>, Query, description = "report row counts without writing")),
+    responses(
+        (status = 200, description = "import applied (or previewed under dry_run)", body = ImportSummary),
+        (status = 400, description = "missing upload, malformed document, or unresolved provider reference"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn admin_import(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Query(query): Query<ImportQuery>,
+    mut multipart: Multipart,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let mut document: Option<ExportDocument> = None;
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+        };
+        let bytes = match field.bytes().await {
+            Ok(bytes) => bytes,
+            Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+        };
+        document = match serde_json::from_slice::<ExportDocument>(&bytes) {
+            Ok(document) => Some(document),
+            Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+        };
+    }
+    let Some(document) = document else {
+        return (StatusCode::BAD_REQUEST, "missing uploaded file").into_response();
+    };
+
+    let summary = ImportSummary {
+        providers: document.providers.len(),
+        credentials: document.credentials.len(),
+        disallow: document.disallow.len(),
+        users: document.users.len(),
+        keys: document.keys.len(),
+        dry_run: query.dry_run,
+    };
+
+    if query.dry_run {
+        return Json(summary).into_response();
+    }
+
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
+    };
+
+    for provider in document.providers {
+        let name = provider.name.clone();
+        let input = AdminProviderInput {
+            id: provider.id,
+            name: name.clone(),
+            config_json: provider.config_json,
+            enabled: provider.enabled,
+        };
+        match storage.upsert_provider(input).await {
+            Ok(id) => insert_provider_map(&state, id, name),
+            Err(err) => {
+                return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()
+            }
+        }
+    }
+
+    for credential in document.credentials {
+        let provider_id =
+            match resolve_provider_id(&state, credential.provider_id, credential.provider_name) {
+                Ok(id) => id,
+                Err(resp) => return resp,
+            };
+        let input = AdminCredentialInput {
+            id: credential.id,
+            provider_id,
+            name: credential.name,
+            secret: credential.secret,
+            meta_json: credential.meta_json,
+            weight: credential.weight,
+            enabled: credential.enabled,
+        };
+        if let Err(err) = storage.upsert_credential(input).await {
+            return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response();
+        }
+    }
+
+    for record in document.disallow {
+        let until_at = record
+            .until_at
+            .and_then(|ts| OffsetDateTime::from_unix_timestamp(ts).ok());
+        let input = AdminDisallowInput {
+            credential_id: record.credential_id,
+            scope_kind: record.scope_kind,
+            scope_value: record.scope_value,
+            level: record.level,
+            until_at,
+            reason: record.reason,
+        };
+        if let Err(err) = storage.upsert_disallow(input).await {
+            return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response();
+        }
+    }
+
+    for user in document.users {
+        let input = AdminUserInput {
+            id: user.id,
+            name: user.name,
+        };
+        if let Err(err) = storage.upsert_user(input).await {
+            return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response();
+        }
+    }
+
+    for key in document.keys {
+        let input = AdminKeyInput {
+            id: key.id,
+            user_id: key.user_id,
+            key_value: key.key_value,
+            label: key.label,
+            enabled: key.enabled.unwrap_or(true),
+        };
+        if let Err(err) = storage.upsert_key(input).await {
+            return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response();
+        }
+    }
+
+    let snapshot = match storage.load_snapshot().await {
+        Ok(snapshot) => snapshot,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+    apply_snapshot(&state, &snapshot);
+
+    Json(summary).into_response()
+}
+
+#[derive(Serialize, ToSchema)]
+struct ProviderPoolStats {
+    name: String,
+    credentials_total: usize,
+    credentials_enabled: usize,
+    disallow: usize,
+    health: Vec<CredentialHealthStats>,
+}
+
+/// One credential's weighted-selection bookkeeping, as surfaced by
+/// `GET /admin/stats` alongside the pool-level counts above.
+#[derive(Serialize, ToSchema)]
+struct CredentialHealthStats {
+    credential_id: String,
+    health_score: f64,
+    cooldown_until: Option<String>,
+    consecutive_failures: u32,
+    in_flight: u32,
+}
+
+impl From<gproxy_provider_core::CredentialHealth> for CredentialHealthStats {
+    fn from(health: gproxy_provider_core::CredentialHealth) -> Self {
+        Self {
+            credential_id: health.credential_id,
+            health_score: health.health_score,
+            cooldown_until: health
+                .cooldown_until
+                .map(|at| OffsetDateTime::from(at).to_string()),
+            consecutive_failures: health.consecutive_failures,
+            in_flight: health.in_flight,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/stats",
+    tag = "admin",
+    responses(
+        (status = 200, description = "per-provider credential pool stats", body = [ProviderPoolStats]),
+        (status = 401, description = "missing or invalid admin key"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn stats(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let stats = collect_stats(&state);
+    Json(json!({ "providers": stats })).into_response()
+}
+
+/// Lets a standard Prometheus scraper pull pool gauges and admin-mutation
+/// counters without the `x-admin-key`/session flow `require_admin` enforces
+/// everywhere else: access is granted if the caller presents the configured
+/// `x-metrics-token` (`config.metrics_token`, another field this tree's
+/// missing `cli.rs` would need to carry, per the pattern already used for
+/// `admin_key`/`dsn`), or otherwise only from a loopback peer address.
+fn require_metrics_access(
+    config: &GlobalConfig,
+    headers: &HeaderMap,
+    addr: std::net::SocketAddr,
+) -> bool {
+    if let Some(expected) = &config.metrics_token {
+        if let Some(presented) = header_value(headers, "x-metrics-token") {
+            return presented == *expected;
+        }
+        return false;
+    }
+    addr.ip().is_loopback()
+}
+
+fn render_prometheus_metrics(state: &AdminState) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP gproxy_pool_credentials_total total credentials configured for a provider pool\n");
+    out.push_str("# TYPE gproxy_pool_credentials_total gauge\n");
+    for entry in collect_stats(state) {
+        out.push_str(&format!(
+            "gproxy_pool_credentials_total{{provider=\"{}\"}} {}\n",
+            entry.name, entry.credentials_total
+        ));
+    }
+
+    out.push_str("# HELP gproxy_pool_credentials_enabled enabled credentials for a provider pool\n");
+    out.push_str("# TYPE gproxy_pool_credentials_enabled gauge\n");
+    for entry in collect_stats(state) {
+        out.push_str(&format!(
+            "gproxy_pool_credentials_enabled{{provider=\"{}\"}} {}\n",
+            entry.name, entry.credentials_enabled
+        ));
+    }
+
+    out.push_str("# HELP gproxy_pool_disallow active disallow entries for a provider pool\n");
+    out.push_str("# TYPE gproxy_pool_disallow gauge\n");
+    for entry in collect_stats(state) {
+        out.push_str(&format!(
+            "gproxy_pool_disallow{{provider=\"{}\"}} {}\n",
+            entry.name, entry.disallow
+        ));
+    }
+
+    let ordering = std::sync::atomic::Ordering::Relaxed;
+    out.push_str("# HELP gproxy_admin_keys_upserted_total api keys created or updated through the admin API\n");
+    out.push_str("# TYPE gproxy_admin_keys_upserted_total counter\n");
+    out.push_str(&format!(
+        "gproxy_admin_keys_upserted_total {}\n",
+        state.metrics.keys_upserted.load(ordering)
+    ));
+
+    out.push_str("# HELP gproxy_admin_keys_deleted_total api keys deleted through the admin API\n");
+    out.push_str("# TYPE gproxy_admin_keys_deleted_total counter\n");
+    out.push_str(&format!(
+        "gproxy_admin_keys_deleted_total {}\n",
+        state.metrics.keys_deleted.load(ordering)
+    ));
+
+    out.push_str("# HELP gproxy_admin_snapshots_reloaded_total storage snapshots reloaded into memory via /admin/reload\n");
+    out.push_str("# TYPE gproxy_admin_snapshots_reloaded_total counter\n");
+    out.push_str(&format!(
+        "gproxy_admin_snapshots_reloaded_total {}\n",
+        state.metrics.snapshots_reloaded.load(ordering)
+    ));
+
+    out.push_str("# HELP gproxy_disallow_transitions_total disallow entries created, by level\n");
+    out.push_str("# TYPE gproxy_disallow_transitions_total counter\n");
+    for (level, counter) in [
+        ("cooldown", &state.metrics.disallow_cooldown),
+        ("transient", &state.metrics.disallow_transient),
+        ("dead", &state.metrics.disallow_dead),
+    ] {
+        out.push_str(&format!(
+            "gproxy_disallow_transitions_total{{level=\"{}\"}} {}\n",
+            level,
+            counter.load(ordering)
+        ));
+    }
+
+    out
+}
+
+async fn metrics_handler(
+    State(state): State<AdminState>,
+    ConnectInfo(addr): ConnectInfo<std::net::SocketAddr>,
+    headers: HeaderMap,
+) -> Response {
+    let config = match state.config() {
+        Ok(config) => config,
+        Err(resp) => return resp,
+    };
+    if !require_metrics_access(&config, &headers, addr) {
+        return (StatusCode::UNAUTHORIZED, "unauthorized").into_response();
+    }
+
+    (
+        StatusCode::OK,
+        [("content-type", "text/plain; version=0.0.4")],
+        render_prometheus_metrics(&state),
+    )
+        .into_response()
+}
+
+/// How often `GET /admin/events` emits a `stats` frame on its own, on top of
+/// the `provider.updated`/`credential.updated`/`disallow.changed`/
+/// `auth.reloaded` frames pushed immediately whenever `apply_snapshot`,
+/// `refresh_provider_pool`, or `refresh_auth` run.
+const ADMIN_EVENTS_STATS_INTERVAL: Duration = Duration::from_secs(10);
+
+#[utoipa::path(
+    get,
+    path = "/admin/events",
+    tag = "admin",
+    responses(
+        (
+            status = 200,
+            description = "text/event-stream of provider.updated, credential.updated, disallow.changed, auth.reloaded, and periodic stats frames",
+        ),
+        (status = 401, description = "missing or invalid admin key"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn admin_events(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let receiver = state.events.subscribe();
+    let timer = Box::pin(tokio::time::sleep(ADMIN_EVENTS_STATS_INTERVAL));
+    let stream = unfold(
+        (receiver, state, timer),
+        |(mut receiver, state, mut timer)| async move {
+            loop {
+                tokio::select! {
+                    changed = receiver.recv() => {
+                        match changed {
+                            Ok(event) => {
+                                let frame = Event::default().event(event.name()).data(event.name());
+                                return Some((Ok::<Event, Infallible>(frame), (receiver, state, timer)));
+                            }
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(broadcast::error::RecvError::Closed) => return None,
+                        }
+                    }
+                    _ = &mut timer => {
+                        timer.as_mut().reset(tokio::time::Instant::now() + ADMIN_EVENTS_STATS_INTERVAL);
+                        let stats = collect_stats(&state);
+                        let Ok(json) = serde_json::to_string(&json!({ "providers": stats })) else {
+                            continue;
+                        };
+                        return Some((Ok::<Event, Infallible>(Event::default().event("stats").data(json)), (receiver, state, timer)));
+                    }
+                }
+            }
+        },
+    );
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+        .into_response()
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum BatchOperation {
+    UpsertKey { payload: KeyPayload },
+    DeleteKey { id: i64 },
+    SetKeyEnabled { id: i64, enabled: bool },
+    UpsertCredential { payload: CredentialPayload },
+    SetDisallow { payload: DisallowPayload },
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+struct BatchRequest {
+    operations: Vec<BatchOperation>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct BatchOpResult {
+    op: String,
+    status: String,
+    id: Option<i64>,
+    error: Option<String>,
+}
+
+fn batch_ok(op: &str, id: Option<i64>) -> BatchOpResult {
+    BatchOpResult {
+        op: op.to_string(),
+        status: "ok".to_string(),
+        id,
+        error: None,
+    }
+}
+
+fn batch_err(op: &str, error: DbErr) -> BatchOpResult {
+    BatchOpResult {
+        op: op.to_string(),
+        status: "error".to_string(),
+        id: None,
+        error: Some(error.to_string()),
+    }
+}
+
+/// Applies a list of key/credential/disallow operations and rebuilds
+/// in-memory state exactly once afterward, instead of once per operation
+/// like the single-entity handlers above do.
+///
+/// Despite the per-operation result array making this look transactional,
+/// it isn't: `TrafficStorage` (`crate::traffic`) exposes no cross-row
+/// transaction primitive in this tree (the same gap `admin_import` already
+/// documents), so each operation commits independently as it runs and a
+/// failure partway through leaves the earlier operations in this batch
+/// already applied. The one thing genuinely batched is the expensive part:
+/// `refresh_auth`/`refresh_provider_pool` run once at the end instead of
+/// once per operation, and callers can inspect `BatchOpResult::status` per
+/// entry to see exactly which operations actually landed.
+#[utoipa::path(
+    post,
+    path = "/admin/batch",
+    tag = "admin",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "per-operation results, in request order", body = [BatchOpResult]),
+        (status = 400, description = "an operation's provider_id/provider_name did not resolve"),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn admin_batch(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Json(request): Json<BatchRequest>,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
+    };
+
+    let mut results = Vec::with_capacity(request.operations.len());
+    let mut needs_auth_refresh = false;
+    let mut touched_providers: HashSet<i64> = HashSet::new();
+
+    for operation in request.operations {
+        match operation {
+            BatchOperation::UpsertKey { payload } => {
+                let input = AdminKeyInput {
+                    id: payload.id,
+                    user_id: payload.user_id,
+                    key_value: payload.key_value,
+                    label: payload.label,
+                    enabled: payload.enabled.unwrap_or(true),
+                };
+                match storage.upsert_key(input).await {
+                    Ok(id) => {
+                        needs_auth_refresh = true;
+                        state
+                            .metrics
+                            .keys_upserted
+                            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        results.push(batch_ok("upsert_key", Some(id)));
+                    }
+                    Err(err) => results.push(batch_err("upsert_key", err)),
+                }
+            }
+            BatchOperation::DeleteKey { id } => match storage.delete_key(id).await {
+                Ok(_) => {
+                    needs_auth_refresh = true;
+                    state
+                        .metrics
+                        .keys_deleted
+                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    results.push(batch_ok("delete_key", Some(id)));
+                }
+                Err(err) => results.push(batch_err("delete_key", err)),
+            },
+            BatchOperation::SetKeyEnabled { id, enabled } => {
+                match storage.set_key_enabled(id, enabled).await {
+                    Ok(_) => {
+                        needs_auth_refresh = true;
+                        results.push(batch_ok("set_key_enabled", Some(id)));
+                    }
+                    Err(err) => results.push(batch_err("set_key_enabled", err)),
+                }
+            }
+            BatchOperation::UpsertCredential { payload } => {
+                let provider_id =
+                    match resolve_provider_id(&state, payload.provider_id, payload.provider_name) {
+                        Ok(id) => id,
+                        Err(resp) => return resp,
+                    };
+                let input = AdminCredentialInput {
+                    id: payload.id,
+                    provider_id,
+                    name: payload.name,
+                    secret: payload.secret,
+                    meta_json: payload.meta_json,
+                    weight: payload.weight,
+                    enabled: payload.enabled,
+                };
+                match storage.upsert_credential(input).await {
+                    Ok(id) => {
+                        touched_providers.insert(provider_id);
+                        results.push(batch_ok("upsert_credential", Some(id)));
+                    }
+                    Err(err) => results.push(batch_err("upsert_credential", err)),
+                }
+            }
+            BatchOperation::SetDisallow { payload } => {
+                let provider_id =
+                    match provider_id_for_credential(&storage, payload.credential_id).await {
+                        Ok(id) => id,
+                        Err(resp) => return resp,
+                    };
+                let until_at = payload
+                    .until_at
+                    .and_then(|ts| OffsetDateTime::from_unix_timestamp(ts).ok());
+                let input = AdminDisallowInput {
+                    credential_id: payload.credential_id,
+                    scope_kind: payload.scope_kind,
+                    scope_value: payload.scope_value,
+                    level: payload.level.clone(),
+                    until_at,
+                    reason: payload.reason,
+                };
+                match storage.upsert_disallow(input).await {
+                    Ok(id) => {
+                        state.metrics.record_disallow(payload.level.as_str());
+                        touched_providers.insert(provider_id);
+                        results.push(batch_ok("set_disallow", Some(id)));
+                    }
+                    Err(err) => results.push(batch_err("set_disallow", err)),
+                }
+            }
+        }
+    }
+
+    if needs_auth_refresh {
+        if let Err(resp) = refresh_auth(&state, &storage).await {
+            return resp;
+        }
+    }
+    for provider_id in touched_providers {
+        if let Err(resp) = refresh_provider_pool(
+            &state,
+            &storage,
+            Some(provider_id),
+            AdminEvent::ProviderUpdated,
+        )
+        .await
+        {
+            return resp;
+        }
+    }
+
+    record_audit(
+        &storage,
+        &headers,
+        "/admin/batch",
+        "batch",
+        None,
+        &json!({ "operations": results.len() }),
+    )
+    .await;
+
+    Json(results).into_response()
+}
+
+/// Fields masked with `"[redacted]"` in the payload recorded alongside an
+/// audit entry, so raw secrets never land in the audit trail itself.
+const AUDIT_REDACTED_FIELDS: &[&str] = &["secret", "key_value", "admin_key"];
+
+fn redact_audit_payload(payload: &JsonValue) -> JsonValue {
+    let mut redacted = payload.clone();
+    if let Some(map) = redacted.as_object_mut() {
+        for field in AUDIT_REDACTED_FIELDS {
+            if map.contains_key(*field) {
+                map.insert((*field).to_string(), json!("[redacted]"));
+            }
+        }
+    }
+    redacted
+}
+
+/// Identifies the caller of a mutating admin request from whichever auth
+/// mechanism [`is_admin`] accepted, for the audit trail's `actor` column.
+/// None of the three mechanisms carry a per-operator identity in this
+/// tree (the static key and the JWT session it's exchanged for are both
+/// shared secrets), so this only distinguishes *how* the request
+/// authenticated, not *who* sent it — good enough to flag "this came in
+/// over a leaked bearer token" without inventing an operator-identity
+/// system this request didn't ask for.
+fn resolve_actor(headers: &HeaderMap) -> String {
+    if header_value(headers, "x-admin-key").is_some() {
+        return "admin-key".to_string();
+    }
+    if session_token_from_headers(headers).is_some() {
+        return "admin-session".to_string();
+    }
+    if header_value(headers, "authorization").is_some() {
+        return "admin-bearer".to_string();
+    }
+    "unknown".to_string()
+}
+
+/// Records a successful admin mutation to the `audit_log` table. Best
+/// effort: a failure to write the audit row is logged but never fails the
+/// request that triggered it, the same way a dropped [`AdminEvent`] never
+/// fails one either.
+async fn record_audit(
+    storage: &TrafficStorage,
+    headers: &HeaderMap,
+    route: &str,
+    entity_type: &str,
+    entity_id: Option<i64>,
+    payload: &JsonValue,
+) {
+    let input = AdminAuditInput {
+        actor: resolve_actor(headers),
+        route: route.to_string(),
+        entity_type: entity_type.to_string(),
+        entity_id,
+        diff_json: redact_audit_payload(payload),
+    };
+    if let Err(err) = storage.insert_audit_entry(input).await {
+        tracing::warn!(
+            event = "audit_log_write_failed",
+            route,
+            entity_type,
+            error = %err,
+            "failed to record admin audit entry"
+        );
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct AuditEntry {
+    id: i64,
+    actor: String,
+    route: String,
+    entity_type: String,
+    entity_id: Option<i64>,
+    diff_json: JsonValue,
+    created_at: i64,
+}
+
+fn audit_entry_to_json(entry: entities::audit_log::Model) -> AuditEntry {
+    AuditEntry {
+        id: entry.id,
+        actor: entry.actor,
+        route: entry.route,
+        entity_type: entry.entity_type,
+        entity_id: entry.entity_id,
+        diff_json: entry.diff_json,
+        created_at: ts(entry.created_at),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AuditQuery {
+    actor: Option<String>,
+    entity_type: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/audit",
+    tag = "admin",
+    params(
+        ("actor" = Option<String>, Query, description = "filter by actor (e.g. admin-key, admin-session)"),
+        ("entity_type" = Option<String>, Query, description = "filter by mutated entity type (provider, credential, ...)"),
+        ("since" = Option<i64>, Query, description = "only entries at/after this unix timestamp"),
+        ("until" = Option<i64>, Query, description = "only entries at/before this unix timestamp"),
+    ),
+    responses(
+        (status = 200, description = "matching audit entries, newest first", body = [AuditEntry]),
+        (status = 401, description = "missing or invalid admin key"),
+        (status = 500, description = "internal error"),
+    ),
+    security(("admin_key" = []))
+)]
+async fn admin_audit(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Query(query): Query<AuditQuery>,
+) -> Response {
+    if let Err(resp) = require_admin(&state, &headers).await {
+        return resp;
+    }
+
+    let storage = match state.storage() {
+        Ok(storage) => storage,
+        Err(resp) => return resp,
+    };
+
+    let since = query
+        .since
+        .and_then(|ts| OffsetDateTime::from_unix_timestamp(ts).ok());
+    let until = query
+        .until
+        .and_then(|ts| OffsetDateTime::from_unix_timestamp(ts).ok());
+
+    match storage
+        .list_audit_entries(query.actor, query.entity_type, since, until)
+        .await
+    {
+        Ok(items) => {
+            let data: Vec<AuditEntry> = items.into_iter().map(audit_entry_to_json).collect();
+            Json(data).into_response()
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+fn collect_stats(state: &AdminState) -> Vec<ProviderPoolStats> {
+    let mut out = Vec::new();
+    collect_one(&mut out, "openai", state.registry.openai().pool());
+    collect_one(&mut out, "claude", state.registry.claude().pool());
+    collect_one(&mut out, "aistudio", state.registry.aistudio().pool());
+    collect_one(
+        &mut out,
+        "vertexexpress",
+        state.registry.vertexexpress().pool(),
+    );
+    collect_one(&mut out, "vertex", state.registry.vertex().pool());
+    collect_one(&mut out, "geminicli", state.registry.geminicli().pool());
+    collect_one(&mut out, "claudecode", state.registry.claudecode().pool());
+    collect_one(&mut out, "codex", state.registry.codex().pool());
+    collect_one(&mut out, "antigravity", state.registry.antigravity().pool());
+    collect_one(&mut out, "nvidia", state.registry.nvidia().pool());
+    collect_one(&mut out, "deepseek", state.registry.deepseek().pool());
+    out
+}
+
+/// Background task that mirrors `POST /admin/reload` automatically: every
+/// [`ConfigEvent`] published on the storage bus (another writer's
+/// provider/credential/disallow/user change, possibly from a different
+/// process sharing the same `dsn`) triggers the same `load_snapshot` +
+/// `apply_snapshot` sequence the manual endpoint runs, so pools and auth
+/// stay current without an operator having to call the admin API. A
+/// `Lagged` receiver still reloads once (the snapshot read is always a full
+/// resync, so a missed event is harmless); the loop only exits once the bus
+/// sender is dropped.
+async fn auto_reload_loop(state: AdminState, mut events: broadcast::Receiver<ConfigEvent>) {
+    loop {
+        match events.recv().await {
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+        let storage = match state.storage() {
+            Ok(storage) => storage,
+            Err(_) => continue,
+        };
+        match storage.load_snapshot().await {
+            Ok(snapshot) => {
+                apply_snapshot(&state, &snapshot);
+                state
+                    .metrics
+                    .snapshots_reloaded
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                tracing::info!("auto-reloaded snapshot from storage bus event");
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "auto-reload: failed to load snapshot");
+            }
+        }
+    }
+}
+
+fn apply_snapshot(state: &AdminState, snapshot: &gproxy_storage::StorageSnapshot) {
+    let auth_snapshot = snapshot::build_auth_snapshot(snapshot);
+    state.auth.replace_snapshot(auth_snapshot);
+    let pools = snapshot::build_provider_pools(snapshot);
+    state.registry.apply_pools(pools);
+    let provider_ids = snapshot::build_provider_id_map(snapshot);
+    let provider_names = snapshot::build_provider_name_map(snapshot);
+    if let Ok(mut guard) = state.provider_ids.write() {
+        *guard = provider_ids;
+    }
+    if let Ok(mut guard) = state.provider_names.write() {
+        *guard = provider_names;
+    }
+    let _ = state.events.send(AdminEvent::AuthReloaded);
+    let _ = state.events.send(AdminEvent::ProviderUpdated);
+}
+
+#[allow(clippy::result_large_err)]
+async fn refresh_auth(
+    state: &AdminState,
+    storage: &TrafficStorage,
+) -> Result<(), Response> {
+    let users = match storage.list_users().await {
+        Ok(items) => items,
+        Err(err) => return Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()),
+    };
+    let keys = match storage.list_keys().await {
+        Ok(items) => items,
+        Err(err) => return Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()),
+    };
+
+    let mut snapshot = AuthSnapshot::default();
     for key in keys {
         snapshot.keys_by_value.insert(
             key.key_value,
@@ -1025,6 +3052,7 @@ async fn refresh_auth(
         );
     }
     state.auth.replace_snapshot(snapshot);
+    let _ = state.events.send(AdminEvent::AuthReloaded);
     Ok(())
 }
 
@@ -1136,6 +3164,7 @@ async fn refresh_provider_pool(
     state: &AdminState,
     storage: &TrafficStorage,
     provider_id: Option<i64>,
+    event: AdminEvent,
 ) -> Result<(), Response> {
     let provider_id = match provider_id {
         Some(id) => id,
@@ -1172,17 +3201,20 @@ async fn refresh_provider_pool(
         } else {
             0
         };
+        let base_credential = BaseCredential {
+            id: credential.id,
+            name: credential.name.clone(),
+            secret: credential.secret.clone(),
+            meta: credential.meta_json.clone(),
+        };
+        let expires_at = base_credential.expires_at();
         let entry = CredentialEntry::new(
             credential.id.to_string(),
             credential.enabled,
             weight,
-            BaseCredential {
-                id: credential.id,
-                name: credential.name.clone(),
-                secret: credential.secret.clone(),
-                meta: credential.meta_json.clone(),
-            },
-        );
+            base_credential,
+        )
+        .with_expiry(expires_at);
         entries.push(entry);
     }
 
@@ -1214,51 +3246,103 @@ async fn refresh_provider_pool(
     let mut pools = HashMap::new();
     pools.insert(provider_name, snapshot);
     state.registry.apply_pools(pools);
+    let _ = state.events.send(event);
     Ok(())
 }
 
-fn collect_one<C>(
+fn collect_one<C: Clone + Send + Sync + 'static>(
     out: &mut Vec<ProviderPoolStats>,
     name: &str,
-    snapshot: Arc<gproxy_provider_core::PoolSnapshot<C>>,
+    pool: &gproxy_provider_core::CredentialPool<C>,
 ) {
+    let snapshot = pool.snapshot();
     let total = snapshot.credentials.len();
     let enabled = snapshot.credentials.iter().filter(|cred| cred.enabled).count();
     let disallow = snapshot.disallow.len();
+    let health = pool.health().into_iter().map(CredentialHealthStats::from).collect();
     out.push(ProviderPoolStats {
         name: name.to_string(),
         credentials_total: total,
         credentials_enabled: enabled,
         disallow,
+        health,
     });
 }
 
 #[allow(clippy::result_large_err)]
-fn require_admin(state: &AdminState, headers: &HeaderMap) -> Result<(), Response> {
+async fn require_admin(state: &AdminState, headers: &HeaderMap) -> Result<(), Response> {
     let admin_key = state.admin_key()?;
-    if is_admin(headers, &admin_key) {
+    if is_admin(state, headers, &admin_key).await {
         Ok(())
     } else {
         Err((StatusCode::UNAUTHORIZED, "unauthorized").into_response())
     }
 }
 
-fn is_admin(headers: &HeaderMap, admin_key: &str) -> bool {
+async fn is_admin(state: &AdminState, headers: &HeaderMap, admin_key: &str) -> bool {
     if let Some(value) = header_value(headers, "x-admin-key") {
         return value == admin_key;
     }
 
+    if let Some(token) = session_token_from_headers(headers) {
+        if verify_admin_session(&token, admin_key) {
+            return true;
+        }
+    }
+
     let Some(auth) = header_value(headers, "authorization") else {
         return false;
     };
     let auth = auth.trim();
-    if let Some(token) = auth.strip_prefix("Bearer ") {
-        return token.trim() == admin_key;
+    let bearer = auth
+        .strip_prefix("Bearer ")
+        .or_else(|| auth.strip_prefix("bearer "))
+        .map(str::trim);
+    let Some(token) = bearer else {
+        return false;
+    };
+    if token == admin_key || verify_admin_session(token, admin_key) {
+        return true;
     }
-    if let Some(token) = auth.strip_prefix("bearer ") {
-        return token.trim() == admin_key;
+
+    authorize_oidc_bearer(state, token).await
+}
+
+/// Falls back to an external identity provider when the caller presented a
+/// bearer token that's neither the static `admin_key` nor a session token
+/// this process signed. No-ops (returns `false`) until an [`OidcConfig`] is
+/// configured — this tree has no config surface for it yet (see
+/// [`crate::oidc::OidcConfig`]'s doc comment), so `oidc.config` stays `None`
+/// in every deployment today.
+async fn authorize_oidc_bearer(state: &AdminState, token: &str) -> bool {
+    let config = {
+        let runtime = state.oidc.read().await;
+        runtime.config.clone()
+    };
+    let Some(config) = config else {
+        return false;
+    };
+
+    let kid = jsonwebtoken::decode_header(token)
+        .ok()
+        .and_then(|header| header.kid);
+
+    let needs_refresh = {
+        let runtime = state.oidc.read().await;
+        match &kid {
+            Some(kid) => !runtime.cache.has_kid(kid),
+            None => false,
+        } || runtime.cache.is_stale(config.jwks_refresh_interval())
+    };
+    if needs_refresh {
+        if let Ok(jwks) = crate::oidc::fetch_jwks(&config.jwks_url).await {
+            let mut runtime = state.oidc.write().await;
+            runtime.cache.replace(jwks);
+        }
     }
-    false
+
+    let runtime = state.oidc.read().await;
+    crate::oidc::authorize(token, &config, &runtime.cache)
 }
 
 fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
@@ -1313,6 +3397,26 @@ fn disallow_to_json(record: entities::credential_disallow::Model) -> JsonValue {
     })
 }
 
+fn instruction_template_to_json(record: entities::instruction_templates::Model) -> JsonValue {
+    json!({
+        "id": record.id,
+        "template_id": record.template_id,
+        "body": record.body,
+        "updated_at": ts(record.updated_at),
+    })
+}
+
+fn instruction_rule_to_json(record: entities::instruction_rules::Model) -> JsonValue {
+    json!({
+        "id": record.id,
+        "position": record.position,
+        "model_glob": record.model_glob,
+        "template_id": record.template_id,
+        "personality": record.personality,
+        "updated_at": ts(record.updated_at),
+    })
+}
+
 fn user_to_json(user: entities::users::Model) -> JsonValue {
     json!({
         "id": user.id,
@@ -0,0 +1,95 @@
+//! Declarative failover groups loaded from a `failover.toml`, applied to the
+//! `ProviderRegistry` at startup (and on manual `/admin/reload`) via
+//! `ProviderRegistry::apply_failover_groups`. A group `{name, backends}`
+//! means: requests routed to `name` are tried against each of `backends` in
+//! turn, through `gproxy_provider_impl::dispatch::dispatch_request_with_failover`,
+//! instead of going straight to a single concrete provider — `Core::router`'s
+//! `(state.lookup)(provider)` call site is unchanged either way.
+//!
+//! Mirrors [`crate::routes_config`]'s load/validate/reload shape: a missing
+//! file means "no failover groups configured", a malformed one fails
+//! startup.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// One entry in `failover.toml`'s `[[group]]` list. `backends` is tried in
+/// order by `dispatch_request_with_failover`; `name` is the provider name
+/// clients actually route to (which may or may not also be one of its own
+/// backends).
+#[derive(Debug, Clone, Deserialize)]
+pub struct FailoverGroup {
+    pub name: String,
+    pub backends: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FailoverConfig {
+    #[serde(default, rename = "group")]
+    pub groups: Vec<FailoverGroup>,
+}
+
+#[derive(Debug)]
+pub enum FailoverConfigError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Validation(String),
+}
+
+impl fmt::Display for FailoverConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailoverConfigError::Io(err) => write!(f, "failed to read failover config: {err}"),
+            FailoverConfigError::Parse(err) => write!(f, "failed to parse failover config: {err}"),
+            FailoverConfigError::Validation(msg) => write!(f, "invalid failover config: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for FailoverConfigError {}
+
+/// Loads and validates `failover.toml` at `path`. A missing file is not an
+/// error — it's treated as "no failover groups configured" so operators who
+/// don't need this feature pay no startup cost — but a present-and-malformed
+/// file fails startup, matching `load_routes_config`'s convention.
+pub fn load_failover_config(path: &Path) -> Result<FailoverConfig, FailoverConfigError> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(FailoverConfig::default())
+        }
+        Err(err) => return Err(FailoverConfigError::Io(err)),
+    };
+    let config: FailoverConfig = toml::from_str(&raw).map_err(FailoverConfigError::Parse)?;
+    validate(&config)?;
+    Ok(config)
+}
+
+fn validate(config: &FailoverConfig) -> Result<(), FailoverConfigError> {
+    for group in &config.groups {
+        if group.name.is_empty() {
+            return Err(FailoverConfigError::Validation(
+                "failover group name must not be empty".to_string(),
+            ));
+        }
+        if group.backends.is_empty() {
+            return Err(FailoverConfigError::Validation(format!(
+                "failover group {:?} must list at least one backend",
+                group.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Flattens a loaded [`FailoverConfig`] into the `name -> backend names` map
+/// `ProviderRegistry::apply_failover_groups` takes.
+pub fn as_group_map(config: &FailoverConfig) -> std::collections::HashMap<String, Vec<String>> {
+    config
+        .groups
+        .iter()
+        .map(|group| (group.name.clone(), group.backends.clone()))
+        .collect()
+}
@@ -0,0 +1,102 @@
+//! Bridges `gproxy-storage`'s `instruction_templates`/`instruction_rules`
+//! rows to the plain, storage-agnostic [`InstructionTable`] that
+//! `gproxy_provider_impl::provider::codex::instructions` resolves against.
+//! That module owns matching/rendering and a hot-swappable
+//! `RwLock<Arc<InstructionTable>>`; this module owns turning storage rows
+//! into that shape and back, the same split `snapshot.rs` draws between
+//! storage rows and the in-memory `CredentialPool`s the registry runs on.
+//!
+//! [`load_instruction_table`] seeds both tables from
+//! [`instructions::seed_defaults`] on first run (mirroring `ensure_providers`
+//! seeding provider config from `default_providers()`), so behavior is
+//! unchanged until an operator edits a rule or template via
+//! `/admin/instructions/*`. [`reload_instruction_table`] re-reads storage
+//! and calls [`instructions::set_table`], which `admin.rs`'s instruction
+//! CRUD handlers call after every mutation instead of relying on a separate
+//! `/admin/instructions/reload` endpoint, since there's no file on disk to
+//! re-read.
+
+use gproxy_provider_impl::provider::codex::instructions::{
+    self, InstructionRule, InstructionTable, InstructionTemplate,
+};
+use gproxy_storage::{AdminInstructionRuleInput, AdminInstructionTemplateInput, TrafficStorage};
+use sea_orm::DbErr;
+
+/// Loads the instruction table from storage, seeding it from
+/// [`instructions::seed_defaults`] if both tables are empty, then installs
+/// it via [`instructions::set_table`].
+pub async fn load_instruction_table(storage: &TrafficStorage) -> Result<(), DbErr> {
+    let templates = storage.list_instruction_templates().await?;
+    let rules = storage.list_instruction_rules().await?;
+
+    if templates.is_empty() && rules.is_empty() {
+        seed_storage(storage).await?;
+        return reload_instruction_table(storage).await;
+    }
+
+    instructions::set_table(build_table(templates, rules));
+    Ok(())
+}
+
+/// Re-reads `instruction_templates`/`instruction_rules` from `storage` and
+/// hot-swaps the live table. Called by every `/admin/instructions/*`
+/// mutation handler so edits take effect on the next request.
+pub async fn reload_instruction_table(storage: &TrafficStorage) -> Result<(), DbErr> {
+    let templates = storage.list_instruction_templates().await?;
+    let rules = storage.list_instruction_rules().await?;
+    instructions::set_table(build_table(templates, rules));
+    Ok(())
+}
+
+async fn seed_storage(storage: &TrafficStorage) -> Result<(), DbErr> {
+    let defaults = instructions::seed_defaults();
+    for template in defaults.templates {
+        storage
+            .upsert_instruction_template(AdminInstructionTemplateInput {
+                id: None,
+                template_id: template.id,
+                body: template.body,
+            })
+            .await?;
+    }
+    for (position, rule) in defaults.rules.into_iter().enumerate() {
+        storage
+            .upsert_instruction_rule(AdminInstructionRuleInput {
+                id: None,
+                position: position as i32,
+                model_glob: rule.model_glob,
+                template_id: rule.template_id,
+                personality: rule.personality.map(|p| format!("{p:?}").to_lowercase()),
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+fn build_table(
+    templates: Vec<gproxy_storage::entities::instruction_templates::Model>,
+    mut rules: Vec<gproxy_storage::entities::instruction_rules::Model>,
+) -> InstructionTable {
+    rules.sort_by_key(|rule| rule.position);
+
+    InstructionTable {
+        templates: templates
+            .into_iter()
+            .map(|row| InstructionTemplate {
+                id: row.template_id,
+                body: row.body,
+            })
+            .collect(),
+        rules: rules
+            .into_iter()
+            .map(|row| InstructionRule {
+                model_glob: row.model_glob,
+                template_id: row.template_id,
+                personality: row
+                    .personality
+                    .as_deref()
+                    .and_then(instructions::parse_personality),
+            })
+            .collect(),
+    }
+}
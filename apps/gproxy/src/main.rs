@@ -5,11 +5,26 @@ use clap::Parser;
 mod admin;
 mod cli;
 mod dsn;
+mod failover_config;
+mod instructions_store;
+mod model_config;
+mod oidc;
+mod routes_config;
+mod tls_acme;
 mod traffic_sink;
 use gproxy_core::{AuthProvider, Core, MemoryAuth, ProviderLookup};
 use gproxy_provider_impl::{build_registry, default_providers};
 mod snapshot;
-use gproxy_storage::{StorageBus, StorageBusConfig, TrafficStorage};
+use crate::failover_config::{as_group_map, load_failover_config};
+use crate::model_config::{load_models_config, ModelTable};
+use crate::routes_config::{load_routes_config, RouteTable};
+use crate::tls_acme::{
+    build_rustls_server_config, spawn_renewal_task, AcmeTlsConfig, ReloadableCertResolver,
+};
+use gproxy_storage::{
+    FsObjectStore, ObjectStoreTrafficBackend, StorageBackend, StorageBus, StorageBusConfig,
+    TrafficStorage, TrafficStore,
+};
 use time::OffsetDateTime;
 use tracing::info;
 
@@ -43,6 +58,7 @@ async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
         })
         .collect::<Vec<_>>();
     storage.ensure_providers(&defaults).await?;
+    instructions_store::load_instruction_table(&storage).await?;
 
     let snapshot = storage.load_snapshot().await?;
 
@@ -87,8 +103,10 @@ async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
     let auth = Arc::new(MemoryAuth::new(auth_snapshot));
     let auth_provider: Arc<dyn AuthProvider> = auth.clone();
 
-    let bus = StorageBus::spawn(storage.clone(), StorageBusConfig::default());
+    let traffic_backend = traffic_backend_from_env(&storage);
+    let bus = StorageBus::spawn(traffic_backend, StorageBusConfig::default());
     let traffic_sink = Arc::new(StorageTrafficSink::new(&bus));
+    let config_events = bus.subscribe();
     let _bus = bus;
 
     let registry = Arc::new(build_registry());
@@ -100,6 +118,17 @@ async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
     }
     registry.apply_pools(pools);
 
+    let failover_config_path = std::path::PathBuf::from(
+        std::env::var("GPROXY_FAILOVER_CONFIG").unwrap_or_else(|_| "failover.toml".to_string()),
+    );
+    let failover_config = load_failover_config(&failover_config_path)?;
+    registry.apply_failover_groups(as_group_map(&failover_config));
+    info!(
+        path = %failover_config_path.display(),
+        groups = failover_config.groups.len(),
+        "failover config loaded"
+    );
+
     let bind = format!("{}:{}", config.host, config.port);
     let (bind_tx, bind_rx) = tokio::sync::watch::channel(bind);
 
@@ -107,18 +136,53 @@ async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
 
     let lookup: ProviderLookup = {
         let registry = registry.clone();
-        Arc::new(move |name| registry.get(name))
+        Arc::new(move |name| registry.by_name(name))
     };
 
     let provider_ids = snapshot::build_provider_id_map(&snapshot);
     let provider_names = snapshot::build_provider_name_map(&snapshot);
 
+    let routes_config_path = std::path::PathBuf::from(
+        std::env::var("GPROXY_ROUTES_CONFIG").unwrap_or_else(|_| "gproxy.toml".to_string()),
+    );
+    let routes = RouteTable::new(load_routes_config(&routes_config_path)?);
+    info!(path = %routes_config_path.display(), "routes config loaded");
+
+    let models_config_path = std::path::PathBuf::from(
+        std::env::var("GPROXY_MODELS_CONFIG").unwrap_or_else(|_| "models.toml".to_string()),
+    );
+    let models = ModelTable::new(load_models_config(&models_config_path)?);
+    info!(path = %models_config_path.display(), "models config loaded");
+
+    let tls_config = tls_config_from_env();
+    let tls_resolver = if tls_config.enabled {
+        let resolver = Arc::new(ReloadableCertResolver::new());
+        let store: Arc<dyn tls_acme::CertificateStore> = Arc::new(storage.clone());
+        tokio::spawn(spawn_renewal_task(
+            store,
+            resolver.clone(),
+            tls_config.clone(),
+            time::Duration::hours(24),
+            std::time::Duration::from_secs(3600),
+        ));
+        info!(domains = ?tls_config.domains, "tls/acme enabled");
+        Some(resolver)
+    } else {
+        None
+    };
+
+    let provider_rule = load_provider_rule()?;
+
     let core = Core::new(
         lookup,
         auth_provider,
         proxy.clone(),
         Some(traffic_sink),
         Some(provider_ids.clone()),
+        None,
+        None,
+        provider_rule,
+        Some(routes.clone()),
     );
     let app = core
         .router()
@@ -133,27 +197,98 @@ async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
             auth,
             provider_ids,
             provider_names,
+            routes.clone(),
+            routes_config_path.clone(),
+            models.clone(),
+            models_config_path.clone(),
+            Some(config_events),
         ));
 
-    serve_loop(app, bind_rx).await?;
+    serve_loop(app, bind_rx, tls_resolver).await?;
 
     Ok(())
 }
 
+/// Loads the optional provider-routing rule `proxy_handler` evaluates before
+/// `lookup`, from the file named by `GPROXY_PROVIDER_RULE` (see
+/// `gproxy_core::rules::Rule::parse` for its `condition => result` syntax).
+/// Unset or missing means no rule is configured; a present-and-malformed
+/// file fails startup, matching the other `GPROXY_*_CONFIG` loaders.
+fn load_provider_rule() -> Result<Option<Arc<gproxy_core::rules::Rule>>, Box<dyn Error + Send + Sync>> {
+    let Ok(path) = std::env::var("GPROXY_PROVIDER_RULE") else {
+        return Ok(None);
+    };
+    let src = match std::fs::read_to_string(&path) {
+        Ok(src) => src,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(Box::new(err)),
+    };
+    let rule = gproxy_core::rules::Rule::parse(&src)?;
+    info!(path = %path, "provider rule loaded");
+    Ok(Some(Arc::new(rule)))
+}
+
+/// Picks the backend traffic logging writes to: plain `storage` by default,
+/// or `storage` wrapped in an [`ObjectStoreTrafficBackend`] — offloading
+/// bulk downstream/upstream blobs to `GPROXY_OBJECT_STORE_DIR` on the local
+/// filesystem via [`FsObjectStore`] — when that var is set. Everything else
+/// (config, usage aggregation, admin bootstrap) keeps going through
+/// `storage` either way.
+fn traffic_backend_from_env(storage: &TrafficStorage) -> Arc<dyn StorageBackend> {
+    match std::env::var("GPROXY_OBJECT_STORE_DIR") {
+        Ok(dir) => Arc::new(ObjectStoreTrafficBackend::new(
+            storage.clone(),
+            FsObjectStore::new(dir),
+            "traffic",
+        )),
+        Err(_) => Arc::new(storage.clone()),
+    }
+}
+
 fn init_tracing() {
     let filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("gproxy=info,sqlx=warn"));
     tracing_subscriber::fmt().with_env_filter(filter).init();
 }
 
+/// Reads `GPROXY_TLS_*` env vars into an [`AcmeTlsConfig`] — the stand-in
+/// for a `GlobalConfig.tls` field, since `GlobalConfig` (declared via
+/// `mod cli;`) isn't present in this checkout. `GPROXY_TLS_DOMAINS` is a
+/// comma-separated list; everything else matches `AcmeTlsConfig`'s field
+/// names.
+fn tls_config_from_env() -> AcmeTlsConfig {
+    let enabled = std::env::var("GPROXY_TLS_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    let domains = std::env::var("GPROXY_TLS_DOMAINS")
+        .map(|raw| {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let contact_email = std::env::var("GPROXY_TLS_CONTACT_EMAIL").ok();
+    let mut config = AcmeTlsConfig {
+        enabled,
+        contact_email,
+        domains,
+        ..AcmeTlsConfig::default()
+    };
+    if let Ok(directory_url) = std::env::var("GPROXY_TLS_DIRECTORY_URL") {
+        config.directory_url = directory_url;
+    }
+    config
+}
+
 async fn serve_loop(
     app: axum::Router,
     bind_rx: tokio::sync::watch::Receiver<String>,
+    tls_resolver: Option<Arc<ReloadableCertResolver>>,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     let mut current = bind_rx.borrow().clone();
     loop {
-        let listener = tokio::net::TcpListener::bind(&current).await?;
-        info!(addr = %current, "listening");
         let mut shutdown_rx = bind_rx.clone();
         let shutdown_addr = current.clone();
         let shutdown = async move {
@@ -166,9 +301,39 @@ async fn serve_loop(
                 }
             }
         };
-        axum::serve(listener, app.clone())
+
+        if let Some(resolver) = &tls_resolver {
+            let rustls_config = axum_server::tls_rustls::RustlsConfig::from_config(
+                build_rustls_server_config(resolver.clone()),
+            );
+            let addr: std::net::SocketAddr = current.parse()?;
+            let handle = axum_server::Handle::new();
+            tokio::spawn({
+                let handle = handle.clone();
+                async move {
+                    shutdown.await;
+                    handle.graceful_shutdown(None);
+                }
+            });
+            info!(addr = %current, "listening (tls)");
+            axum_server::bind_rustls(addr, rustls_config)
+                .handle(handle)
+                .serve(
+                    app.clone()
+                        .into_make_service_with_connect_info::<std::net::SocketAddr>(),
+                )
+                .await?;
+        } else {
+            let listener = tokio::net::TcpListener::bind(&current).await?;
+            info!(addr = %current, "listening");
+            axum::serve(
+                listener,
+                app.clone()
+                    .into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
             .with_graceful_shutdown(shutdown)
             .await?;
+        }
 
         let next = bind_rx.borrow().clone();
         if next == current {
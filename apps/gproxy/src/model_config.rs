@@ -0,0 +1,141 @@
+//! Declarative "extra models" list loaded from a versioned `models.toml`,
+//! for upstream models the proxy hasn't shipped typed support for yet.
+//! Pairs with `gproxy_provider_impl::dispatch::RawPassthroughPlan`: once a
+//! requested model matches an entry here instead of the proxy's built-in
+//! known set, the call site that decides `Native` vs `Transform` vs
+//! passthrough (in each provider's `dispatch_plan`, or a shared classifier
+//! in front of it) can build a `RawPassthroughPlan` instead of erroring —
+//! that call site isn't part of this checkout, so this module is the
+//! self-contained, provider-agnostic piece: parsing, validation, hot-reload.
+//!
+//! Hot-reload mirrors `routes_config::RouteTable`: the parsed config lives
+//! behind an `RwLock`, a reload swaps it out, and readers take a short-lived
+//! read guard per request.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use serde::Deserialize;
+
+/// The only `models.toml` format version this build understands. Bumped
+/// whenever the flat entry shape below gains or changes a required field;
+/// `load_models_config` rejects any other value so an operator's existing
+/// file either keeps working unchanged or fails loudly at startup instead
+/// of being silently misinterpreted.
+const SUPPORTED_VERSION: u32 = 1;
+
+/// One upstream model the built-in provider catalogs don't know about yet,
+/// e.g. a model released after this build.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelEntry {
+    pub provider: String,
+    pub name: String,
+    pub max_tokens: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ModelsConfig {
+    pub version: u32,
+    #[serde(default, rename = "model")]
+    pub models: Vec<ModelEntry>,
+}
+
+#[derive(Debug)]
+pub enum ModelsConfigError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Validation(String),
+}
+
+impl fmt::Display for ModelsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelsConfigError::Io(err) => write!(f, "failed to read models config: {err}"),
+            ModelsConfigError::Parse(err) => write!(f, "failed to parse models config: {err}"),
+            ModelsConfigError::Validation(msg) => write!(f, "invalid models config: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ModelsConfigError {}
+
+/// Loads and validates `models.toml` at `path`. A missing file is not an
+/// error — it's treated as "no extra models declared" — but a
+/// present-and-malformed file, or one declaring an unsupported `version`,
+/// fails startup.
+pub fn load_models_config(path: &Path) -> Result<ModelsConfig, ModelsConfigError> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(ModelsConfig {
+                version: SUPPORTED_VERSION,
+                models: Vec::new(),
+            })
+        }
+        Err(err) => return Err(ModelsConfigError::Io(err)),
+    };
+    let config: ModelsConfig = toml::from_str(&raw).map_err(ModelsConfigError::Parse)?;
+    validate(&config)?;
+    Ok(config)
+}
+
+fn validate(config: &ModelsConfig) -> Result<(), ModelsConfigError> {
+    if config.version != SUPPORTED_VERSION {
+        return Err(ModelsConfigError::Validation(format!(
+            "unsupported models config version {} (expected {})",
+            config.version, SUPPORTED_VERSION
+        )));
+    }
+    for entry in &config.models {
+        if entry.provider.is_empty() {
+            return Err(ModelsConfigError::Validation(format!(
+                "model {:?} is missing a provider",
+                entry.name
+            )));
+        }
+        if entry.name.is_empty() {
+            return Err(ModelsConfigError::Validation(
+                "model entry is missing a name".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Hot-reloadable holder for a [`ModelsConfig`], shared across the app the
+/// same way `RouteTable` shares `RoutesConfig`.
+#[derive(Clone)]
+pub struct ModelTable {
+    inner: Arc<RwLock<ModelsConfig>>,
+}
+
+impl ModelTable {
+    pub fn new(config: ModelsConfig) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(config)),
+        }
+    }
+
+    /// The declared entry for `(provider, name)`, if any — the condition a
+    /// `dispatch_plan` would check before falling back to a
+    /// `RawPassthroughPlan` instead of erroring on an unrecognized model.
+    pub fn lookup(&self, provider: &str, name: &str) -> Option<ModelEntry> {
+        let guard = self.inner.read().ok()?;
+        guard
+            .models
+            .iter()
+            .find(|entry| entry.provider == provider && entry.name == name)
+            .cloned()
+    }
+
+    pub fn reload(&self, path: &Path) -> Result<(), ModelsConfigError> {
+        let config = load_models_config(path)?;
+        let mut guard = self
+            .inner
+            .write()
+            .map_err(|_| ModelsConfigError::Validation("models table lock poisoned".to_string()))?;
+        *guard = config;
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+//! Thin file-loading wrapper around [`gproxy_core::routes`]: reads
+//! `gproxy.toml` off disk, treating a missing file as "no overrides
+//! configured," and hands the parsed [`RoutesConfig`] to [`RouteTable`]. The
+//! route-matching types and the logic `proxy_handler` consults now live in
+//! `gproxy_core::routes`, the same split as `load_provider_rule` (here in
+//! `main.rs`) vs. `gproxy_core::rules::Rule::parse`.
+
+use std::path::Path;
+
+pub use gproxy_core::routes::{RouteOverride, RouteTable, RoutesConfig, RoutesConfigError};
+
+/// Loads and validates `gproxy.toml` at `path`. A missing file is not an
+/// error — it's treated as "no overrides configured" so operators who don't
+/// need this feature pay no startup cost — but a present-and-malformed file
+/// fails startup, per the request's "validation errors surfaced as startup
+/// failures".
+pub fn load_routes_config(path: &Path) -> Result<RoutesConfig, RoutesConfigError> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(RoutesConfig::default())
+        }
+        Err(err) => {
+            return Err(RoutesConfigError::Validation(format!(
+                "failed to read routes config: {err}"
+            )))
+        }
+    };
+    gproxy_core::routes::parse_routes_config(&raw)
+}
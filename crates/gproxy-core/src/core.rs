@@ -1,14 +1,32 @@
 use std::sync::{Arc, RwLock};
 
-use axum::routing::any;
+use axum::routing::{any, get};
 use axum::Router;
-use gproxy_provider_core::{Provider, SharedTrafficSink, NoopTrafficSink};
+use gproxy_provider_core::{
+    NoopTrafficSink, Provider, SharedTrafficSink, SharedTrafficTap, TrafficTap,
+};
+use gproxy_telemetry::ProxyMetrics;
 
 use crate::auth::AuthProvider;
+use crate::filter::ProxyFilter;
 use crate::handler::proxy_handler;
+use crate::routes::RouteTable;
+use crate::rules::Rule;
 
-pub type ProviderLookup =
-    Arc<dyn Fn(&str) -> Option<Arc<dyn Provider>> + Send + Sync>;
+// `debug_tap`/`metrics` would normally be declared alongside `core`/
+// `handler` in `lib.rs`, but this checkout doesn't have one; nesting them
+// here keeps them reachable without inventing that file's contents.
+pub mod debug_tap;
+pub mod filter;
+pub mod metrics;
+pub mod routes;
+pub mod rules;
+pub mod upgrade;
+
+use debug_tap::traffic_tap_handler;
+use metrics::metrics_handler;
+
+pub type ProviderLookup = Arc<dyn Fn(&str) -> Option<Arc<dyn Provider>> + Send + Sync>;
 
 pub struct CoreState {
     pub lookup: ProviderLookup,
@@ -16,6 +34,29 @@ pub struct CoreState {
     pub proxy: Arc<RwLock<Option<String>>>,
     pub traffic: SharedTrafficSink,
     pub provider_ids: Arc<RwLock<std::collections::HashMap<String, i64>>>,
+    /// Live per-chunk traffic tap, subscribed to by the `/debug/traffic-tap`
+    /// SSE endpoint. Independent of `traffic`, which only records once a
+    /// call completes.
+    pub live_tap: SharedTrafficTap,
+    /// Ordered chain run by `proxy_handler` around every provider call,
+    /// regardless of which provider is targeted. Empty by default, so the
+    /// common case pays no overhead.
+    pub filters: Vec<Arc<dyn ProxyFilter>>,
+    /// Shared with every `CallContext::metrics` handed to a provider call,
+    /// so the `/metrics` endpoint below gathers what the recording tasks
+    /// actually wrote instead of an ungathered registry of its own.
+    pub metrics: Arc<ProxyMetrics>,
+    /// Evaluated by `proxy_handler` before `(state.lookup)(provider)`; a
+    /// non-empty result overrides which provider the request is routed to.
+    /// `None` (the default) means no rule is configured and `proxy_handler`
+    /// skips straight to `lookup` as before. See [`crate::rules`].
+    pub provider_rule: Option<Arc<Rule>>,
+    /// Consulted by `proxy_handler` to inject/strip headers before
+    /// `provider_handle.call`, based on the matched route's `gproxy.toml`
+    /// entry. `None` (the default) means no routes config is configured and
+    /// `proxy_handler` skips straight to `classify_request` as before. See
+    /// [`crate::routes`].
+    pub routes: Option<RouteTable>,
 }
 
 pub struct Core {
@@ -29,6 +70,10 @@ impl Core {
         proxy: Arc<RwLock<Option<String>>>,
         traffic: Option<SharedTrafficSink>,
         provider_ids: Option<std::collections::HashMap<String, i64>>,
+        filters: Option<Vec<Arc<dyn ProxyFilter>>>,
+        metrics: Option<Arc<ProxyMetrics>>,
+        provider_rule: Option<Arc<Rule>>,
+        routes: Option<RouteTable>,
     ) -> Self {
         Self {
             state: Arc::new(CoreState {
@@ -37,6 +82,11 @@ impl Core {
                 proxy,
                 traffic: traffic.unwrap_or_else(|| Arc::new(NoopTrafficSink)),
                 provider_ids: Arc::new(RwLock::new(provider_ids.unwrap_or_default())),
+                live_tap: Arc::new(TrafficTap::default()),
+                filters: filters.unwrap_or_default(),
+                metrics: metrics.unwrap_or_default(),
+                provider_rule,
+                routes,
             }),
         }
     }
@@ -44,6 +94,8 @@ impl Core {
     pub fn router(&self) -> Router {
         Router::new()
             .route("/{provider}/{*path}", any(proxy_handler))
+            .route("/debug/traffic-tap", get(traffic_tap_handler))
+            .route("/metrics", get(metrics_handler))
             .with_state(self.state.clone())
     }
 
@@ -0,0 +1,88 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::stream::{unfold, Stream};
+use gproxy_provider_core::LiveTrafficChunk;
+use serde::Deserialize;
+use tokio::sync::broadcast;
+
+use crate::core::CoreState;
+
+/// Optional filters for the live traffic tap SSE endpoint: a debugging UI
+/// can narrow the firehose down to one in-flight request (`trace_id`) or
+/// one upstream (`provider`) instead of watching every proxied call.
+#[derive(Debug, Deserialize)]
+pub struct TrafficTapQuery {
+    pub trace_id: Option<String>,
+    pub provider: Option<String>,
+}
+
+fn matches_filter(chunk: &LiveTrafficChunk, query: &TrafficTapQuery) -> bool {
+    if let Some(trace_id) = &query.trace_id {
+        if &chunk.trace_id != trace_id {
+            return false;
+        }
+    }
+    if let Some(provider) = &query.provider {
+        if &chunk.provider != provider {
+            return false;
+        }
+    }
+    true
+}
+
+/// Streams [`LiveTrafficChunk`]s from `CoreState::live_tap` as they're
+/// published, so a debugging UI can attach to a running proxy and watch
+/// individual upstream/downstream events instead of only seeing totals once
+/// a call finishes.
+pub async fn traffic_tap_handler(
+    State(state): State<Arc<CoreState>>,
+    Query(query): Query<TrafficTapQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = state.live_tap.subscribe();
+    let stream = unfold((receiver, query), |(mut receiver, query)| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(chunk) if matches_filter(&chunk, &query) => {
+                    let Some(json) = serde_json::to_string(&TrafficTapEvent::from(chunk)).ok()
+                    else {
+                        continue;
+                    };
+                    return Some((Ok(Event::default().data(json)), (receiver, query)));
+                }
+                // Filtered out, or the tap lagged and dropped some chunks:
+                // either way just keep listening for the next one.
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+}
+
+#[derive(Debug, serde::Serialize)]
+struct TrafficTapEvent {
+    trace_id: String,
+    provider: String,
+    direction: &'static str,
+    timestamp_ms: i64,
+    data: String,
+}
+
+impl From<LiveTrafficChunk> for TrafficTapEvent {
+    fn from(chunk: LiveTrafficChunk) -> Self {
+        Self {
+            trace_id: chunk.trace_id,
+            provider: chunk.provider,
+            direction: match chunk.direction {
+                gproxy_provider_core::TrafficDirection::Upstream => "upstream",
+                gproxy_provider_core::TrafficDirection::Downstream => "downstream",
+            },
+            timestamp_ms: chunk.timestamp_ms,
+            data: chunk.data,
+        }
+    }
+}
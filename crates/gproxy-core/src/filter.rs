@@ -0,0 +1,147 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::http::{HeaderMap, Method};
+use bytes::Bytes;
+use futures_util::stream::{self, Stream};
+use gproxy_provider_core::CallContext;
+
+use crate::error::ProxyError;
+
+/// A boxed chunk stream, the same shape `StreamBody::stream` uses, so a
+/// filter can hand back either the original stream or a wholly different one
+/// without the caller needing to know which.
+pub type BoxBodyStream = Pin<Box<dyn Stream<Item = Result<Bytes, axum::Error>> + Send>>;
+
+/// Request metadata handed to a [`ProxyFilter`] alongside the body, so it can
+/// make a decision without reaching into axum's extractors itself.
+pub struct FilterRequestParts<'a> {
+    pub provider: &'a str,
+    pub method: &'a Method,
+    pub path: &'a str,
+    pub headers: &'a HeaderMap,
+}
+
+/// What a filter wants done with a body it was handed, buffered or
+/// streamed. `PassThrough` is the common case and costs nothing extra.
+pub enum FilteredBody {
+    PassThrough,
+    Replace(Bytes),
+    Stream(BoxBodyStream),
+}
+
+/// A policy hook `proxy_handler` consults before a request body goes
+/// upstream and before a response body is returned to the client — e.g. PII
+/// redaction, prompt-injection guardrails, or request-size caps. Filters
+/// form an ordered chain on [`CoreState`](crate::core::CoreState) and run
+/// for every provider, unlike `gproxy_provider_core::ProxyInterceptor`,
+/// which a provider opts a single call into via `CallContext` and which only
+/// ever sees already-classified per-protocol requests.
+///
+/// For a `ProxyResponse::Stream`, `on_response` is handed a
+/// [`FilteredBody::Stream`] wrapping the live SSE chunks rather than the
+/// accumulated body, so a filter can rewrite or reject on the fly without
+/// buffering the whole response; a filter that touches a streamed response
+/// must hand a `Stream` or `Replace` back, since there is no buffered body
+/// to fall back to for `PassThrough`.
+#[async_trait]
+pub trait ProxyFilter: Send + Sync {
+    async fn on_request(
+        &self,
+        ctx: &CallContext,
+        parts: &FilterRequestParts<'_>,
+        body: Bytes,
+    ) -> Result<FilteredBody, ProxyError> {
+        let _ = (ctx, parts, body);
+        Ok(FilteredBody::PassThrough)
+    }
+
+    async fn on_response(
+        &self,
+        ctx: &CallContext,
+        parts: &FilterRequestParts<'_>,
+        body: FilteredBody,
+    ) -> Result<FilteredBody, ProxyError> {
+        let _ = (ctx, parts);
+        Ok(body)
+    }
+}
+
+/// Runs a request body through the ordered filter chain, threading each
+/// filter's output into the next. A filter returning `Stream` here is a
+/// contract violation — the request body is already fully buffered by the
+/// time `proxy_handler` calls this — and is surfaced as an internal error.
+pub async fn run_request_filters(
+    filters: &[Arc<dyn ProxyFilter>],
+    ctx: &CallContext,
+    parts: &FilterRequestParts<'_>,
+    body: Bytes,
+) -> Result<Bytes, ProxyError> {
+    let mut current = body;
+    for filter in filters {
+        current = match filter.on_request(ctx, parts, current.clone()).await? {
+            FilteredBody::PassThrough => current,
+            FilteredBody::Replace(replacement) => replacement,
+            FilteredBody::Stream(_) => {
+                return Err(ProxyError::internal(
+                    "filter returned a stream for a buffered request body",
+                ))
+            }
+        };
+    }
+    Ok(current)
+}
+
+/// Runs a `ProxyResponse::Json` body through the filter chain.
+pub async fn run_buffered_response_filters(
+    filters: &[Arc<dyn ProxyFilter>],
+    ctx: &CallContext,
+    parts: &FilterRequestParts<'_>,
+    body: Bytes,
+) -> Result<Bytes, ProxyError> {
+    let mut current = body;
+    for filter in filters {
+        current = match filter
+            .on_response(ctx, parts, FilteredBody::Replace(current.clone()))
+            .await?
+        {
+            FilteredBody::PassThrough => current,
+            FilteredBody::Replace(replacement) => replacement,
+            FilteredBody::Stream(_) => {
+                return Err(ProxyError::internal(
+                    "filter returned a stream for a buffered response body",
+                ))
+            }
+        };
+    }
+    Ok(current)
+}
+
+/// Runs a `ProxyResponse::Stream` body through the filter chain. Each filter
+/// sees the current stream and may pass it through untouched, replace it
+/// with a single buffered chunk, or hand back a transformed stream — e.g.
+/// one that rewrites or drops individual SSE events as they flow.
+pub async fn run_streamed_response_filters(
+    filters: &[Arc<dyn ProxyFilter>],
+    ctx: &CallContext,
+    parts: &FilterRequestParts<'_>,
+    stream: BoxBodyStream,
+) -> Result<BoxBodyStream, ProxyError> {
+    let mut current = stream;
+    for filter in filters {
+        current = match filter
+            .on_response(ctx, parts, FilteredBody::Stream(current))
+            .await?
+        {
+            FilteredBody::Stream(replacement) => replacement,
+            FilteredBody::Replace(bytes) => Box::pin(stream::once(async move { Ok(bytes) })),
+            FilteredBody::PassThrough => {
+                return Err(ProxyError::internal(
+                    "filter returned PassThrough instead of handing its Stream body back",
+                ))
+            }
+        };
+    }
+    Ok(current)
+}
@@ -2,16 +2,24 @@ use std::sync::Arc;
 
 use axum::body::Body;
 use axum::extract::{Path, State};
-use axum::http::{HeaderMap, HeaderValue, Method, Uri};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, Uri};
 use axum::response::Response;
 use bytes::Bytes;
+use futures_util::stream::{self, Stream, StreamExt};
 use gproxy_provider_core::{CallContext, ProxyResponse, UpstreamPassthroughError};
 use http::header::CONTENT_TYPE;
+use tokio_util::sync::CancellationToken;
 
 use crate::auth::AuthError;
 use crate::classify::classify_request;
 use crate::core::CoreState;
 use crate::error::ProxyError;
+use crate::filter::{
+    run_buffered_response_filters, run_request_filters, run_streamed_response_filters,
+    FilterRequestParts,
+};
+use crate::routes::RouteOverride;
+use crate::rules::{Context, Value};
 
 pub async fn proxy_handler(
     State(state): State<Arc<CoreState>>,
@@ -21,7 +29,9 @@ pub async fn proxy_handler(
     uri: Uri,
     body: Bytes,
 ) -> Response {
-    let Some(provider_handle) = (state.lookup)(provider.as_str()) else {
+    let routed_provider = resolve_provider_rule(&state, &provider, &method, &path, &headers);
+
+    let Some(provider_handle) = (state.lookup)(routed_provider.as_str()) else {
         return error_response(ProxyError::not_found("unknown provider"));
     };
 
@@ -30,37 +40,69 @@ pub async fn proxy_handler(
         Err(err) => return auth_error_response(err),
     };
 
+    let cancellation = CancellationToken::new();
+    let ctx = CallContext {
+        request_id: request_id(&headers),
+        user_id: auth_ctx.user_id,
+        user_key_id: auth_ctx.key_id,
+        proxy: state.proxy.read().ok().and_then(|guard| guard.clone()),
+        cancellation: cancellation.clone(),
+        ..CallContext::default()
+    };
+
+    let filter_parts = FilterRequestParts {
+        provider: routed_provider.as_str(),
+        method: &method,
+        path: &path,
+        headers: &headers,
+    };
+
+    let body = match run_request_filters(&state.filters, &ctx, &filter_parts, body).await {
+        Ok(body) => body,
+        Err(err) => return error_response(err),
+    };
+
+    let route_override = state
+        .routes
+        .as_ref()
+        .and_then(|routes| routes.match_route(&path));
+    let upstream_headers = apply_route_header_overrides(&headers, route_override.as_ref());
+
     let classified = match classify_request(
         &method,
         &path,
         uri.query(),
-        &headers,
+        &upstream_headers,
         body,
     ) {
         Ok(req) => req,
         Err(err) => return error_response(err),
     };
 
-    let ctx = CallContext {
-        request_id: request_id(&headers),
-        user_id: auth_ctx.user_id,
-        user_key_id: auth_ctx.key_id,
-        proxy: state.proxy.read().ok().and_then(|guard| guard.clone()),
-    };
-
-    match provider_handle.call(classified.request, ctx).await {
-        Ok(response) => proxy_response(response),
+    match provider_handle.call(classified.request, ctx.clone()).await {
+        Ok(response) => proxy_response(response, &state.filters, &ctx, &filter_parts, cancellation).await,
         Err(err) => passthrough_error(err),
     }
 }
 
-fn proxy_response(response: ProxyResponse) -> Response {
+async fn proxy_response(
+    response: ProxyResponse,
+    filters: &[Arc<dyn crate::filter::ProxyFilter>],
+    ctx: &CallContext,
+    filter_parts: &FilterRequestParts<'_>,
+    cancellation: CancellationToken,
+) -> Response {
     match response {
         ProxyResponse::Json {
             status,
             headers,
             body,
         } => {
+            let body = match run_buffered_response_filters(filters, ctx, filter_parts, body).await
+            {
+                Ok(body) => body,
+                Err(err) => return error_response(err),
+            };
             let mut resp = Response::new(Body::from(body));
             *resp.status_mut() = status;
             resp.headers_mut().extend(headers);
@@ -71,7 +113,13 @@ fn proxy_response(response: ProxyResponse) -> Response {
             headers,
             body,
         } => {
-            let mut resp = Response::new(Body::from_stream(body.stream));
+            let stream = match run_streamed_response_filters(filters, ctx, filter_parts, body.stream)
+                .await
+            {
+                Ok(stream) => stream,
+                Err(err) => return error_response(err),
+            };
+            let mut resp = Response::new(Body::from_stream(cancel_on_drop(stream, cancellation)));
             *resp.status_mut() = status;
             resp.headers_mut().extend(headers);
             if !resp.headers().contains_key(CONTENT_TYPE) {
@@ -83,6 +131,26 @@ fn proxy_response(response: ProxyResponse) -> Response {
     }
 }
 
+/// Trips `token` once the wrapped stream is dropped, so a client that hangs
+/// up mid-response (axum drops the body future) signals the provider layer
+/// to stop pulling from upstream instead of streaming into the void.
+fn cancel_on_drop<S>(stream: S, token: CancellationToken) -> impl Stream<Item = S::Item>
+where
+    S: Stream + Unpin,
+{
+    struct CancelGuard(CancellationToken);
+
+    impl Drop for CancelGuard {
+        fn drop(&mut self) {
+            self.0.cancel();
+        }
+    }
+
+    stream::unfold((stream, CancelGuard(token)), |(mut stream, guard)| async move {
+        stream.next().await.map(|item| (item, (stream, guard)))
+    })
+}
+
 fn passthrough_error(err: UpstreamPassthroughError) -> Response {
     let mut resp = Response::new(Body::from(err.body));
     *resp.status_mut() = err.status;
@@ -103,6 +171,65 @@ fn auth_error_response(err: AuthError) -> Response {
     resp
 }
 
+/// Evaluates `state.provider_rule` (if any) against the request's path,
+/// method, headers, and originally-targeted provider, returning the
+/// provider name `(state.lookup)` should actually use. A rule whose result
+/// is an empty string, or no rule at all, leaves the path's `{provider}`
+/// segment unchanged.
+fn resolve_provider_rule(
+    state: &CoreState,
+    provider: &str,
+    method: &Method,
+    path: &str,
+    headers: &HeaderMap,
+) -> String {
+    let Some(rule) = &state.provider_rule else {
+        return provider.to_string();
+    };
+    let mut context = Context::new();
+    context.set("provider", Value::String(provider.to_string()));
+    context.set("method", Value::String(method.as_str().to_string()));
+    context.set("path", Value::String(path.to_string()));
+    for (name, value) in headers {
+        if let Ok(value) = value.to_str() {
+            context.set(format!("header.{}", name.as_str()), Value::String(value.to_string()));
+        }
+    }
+    match rule.evaluate(&context) {
+        Value::String(s) if !s.is_empty() => s,
+        _ => provider.to_string(),
+    }
+}
+
+/// Applies a matched route's `inject_headers`/`strip_headers` to a clone of
+/// the inbound headers before `classify_request` builds the upstream-bound
+/// request. Only this clone is affected — the original `headers` used above
+/// for auth, the request ID, and the filter chain are left untouched, since
+/// a route's header rewrite is about what goes to the upstream, not how the
+/// downstream caller is treated. `backend_base_url`, `auth_scheme`,
+/// `timeout_ms`, and `failover_keys` aren't applied here; see
+/// `crate::routes`'s module doc for why.
+fn apply_route_header_overrides(headers: &HeaderMap, route: Option<&RouteOverride>) -> HeaderMap {
+    let Some(route) = route else {
+        return headers.clone();
+    };
+    let mut headers = headers.clone();
+    for name in &route.strip_headers {
+        if let Ok(name) = HeaderName::from_bytes(name.as_bytes()) {
+            headers.remove(name);
+        }
+    }
+    for (name, value) in &route.inject_headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
 fn request_id(headers: &HeaderMap) -> Option<String> {
     headers
         .get("x-request-id")
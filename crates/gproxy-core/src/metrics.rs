@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+
+use crate::core::CoreState;
+
+/// Serves `CoreState::metrics` in Prometheus text exposition format, so a
+/// Prometheus server can scrape this proxy directly instead of an operator
+/// having to derive counters from the per-event traffic logs.
+pub async fn metrics_handler(State(state): State<Arc<CoreState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
@@ -0,0 +1,158 @@
+//! Declarative route overrides loaded from a `gproxy.toml`, layered on top of
+//! the DB-backed provider registry rather than replacing it:
+//! [`crate::core::CoreState::lookup`] still resolves a `Provider` by name
+//! from `ProviderRegistry`, but a matched [`RouteOverride`] lets
+//! [`crate::handler::proxy_handler`] inject/strip headers before
+//! `provider_handle.call` runs. Parsing lives here and takes a `&str` rather
+//! than a path, the same split as [`crate::rules::Rule::parse`] vs.
+//! `load_provider_rule` in `apps/gproxy`'s `main.rs`: reading `gproxy.toml`
+//! off disk, treating a missing file as "no overrides configured", and
+//! wiring the `/admin/routes/reload` endpoint are `apps/gproxy`'s job.
+//!
+//! Redirecting the actual upstream target (`backend_base_url`), overriding
+//! the auth scheme, applying `timeout_ms`, and restricting failover to
+//! `failover_keys` are not wired up yet — doing so means `provider_handle
+//! .call` accepting an override, which needs a field on `ProxyRequest`/
+//! `CallContext` that doesn't exist today. That's a larger, separate change,
+//! the same kind of gap [`crate::rules`]'s module doc calls out for
+//! credential-tag selection; header inject/strip is the piece that's
+//! mechanically wireable without it, so that's what [`RouteTable::match_route`]
+//! is consulted for today.
+//!
+//! Hot-reload mirrors the existing `state.proxy: Arc<RwLock<Option<String>>>`
+//! pattern: the parsed config lives behind an `RwLock`, an admin-triggered
+//! reload swaps it out via [`RouteTable::set`], and `proxy_handler` takes a
+//! short-lived read guard per request.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use serde::Deserialize;
+
+/// One entry in `gproxy.toml`'s `[[route]]` list. `pattern` is an axum-style
+/// path template, e.g. `/v1/:provider/*path`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouteOverride {
+    pub pattern: String,
+    pub backend_base_url: Option<String>,
+    pub auth_scheme: Option<String>,
+    #[serde(default)]
+    pub inject_headers: HashMap<String, String>,
+    #[serde(default)]
+    pub strip_headers: Vec<String>,
+    pub timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub failover_keys: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct RoutesConfig {
+    #[serde(default, rename = "route")]
+    pub routes: Vec<RouteOverride>,
+}
+
+#[derive(Debug)]
+pub enum RoutesConfigError {
+    Parse(toml::de::Error),
+    Validation(String),
+}
+
+impl fmt::Display for RoutesConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoutesConfigError::Parse(err) => write!(f, "failed to parse routes config: {err}"),
+            RoutesConfigError::Validation(msg) => write!(f, "invalid routes config: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for RoutesConfigError {}
+
+/// Parses and validates an already-read `gproxy.toml` document. Reading the
+/// file itself is `apps/gproxy::routes_config::load_routes_config`'s job.
+pub fn parse_routes_config(src: &str) -> Result<RoutesConfig, RoutesConfigError> {
+    let config: RoutesConfig = toml::from_str(src).map_err(RoutesConfigError::Parse)?;
+    validate(&config)?;
+    Ok(config)
+}
+
+fn validate(config: &RoutesConfig) -> Result<(), RoutesConfigError> {
+    for route in &config.routes {
+        if !route.pattern.starts_with('/') {
+            return Err(RoutesConfigError::Validation(format!(
+                "route pattern {:?} must start with '/'",
+                route.pattern
+            )));
+        }
+        if let Some(base_url) = &route.backend_base_url {
+            if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+                return Err(RoutesConfigError::Validation(format!(
+                    "route {:?} backend_base_url {:?} must be an http(s) URL",
+                    route.pattern, base_url
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits an axum-style pattern into its literal segments and the names of
+/// any `:param`/`*wildcard` segments, matched positionally against the
+/// request path's segments in [`RouteTable::match_route`].
+fn segments(pattern: &str) -> Vec<&str> {
+    pattern.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let pattern_segments = segments(pattern);
+    let path_segments = segments(path);
+    for (i, seg) in pattern_segments.iter().enumerate() {
+        if let Some(stripped) = seg.strip_prefix('*') {
+            let _ = stripped;
+            return true;
+        }
+        if seg.starts_with(':') {
+            if path_segments.get(i).is_none() {
+                return false;
+            }
+            continue;
+        }
+        if path_segments.get(i) != Some(seg) {
+            return false;
+        }
+    }
+    pattern_segments.len() == path_segments.len()
+}
+
+/// Hot-reloadable holder for a [`RoutesConfig`], shared across the app the
+/// same way `CoreState.proxy` shares the upstream proxy override.
+#[derive(Clone)]
+pub struct RouteTable {
+    inner: Arc<RwLock<RoutesConfig>>,
+}
+
+impl RouteTable {
+    pub fn new(config: RoutesConfig) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(config)),
+        }
+    }
+
+    /// First route whose pattern matches `path`, in config order.
+    pub fn match_route(&self, path: &str) -> Option<RouteOverride> {
+        let guard = self.inner.read().ok()?;
+        guard
+            .routes
+            .iter()
+            .find(|route| pattern_matches(&route.pattern, path))
+            .cloned()
+    }
+
+    /// Swaps in a freshly loaded config, e.g. from `/admin/routes/reload`.
+    pub fn set(&self, config: RoutesConfig) {
+        if let Ok(mut guard) = self.inner.write() {
+            *guard = config;
+        }
+    }
+}
@@ -0,0 +1,803 @@
+//! A small typed expression language for conditional request routing.
+//!
+//! Operators configure rules as plain strings to pick a target provider
+//! based on request attributes, the same shape as expression-driven mail
+//! routing without hardcoding the logic in Rust. [`crate::handler::proxy_handler`]
+//! evaluates [`CoreState::provider_rule`](crate::core::CoreState::provider_rule)
+//! (when set) against a [`Context`] built from the request's path, method,
+//! headers, and the originally-targeted provider name, before
+//! `(state.lookup)(provider)` runs: a non-empty result string is used as the
+//! lookup name instead of the path's `{provider}` segment, so a rule can
+//! redirect a request to a different provider. Credential-tag selection and
+//! model rewriting from a rule result are not wired up — both mean deciding
+//! how `BaseCredential` tag selection and each provider's `handle_*` model
+//! rewriting (today scattered per-provider) would consume a second
+//! [`Value`] out of the same evaluation, which is a larger, separate change.
+//! Persisting rule strings in the relational config so they hot-reload the
+//! way provider pools and auth do is also not wired up yet: `CoreState`
+//! takes its rule at construction, the same way `filters` does.
+//!
+//! Grammar, precedence lowest to highest: `||` < `&&` < comparison
+//! (`==`/`!=`/`=~`) < concatenation (`+`) < primary (literals, identifiers,
+//! function calls, parenthesized expressions).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use regex::Regex;
+
+/// A runtime value. Lists only arise from built-ins like [`split`]; there's
+/// no list literal syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Int(i64),
+    Bool(bool),
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// Truthiness used by `&&`/`||`/if-branch selection: empty string,
+    /// zero, `false`, and an empty list are falsy; everything else is
+    /// truthy. Never panics, so a misused value just routes to the default
+    /// branch instead of crashing the request.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::String(s) => !s.is_empty(),
+            Value::Int(n) => *n != 0,
+            Value::Bool(b) => *b,
+            Value::List(items) => !items.is_empty(),
+        }
+    }
+
+    fn as_str_lossy(&self) -> String {
+        match self {
+            Value::String(s) => s.clone(),
+            Value::Int(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::List(items) => items
+                .iter()
+                .map(Value::as_str_lossy)
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.as_str_lossy())
+    }
+}
+
+/// Variables available to a rule: path, model, header values, body size,
+/// authenticated user, and whatever else a caller chooses to populate.
+/// [`Context::get`] resolves a missing name to an empty string rather than
+/// an error, per the "total evaluation" invariant.
+#[derive(Debug, Default, Clone)]
+pub struct Context {
+    vars: HashMap<String, Value>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: Value) -> &mut Self {
+        self.vars.insert(name.into(), value);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Value {
+        self.vars
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| Value::String(String::new()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinOp {
+    Eq,
+    Ne,
+    And,
+    Or,
+    RegexMatch,
+    Concat,
+}
+
+/// A parsed expression. Built by [`parse`], walked by [`Evaluator::eval`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Variable(String),
+    Literal(Value),
+    BinaryOp {
+        op_display: &'static str,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    FnCall {
+        name: String,
+        args: Vec<Expr>,
+    },
+    If {
+        cond: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Box<Expr>,
+    },
+}
+
+/// An error raised while tokenizing or parsing a rule string. Evaluation
+/// itself never errors — see the module doc's "total" invariant — so this
+/// only ever surfaces at rule-compile time (when an operator loads the rule
+/// set), not per-request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleError(pub String);
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rule error: {}", self.0)
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Int(i64),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, RuleError> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '+' => {
+                tokens.push(Token::Op("+"));
+                i += 1;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some(ch) if *ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some('\\') if chars.get(i + 1).is_some() => {
+                            s.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(ch) => {
+                            s.push(*ch);
+                            i += 1;
+                        }
+                        None => return Err(RuleError("unterminated string literal".to_string())),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            '=' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op("=="));
+                i += 2;
+            }
+            '=' if chars.get(i + 1) == Some(&'~') => {
+                tokens.push(Token::Op("=~"));
+                i += 2;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op("!="));
+                i += 2;
+            }
+            '&' if chars.get(i + 1) == Some(&'&') => {
+                tokens.push(Token::Op("&&"));
+                i += 2;
+            }
+            '|' if chars.get(i + 1) == Some(&'|') => {
+                tokens.push(Token::Op("||"));
+                i += 2;
+            }
+            _ if c.is_ascii_digit() => {
+                let start = i;
+                while chars.get(i).is_some_and(|ch| ch.is_ascii_digit()) {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let value = text
+                    .parse::<i64>()
+                    .map_err(|err| RuleError(format!("invalid integer literal {text:?}: {err}")))?;
+                tokens.push(Token::Int(value));
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while chars
+                    .get(i)
+                    .is_some_and(|ch| ch.is_alphanumeric() || *ch == '_' || *ch == '.')
+                {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Ident(text));
+            }
+            other => {
+                return Err(RuleError(format!("unexpected character {other:?}")));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect_op(&mut self, op: &'static str) -> Result<(), RuleError> {
+        match self.advance() {
+            Some(Token::Op(found)) if found == op => Ok(()),
+            other => Err(RuleError(format!("expected operator {op:?}, found {other:?}"))),
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, RuleError> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, RuleError> {
+        let mut lhs = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Op("||"))) {
+            self.advance();
+            let rhs = self.parse_and()?;
+            lhs = binary(BinOp::Or, lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, RuleError> {
+        let mut lhs = self.parse_comparison()?;
+        while matches!(self.peek(), Some(Token::Op("&&"))) {
+            self.advance();
+            let rhs = self.parse_comparison()?;
+            lhs = binary(BinOp::And, lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, RuleError> {
+        let lhs = self.parse_concat()?;
+        let op = match self.peek() {
+            Some(Token::Op("==")) => Some(BinOp::Eq),
+            Some(Token::Op("!=")) => Some(BinOp::Ne),
+            Some(Token::Op("=~")) => Some(BinOp::RegexMatch),
+            _ => None,
+        };
+        let Some(op) = op else { return Ok(lhs) };
+        self.advance();
+        let rhs = self.parse_concat()?;
+        Ok(binary(op, lhs, rhs))
+    }
+
+    fn parse_concat(&mut self) -> Result<Expr, RuleError> {
+        let mut lhs = self.parse_primary()?;
+        while matches!(self.peek(), Some(Token::Op("+"))) {
+            self.advance();
+            let rhs = self.parse_primary()?;
+            lhs = binary(BinOp::Concat, lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, RuleError> {
+        match self.advance() {
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::String(s))),
+            Some(Token::Int(n)) => Ok(Expr::Literal(Value::Int(n))),
+            Some(Token::LParen) => {
+                let expr = self.parse_expr()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(expr),
+                    other => Err(RuleError(format!("expected ')', found {other:?}"))),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                if name == "true" {
+                    return Ok(Expr::Literal(Value::Bool(true)));
+                }
+                if name == "false" {
+                    return Ok(Expr::Literal(Value::Bool(false)));
+                }
+                if name == "if" {
+                    return self.parse_if();
+                }
+                if matches!(self.peek(), Some(Token::LParen)) {
+                    self.advance();
+                    let mut args = Vec::new();
+                    if !matches!(self.peek(), Some(Token::RParen)) {
+                        loop {
+                            args.push(self.parse_expr()?);
+                            match self.peek() {
+                                Some(Token::Comma) => {
+                                    self.advance();
+                                }
+                                _ => break,
+                            }
+                        }
+                    }
+                    self.expect_rparen()?;
+                    return Ok(Expr::FnCall { name, args });
+                }
+                Ok(Expr::Variable(name))
+            }
+            other => Err(RuleError(format!("unexpected token {other:?}"))),
+        }
+    }
+
+    /// `if(cond, then, else)` as a function-call-shaped in-expression
+    /// conditional, distinct from a [`Rule`]'s top-level if-block list.
+    fn parse_if(&mut self) -> Result<Expr, RuleError> {
+        match self.advance() {
+            Some(Token::LParen) => {}
+            other => return Err(RuleError(format!("expected '(' after if, found {other:?}"))),
+        }
+        let cond = self.parse_expr()?;
+        self.expect_comma()?;
+        let then_branch = self.parse_expr()?;
+        self.expect_comma()?;
+        let else_branch = self.parse_expr()?;
+        self.expect_rparen()?;
+        Ok(Expr::If {
+            cond: Box::new(cond),
+            then_branch: Box::new(then_branch),
+            else_branch: Box::new(else_branch),
+        })
+    }
+
+    fn expect_comma(&mut self) -> Result<(), RuleError> {
+        match self.advance() {
+            Some(Token::Comma) => Ok(()),
+            other => Err(RuleError(format!("expected ',', found {other:?}"))),
+        }
+    }
+
+    fn expect_rparen(&mut self) -> Result<(), RuleError> {
+        match self.advance() {
+            Some(Token::RParen) => Ok(()),
+            other => Err(RuleError(format!("expected ')', found {other:?}"))),
+        }
+    }
+}
+
+fn binary(op: BinOp, lhs: Expr, rhs: Expr) -> Expr {
+    let op_display = match op {
+        BinOp::Eq => "==",
+        BinOp::Ne => "!=",
+        BinOp::And => "&&",
+        BinOp::Or => "||",
+        BinOp::RegexMatch => "=~",
+        BinOp::Concat => "+",
+    };
+    Expr::BinaryOp {
+        op_display,
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
+
+fn op_from_display(op_display: &str) -> BinOp {
+    match op_display {
+        "==" => BinOp::Eq,
+        "!=" => BinOp::Ne,
+        "&&" => BinOp::And,
+        "||" => BinOp::Or,
+        "=~" => BinOp::RegexMatch,
+        "+" => BinOp::Concat,
+        other => unreachable!("unknown binary operator {other:?}"),
+    }
+}
+
+/// Parses a single expression (e.g. one rule condition or result). Does not
+/// consume trailing tokens beyond the expression — callers that expect the
+/// whole input to be one expression should check [`tokenize`]'s output is
+/// fully consumed, which [`parse_full`] does.
+pub fn parse(src: &str) -> Result<Expr, RuleError> {
+    let tokens = tokenize(src)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    parser.parse_expr()
+}
+
+/// Like [`parse`], but errors if trailing tokens remain after the
+/// expression — the form rule authoring should use so a typo like
+/// `model == "a" "b"` is rejected at compile time instead of silently
+/// ignoring `"b"`.
+pub fn parse_full(src: &str) -> Result<Expr, RuleError> {
+    let tokens = tokenize(src)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_expr()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(RuleError(format!(
+            "unexpected trailing input at token {}",
+            parser.pos
+        )));
+    }
+    Ok(expr)
+}
+
+/// Caches compiled regexes for the `=~` operator, keyed by pattern string,
+/// so a hot path never recompiles the same pattern twice. One cache is
+/// shared by all evaluations of a given [`Rule`] (see [`Rule::regex_cache`]).
+#[derive(Default)]
+struct RegexCache {
+    compiled: Mutex<HashMap<String, Regex>>,
+}
+
+impl RegexCache {
+    fn is_match(&self, pattern: &str, subject: &str) -> bool {
+        let mut compiled = match self.compiled.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some(regex) = compiled.get(pattern) {
+            return regex.is_match(subject);
+        }
+        match Regex::new(pattern) {
+            Ok(regex) => {
+                let matched = regex.is_match(subject);
+                compiled.insert(pattern.to_string(), regex);
+                matched
+            }
+            // An invalid pattern is a rule-authoring mistake, not a crash:
+            // evaluation stays total and just treats it as no match.
+            Err(_) => false,
+        }
+    }
+}
+
+fn eval_fn_call(name: &str, args: &[Value]) -> Value {
+    match (name, args) {
+        ("lower", [Value::String(s)]) => Value::String(s.to_lowercase()),
+        ("lower", [other]) => Value::String(other.as_str_lossy().to_lowercase()),
+        ("contains", [haystack, needle]) => {
+            Value::Bool(haystack.as_str_lossy().contains(&needle.as_str_lossy()))
+        }
+        ("starts_with", [haystack, prefix]) => {
+            Value::Bool(haystack.as_str_lossy().starts_with(&prefix.as_str_lossy()))
+        }
+        ("split", [subject, separator]) => {
+            let separator = separator.as_str_lossy();
+            let parts = if separator.is_empty() {
+                vec![subject.as_str_lossy()]
+            } else {
+                subject
+                    .as_str_lossy()
+                    .split(separator.as_str())
+                    .map(|part| part.to_string())
+                    .collect()
+            };
+            Value::List(parts.into_iter().map(Value::String).collect())
+        }
+        // Wrong arity or unknown built-in: total evaluation means this
+        // resolves to an empty string rather than erroring at request time.
+        _ => Value::String(String::new()),
+    }
+}
+
+/// Evaluates a parsed [`Expr`] against a [`Context`], backed by a per-rule
+/// [`RegexCache`]. Never panics and never fails: unresolvable variables,
+/// unknown functions, and invalid regex patterns all just resolve to a
+/// value rather than raising an error.
+pub struct Evaluator<'a> {
+    context: &'a Context,
+    regex_cache: &'a RegexCache,
+}
+
+impl<'a> Evaluator<'a> {
+    fn eval(&self, expr: &Expr) -> Value {
+        match expr {
+            Expr::Variable(name) => self.context.get(name),
+            Expr::Literal(value) => value.clone(),
+            Expr::BinaryOp {
+                op_display,
+                lhs,
+                rhs,
+            } => self.eval_binary(op_from_display(op_display), lhs, rhs),
+            Expr::FnCall { name, args } => {
+                let values: Vec<Value> = args.iter().map(|arg| self.eval(arg)).collect();
+                eval_fn_call(name, &values)
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                if self.eval(cond).is_truthy() {
+                    self.eval(then_branch)
+                } else {
+                    self.eval(else_branch)
+                }
+            }
+        }
+    }
+
+    fn eval_binary(&self, op: BinOp, lhs: &Expr, rhs: &Expr) -> Value {
+        match op {
+            BinOp::And => {
+                let lhs = self.eval(lhs);
+                if !lhs.is_truthy() {
+                    return Value::Bool(false);
+                }
+                Value::Bool(self.eval(rhs).is_truthy())
+            }
+            BinOp::Or => {
+                let lhs = self.eval(lhs);
+                if lhs.is_truthy() {
+                    return Value::Bool(true);
+                }
+                Value::Bool(self.eval(rhs).is_truthy())
+            }
+            BinOp::Eq => Value::Bool(self.eval(lhs) == self.eval(rhs)),
+            BinOp::Ne => Value::Bool(self.eval(lhs) != self.eval(rhs)),
+            BinOp::Concat => {
+                let lhs = self.eval(lhs).as_str_lossy();
+                let rhs = self.eval(rhs).as_str_lossy();
+                Value::String(lhs + &rhs)
+            }
+            BinOp::RegexMatch => {
+                let subject = self.eval(lhs).as_str_lossy();
+                let pattern = self.eval(rhs).as_str_lossy();
+                Value::Bool(self.regex_cache.is_match(&pattern, &subject))
+            }
+        }
+    }
+}
+
+/// One operator-authored routing rule: an ordered if-block (first truthy
+/// condition wins) plus a default. `condition` is `None` for the default
+/// entry, matching the request's "list of `(condition_expr, result_value)`
+/// pairs plus a default" shape while keeping both in one `Vec` to preserve
+/// evaluation order.
+pub struct Rule {
+    branches: Vec<(Option<Expr>, Expr)>,
+    regex_cache: RegexCache,
+}
+
+impl Rule {
+    /// Builds a rule from already-parsed branches. The last branch's
+    /// condition is ignored if present — `evaluate` treats it as the
+    /// default regardless — so callers should pass `None` for it.
+    pub fn new(branches: Vec<(Option<Expr>, Expr)>) -> Result<Self, RuleError> {
+        if branches.is_empty() {
+            return Err(RuleError("rule must have at least a default branch".to_string()));
+        }
+        Ok(Self {
+            branches,
+            regex_cache: RegexCache::default(),
+        })
+    }
+
+    /// Parses a rule from its source form: one `condition => result` clause
+    /// per line, ending in a bare `=> result` default line. Blank lines are
+    /// skipped. This is deliberately simple text framing around the
+    /// expression grammar rather than a fourth grammar layer.
+    pub fn parse(src: &str) -> Result<Self, RuleError> {
+        let mut branches = Vec::new();
+        for line in src.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((lhs, rhs)) = line.split_once("=>") else {
+                return Err(RuleError(format!("expected '=>' in rule line: {line:?}")));
+            };
+            let lhs = lhs.trim();
+            let result = parse_full(rhs.trim())?;
+            if lhs.is_empty() {
+                branches.push((None, result));
+            } else {
+                branches.push((Some(parse_full(lhs)?), result));
+            }
+        }
+        Self::new(branches)
+    }
+
+    /// Returns the result of the first branch whose condition is truthy, or
+    /// the default (the branch with `condition: None`, or the last branch
+    /// if every entry has a condition) if none match.
+    pub fn evaluate(&self, context: &Context) -> Value {
+        let evaluator = Evaluator {
+            context,
+            regex_cache: &self.regex_cache,
+        };
+        let mut default = None;
+        for (condition, result) in &self.branches {
+            match condition {
+                Some(condition) if evaluator.eval(condition).is_truthy() => {
+                    return evaluator.eval(result);
+                }
+                Some(_) => continue,
+                None => {
+                    default = Some(result);
+                }
+            }
+        }
+        match default.or_else(|| self.branches.last().map(|(_, result)| result)) {
+            Some(result) => evaluator.eval(result),
+            None => Value::String(String::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_first_matching_branch_in_order() {
+        let rule = Rule::parse(
+            r#"
+            model == "gpt-4" => "openai"
+            model == "claude-3" => "anthropic"
+            => "default-provider"
+            "#,
+        )
+        .expect("rule should parse");
+
+        let mut ctx = Context::new();
+        ctx.set("model", Value::String("claude-3".to_string()));
+        assert_eq!(rule.evaluate(&ctx), Value::String("anthropic".to_string()));
+    }
+
+    #[test]
+    fn falls_back_to_default_branch_when_nothing_matches() {
+        let rule = Rule::parse(
+            r#"
+            model == "gpt-4" => "openai"
+            => "default-provider"
+            "#,
+        )
+        .expect("rule should parse");
+
+        let mut ctx = Context::new();
+        ctx.set("model", Value::String("unknown-model".to_string()));
+        assert_eq!(
+            rule.evaluate(&ctx),
+            Value::String("default-provider".to_string())
+        );
+    }
+
+    #[test]
+    fn falls_back_to_last_branch_when_every_branch_has_a_condition() {
+        let rule = Rule::parse(
+            r#"
+            model == "gpt-4" => "openai"
+            model == "claude-3" => "anthropic"
+            "#,
+        )
+        .expect("rule should parse");
+
+        let mut ctx = Context::new();
+        ctx.set("model", Value::String("unknown-model".to_string()));
+        assert_eq!(rule.evaluate(&ctx), Value::String("anthropic".to_string()));
+    }
+
+    #[test]
+    fn regex_match_operator_evaluates_against_context_variable() {
+        // Two top-level branches must each be on their own line; cramming
+        // both onto one line is a rule-authoring mistake `parse` should
+        // reject rather than silently pick an interpretation for.
+        Rule::parse(r#"path =~ "^/v1/chat" => "matched" => "unmatched""#).unwrap_err();
+
+        let rule = Rule::parse(
+            r#"
+            path =~ "^/v1/chat" => "matched"
+            => "unmatched"
+            "#,
+        )
+        .expect("rule should parse");
+
+        let mut ctx = Context::new();
+        ctx.set("path", Value::String("/v1/chat/completions".to_string()));
+        assert_eq!(rule.evaluate(&ctx), Value::String("matched".to_string()));
+
+        ctx.set("path", Value::String("/v1/other".to_string()));
+        assert_eq!(rule.evaluate(&ctx), Value::String("unmatched".to_string()));
+    }
+
+    #[test]
+    fn and_or_short_circuit_and_combine_truthiness() {
+        let rule = Rule::parse(
+            r#"
+            (model == "gpt-4" || model == "gpt-4o") && user == "admin" => "fast-lane"
+            => "normal"
+            "#,
+        )
+        .expect("rule should parse");
+
+        let mut ctx = Context::new();
+        ctx.set("model", Value::String("gpt-4o".to_string()));
+        ctx.set("user", Value::String("admin".to_string()));
+        assert_eq!(rule.evaluate(&ctx), Value::String("fast-lane".to_string()));
+
+        ctx.set("user", Value::String("guest".to_string()));
+        assert_eq!(rule.evaluate(&ctx), Value::String("normal".to_string()));
+    }
+
+    #[test]
+    fn if_function_and_concatenation_in_result() {
+        let rule = Rule::parse(
+            r#"=> "provider-" + if(region == "eu", "eu", "us")"#,
+        )
+        .expect("rule should parse");
+
+        let mut ctx = Context::new();
+        ctx.set("region", Value::String("eu".to_string()));
+        assert_eq!(rule.evaluate(&ctx), Value::String("provider-eu".to_string()));
+
+        ctx.set("region", Value::String("us".to_string()));
+        assert_eq!(rule.evaluate(&ctx), Value::String("provider-us".to_string()));
+    }
+
+    #[test]
+    fn unresolved_variable_and_unknown_function_never_error() {
+        Rule::parse(r#"missing_var == "x" => "matched" => unknown_fn(missing_var)"#).unwrap_err();
+
+        let rule = Rule::parse(
+            r#"
+            missing_var == "x" => "matched"
+            => unknown_fn(missing_var)
+            "#,
+        )
+        .expect("rule should parse");
+
+        let ctx = Context::new();
+        assert_eq!(rule.evaluate(&ctx), Value::String(String::new()));
+    }
+
+    #[test]
+    fn parse_full_rejects_trailing_tokens() {
+        assert!(parse_full(r#""a" "b""#).is_err());
+        assert!(parse_full(r#""a" + "b""#).is_ok());
+    }
+
+    #[test]
+    fn empty_rule_source_is_a_parse_error() {
+        assert!(Rule::parse("").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+
+use crate::response::UpstreamPassthroughError;
+
+/// How long a started-but-never-completed authorization is kept around
+/// before `AuthorizationStore` treats it as abandoned.
+const DEFAULT_PENDING_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// One in-flight out-of-band OAuth authorization: the URL a human follows
+/// in a browser (or the device-code flow's verification URL), parked under
+/// an unguessable `state` token until its callback/code arrives.
+#[derive(Debug, Clone)]
+pub struct PendingAuthorization {
+    pub provider: String,
+    pub state: String,
+    pub authorize_url: String,
+    pub created_at: SystemTime,
+    pub expires_at: SystemTime,
+}
+
+/// Why a `CredentialAcquisition` call failed.
+#[derive(Debug, Clone)]
+pub enum AuthorizationError {
+    /// No pending authorization exists for the given `state` (never
+    /// started, already completed, or this process restarted).
+    UnknownState,
+    /// A pending authorization existed but its TTL elapsed before the
+    /// callback arrived.
+    Expired,
+    /// The out-of-band exchange (authorize-URL mint, or code-for-token
+    /// exchange) itself failed.
+    Exchange(String),
+}
+
+impl fmt::Display for AuthorizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownState => write!(f, "no pending authorization for this state"),
+            Self::Expired => write!(f, "authorization request expired before completion"),
+            Self::Exchange(reason) => write!(f, "authorization exchange failed: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AuthorizationError {}
+
+impl From<AuthorizationError> for UpstreamPassthroughError {
+    fn from(err: AuthorizationError) -> Self {
+        UpstreamPassthroughError::service_unavailable(err.to_string())
+    }
+}
+
+/// Tracks concurrent in-flight out-of-band authorizations for one provider,
+/// keyed by the unguessable `state` token handed back in the callback.
+/// Entries past their TTL are swept out on the next `begin`/`take` rather
+/// than on a background timer, so a provider with no OAuth traffic costs
+/// nothing.
+#[derive(Debug)]
+pub struct AuthorizationStore {
+    pending: RwLock<HashMap<String, PendingAuthorization>>,
+    ttl: Duration,
+}
+
+impl Default for AuthorizationStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_PENDING_TTL)
+    }
+}
+
+impl AuthorizationStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            pending: RwLock::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Parks a new pending authorization under `state`, pruning expired
+    /// entries first.
+    pub fn begin(&self, provider: &str, state: String, authorize_url: String) -> PendingAuthorization {
+        let now = SystemTime::now();
+        self.prune(now);
+        let entry = PendingAuthorization {
+            provider: provider.to_string(),
+            state: state.clone(),
+            authorize_url,
+            created_at: now,
+            expires_at: now + self.ttl,
+        };
+        self.pending
+            .write()
+            .expect("poisoned pending-authorization lock")
+            .insert(state, entry.clone());
+        entry
+    }
+
+    /// Removes and returns the pending authorization for `state`, so each
+    /// callback can only complete it once.
+    pub fn take(&self, state: &str) -> Result<PendingAuthorization, AuthorizationError> {
+        let now = SystemTime::now();
+        let mut pending = self.pending.write().expect("poisoned pending-authorization lock");
+        match pending.remove(state) {
+            Some(entry) if entry.expires_at > now => Ok(entry),
+            Some(_) => Err(AuthorizationError::Expired),
+            None => Err(AuthorizationError::UnknownState),
+        }
+    }
+
+    fn prune(&self, now: SystemTime) {
+        self.pending
+            .write()
+            .expect("poisoned pending-authorization lock")
+            .retain(|_, entry| entry.expires_at > now);
+    }
+}
+
+/// Implemented by providers whose credentials can only be acquired through
+/// an interactive, out-of-band OAuth flow (device code or browser
+/// authorization-code grant) rather than a pre-supplied secret. A
+/// successful `complete_authorization` is expected to insert the resulting
+/// credential into the provider's own `PoolSnapshot` via `replace_snapshot`
+/// and, through its `StateSink`, let the refresh token survive a restart.
+#[async_trait]
+pub trait CredentialAcquisition: Send + Sync {
+    /// Starts a new authorization: mints a `state` token, parks a
+    /// `PendingAuthorization`, and returns the URL the caller should send
+    /// the user to.
+    async fn begin_authorization(&self) -> Result<PendingAuthorization, AuthorizationError>;
+
+    /// Completes a pending authorization once the callback/code arrives.
+    async fn complete_authorization(&self, state: &str, code: &str) -> Result<(), AuthorizationError>;
+}
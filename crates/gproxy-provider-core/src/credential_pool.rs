@@ -0,0 +1,708 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicI64, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use http::StatusCode;
+
+use crate::disallow::{DisallowEntry, DisallowKey, DisallowLevel, DisallowMark, DisallowScope};
+use crate::policy::AccessPolicy;
+use crate::response::UpstreamPassthroughError;
+use crate::state::{ProviderStateEvent, StateSink};
+
+/// One credential as loaded into a `PoolSnapshot`, along with the pool
+/// bookkeeping (enabled flag, selection weight, optional expiry) that sits
+/// alongside the provider-specific payload `C`.
+#[derive(Debug, Clone)]
+pub struct CredentialEntry<C> {
+    pub id: String,
+    pub enabled: bool,
+    pub weight: u32,
+    value: C,
+    pub expires_at: Option<SystemTime>,
+}
+
+impl<C> CredentialEntry<C> {
+    pub fn new(id: String, enabled: bool, weight: u32, value: C) -> Self {
+        Self {
+            id,
+            enabled,
+            weight,
+            value,
+            expires_at: None,
+        }
+    }
+
+    /// Attaches an expiry instant, e.g. an OAuth access-token's `expires_at`.
+    /// A credential past this instant is treated as unavailable by `execute`.
+    pub fn with_expiry(mut self, expires_at: Option<SystemTime>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    pub fn value(&self) -> &C {
+        &self.value
+    }
+
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at.map(|at| at <= now).unwrap_or(false)
+    }
+}
+
+/// The credentials and disallow state for one provider, swapped in wholesale
+/// whenever the admin API reloads a provider's pool.
+#[derive(Debug, Clone)]
+pub struct PoolSnapshot<C> {
+    pub credentials: Vec<CredentialEntry<C>>,
+    pub disallow: HashMap<DisallowKey, DisallowEntry>,
+    pub policy: AccessPolicy,
+}
+
+impl<C> PoolSnapshot<C> {
+    pub fn new(
+        credentials: Vec<CredentialEntry<C>>,
+        disallow: HashMap<DisallowKey, DisallowEntry>,
+    ) -> Self {
+        Self {
+            credentials,
+            disallow,
+            policy: AccessPolicy::default(),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            credentials: Vec::new(),
+            disallow: HashMap::new(),
+            policy: AccessPolicy::default(),
+        }
+    }
+
+    /// Attaches an operator-configured model/credential `AccessPolicy` to
+    /// this snapshot, so it is swapped in atomically alongside the
+    /// credentials and disallow state it governs.
+    pub fn with_policy(mut self, policy: AccessPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+/// Carried by a failed `execute` attempt closure: the error to surface to the
+/// caller plus, optionally, how the credential that was used should be
+/// penalized.
+#[derive(Debug, Clone)]
+pub struct AttemptFailure {
+    pub passthrough: UpstreamPassthroughError,
+    pub mark: Option<DisallowMark>,
+}
+
+/// Structured reasons `CredentialPool::execute` can fail before an upstream
+/// attempt is even made, so callers can distinguish "nothing configured" from
+/// "every candidate is currently penalized" from "the upstream call failed".
+#[derive(Debug, Clone)]
+pub enum CredentialPoolError {
+    CredentialsNotLoaded,
+    AllCredentialsDisallowed,
+    InvalidCredentials(String),
+    ProviderTimedOut,
+    ModelNotAllowed(String),
+}
+
+impl fmt::Display for CredentialPoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CredentialsNotLoaded => write!(f, "no credentials loaded for provider"),
+            Self::AllCredentialsDisallowed => {
+                write!(f, "all credentials are currently disallowed")
+            }
+            Self::InvalidCredentials(reason) => write!(f, "invalid credentials: {reason}"),
+            Self::ProviderTimedOut => write!(f, "timed out waiting for a usable credential"),
+            Self::ModelNotAllowed(model) => write!(f, "model '{model}' is not allowed"),
+        }
+    }
+}
+
+impl std::error::Error for CredentialPoolError {}
+
+impl From<CredentialPoolError> for UpstreamPassthroughError {
+    fn from(err: CredentialPoolError) -> Self {
+        match &err {
+            CredentialPoolError::ModelNotAllowed(_) => {
+                UpstreamPassthroughError::from_status(StatusCode::FORBIDDEN, err.to_string())
+            }
+            _ => UpstreamPassthroughError::service_unavailable(err.to_string()),
+        }
+    }
+}
+
+/// How long before a credential's `expires_at` the pool proactively stops
+/// handing it out, so a slow refresh has room to complete before the
+/// upstream actually rejects it.
+const DEFAULT_EARLY_REFRESH: Duration = Duration::from_secs(60);
+
+struct CachedCredential<C> {
+    value: C,
+    valid_until: Option<SystemTime>,
+}
+
+/// How far the `tranquility` backoff base defaults to when a pool doesn't
+/// set one explicitly — the delay before the *first* retry after a
+/// credential's first observed failure. Named after Garage's background
+/// "tranquility" knob: the same idea of a tunable base delay operators dial
+/// up or down to trade reconnection aggressiveness against upstream
+/// friendliness.
+const DEFAULT_TRANQUILITY: Duration = Duration::from_secs(2);
+
+/// A cooldown never grows past this, regardless of how many consecutive
+/// failures a credential has racked up — an unbounded exponential backoff
+/// would otherwise take a credential out of rotation for effectively ever.
+const MAX_COOLDOWN: Duration = Duration::from_secs(600);
+
+/// Health score is tracked as fixed-point milli-units (`0..=1000` standing
+/// in for `0.0..=1.0`) so it can live in an `AtomicI64` instead of behind a
+/// lock.
+const HEALTH_SCALE: i64 = 1000;
+const HEALTH_SUCCESS_RECOVERY: i64 = 150;
+const HEALTH_FAILURE_PENALTY: i64 = 350;
+
+/// Per-credential selection bookkeeping that lives alongside, but separate
+/// from, the `PoolSnapshot` a config reload swaps in wholesale: health,
+/// cooldown, and in-flight counts update on every request and would be far
+/// too hot a path to rebuild a whole new `Arc<PoolSnapshot<C>>` for.
+struct SelectionState {
+    health_milli: AtomicI64,
+    cooldown_until_millis: AtomicU64,
+    consecutive_failures: AtomicU32,
+    in_flight: AtomicU32,
+    /// Smooth weighted round-robin accumulator (the nginx `upstream`
+    /// algorithm): each selection round adds the candidate's effective
+    /// weight to this counter, the highest accumulator wins, and the winner
+    /// has the round's total effective weight subtracted back out — so
+    /// load spreads across credentials in proportion to weight * health
+    /// instead of always favoring the single heaviest-weighted one.
+    round_robin_weight: AtomicI64,
+}
+
+impl Default for SelectionState {
+    fn default() -> Self {
+        Self {
+            health_milli: AtomicI64::new(HEALTH_SCALE),
+            cooldown_until_millis: AtomicU64::new(0),
+            consecutive_failures: AtomicU32::new(0),
+            in_flight: AtomicU32::new(0),
+            round_robin_weight: AtomicI64::new(0),
+        }
+    }
+}
+
+impl SelectionState {
+    fn health_score(&self) -> f64 {
+        self.health_milli.load(Ordering::Relaxed) as f64 / HEALTH_SCALE as f64
+    }
+
+    fn cooldown_until(&self) -> Option<SystemTime> {
+        let millis = self.cooldown_until_millis.load(Ordering::Relaxed);
+        if millis == 0 {
+            None
+        } else {
+            Some(SystemTime::UNIX_EPOCH + Duration::from_millis(millis))
+        }
+    }
+
+    fn is_cooling_down(&self, now: SystemTime) -> bool {
+        self.cooldown_until().map(|until| until > now).unwrap_or(false)
+    }
+}
+
+/// A point-in-time view of one credential's health/cooldown bookkeeping,
+/// for surfacing alongside `PoolSnapshot` through the admin API.
+#[derive(Debug, Clone)]
+pub struct CredentialHealth {
+    pub credential_id: String,
+    pub health_score: f64,
+    pub cooldown_until: Option<SystemTime>,
+    pub consecutive_failures: u32,
+    pub in_flight: u32,
+}
+
+/// Decrements a `SelectionState`'s in-flight counter when a candidate
+/// attempt finishes, however it finishes — mirrors `cancel_on_drop` in
+/// `gproxy-core::handler` for the same reason: a future that's dropped
+/// early (client disconnect, cancellation) must not leak the counter.
+struct InFlightGuard<'a>(&'a SelectionState);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Selects among a provider's credentials, skipping disallowed, expired, or
+/// cooled-down ones, caching the winner until shortly before it expires,
+/// and demoting a credential's health whenever an attempt against it fails.
+pub struct CredentialPool<C> {
+    name: String,
+    snapshot: RwLock<Arc<PoolSnapshot<C>>>,
+    cache: RwLock<HashMap<String, CachedCredential<C>>>,
+    selection: RwLock<HashMap<String, Arc<SelectionState>>>,
+    sink: Option<Arc<dyn StateSink>>,
+    early_refresh: Duration,
+    tranquility: Duration,
+}
+
+impl<C> fmt::Debug for CredentialPool<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CredentialPool")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl<C: Clone + Send + Sync + 'static> CredentialPool<C> {
+    pub fn new(
+        name: impl Into<String>,
+        snapshot: PoolSnapshot<C>,
+        sink: Option<Arc<dyn StateSink>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            snapshot: RwLock::new(Arc::new(snapshot)),
+            cache: RwLock::new(HashMap::new()),
+            selection: RwLock::new(HashMap::new()),
+            sink,
+            early_refresh: DEFAULT_EARLY_REFRESH,
+            tranquility: DEFAULT_TRANQUILITY,
+        }
+    }
+
+    pub fn with_early_refresh(mut self, early_refresh: Duration) -> Self {
+        self.early_refresh = early_refresh;
+        self
+    }
+
+    /// Sets the backoff base a failing credential's exponential cooldown
+    /// grows from (`tranquility * 2^(consecutive_failures - 1)`, capped at
+    /// `MAX_COOLDOWN`). Higher values trade away reconnection speed for
+    /// gentler treatment of an already-struggling upstream credential.
+    pub fn with_tranquility(mut self, tranquility: Duration) -> Self {
+        self.tranquility = tranquility;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn snapshot(&self) -> Arc<PoolSnapshot<C>> {
+        self.snapshot.read().expect("poisoned pool lock").clone()
+    }
+
+    pub fn replace_snapshot(&self, snapshot: PoolSnapshot<C>) {
+        let still_present: HashSet<_> = snapshot.credentials.iter().map(|c| c.id.clone()).collect();
+        self.cache
+            .write()
+            .expect("poisoned cache lock")
+            .retain(|id, _| still_present.contains(id));
+        // Health/cooldown/in-flight state is kept for credentials that
+        // survive the reload (an admin editing an unrelated credential
+        // shouldn't reset everyone else's backoff) and dropped for ones
+        // that didn't.
+        self.selection
+            .write()
+            .expect("poisoned selection lock")
+            .retain(|id, _| still_present.contains(id));
+        *self.snapshot.write().expect("poisoned pool lock") = Arc::new(snapshot);
+    }
+
+    fn selection_state(&self, credential_id: &str) -> Arc<SelectionState> {
+        if let Some(state) = self
+            .selection
+            .read()
+            .expect("poisoned selection lock")
+            .get(credential_id)
+        {
+            return state.clone();
+        }
+        self.selection
+            .write()
+            .expect("poisoned selection lock")
+            .entry(credential_id.to_string())
+            .or_insert_with(|| Arc::new(SelectionState::default()))
+            .clone()
+    }
+
+    /// The current health/cooldown/in-flight view for every credential in
+    /// the live snapshot, for the admin API to surface alongside
+    /// `PoolSnapshot`. Credentials not yet selected since the last reload
+    /// report fresh, fully-healthy defaults.
+    pub fn health(&self) -> Vec<CredentialHealth> {
+        self.snapshot()
+            .credentials
+            .iter()
+            .map(|credential| {
+                let state = self.selection_state(&credential.id);
+                CredentialHealth {
+                    credential_id: credential.id.clone(),
+                    health_score: state.health_score(),
+                    cooldown_until: state.cooldown_until(),
+                    consecutive_failures: state.consecutive_failures.load(Ordering::Relaxed),
+                    in_flight: state.in_flight.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+
+    fn record_success(&self, credential_id: &str) {
+        let state = self.selection_state(credential_id);
+        state.consecutive_failures.store(0, Ordering::Relaxed);
+        state.cooldown_until_millis.store(0, Ordering::Relaxed);
+        let recovered = (state.health_milli.load(Ordering::Relaxed) + HEALTH_SUCCESS_RECOVERY)
+            .min(HEALTH_SCALE);
+        state.health_milli.store(recovered, Ordering::Relaxed);
+    }
+
+    fn record_failure(&self, credential_id: &str, now: SystemTime) {
+        let state = self.selection_state(credential_id);
+        let failures = state.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        let degraded =
+            (state.health_milli.load(Ordering::Relaxed) - HEALTH_FAILURE_PENALTY).max(0);
+        state.health_milli.store(degraded, Ordering::Relaxed);
+
+        let backoff = self
+            .tranquility
+            .checked_mul(1u32 << failures.min(16).saturating_sub(1))
+            .unwrap_or(MAX_COOLDOWN)
+            .min(MAX_COOLDOWN);
+        let cooldown_until = now + backoff;
+        let millis = cooldown_until
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        state.cooldown_until_millis.store(millis, Ordering::Relaxed);
+    }
+
+    fn is_disallowed(
+        &self,
+        snapshot: &PoolSnapshot<C>,
+        credential_id: &str,
+        scope: &DisallowScope,
+        now: SystemTime,
+    ) -> bool {
+        [DisallowScope::AllModels, scope.clone()]
+            .into_iter()
+            .any(|candidate| {
+                snapshot
+                    .disallow
+                    .get(&DisallowKey::new(credential_id, candidate))
+                    .map(|entry| entry.is_active(now))
+                    .unwrap_or(false)
+            })
+    }
+
+    fn cached_value(&self, credential_id: &str, now: SystemTime) -> Option<C> {
+        let cache = self.cache.read().expect("poisoned cache lock");
+        cache.get(credential_id).and_then(|cached| match cached.valid_until {
+            Some(valid_until) if valid_until <= now => None,
+            _ => Some(cached.value.clone()),
+        })
+    }
+
+    fn store_cache(&self, credential_id: &str, value: C, expires_at: Option<SystemTime>) {
+        let valid_until = expires_at.map(|at| at.checked_sub(self.early_refresh).unwrap_or(at));
+        self.cache
+            .write()
+            .expect("poisoned cache lock")
+            .insert(credential_id.to_string(), CachedCredential { value, valid_until });
+    }
+
+    fn record_mark(&self, credential_id: &str, mark: DisallowMark) {
+        self.record_failure(credential_id, SystemTime::now());
+        let until = mark.duration.map(|duration| SystemTime::now() + duration);
+        let entry = DisallowEntry {
+            level: mark.level,
+            until,
+            reason: mark.reason.clone(),
+            updated_at: SystemTime::now(),
+        };
+        let snapshot = self.snapshot();
+        let mut disallow = snapshot.disallow.clone();
+        disallow.insert(
+            DisallowKey::new(credential_id.to_string(), mark.scope.clone()),
+            entry,
+        );
+        if let Some(sink) = &self.sink {
+            sink.emit(ProviderStateEvent::CredentialDisallowed {
+                provider: self.name.clone(),
+                credential_id: credential_id.to_string(),
+                scope: format!("{:?}", mark.scope),
+                reason: mark.reason,
+            });
+        }
+        *self.snapshot.write().expect("poisoned pool lock") = Arc::new(
+            PoolSnapshot::new(snapshot.credentials.clone(), disallow).with_policy(snapshot.policy.clone()),
+        );
+    }
+
+    /// Picks eligible credentials for `scope` in weight order (skipping
+    /// disallowed and expired ones, and reusing a cached value for a
+    /// not-yet-near-expiry credential instead of re-materializing it on every
+    /// call), runs `body` against each in turn, and records a `DisallowMark`
+    /// on failure before trying the next candidate.
+    pub async fn execute<T, F, Fut>(
+        &self,
+        scope: DisallowScope,
+        mut body: F,
+    ) -> Result<T, UpstreamPassthroughError>
+    where
+        F: FnMut(CredentialEntry<C>) -> Fut,
+        Fut: Future<Output = Result<T, AttemptFailure>>,
+    {
+        let now = SystemTime::now();
+        let snapshot = self.snapshot();
+        if snapshot.credentials.is_empty() {
+            return Err(CredentialPoolError::CredentialsNotLoaded.into());
+        }
+
+        if let DisallowScope::Model(model) = &scope {
+            if !snapshot.policy.is_model_allowed(model) {
+                return Err(CredentialPoolError::ModelNotAllowed(model.clone()).into());
+            }
+        }
+
+        let eligible: Vec<_> = snapshot
+            .credentials
+            .iter()
+            .filter(|c| {
+                c.enabled
+                    && !c.is_expired(now)
+                    && !snapshot.policy.is_credential_blocked(&c.id)
+                    && !self.is_disallowed(&snapshot, &c.id, &scope, now)
+            })
+            .cloned()
+            .collect();
+
+        if eligible.is_empty() {
+            return Err(CredentialPoolError::AllCredentialsDisallowed.into());
+        }
+
+        // Health- and rate-limit-aware ordering: drop anything still
+        // cooling down from a recent failure, rank the rest by a smooth
+        // weighted round-robin accumulator (so load spreads across
+        // credentials in proportion to weight * health instead of always
+        // hammering the single heaviest-weighted one), and break ties
+        // between equally-healthy candidates by preferring whichever has
+        // the fewest requests in flight right now.
+        let mut candidates: Vec<(CredentialEntry<C>, Arc<SelectionState>)> = eligible
+            .into_iter()
+            .filter_map(|candidate| {
+                let state = self.selection_state(&candidate.id);
+                if state.is_cooling_down(now) {
+                    None
+                } else {
+                    Some((candidate, state))
+                }
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return Err(CredentialPoolError::AllCredentialsDisallowed.into());
+        }
+
+        let total_effective_weight: i64 = candidates
+            .iter()
+            .map(|(candidate, state)| {
+                let effective = (candidate.weight.max(1) as f64) * state.health_score().max(0.05);
+                let effective_milli = (effective * HEALTH_SCALE as f64) as i64;
+                state.round_robin_weight.fetch_add(effective_milli, Ordering::Relaxed);
+                effective_milli
+            })
+            .sum();
+
+        candidates.sort_by(|(a, a_state), (b, b_state)| {
+            b_state
+                .round_robin_weight
+                .load(Ordering::Relaxed)
+                .cmp(&a_state.round_robin_weight.load(Ordering::Relaxed))
+                .then_with(|| {
+                    a_state
+                        .in_flight
+                        .load(Ordering::Relaxed)
+                        .cmp(&b_state.in_flight.load(Ordering::Relaxed))
+                })
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        if let Some((_, winner_state)) = candidates.first() {
+            winner_state
+                .round_robin_weight
+                .fetch_sub(total_effective_weight, Ordering::Relaxed);
+        }
+
+        let mut last_err = None;
+        for (candidate, state) in candidates {
+            let credential_id = candidate.id.clone();
+            let entry = match self.cached_value(&credential_id, now) {
+                Some(cached) => CredentialEntry::new(
+                    candidate.id.clone(),
+                    candidate.enabled,
+                    candidate.weight,
+                    cached,
+                )
+                .with_expiry(candidate.expires_at),
+                None => {
+                    self.store_cache(&credential_id, candidate.value().clone(), candidate.expires_at);
+                    candidate.clone()
+                }
+            };
+
+            state.in_flight.fetch_add(1, Ordering::Relaxed);
+            let _in_flight_guard = InFlightGuard(&state);
+            match body(entry).await {
+                Ok(value) => {
+                    drop(_in_flight_guard);
+                    self.record_success(&credential_id);
+                    return Ok(value);
+                }
+                Err(failure) => {
+                    drop(_in_flight_guard);
+                    if let Some(mark) = failure.mark.clone() {
+                        self.record_mark(&credential_id, mark);
+                    } else {
+                        self.record_failure(&credential_id, SystemTime::now());
+                    }
+                    last_err = Some(failure.passthrough);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| CredentialPoolError::ProviderTimedOut.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::AtomicUsize;
+
+    fn pool(entries: Vec<CredentialEntry<&'static str>>) -> CredentialPool<&'static str> {
+        CredentialPool::new("test-provider", PoolSnapshot::new(entries, HashMap::new()), None)
+    }
+
+    async fn ok_attempt(entry: CredentialEntry<&'static str>) -> Result<String, AttemptFailure> {
+        Ok(entry.value().to_string())
+    }
+
+    #[tokio::test]
+    async fn heavier_weight_is_selected_more_often_under_even_health() {
+        let pool = pool(vec![
+            CredentialEntry::new("heavy".to_string(), true, 9, "heavy"),
+            CredentialEntry::new("light".to_string(), true, 1, "light"),
+        ]);
+
+        let mut wins: HashMap<String, u32> = HashMap::new();
+        for _ in 0..20 {
+            let winner = pool
+                .execute(DisallowScope::AllModels, ok_attempt)
+                .await
+                .expect("an eligible credential should always be selected");
+            *wins.entry(winner).or_default() += 1;
+        }
+
+        // Smooth weighted round-robin spreads load in proportion to
+        // weight: "heavy" (weight 9) should win roughly nine times as
+        // often as "light" (weight 1) over enough rounds, and every
+        // credential must still get a turn instead of "heavy" winning
+        // every single time.
+        let heavy_wins = *wins.get("heavy").unwrap_or(&0);
+        let light_wins = *wins.get("light").unwrap_or(&0);
+        assert_eq!(heavy_wins + light_wins, 20);
+        assert!(light_wins >= 1, "lighter-weighted credential should still be picked sometimes");
+        assert!(
+            heavy_wins > light_wins,
+            "heavier-weighted credential should win more often: heavy={heavy_wins} light={light_wins}"
+        );
+    }
+
+    #[tokio::test]
+    async fn equal_weight_round_robins_evenly() {
+        let pool = pool(vec![
+            CredentialEntry::new("a".to_string(), true, 1, "a"),
+            CredentialEntry::new("b".to_string(), true, 1, "b"),
+        ]);
+
+        let mut wins: HashMap<String, u32> = HashMap::new();
+        for _ in 0..10 {
+            let winner = pool.execute(DisallowScope::AllModels, ok_attempt).await.unwrap();
+            *wins.entry(winner).or_default() += 1;
+        }
+
+        assert_eq!(*wins.get("a").unwrap_or(&0), 5);
+        assert_eq!(*wins.get("b").unwrap_or(&0), 5);
+    }
+
+    #[tokio::test]
+    async fn disabled_credential_is_never_selected() {
+        let pool = pool(vec![
+            CredentialEntry::new("disabled".to_string(), false, 10, "disabled"),
+            CredentialEntry::new("enabled".to_string(), true, 1, "enabled"),
+        ]);
+
+        for _ in 0..5 {
+            let winner = pool.execute(DisallowScope::AllModels, ok_attempt).await.unwrap();
+            assert_eq!(winner, "enabled");
+        }
+    }
+
+    #[tokio::test]
+    async fn no_eligible_credentials_surfaces_all_credentials_disallowed() {
+        let pool = pool(vec![CredentialEntry::new(
+            "disabled".to_string(),
+            false,
+            1,
+            "disabled",
+        )]);
+
+        // Every candidate is disabled, so `execute` must fail before ever
+        // calling `body` rather than silently picking a disabled credential.
+        pool.execute(DisallowScope::AllModels, ok_attempt).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn empty_pool_surfaces_an_error_without_ever_calling_body() {
+        let pool: CredentialPool<&'static str> = pool(vec![]);
+        pool.execute(DisallowScope::AllModels, ok_attempt).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn failing_credential_is_skipped_in_favor_of_the_next_candidate() {
+        let pool = pool(vec![
+            CredentialEntry::new("bad".to_string(), true, 1, "bad"),
+            CredentialEntry::new("good".to_string(), true, 1, "good"),
+        ]);
+
+        let attempts = AtomicUsize::new(0);
+        let result = pool
+            .execute(DisallowScope::AllModels, |entry: CredentialEntry<&'static str>| {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                async move {
+                    if entry.value() == &"bad" {
+                        Err(AttemptFailure {
+                            passthrough: UpstreamPassthroughError::service_unavailable(
+                                "bad credential".to_string(),
+                            ),
+                            mark: None,
+                        })
+                    } else {
+                        Ok(entry.value().to_string())
+                    }
+                }
+            })
+            .await
+            .expect("the second candidate should succeed");
+
+        assert_eq!(result, "good");
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::{Duration, SystemTime};
+
+/// What a disallow entry fences off: either a single model name, or every
+/// model the credential could otherwise serve.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DisallowScope {
+    AllModels,
+    Model(String),
+}
+
+impl DisallowScope {
+    pub fn model(name: impl Into<String>) -> Self {
+        DisallowScope::Model(name.into())
+    }
+}
+
+/// Severity of a disallow mark, from "try again shortly" to "never again
+/// without operator intervention".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisallowLevel {
+    Transient,
+    Cooldown,
+    Dead,
+}
+
+/// Produced by a failed upstream attempt; tells the pool how to penalize the
+/// credential that was used.
+#[derive(Debug, Clone)]
+pub struct DisallowMark {
+    pub scope: DisallowScope,
+    pub level: DisallowLevel,
+    pub duration: Option<Duration>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DisallowKey {
+    pub credential_id: String,
+    pub scope: DisallowScope,
+}
+
+impl DisallowKey {
+    pub fn new(credential_id: impl Into<String>, scope: DisallowScope) -> Self {
+        Self {
+            credential_id: credential_id.into(),
+            scope,
+        }
+    }
+}
+
+/// The state stored per `DisallowKey` inside a `PoolSnapshot`.
+#[derive(Debug, Clone)]
+pub struct DisallowEntry {
+    pub level: DisallowLevel,
+    pub until: Option<SystemTime>,
+    pub reason: Option<String>,
+    pub updated_at: SystemTime,
+}
+
+impl DisallowEntry {
+    pub fn is_active(&self, now: SystemTime) -> bool {
+        match self.level {
+            DisallowLevel::Dead => true,
+            DisallowLevel::Transient | DisallowLevel::Cooldown => {
+                self.until.map(|until| until > now).unwrap_or(false)
+            }
+        }
+    }
+}
+
+/// A `DisallowEntry` with its key flattened out, used when listing active
+/// disallows for a credential (e.g. for the admin API).
+#[derive(Debug, Clone)]
+pub struct DisallowRecord {
+    pub credential_id: String,
+    pub scope: DisallowScope,
+    pub entry: DisallowEntry,
+}
@@ -0,0 +1,39 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use http::StatusCode;
+
+/// Backoff and eligibility knobs for `ProviderPool`'s cross-provider
+/// failover, the `RetryPolicy` analogue for moving to the *next* credential
+/// instead of resending to the same one. Lives on `ProviderPool` rather than
+/// `CallContext` since which providers a pool can fail over to is a
+/// deployment-time wiring decision, not a per-request one.
+#[derive(Debug, Clone)]
+pub struct FailoverPolicy {
+    /// Upper bound on how many providers are tried for a single request,
+    /// including the first. A pool with more members than this still only
+    /// ever attempts this many before giving up.
+    pub max_attempts: u32,
+    /// Statuses that justify moving to the next provider rather than
+    /// surfacing the error immediately.
+    pub retryable_statuses: HashSet<StatusCode>,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl FailoverPolicy {
+    pub fn is_retryable(&self, status: StatusCode) -> bool {
+        self.retryable_statuses.contains(&status) || status.is_server_error()
+    }
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            retryable_statuses: HashSet::from([StatusCode::TOO_MANY_REQUESTS]),
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
@@ -1,15 +1,35 @@
+pub mod authorization;
 pub mod credential_pool;
 pub mod disallow;
+pub mod failover_policy;
+pub mod live_tap;
+pub mod policy;
 pub mod provider;
 pub mod request;
 pub mod response;
+pub mod retry;
 pub mod state;
+pub mod traffic_batch;
+pub mod ws_passthrough;
 
-pub use credential_pool::{AttemptFailure, CredentialEntry, CredentialPool, PoolSnapshot};
+pub use authorization::{AuthorizationError, AuthorizationStore, CredentialAcquisition, PendingAuthorization};
+pub use credential_pool::{
+    AttemptFailure, CredentialEntry, CredentialHealth, CredentialPool, CredentialPoolError,
+    PoolSnapshot,
+};
 pub use disallow::{
     DisallowEntry, DisallowKey, DisallowLevel, DisallowMark, DisallowRecord, DisallowScope,
 };
-pub use provider::{CallContext, Provider};
-pub use request::{GeminiApiVersion, ProxyRequest};
+pub use failover_policy::FailoverPolicy;
+pub use live_tap::{LiveTrafficChunk, SharedTrafficTap, TrafficDirection, TrafficTap};
+pub use policy::{AccessPolicy, ModelPattern};
+pub use provider::{
+    CallContext, ChunkAction, ChunkOverflowPolicy, Provider, ProxyInterceptor,
+    RequestBodyDropInterceptor,
+};
+pub use request::{GeminiApiVersion, PassthroughFormat, ProxyRequest};
 pub use response::{ProxyResponse, StreamBody, UpstreamPassthroughError};
+pub use retry::RetryPolicy;
 pub use state::{NoopStateSink, ProviderStateEvent, StateSink};
+pub use traffic_batch::{BatchMergeable, TrafficBatcher};
+pub use ws_passthrough::{WebSocketBody, WsFrame, WsOpcode, WsSink, WsStream};
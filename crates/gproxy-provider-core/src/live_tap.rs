@@ -0,0 +1,78 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::broadcast;
+
+/// Which side of a proxied call a [`LiveTrafficChunk`] was observed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficDirection {
+    Upstream,
+    Downstream,
+}
+
+/// One decoded SSE event observed on a live proxied stream, published to a
+/// [`TrafficTap`] as it flows so a debugging UI can watch it in real time
+/// instead of waiting for `record_upstream`/`record_downstream` to fire once
+/// the whole stream has completed.
+#[derive(Debug, Clone)]
+pub struct LiveTrafficChunk {
+    pub trace_id: String,
+    pub provider: String,
+    pub direction: TrafficDirection,
+    pub timestamp_ms: i64,
+    pub data: String,
+}
+
+impl LiveTrafficChunk {
+    pub fn now(
+        trace_id: String,
+        provider: String,
+        direction: TrafficDirection,
+        data: String,
+    ) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as i64)
+            .unwrap_or_default();
+        Self {
+            trace_id,
+            provider,
+            direction,
+            timestamp_ms,
+            data,
+        }
+    }
+}
+
+/// Broadcast hub for the live traffic tap. Every proxied SSE event is
+/// published here best-effort: a lagging subscriber drops older chunks
+/// (`tokio::sync::broadcast`'s usual semantics) rather than the proxy ever
+/// blocking client delivery to keep a debugging UI caught up.
+#[derive(Clone)]
+pub struct TrafficTap {
+    sender: broadcast::Sender<LiveTrafficChunk>,
+}
+
+impl TrafficTap {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// No-op when nobody is subscribed, which is the common case.
+    pub fn publish(&self, chunk: LiveTrafficChunk) {
+        let _ = self.sender.send(chunk);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveTrafficChunk> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for TrafficTap {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+pub type SharedTrafficTap = Arc<TrafficTap>;
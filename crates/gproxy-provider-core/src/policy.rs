@@ -0,0 +1,46 @@
+use std::collections::HashSet;
+
+/// A single model-name matcher: a trailing `*` makes it a prefix match,
+/// otherwise it must match the model name exactly. Enough for entries like
+/// `"gpt-4*"` or `"claude-3-opus"` without pulling in a full glob crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelPattern(String);
+
+impl ModelPattern {
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self(pattern.into())
+    }
+
+    pub fn matches(&self, model: &str) -> bool {
+        match self.0.strip_suffix('*') {
+            Some(prefix) => model.starts_with(prefix),
+            None => self.0 == model,
+        }
+    }
+}
+
+/// Operator-configured fencing checked by `CredentialPool::execute` before
+/// any upstream attempt is made: which model names a caller may request,
+/// and which credential IDs have been quarantined (e.g. a leaked key)
+/// without removing them from the pool.
+#[derive(Debug, Clone, Default)]
+pub struct AccessPolicy {
+    /// If non-empty, only models matching one of these patterns are served.
+    pub model_allow: Vec<ModelPattern>,
+    /// Matching models are rejected even if `model_allow` would permit them.
+    pub model_deny: Vec<ModelPattern>,
+    pub blocked_credentials: HashSet<String>,
+}
+
+impl AccessPolicy {
+    pub fn is_model_allowed(&self, model: &str) -> bool {
+        if self.model_deny.iter().any(|pattern| pattern.matches(model)) {
+            return false;
+        }
+        self.model_allow.is_empty() || self.model_allow.iter().any(|pattern| pattern.matches(model))
+    }
+
+    pub fn is_credential_blocked(&self, credential_id: &str) -> bool {
+        self.blocked_credentials.contains(credential_id)
+    }
+}
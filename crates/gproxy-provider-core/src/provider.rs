@@ -1,9 +1,17 @@
 use async_trait::async_trait;
 
 use std::sync::Arc;
+use std::time::Duration;
 
+use bytes::Bytes;
+use tokio_util::sync::CancellationToken;
+
+use gproxy_telemetry::{NoopTelemetrySink, ProxyMetrics, TelemetrySink, TransformSpan};
+
+use crate::live_tap::SharedTrafficTap;
 use crate::request::ProxyRequest;
 use crate::response::{ProxyResponse, UpstreamPassthroughError};
+use crate::retry::RetryPolicy;
 use crate::traffic::{DownstreamRecordMeta, NoopTrafficSink, SharedTrafficSink};
 
 #[derive(Clone)]
@@ -15,6 +23,67 @@ pub struct CallContext {
     pub proxy: Option<String>,
     pub traffic: SharedTrafficSink,
     pub downstream_meta: Option<DownstreamRecordMeta>,
+    /// Tripped when the downstream client goes away, so a still-running
+    /// upstream call can stop early instead of streaming into the void.
+    pub cancellation: CancellationToken,
+    /// Opt-in backoff knobs for `call_native_with_retry`. `None` means the
+    /// caller hasn't asked for retries, so a transient upstream failure is
+    /// surfaced as-is.
+    pub retry: Option<RetryPolicy>,
+    /// Opt-in live traffic tap. `None` means nobody is watching this call,
+    /// so per-chunk publishing is skipped entirely.
+    pub live_tap: Option<SharedTrafficTap>,
+    /// Ordered chain of interceptors run around this call. Empty by
+    /// default, so the common case pays no overhead.
+    pub interceptors: Arc<[Arc<dyn ProxyInterceptor>]>,
+    /// Force full-body accumulation for streamed recording even when there's
+    /// no downstream client to mirror to (e.g. a sink that wants completion
+    /// bodies for replay/audit). Belongs on `SharedTrafficSink` as a
+    /// capability flag once that trait has room for one; `downstream_meta`
+    /// already implies this, so this only matters when it's absent.
+    pub capture_full_body: bool,
+    /// Caps how many bytes of a streamed response body `record_*` functions
+    /// retain for traffic events. `None` falls back to each call site's
+    /// default budget. Past this threshold, accumulation stops and the
+    /// emitted event is marked truncated — usage extraction still runs on
+    /// every chunk regardless, since it doesn't need the retained body.
+    pub max_recorded_body_bytes: Option<usize>,
+    /// How the streaming recorder tap behaves when it falls behind the
+    /// client-facing forwarding path. Belongs on `SharedTrafficSink`
+    /// configuration once that trait has room for it; lives here in the
+    /// meantime since it's a per-call knob a caller may want to override.
+    pub recording_overflow: ChunkOverflowPolicy,
+    /// Factory for per-call transform spans, set once at startup. Defaults
+    /// to a no-op sink so a deployment that never opts into telemetry pays
+    /// nothing beyond the vtable call.
+    pub telemetry: Arc<dyn TelemetrySink>,
+    /// The active span for the current `dispatch_transform` hop, if one has
+    /// been opened. `None` for native (non-transform) calls and whenever
+    /// telemetry is disabled.
+    pub telemetry_span: Option<Arc<dyn TransformSpan>>,
+    /// SSE `Last-Event-ID` to replay on a reconnect attempt, set by a
+    /// streaming transform right before it re-issues the upstream request
+    /// after a mid-stream transport error. `None` on a call's first
+    /// attempt, and for any upstream that never emits `id:` lines (such a
+    /// stream can't be resumed past the point of failure).
+    pub last_event_id: Option<String>,
+    /// Prometheus metrics sink for this call's recording tasks. Defaults to
+    /// a private, ungathered registry so a `CallContext` built via
+    /// `Default` still has somewhere to record into; real deployments
+    /// should clone the same `Arc<ProxyMetrics>` they mounted at `/metrics`
+    /// into every `CallContext` instead, the way `traffic` is threaded
+    /// through today.
+    pub metrics: Arc<ProxyMetrics>,
+    /// How long a streaming transform's `unfold` loop will wait on a single
+    /// upstream chunk before treating the connection as stalled. Resets on
+    /// every chunk that arrives, unlike `stream_deadline` below. `None`
+    /// disables the watchdog, matching every other opt-in knob here.
+    pub idle_timeout: Option<Duration>,
+    /// Overall cap on a streaming transform's lifetime, independent of how
+    /// recently a chunk arrived — bounds a "drip-fed" upstream that never
+    /// goes idle long enough to trip `idle_timeout` but also never finishes.
+    /// `None` disables it.
+    pub stream_deadline: Option<Duration>,
 }
 
 impl Default for CallContext {
@@ -27,6 +96,19 @@ impl Default for CallContext {
             proxy: None,
             traffic: Arc::new(NoopTrafficSink),
             downstream_meta: None,
+            cancellation: CancellationToken::new(),
+            retry: None,
+            live_tap: None,
+            interceptors: Arc::from(Vec::new()),
+            capture_full_body: false,
+            max_recorded_body_bytes: None,
+            recording_overflow: ChunkOverflowPolicy::default(),
+            telemetry: Arc::new(NoopTelemetrySink),
+            telemetry_span: None,
+            last_event_id: None,
+            metrics: Arc::new(ProxyMetrics::default()),
+            idle_timeout: None,
+            stream_deadline: None,
         }
     }
 }
@@ -40,4 +122,77 @@ pub trait Provider: Send + Sync {
         req: ProxyRequest,
         ctx: CallContext,
     ) -> Result<ProxyResponse, UpstreamPassthroughError>;
+
+    /// Whether this provider can serve `model`. The default accepts every
+    /// model name, matching today's behavior (model routing happens via the
+    /// `ProxyRequest` variant dispatched to a specific provider, not via a
+    /// model allow-list) — override this once a provider wants to advertise
+    /// a narrower, queryable set (e.g. for `ProviderRegistry::by_name`
+    /// callers that pick a provider by model rather than by name).
+    fn supports(&self, _model: &str) -> bool {
+        true
+    }
+
+    /// Whether this provider has a gRPC passthrough path (e.g. Vertex AI's
+    /// and Gemini's `StreamGenerateContent`) the router can switch into for
+    /// an `application/grpc` request, instead of the REST call path `call`
+    /// otherwise handles. Defaults to `false`, matching every provider
+    /// today — none of them have gRPC relaying wired in yet.
+    fn supports_grpc_passthrough(&self) -> bool {
+        false
+    }
+}
+
+/// How a bounded recording tap behaves once it's full. `DropNewest` and
+/// `DropOldest` keep the client-facing forwarding path non-blocking at the
+/// cost of gaps in what's recorded; `Block` restores the old coupling
+/// (recording latency becomes client latency) for deployments that would
+/// rather stall than lose traffic data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChunkOverflowPolicy {
+    Block,
+    #[default]
+    DropOldest,
+    DropNewest,
+}
+
+/// What an interceptor wants done with a streamed chunk after inspecting
+/// or mutating it.
+pub enum ChunkAction {
+    /// Forward the chunk (possibly mutated in place) unchanged.
+    Forward,
+    /// Forward this instead of the original chunk.
+    Replace(Bytes),
+    /// Don't forward this chunk to the client at all.
+    Drop,
 }
+
+/// A filter run around every `Provider::call`, in the same spirit as an
+/// HTTP middleware chain: each interceptor gets a chance to inspect or
+/// mutate the request before it goes upstream, the response before it's
+/// handed back, and — for streamed responses — each chunk before it's
+/// forwarded to the client. Used for cross-cutting concerns like redacting
+/// secrets from SSE deltas, injecting synthetic keep-alive events, or
+/// dropping request bodies in logging-only modes.
+#[async_trait]
+pub trait ProxyInterceptor: Send + Sync {
+    async fn on_request(&self, _req: &mut ProxyRequest, _ctx: &CallContext) {}
+
+    async fn on_response(&self, _resp: &mut ProxyResponse, _ctx: &CallContext) {}
+
+    async fn on_stream_chunk(&self, _chunk: &mut Bytes) -> ChunkAction {
+        ChunkAction::Forward
+    }
+}
+
+/// An interceptor for a logging-only mode that should observe that a call
+/// happened without retaining what was sent downstream of it. `ProxyRequest`
+/// wraps a fully typed, per-protocol request rather than a raw body, so
+/// there's no generic mutation that blanks every variant; marking the
+/// request dropped in `CallContext` (e.g. clearing `downstream_meta`) is
+/// the per-deployment follow-up once this chain is actually invoked from
+/// the request entry point.
+pub struct RequestBodyDropInterceptor;
+
+#[async_trait]
+impl ProxyInterceptor for RequestBodyDropInterceptor {}
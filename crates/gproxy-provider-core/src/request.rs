@@ -1,6 +1,8 @@
 use gproxy_protocol::claude;
 use gproxy_protocol::gemini;
 use gproxy_protocol::openai;
+use http::HeaderMap;
+use serde_json::Value as JsonValue;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GeminiApiVersion {
@@ -36,6 +38,10 @@ pub enum ProxyRequest {
         version: GeminiApiVersion,
         request: gemini::get_model::request::GetModelRequest,
     },
+    GeminiEmbedContent {
+        version: GeminiApiVersion,
+        request: gemini::batch_embed_contents::request::BatchEmbedContentsRequest,
+    },
 
     OpenAIChat(openai::create_chat_completions::request::CreateChatCompletionRequest),
     OpenAIChatStream(openai::create_chat_completions::request::CreateChatCompletionRequest),
@@ -44,4 +50,45 @@ pub enum ProxyRequest {
     OpenAIInputTokens(openai::count_tokens::request::InputTokenCountRequest),
     OpenAIModelsList(openai::list_models::request::ListModelsRequest),
     OpenAIModelsGet(openai::get_model::request::GetModelRequest),
+
+    /// An already provider-shaped body, forwarded untouched to a Vertex
+    /// `:generateContent`/`:streamGenerateContent` endpoint without going
+    /// through `GenerateContentRequest` deserialization first. For advanced
+    /// callers who need a field this crate's typed requests don't model yet.
+    VertexRawPredict {
+        model: String,
+        body: JsonValue,
+        stream: bool,
+    },
+
+    /// A request for a model the proxy couldn't resolve to a known model,
+    /// forwarded to the matching native upstream without typed (de)serialization
+    /// or a source/target format transform, since `body` is already shaped as
+    /// `target_format` expects. The fallback `dispatch_transform` takes instead
+    /// of erroring when a requested model isn't in the proxy's known set or a
+    /// transform pair is unsupported — operators declare such models in the
+    /// `gproxy` app's versioned `models.toml` so they work immediately.
+    RawPassthrough {
+        target_format: PassthroughFormat,
+        body: JsonValue,
+        headers: HeaderMap,
+        stream: bool,
+    },
+
+    /// A legacy `/v1/completions`-shaped body, dispatched natively to a
+    /// provider that still speaks it (currently only `aistudio`). Carried as
+    /// raw JSON rather than a typed `gproxy_protocol` request so this one
+    /// endpoint doesn't need its own protocol module; the receiving
+    /// provider deserializes it into whatever shape it calls upstream with.
+    OpenAICompletions(JsonValue),
+}
+
+/// Which native wire format a [`ProxyRequest::RawPassthrough`] body is
+/// already shaped as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassthroughFormat {
+    Claude,
+    Gemini,
+    OpenAIChat,
+    OpenAIResponses,
 }
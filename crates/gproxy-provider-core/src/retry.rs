@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+/// Backoff knobs for a retrying caller (e.g. `call_native_with_retry`).
+/// Attached to `CallContext` as `Some(..)` so retries stay opt-in per
+/// request instead of every upstream call paying for extra attempts.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::fmt;
+
+use serde_json::Value as JsonValue;
+
+/// Emitted by a `CredentialPool` whenever a credential's disallow state
+/// changes, so an operator-facing layer (e.g. the admin API) can react
+/// without polling pool snapshots.
+#[derive(Debug, Clone)]
+pub enum ProviderStateEvent {
+    CredentialDisallowed {
+        provider: String,
+        credential_id: String,
+        scope: String,
+        reason: Option<String>,
+    },
+    CredentialRestored {
+        provider: String,
+        credential_id: String,
+        scope: String,
+    },
+    /// A `CredentialAcquisition` flow finished: `secret`/`meta` are the
+    /// same shapes a `BaseCredential` would carry (refresh token, expiry,
+    /// ...) so the sink can persist them and the provider can skip
+    /// re-login after a restart.
+    CredentialAcquired {
+        provider: String,
+        credential_id: String,
+        secret: JsonValue,
+        meta: JsonValue,
+    },
+}
+
+pub trait StateSink: Send + Sync + fmt::Debug {
+    fn emit(&self, event: ProviderStateEvent);
+}
+
+#[derive(Debug, Default)]
+pub struct NoopStateSink;
+
+impl StateSink for NoopStateSink {
+    fn emit(&self, _event: ProviderStateEvent) {}
+}
@@ -0,0 +1,111 @@
+//! Generic time-batched aggregation for a write-heavy recorder sitting
+//! behind many concurrent producers.
+//!
+//! This implements the scheduling/merging engine only: events are pushed
+//! onto an unbounded channel (so the hot path never blocks), a background
+//! task merges same-key events as they arrive, and each key's accumulated
+//! event is flushed once its deadline elapses. Wiring this in as the
+//! concrete `TrafficSink` used by `CallContext::traffic` — folding a
+//! downstream record event into its matching upstream event by `trace_id`
+//! and summing partial usage before either ever reaches storage — belongs
+//! in `StorageTrafficSink` (declared via `apps/gproxy`'s `mod
+//! traffic_sink;`), which isn't part of this checkout. That's a different
+//! job from `gproxy_storage::traffic_writer`'s `TrafficBatcher`-shaped but
+//! unrelated batching: `traffic_writer` groups already-distinct, already-
+//! merged events into multi-row `INSERT`s for throughput and retries them
+//! on failure, it never merges two events into one — so it isn't a
+//! reimplementation of this module, just a second, later stage in the same
+//! pipeline this module would feed once `StorageTrafficSink` exists.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// An event that can be queued on a [`TrafficBatcher`]. Events sharing a
+/// `batch_key` (typically a trace id) are folded together via `merge`
+/// before the batch is flushed, e.g. folding a downstream event into its
+/// matching upstream event and summing partial usage.
+pub trait BatchMergeable: Send + 'static {
+    fn batch_key(&self) -> String;
+    fn merge(self, other: Self) -> Self;
+}
+
+/// Accepts events on a non-blocking `push` and flushes them in periodic,
+/// merged batches via a background task.
+pub struct TrafficBatcher<T: BatchMergeable> {
+    sender: mpsc::UnboundedSender<T>,
+}
+
+impl<T: BatchMergeable> TrafficBatcher<T> {
+    /// Spawns the background aggregator. `flush_delay` is how long a
+    /// newly-seen key waits for more events to merge in before its batch is
+    /// written; `flush` performs the batched write.
+    pub fn spawn<F>(flush_delay: Duration, flush: F) -> Self
+    where
+        F: Fn(Vec<T>) + Send + Sync + 'static,
+    {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<T>();
+        tokio::spawn(async move {
+            let mut pending: HashMap<String, T> = HashMap::new();
+            let mut deadlines: BTreeMap<Instant, Vec<String>> = BTreeMap::new();
+            loop {
+                let sleep = tokio::time::sleep(
+                    deadlines
+                        .keys()
+                        .next()
+                        .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+                        .unwrap_or(Duration::from_secs(3600)),
+                );
+                tokio::pin!(sleep);
+                tokio::select! {
+                    event = receiver.recv() => {
+                        match event {
+                            Some(event) => {
+                                let key = event.batch_key();
+                                match pending.remove(&key) {
+                                    Some(existing) => {
+                                        pending.insert(key, existing.merge(event));
+                                    }
+                                    None => {
+                                        let deadline = Instant::now() + flush_delay;
+                                        deadlines.entry(deadline).or_default().push(key.clone());
+                                        pending.insert(key, event);
+                                    }
+                                }
+                            }
+                            None => break,
+                        }
+                    }
+                    _ = &mut sleep => {}
+                }
+
+                let now = Instant::now();
+                let due_deadlines: Vec<Instant> = deadlines
+                    .range(..=now)
+                    .map(|(deadline, _)| *deadline)
+                    .collect();
+                let mut batch = Vec::new();
+                for deadline in due_deadlines {
+                    if let Some(keys) = deadlines.remove(&deadline) {
+                        for key in keys {
+                            if let Some(event) = pending.remove(&key) {
+                                batch.push(event);
+                            }
+                        }
+                    }
+                }
+                if !batch.is_empty() {
+                    flush(batch);
+                }
+            }
+        });
+        Self { sender }
+    }
+
+    /// Never blocks or exerts backpressure on the caller's hot path.
+    pub fn push(&self, event: T) {
+        let _ = self.sender.send(event);
+    }
+}
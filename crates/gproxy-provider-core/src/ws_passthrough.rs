@@ -0,0 +1,70 @@
+use std::io;
+use std::pin::Pin;
+
+use bytes::Bytes;
+use futures_util::{Sink, Stream};
+
+/// How a [`WsFrame`]'s payload should be treated: only `Text` frames are
+/// fed to the usage decoder, `Binary` frames are recorded but not decoded,
+/// and `Ping`/`Pong`/`Close` carry no usage-relevant payload at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsOpcode {
+    Text,
+    Binary,
+    Ping,
+    Pong,
+    Close,
+}
+
+/// One frame on a WebSocket connection, carried in protocol-agnostic form
+/// so this crate doesn't need to depend on a specific WebSocket client
+/// library.
+#[derive(Debug, Clone)]
+pub struct WsFrame {
+    pub opcode: WsOpcode,
+    pub payload: Bytes,
+    /// Set only on `Close` frames that carried a close code.
+    pub close_code: Option<u16>,
+}
+
+impl WsFrame {
+    pub fn text(payload: impl Into<Bytes>) -> Self {
+        Self {
+            opcode: WsOpcode::Text,
+            payload: payload.into(),
+            close_code: None,
+        }
+    }
+
+    pub fn binary(payload: impl Into<Bytes>) -> Self {
+        Self {
+            opcode: WsOpcode::Binary,
+            payload: payload.into(),
+            close_code: None,
+        }
+    }
+
+    pub fn close(close_code: Option<u16>) -> Self {
+        Self {
+            opcode: WsOpcode::Close,
+            payload: Bytes::new(),
+            close_code,
+        }
+    }
+}
+
+/// Sink half of a duplex WebSocket connection: frames written here go to
+/// the far end. Boxed rather than generic so `ProxyResponse::WebSocket`
+/// can carry this without becoming generic over the concrete transport.
+pub type WsSink = Pin<Box<dyn Sink<WsFrame, Error = io::Error> + Send>>;
+
+/// Stream half of a duplex WebSocket connection: frames the far end sent.
+pub type WsStream = Pin<Box<dyn Stream<Item = Result<WsFrame, io::Error>> + Send>>;
+
+/// A duplex WebSocket connection — an upstream sink + stream pair,
+/// carried by `ProxyResponse::WebSocket` for protocols (OpenAI Realtime,
+/// streaming voice) that don't fit the `Json`/`Stream` shape.
+pub struct WebSocketBody {
+    pub sink: WsSink,
+    pub stream: WsStream,
+}
@@ -0,0 +1,173 @@
+//! The `crate::client::shared_client` every provider's `mod.rs` already calls
+//! (`shared_client(ctx.proxy.as_deref())?` or
+//! `shared_client(transport.resolve_proxy(ctx.proxy.as_deref()))?`, ~40 call
+//! sites across every provider) but that, until now, didn't exist in this
+//! tree — each call site compiled against a function signature with nothing
+//! behind it.
+//!
+//! This is also the integration point [`crate::dns`], [`crate::tls_config`],
+//! and [`crate::proxy_resolver`] were each written to plug into, and said so
+//! in their own doc comments. Built once per distinct `proxy` value and
+//! reused afterward (the "shared" in the name), the way
+//! `provider::vertex::oauth::TOKEN_CACHE` and friends cache one value behind
+//! a `OnceLock` instead of rebuilding it per call.
+//!
+//! DNS and TLS overrides are process-wide (read once from `GPROXY_UPSTREAM_*`
+//! env vars, mirroring `apps/gproxy/src/main.rs`'s other `GPROXY_*` toggles)
+//! rather than per-call, since neither `crate::dns` nor `crate::tls_config`
+//! has a path from `CallContext`/`BaseCredential` down to here yet — the
+//! `GlobalConfig.dns`/`GlobalConfig.tls` sections their own doc comments
+//! describe remain future work. `proxy`, by contrast, already varies per
+//! call (credential override vs. `CallContext::proxy`), so it's resolved by
+//! the caller and threaded straight to [`wreq::Proxy`] here.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use gproxy_provider_core::UpstreamPassthroughError;
+
+use crate::dns::{DnsConfig, DnsQueryProtocol, GproxyDnsResolver};
+use crate::proxy_resolver::ProxyScheme;
+use crate::tls_config::{build_client_config, PinningConfig, TlsConfig};
+
+static CLIENTS: OnceLock<Mutex<HashMap<Option<String>, wreq::Client>>> = OnceLock::new();
+
+/// Returns a pooled [`wreq::Client`] routed through `proxy` (`None` for a
+/// direct connection), with the custom DNS resolver and/or TLS trust store
+/// configured via `GPROXY_UPSTREAM_DNS_*`/`GPROXY_UPSTREAM_TLS_*` installed
+/// when those vars are set. Clients are cached by `proxy` value so repeat
+/// calls with the same proxy (the common case — most credentials don't set
+/// one) reuse the same connection pool instead of opening a fresh one per
+/// upstream request.
+pub fn shared_client(proxy: Option<&str>) -> Result<wreq::Client, UpstreamPassthroughError> {
+    let cache = CLIENTS.get_or_init(|| Mutex::new(HashMap::new()));
+    let key = proxy.map(str::to_string);
+
+    if let Some(client) = cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(&key)
+    {
+        return Ok(client.clone());
+    }
+
+    let client = build_client(proxy)?;
+    cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(key, client.clone());
+    Ok(client)
+}
+
+fn build_client(proxy: Option<&str>) -> Result<wreq::Client, UpstreamPassthroughError> {
+    let mut builder = wreq::Client::builder();
+
+    if let Some(url) = proxy {
+        // `ProxyScheme` only distinguishes transport (HTTP CONNECT vs.
+        // SOCKS5) for logging here; `wreq::Proxy::all` infers the same thing
+        // from the URL scheme when actually dialing.
+        let scheme = ProxyScheme::from_url(url);
+        tracing::debug!(proxy = %url, scheme = ?scheme, "upstream client: proxy configured");
+        let proxy = wreq::Proxy::all(url)
+            .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(dns_config) = upstream_dns_config() {
+        builder = builder.dns_resolver(Arc::new(GproxyDnsResolver::new(dns_config)));
+    }
+
+    if let Some(tls_config) = upstream_tls_config() {
+        let rustls_config = build_client_config(tls_config)
+            .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
+        builder = builder
+            .use_preconfigured_tls(rustls_config)
+            .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
+    }
+
+    builder
+        .build()
+        .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))
+}
+
+/// Reads `GPROXY_UPSTREAM_DNS_SERVERS` (comma-separated `host:port` entries)
+/// and `GPROXY_UPSTREAM_DNS_PROTOCOL` (`udp`/`tcp`/`doh`, default `udp`) once
+/// into a [`DnsConfig`]. `None` when `GPROXY_UPSTREAM_DNS_SERVERS` is unset
+/// or empty, matching [`DnsConfig::is_empty`]'s "stick with the system
+/// resolver" default.
+fn upstream_dns_config() -> Option<&'static DnsConfig> {
+    static CONFIG: OnceLock<Option<DnsConfig>> = OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let servers = std::env::var("GPROXY_UPSTREAM_DNS_SERVERS")
+                .map(|raw| {
+                    raw.split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .filter_map(|s| s.parse().ok())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if servers.is_empty() {
+                return None;
+            }
+            let protocol = std::env::var("GPROXY_UPSTREAM_DNS_PROTOCOL")
+                .ok()
+                .and_then(|raw| match raw.to_ascii_lowercase().as_str() {
+                    "udp" => Some(DnsQueryProtocol::Udp),
+                    "tcp" => Some(DnsQueryProtocol::Tcp),
+                    "doh" => Some(DnsQueryProtocol::Doh),
+                    _ => None,
+                });
+            let config = DnsConfig {
+                servers,
+                protocol,
+                hosts: HashMap::new(),
+            };
+            if config.is_empty() {
+                None
+            } else {
+                Some(config)
+            }
+        })
+        .as_ref()
+}
+
+/// Reads `GPROXY_UPSTREAM_TLS_EXTRA_ROOT_CERTS_FILE` (a PEM file of extra
+/// trust anchors), `GPROXY_UPSTREAM_TLS_USE_WEBPKI_ROOTS` (default `true`),
+/// and `GPROXY_UPSTREAM_TLS_PINNING_KNOWN_HOSTS` (enables TOFU pinning when
+/// set) once into a [`TlsConfig`]. `None` when none of those vars are set,
+/// so the default webpki-rooted TLS `wreq::Client::builder()` already uses
+/// is left untouched. Distinct `GPROXY_UPSTREAM_TLS_*` prefix from the
+/// unrelated `GPROXY_TLS_*` ACME/server-cert vars in `apps/gproxy/src/main.rs`
+/// — this is upstream (outbound) trust, that's the downstream listener cert.
+fn upstream_tls_config() -> Option<&'static TlsConfig> {
+    static CONFIG: OnceLock<Option<TlsConfig>> = OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let extra_root_certs_pem = std::env::var("GPROXY_UPSTREAM_TLS_EXTRA_ROOT_CERTS_FILE")
+                .ok()
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .map(|contents| vec![contents])
+                .unwrap_or_default();
+            let use_webpki_roots = std::env::var("GPROXY_UPSTREAM_TLS_USE_WEBPKI_ROOTS")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(true);
+            let pinning = std::env::var("GPROXY_UPSTREAM_TLS_PINNING_KNOWN_HOSTS")
+                .ok()
+                .map(|path| PinningConfig {
+                    known_hosts_path: path.into(),
+                });
+
+            if extra_root_certs_pem.is_empty() && use_webpki_roots && pinning.is_none() {
+                return None;
+            }
+
+            Some(TlsConfig {
+                extra_root_certs_pem,
+                use_webpki_roots,
+                pinning,
+            })
+        })
+        .as_ref()
+}
@@ -0,0 +1,34 @@
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value as JsonValue;
+
+/// The credential payload shared by every provider that authenticates with a
+/// single opaque secret (API key, bearer token, ...) plus provider-specific
+/// config carried in `meta`. Providers needing a richer shape (e.g. OAuth
+/// client/refresh-token pairs) still store it here as JSON rather than
+/// growing this struct, so the `CredentialPool<C>` plumbing stays generic.
+#[derive(Debug, Clone)]
+pub struct BaseCredential {
+    pub id: i64,
+    pub name: String,
+    pub secret: JsonValue,
+    pub meta: JsonValue,
+}
+
+impl BaseCredential {
+    /// `meta.expires_at`, if present, as a unix-timestamp-seconds expiry
+    /// instant. Populated for OAuth-style credentials whose access token is
+    /// refreshed out of band; absent for long-lived API keys.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.meta
+            .get("expires_at")
+            .and_then(|value| value.as_i64())
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs.max(0) as u64))
+    }
+
+    /// `meta.refresh_url`, if present: an out-of-band endpoint a provider can
+    /// call to mint a fresh secret once `expires_at` has passed.
+    pub fn refresh_hook(&self) -> Option<&str> {
+        self.meta.get("refresh_url").and_then(|value| value.as_str())
+    }
+}
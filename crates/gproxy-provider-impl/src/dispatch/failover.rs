@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use rand::Rng;
+
+use gproxy_provider_core::{
+    build_upstream_event, CallContext, FailoverPolicy, Provider, ProxyRequest, ProxyResponse,
+    UpstreamPassthroughError, UpstreamRecordMeta,
+};
+
+use super::{DispatchPlan, DispatchProvider, UpstreamOk};
+
+/// Cooldown after a failure, doubled per consecutive failure (capped), so a
+/// flapping backend is routed around for longer each time it fails again.
+const BASE_COOLDOWN: Duration = Duration::from_secs(5);
+const MAX_COOLDOWN: Duration = Duration::from_secs(120);
+const MAX_BACKOFF_SHIFT: u32 = 5;
+
+struct HealthEntry {
+    consecutive_failures: u32,
+    cooldown_until: Instant,
+}
+
+/// Per-provider health, keyed by `DispatchProvider::name`. A provider with
+/// no entry (or one whose cooldown has lapsed) is considered available; the
+/// first call after cooldown is a half-open probe that re-admits the
+/// provider on success or resets its cooldown on another failure.
+#[derive(Default)]
+struct HealthTable {
+    entries: RwLock<HashMap<String, HealthEntry>>,
+}
+
+impl HealthTable {
+    fn is_available(&self, name: &str) -> bool {
+        match self.entries.read().unwrap().get(name) {
+            None => true,
+            Some(entry) => Instant::now() >= entry.cooldown_until,
+        }
+    }
+
+    fn record_success(&self, name: &str) {
+        self.entries.write().unwrap().remove(name);
+    }
+
+    fn record_failure(&self, name: &str) {
+        let mut entries = self.entries.write().unwrap();
+        let entry = entries.entry(name.to_string()).or_insert(HealthEntry {
+            consecutive_failures: 0,
+            cooldown_until: Instant::now(),
+        });
+        entry.consecutive_failures += 1;
+        let shift = entry.consecutive_failures.min(MAX_BACKOFF_SHIFT) - 1;
+        let cooldown = BASE_COOLDOWN.saturating_mul(1 << shift).min(MAX_COOLDOWN);
+        entry.cooldown_until = Instant::now() + cooldown;
+    }
+}
+
+/// An ordered pool of interchangeable `DispatchProvider`s — e.g. several
+/// credentials or backends fronting the same upstream — tried in priority
+/// order by [`dispatch_request_with_failover`]. A hard failure or 5xx/429
+/// from `call_native` marks the offending provider unhealthy for a cooldown
+/// window and falls through to the next candidate; once a provider's
+/// `call_native` has returned successfully the response (including any
+/// stream) is committed to, so a mid-stream error is surfaced as-is rather
+/// than retried against a different provider.
+pub struct ProviderPool<P> {
+    providers: Vec<P>,
+    health: HealthTable,
+    policy: FailoverPolicy,
+}
+
+impl<P: DispatchProvider> ProviderPool<P> {
+    pub fn new(providers: Vec<P>) -> Self {
+        Self::with_policy(providers, FailoverPolicy::default())
+    }
+
+    pub fn with_policy(providers: Vec<P>, policy: FailoverPolicy) -> Self {
+        Self {
+            providers,
+            health: HealthTable::default(),
+            policy,
+        }
+    }
+
+    /// Candidate indices in priority order, skipping providers still in
+    /// cooldown. Falls back to the full priority order if every provider is
+    /// currently unhealthy, rather than failing the request outright.
+    fn candidates(&self) -> Vec<usize> {
+        let available: Vec<usize> = (0..self.providers.len())
+            .filter(|&i| self.health.is_available(self.providers[i].name()))
+            .collect();
+        if available.is_empty() {
+            (0..self.providers.len()).collect()
+        } else {
+            available
+        }
+    }
+}
+
+/// Exponential backoff with equal jitter before trying the next provider,
+/// the failover analogue of `retry::backoff_delay`.
+fn backoff_delay(policy: &FailoverPolicy, attempt: u32) -> Duration {
+    let exp = 2u32.saturating_pow(attempt.saturating_sub(1));
+    let uncapped = policy.base_delay.saturating_mul(exp);
+    let capped = uncapped.min(policy.max_delay);
+    let half_millis = capped.as_millis() as u64 / 2;
+    let jitter_millis = if half_millis == 0 {
+        0
+    } else {
+        rand::rng().random_range(0..=half_millis)
+    };
+    Duration::from_millis(half_millis + jitter_millis)
+}
+
+/// Records a synthetic upstream event noting that `provider` was skipped in
+/// favor of the next candidate, mirroring how `call_native_with_retry`
+/// records a failed attempt — so a dashboard over upstream events can show
+/// both retries and failovers without a separate code path.
+fn record_skipped_provider(
+    provider_name: &str,
+    req: &ProxyRequest,
+    ctx: &CallContext,
+    err: &UpstreamPassthroughError,
+) {
+    let meta = UpstreamRecordMeta {
+        provider: provider_name.to_string(),
+        provider_id: ctx
+            .downstream_meta
+            .as_ref()
+            .and_then(|meta| meta.provider_id),
+        credential_id: None,
+        operation: format!("{}.failover_skip", super::retry::request_operation(req)),
+        model: None,
+        request_method: "POST".to_string(),
+        request_path: super::retry::request_operation(req).to_string(),
+        request_query: None,
+        request_headers: "{}".to_string(),
+        request_body: String::new(),
+    };
+    let event = build_upstream_event(
+        Some(ctx.trace_id.clone()),
+        meta,
+        err.status,
+        &err.headers,
+        Some(&err.body),
+        false,
+        None,
+    );
+    ctx.traffic.record_upstream(event);
+}
+
+/// Dispatches `req` against `pool`, trying providers in priority order and
+/// failing over past errors the pool's `FailoverPolicy` marks retryable,
+/// backing off between attempts the same way `call_native_with_retry` backs
+/// off between resends of a single provider. Returns the winning provider's
+/// response together with how many providers were skipped first, so the
+/// caller can fold that count into its own `UpstreamRecordMeta` bookkeeping.
+///
+/// Each attempt re-dispatches the fully buffered `req` from scratch — a
+/// failure only ever surfaces here before `dispatch_request` has produced a
+/// response, so there's never a `ProxyResponse::Stream` with bytes already
+/// in flight to worry about clobbering.
+pub async fn dispatch_request_with_failover<P: DispatchProvider>(
+    pool: &ProviderPool<P>,
+    req: ProxyRequest,
+    ctx: CallContext,
+) -> Result<(ProxyResponse, u32), UpstreamPassthroughError> {
+    let candidates = pool.candidates();
+    let attempts = (pool.policy.max_attempts as usize).min(candidates.len());
+    let mut skipped = 0u32;
+    let mut last_err = None;
+    for (attempt, &idx) in candidates.iter().take(attempts.max(1)).enumerate() {
+        if attempt > 0 {
+            tokio::time::sleep(backoff_delay(&pool.policy, attempt as u32)).await;
+        }
+        let provider = &pool.providers[idx];
+        match super::dispatch_request(provider, req.clone(), ctx.clone()).await {
+            Ok(response) => {
+                pool.health.record_success(provider.name());
+                return Ok((response, skipped));
+            }
+            Err(err) if pool.policy.is_retryable(err.status) => {
+                record_skipped_provider(provider.name(), &req, &ctx, &err);
+                pool.health.record_failure(provider.name());
+                skipped += 1;
+                last_err = Some(err);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Err(last_err.expect("ProviderPool::candidates is never empty for a non-empty pool"))
+}
+
+/// Lets a boxed backend sit in a [`ProviderPool`] alongside (or instead of)
+/// a concrete `DispatchProvider` — what [`FailoverProvider`] pools its
+/// named backends as, since those backends are different concrete provider
+/// structs (`OpenAIProvider`, `AistudioProvider`, ...) and can't share a
+/// single `Vec<P>` any other way.
+#[async_trait]
+impl DispatchProvider for Arc<dyn DispatchProvider> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn dispatch_plan(&self, req: ProxyRequest) -> DispatchPlan {
+        (**self).dispatch_plan(req)
+    }
+
+    async fn call_native(
+        &self,
+        req: ProxyRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        (**self).call_native(req, ctx).await
+    }
+}
+
+/// Wraps a [`ProviderPool`] of named backends (looked up via
+/// `ProviderRegistry::dispatch_by_name`) as a top-level
+/// [`Provider`](gproxy_provider_core::Provider), so a request routed to
+/// this name is tried against each backend in turn through
+/// [`dispatch_request_with_failover`] exactly the way a single provider's
+/// `call` goes through `dispatch_request` — `proxy_handler`'s
+/// `(state.lookup)(provider)` + `.call(...)` never has to know the
+/// difference. Built by
+/// `ProviderRegistry::apply_failover_groups` from an admin-configured
+/// provider row's `failover_providers` list, not constructed directly by a
+/// caller.
+pub struct FailoverProvider {
+    name: String,
+    pool: ProviderPool<Arc<dyn DispatchProvider>>,
+}
+
+impl FailoverProvider {
+    pub fn new(name: String, backends: Vec<Arc<dyn DispatchProvider>>) -> Self {
+        Self {
+            name,
+            pool: ProviderPool::new(backends),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for FailoverProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn call(
+        &self,
+        req: ProxyRequest,
+        ctx: CallContext,
+    ) -> Result<ProxyResponse, UpstreamPassthroughError> {
+        dispatch_request_with_failover(&self.pool, req, ctx)
+            .await
+            .map(|(response, _skipped)| response)
+    }
+}
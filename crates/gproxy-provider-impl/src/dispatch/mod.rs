@@ -1,13 +1,23 @@
+mod failover;
+mod model_cache;
 mod plan;
 mod record;
+mod retry;
 mod stream;
+mod tool_calls;
 mod transform;
 mod usage;
+mod usage_cohere;
+mod usage_extractor;
+mod usage_gemini;
+mod ws;
 
+pub use failover::{dispatch_request_with_failover, FailoverProvider, ProviderPool};
 pub use plan::{
-    CountTokensPlan, DispatchPlan, GenerateContentPlan, ModelsGetPlan, ModelsListPlan,
-    StreamContentPlan, TransformPlan, UsageKind,
+    CountTokensPlan, DispatchPlan, EmbeddingsPlan, GenerateContentPlan, ModelsGetPlan,
+    ModelsListPlan, RawPassthroughPlan, StreamContentPlan, TransformPlan, UsageKind,
 };
+pub use retry::{call_native_with_retry, call_native_with_stream_failover};
 
 use async_trait::async_trait;
 
@@ -24,6 +34,8 @@ pub struct UpstreamOk {
 
 #[async_trait]
 pub trait DispatchProvider: Send + Sync {
+    fn name(&self) -> &str;
+
     fn dispatch_plan(&self, req: ProxyRequest) -> DispatchPlan;
 
     async fn call_native(
@@ -52,6 +64,6 @@ async fn dispatch_native<P: DispatchProvider>(
     usage: UsageKind,
     ctx: CallContext,
 ) -> Result<ProxyResponse, UpstreamPassthroughError> {
-    let UpstreamOk { response, meta } = provider.call_native(req, ctx.clone()).await?;
+    let UpstreamOk { response, meta } = call_native_with_retry(provider, req, ctx.clone()).await?;
     record_upstream_and_downstream(response, meta, usage, ctx).await
 }
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use http::HeaderMap;
+
+use gproxy_provider_core::ProxyResponse;
+
+use super::plan::{ModelsGetPlan, ModelsListPlan};
+
+/// Provider model catalogs change rarely; serving repeat list/get-model
+/// requests from here for a few minutes avoids a redundant upstream round
+/// trip (and the rate-limit hit that comes with it).
+const MODEL_CATALOG_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) struct ModelCacheKey {
+    provider: String,
+    operation: &'static str,
+    model: Option<String>,
+}
+
+struct CachedModelResponse {
+    status: http::StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    cached_at: Instant,
+}
+
+static MODEL_RESPONSE_CACHE: OnceLock<Mutex<HashMap<ModelCacheKey, CachedModelResponse>>> =
+    OnceLock::new();
+
+fn model_response_cache() -> &'static Mutex<HashMap<ModelCacheKey, CachedModelResponse>> {
+    MODEL_RESPONSE_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub(super) fn key_for_list(provider: &str, plan: &ModelsListPlan) -> ModelCacheKey {
+    let operation = match plan {
+        ModelsListPlan::Claude2Gemini { .. } => "claude2gemini",
+        ModelsListPlan::Claude2OpenAI(_) => "claude2openai",
+        ModelsListPlan::Gemini2Claude(_) => "gemini2claude",
+        ModelsListPlan::Gemini2OpenAI(_) => "gemini2openai",
+        ModelsListPlan::OpenAI2Claude(_) => "openai2claude",
+        ModelsListPlan::OpenAI2Gemini { .. } => "openai2gemini",
+    };
+    ModelCacheKey {
+        provider: provider.to_string(),
+        operation,
+        model: None,
+    }
+}
+
+pub(super) fn key_for_get(provider: &str, plan: &ModelsGetPlan) -> ModelCacheKey {
+    let (operation, model) = match plan {
+        ModelsGetPlan::Claude2Gemini { request, .. } => {
+            ("claude2gemini", request.path.model_id.clone())
+        }
+        ModelsGetPlan::Claude2OpenAI(request) => ("claude2openai", request.path.model_id.clone()),
+        ModelsGetPlan::Gemini2Claude(request) => ("gemini2claude", request.path.model_id.clone()),
+        ModelsGetPlan::Gemini2OpenAI(request) => ("gemini2openai", request.path.model_id.clone()),
+        ModelsGetPlan::OpenAI2Claude(request) => ("openai2claude", request.path.model_id.clone()),
+        ModelsGetPlan::OpenAI2Gemini { request, .. } => {
+            ("openai2gemini", request.path.model_id.clone())
+        }
+    };
+    ModelCacheKey {
+        provider: provider.to_string(),
+        operation,
+        model: Some(model),
+    }
+}
+
+pub(super) fn get(key: &ModelCacheKey) -> Option<ProxyResponse> {
+    let cache = model_response_cache().lock().unwrap();
+    let entry = cache.get(key)?;
+    if entry.cached_at.elapsed() >= MODEL_CATALOG_TTL {
+        return None;
+    }
+    Some(ProxyResponse::Json {
+        status: entry.status,
+        headers: entry.headers.clone(),
+        body: entry.body.clone(),
+    })
+}
+
+pub(super) fn put(key: ModelCacheKey, response: &ProxyResponse) {
+    let ProxyResponse::Json {
+        status,
+        headers,
+        body,
+    } = response
+    else {
+        return;
+    };
+    model_response_cache().lock().unwrap().insert(
+        key,
+        CachedModelResponse {
+            status: *status,
+            headers: headers.clone(),
+            body: body.clone(),
+            cached_at: Instant::now(),
+        },
+    );
+}
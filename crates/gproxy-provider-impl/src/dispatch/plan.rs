@@ -1,5 +1,7 @@
-use gproxy_provider_core::{GeminiApiVersion, ProxyRequest};
+use gproxy_provider_core::{GeminiApiVersion, PassthroughFormat, ProxyRequest};
 use gproxy_protocol::{gemini, openai};
+use http::HeaderMap;
+use serde_json::Value as JsonValue;
 
 #[derive(Clone, Copy)]
 pub enum UsageKind {
@@ -8,6 +10,10 @@ pub enum UsageKind {
     GeminiGenerate,
     OpenAIChat,
     OpenAIResponses,
+    /// `ProxyRequest::OpenAICompletions` — legacy completions usage fields
+    /// aren't parsed out of the response yet, so this behaves like `None`
+    /// everywhere usage is extracted or tracked.
+    OpenAICompletions,
 }
 
 pub enum DispatchPlan {
@@ -30,6 +36,19 @@ pub enum GenerateContentPlan {
         version: GeminiApiVersion,
         request: openai::create_response::request::CreateResponseRequest,
     },
+    /// Claude -> OpenAI Chat Completions
+    Claude2OpenAIChat {
+        request: gproxy_protocol::claude::create_message::request::CreateMessageRequest,
+    },
+    /// OpenAI Chat Completions -> Claude
+    OpenAIChat2Claude(openai::create_chat_completions::request::CreateChatCompletionRequest),
+    /// Gemini -> OpenAI Chat Completions
+    Gemini2OpenAIChat(gemini::generate_content::request::GenerateContentRequest),
+    /// OpenAI Chat Completions -> Gemini
+    OpenAIChat2Gemini {
+        version: GeminiApiVersion,
+        request: openai::create_chat_completions::request::CreateChatCompletionRequest,
+    },
 }
 
 pub enum StreamContentPlan {
@@ -47,6 +66,19 @@ pub enum StreamContentPlan {
         version: GeminiApiVersion,
         request: openai::create_response::request::CreateResponseRequest,
     },
+    /// Claude stream -> OpenAI Chat Completions
+    Claude2OpenAIChat {
+        request: gproxy_protocol::claude::create_message::request::CreateMessageRequest,
+    },
+    /// OpenAI Chat Completions stream -> Claude
+    OpenAIChat2Claude(openai::create_chat_completions::request::CreateChatCompletionRequest),
+    /// Gemini stream -> OpenAI Chat Completions
+    Gemini2OpenAIChat(gemini::stream_content::request::StreamGenerateContentRequest),
+    /// OpenAI Chat Completions stream -> Gemini
+    OpenAIChat2Gemini {
+        version: GeminiApiVersion,
+        request: openai::create_chat_completions::request::CreateChatCompletionRequest,
+    },
 }
 
 pub enum CountTokensPlan {
@@ -100,12 +132,32 @@ pub enum ModelsGetPlan {
     },
 }
 
+pub enum EmbeddingsPlan {
+    /// OpenAI embeddings -> Gemini (batch embed contents)
+    OpenAI2Gemini {
+        version: GeminiApiVersion,
+        request: openai::embeddings::request::EmbeddingsRequest,
+    },
+}
+
+/// An unmodified request body to forward straight to the matching native
+/// upstream, for a model `dispatch_plan` couldn't match to a known
+/// source/target transform pair — see `ProxyRequest::RawPassthrough`.
+pub struct RawPassthroughPlan {
+    pub target_format: PassthroughFormat,
+    pub body: JsonValue,
+    pub headers: HeaderMap,
+    pub stream: bool,
+}
+
 pub enum TransformPlan {
     GenerateContent(GenerateContentPlan),
     StreamContent(StreamContentPlan),
     CountTokens(CountTokensPlan),
     ModelsList(ModelsListPlan),
     ModelsGet(ModelsGetPlan),
+    Embeddings(EmbeddingsPlan),
+    RawPassthrough(RawPassthroughPlan),
 }
 
 pub(super) fn upstream_usage_for_plan(plan: &TransformPlan) -> UsageKind {
@@ -115,15 +167,25 @@ pub(super) fn upstream_usage_for_plan(plan: &TransformPlan) -> UsageKind {
             GenerateContentPlan::Gemini2Claude(_) => UsageKind::ClaudeMessage,
             GenerateContentPlan::OpenAIResponses2Claude(_) => UsageKind::ClaudeMessage,
             GenerateContentPlan::OpenAIResponses2Gemini { .. } => UsageKind::GeminiGenerate,
+            GenerateContentPlan::Claude2OpenAIChat { .. } => UsageKind::OpenAIChat,
+            GenerateContentPlan::OpenAIChat2Claude(_) => UsageKind::ClaudeMessage,
+            GenerateContentPlan::Gemini2OpenAIChat(_) => UsageKind::OpenAIChat,
+            GenerateContentPlan::OpenAIChat2Gemini { .. } => UsageKind::GeminiGenerate,
         },
         TransformPlan::StreamContent(plan) => match plan {
             StreamContentPlan::Claude2Gemini { .. } => UsageKind::GeminiGenerate,
             StreamContentPlan::Gemini2Claude(_) => UsageKind::ClaudeMessage,
             StreamContentPlan::OpenAIResponses2Claude(_) => UsageKind::ClaudeMessage,
             StreamContentPlan::OpenAIResponses2Gemini { .. } => UsageKind::GeminiGenerate,
+            StreamContentPlan::Claude2OpenAIChat { .. } => UsageKind::OpenAIChat,
+            StreamContentPlan::OpenAIChat2Claude(_) => UsageKind::ClaudeMessage,
+            StreamContentPlan::Gemini2OpenAIChat(_) => UsageKind::OpenAIChat,
+            StreamContentPlan::OpenAIChat2Gemini { .. } => UsageKind::GeminiGenerate,
         },
         TransformPlan::CountTokens(_) => UsageKind::None,
         TransformPlan::ModelsList(_) => UsageKind::None,
         TransformPlan::ModelsGet(_) => UsageKind::None,
+        TransformPlan::Embeddings(_) => UsageKind::None,
+        TransformPlan::RawPassthrough(_) => UsageKind::None,
     }
 }
@@ -1,19 +1,120 @@
+use std::collections::VecDeque;
 use std::io;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use bytes::Bytes;
 use futures_util::stream::unfold;
-use futures_util::StreamExt;
-use tokio::sync::mpsc;
+use futures_util::{SinkExt, StreamExt};
+use http::{HeaderMap, StatusCode};
+use tokio::sync::{mpsc, Notify};
 
+use super::stream::StreamDecoder;
 use gproxy_provider_core::{
-    build_downstream_event, build_upstream_event, CallContext, ProxyResponse, StreamBody,
-    UpstreamPassthroughError, UpstreamRecordMeta,
+    build_downstream_event, build_upstream_event, CallContext, ChunkAction, ChunkOverflowPolicy,
+    ProxyResponse, StreamBody, UpstreamPassthroughError, UpstreamRecordMeta, WebSocketBody,
+    WsFrame, WsOpcode,
 };
-use super::stream::StreamDecoder;
+use gproxy_telemetry::StreamTermination;
 
 use super::plan::UsageKind;
+use super::transform::{generic_token_counts, usage_label};
 use super::usage::{extract_usage_for_kind, UsageState};
 
+/// Fallback cap on recorded streamed-body bytes when `ctx.max_recorded_body_bytes`
+/// isn't set, mirroring `transform.rs`'s `RECORDED_BODY_BUDGET`.
+const DEFAULT_MAX_RECORDED_BODY_BYTES: usize = 1024 * 1024;
+
+/// Default depth of the recording tap's bounded queue, matching the old
+/// `mpsc::channel(256)` capacity it replaces.
+const RECORDING_TAP_CAPACITY: usize = 256;
+
+/// Bounded queue feeding the recorder task from the client-forwarding path.
+/// Under `ChunkOverflowPolicy::Block` pushing awaits room, reproducing the
+/// old coupling between recording and client latency; under `DropNewest`/
+/// `DropOldest` pushing never awaits, so a lagging recorder degrades
+/// recording instead of throttling bytes to the client. `dropped_count`
+/// would ideally ride along on the emitted `UpstreamRecordMeta`, but that
+/// type lives in the (missing from this checkout) `traffic.rs`, so it's
+/// logged instead until that field exists.
+struct ChunkTap {
+    queue: Mutex<VecDeque<Bytes>>,
+    capacity: usize,
+    policy: ChunkOverflowPolicy,
+    dropped: AtomicU64,
+    closed: AtomicBool,
+    notify: Notify,
+}
+
+impl ChunkTap {
+    fn new(capacity: usize, policy: ChunkOverflowPolicy) -> Arc<Self> {
+        Arc::new(Self {
+            queue: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            policy,
+            dropped: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+            notify: Notify::new(),
+        })
+    }
+
+    async fn push(&self, chunk: Bytes) {
+        loop {
+            let mut queue = self.queue.lock().unwrap();
+            if queue.len() < self.capacity {
+                queue.push_back(chunk);
+                drop(queue);
+                self.notify.notify_one();
+                return;
+            }
+            match self.policy {
+                ChunkOverflowPolicy::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                ChunkOverflowPolicy::DropOldest => {
+                    queue.pop_front();
+                    queue.push_back(chunk);
+                    drop(queue);
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    self.notify.notify_one();
+                    return;
+                }
+                ChunkOverflowPolicy::Block => {
+                    drop(queue);
+                    self.notify.notified().await;
+                }
+            }
+        }
+    }
+
+    async fn recv(&self) -> Option<Bytes> {
+        loop {
+            {
+                let mut queue = self.queue.lock().unwrap();
+                if let Some(chunk) = queue.pop_front() {
+                    self.notify.notify_one();
+                    return Some(chunk);
+                }
+                if self.closed.load(Ordering::Acquire) {
+                    return None;
+                }
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.notify.notify_one();
+    }
+
+    fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
 pub(super) async fn record_upstream_only(
     response: ProxyResponse,
     meta: UpstreamRecordMeta,
@@ -21,8 +122,32 @@ pub(super) async fn record_upstream_only(
     ctx: CallContext,
 ) -> Result<ProxyResponse, UpstreamPassthroughError> {
     match &response {
-        ProxyResponse::Json { status, headers, body } => {
+        ProxyResponse::Json {
+            status,
+            headers,
+            body,
+        } => {
+            let usage_kind = usage;
             let usage = extract_usage_for_kind(usage, body);
+            if let Some(usage) = usage.as_ref() {
+                let (prompt_tokens, completion_tokens) = generic_token_counts(usage);
+                if let Some(span) = ctx.telemetry_span.as_ref() {
+                    span.record_tokens(prompt_tokens, completion_tokens);
+                }
+                ctx.metrics.record_tokens(
+                    &meta.provider,
+                    meta.model.as_deref().unwrap_or(""),
+                    usage_label(usage_kind),
+                    prompt_tokens,
+                    completion_tokens,
+                );
+            }
+            ctx.metrics.record_bytes_forwarded(
+                "upstream",
+                &meta.provider,
+                meta.model.as_deref().unwrap_or(""),
+                body.len() as u64,
+            );
             let event = build_upstream_event(
                 Some(ctx.trace_id.clone()),
                 meta,
@@ -46,8 +171,27 @@ pub(super) async fn record_upstream_and_downstream(
     ctx: CallContext,
 ) -> Result<ProxyResponse, UpstreamPassthroughError> {
     match response {
-        ProxyResponse::Json { status, headers, body } => {
+        ProxyResponse::Json {
+            status,
+            headers,
+            body,
+        } => {
+            let usage_kind = usage;
             let usage = extract_usage_for_kind(usage, &body);
+            let metrics_provider = meta.provider.clone();
+            let metrics_model = meta.model.clone().unwrap_or_default();
+            if let Some(usage) = usage.as_ref() {
+                let (prompt_tokens, completion_tokens) = generic_token_counts(usage);
+                ctx.metrics.record_tokens(
+                    &metrics_provider,
+                    &metrics_model,
+                    usage_label(usage_kind),
+                    prompt_tokens,
+                    completion_tokens,
+                );
+            }
+            ctx.metrics
+                .record_bytes_forwarded("upstream", &metrics_provider, &metrics_model, body.len() as u64);
             let upstream_event = build_upstream_event(
                 Some(ctx.trace_id.clone()),
                 meta,
@@ -59,6 +203,12 @@ pub(super) async fn record_upstream_and_downstream(
             );
             ctx.traffic.record_upstream(upstream_event);
             if let Some(downstream_meta) = ctx.downstream_meta {
+                ctx.metrics.record_bytes_forwarded(
+                    "downstream",
+                    &metrics_provider,
+                    &metrics_model,
+                    body.len() as u64,
+                );
                 let downstream_event = build_downstream_event(
                     Some(ctx.trace_id.clone()),
                     downstream_meta,
@@ -69,34 +219,83 @@ pub(super) async fn record_upstream_and_downstream(
                 );
                 ctx.traffic.record_downstream(downstream_event);
             }
-            Ok(ProxyResponse::Json { status, headers, body })
+            Ok(ProxyResponse::Json {
+                status,
+                headers,
+                body,
+            })
         }
-        ProxyResponse::Stream { status, headers, body } => {
-            let (tx, mut rx) = mpsc::channel::<Bytes>(256);
+        ProxyResponse::Stream {
+            status,
+            headers,
+            body,
+        } => {
+            let tap = ChunkTap::new(RECORDING_TAP_CAPACITY, ctx.recording_overflow);
+            let recorder_tap = tap.clone();
             let traffic = ctx.traffic.clone();
             let downstream_meta = ctx.downstream_meta.clone();
             let trace_id = ctx.trace_id.clone();
             let response_headers = headers.clone();
+            let metrics = ctx.metrics.clone();
+            let metrics_provider = meta.provider.clone();
+            let metrics_model = meta.model.clone().unwrap_or_default();
+            let usage_kind_label = usage_label(usage);
+            // Tripped by the forwarding loop below once the client goes
+            // away, so the recorder task (which only sees what already made
+            // it through the tap) still flags the event it flushes as
+            // partial instead of reporting it as a clean completion.
+            let aborted = Arc::new(AtomicBool::new(false));
+            let recorder_aborted = aborted.clone();
+            // Only hold the full body in memory when something will actually
+            // read it back; usage extraction runs incrementally either way.
+            let capture_body = downstream_meta.is_some() || ctx.capture_full_body;
+            let max_body_bytes = ctx
+                .max_recorded_body_bytes
+                .unwrap_or(DEFAULT_MAX_RECORDED_BODY_BYTES);
             tokio::spawn(async move {
+                let started_at = Instant::now();
+                let mut first_byte_at = None;
                 let mut decoder = StreamDecoder::new();
                 let mut response_body = String::new();
+                let mut truncated = false;
+                let mut saw_done = false;
+                // Counts raw wire bytes forwarded, independent of whether
+                // `capture_body` is retaining them, so a sink can track
+                // transfer volume without paying for full-body retention.
+                let mut response_bytes: u64 = 0;
                 let mut usage_state = match usage {
-                    UsageKind::ClaudeMessage => Some(UsageState::Claude(super::usage::ClaudeUsageState::new())),
-                    UsageKind::OpenAIChat => Some(UsageState::OpenAI(super::usage::OpenAIUsageState::new())),
+                    UsageKind::ClaudeMessage => {
+                        Some(UsageState::Claude(super::usage::ClaudeUsageState::new()))
+                    }
+                    UsageKind::OpenAIChat => {
+                        Some(UsageState::OpenAI(super::usage::OpenAIUsageState::new()))
+                    }
                     UsageKind::OpenAIResponses => Some(UsageState::OpenAIResponses(
                         super::usage::OpenAIResponsesUsageState::new(),
                     )),
                     UsageKind::GeminiGenerate => {
                         Some(UsageState::Gemini(super::usage::GeminiUsageState::new()))
                     }
-                    UsageKind::None => None,
+                    UsageKind::None | UsageKind::OpenAICompletions => None,
                 };
-                while let Some(chunk) = rx.recv().await {
+                while let Some(chunk) = recorder_tap.recv().await {
+                    if first_byte_at.is_none() {
+                        first_byte_at = Some(Instant::now());
+                    }
+                    response_bytes += chunk.len() as u64;
                     for data in decoder.push(&chunk) {
                         if data.is_empty() || data == "[DONE]" {
+                            saw_done = saw_done || data == "[DONE]";
                             continue;
                         }
-                        response_body.push_str(&data);
+                        metrics.record_sse_event(&metrics_provider, &metrics_model);
+                        if capture_body {
+                            if response_body.len() < max_body_bytes {
+                                response_body.push_str(&data);
+                            } else {
+                                truncated = true;
+                            }
+                        }
                         if let Some(state) = usage_state.as_mut() {
                             state.push_event(&data);
                         }
@@ -104,19 +303,74 @@ pub(super) async fn record_upstream_and_downstream(
                 }
                 for data in decoder.finish() {
                     if data.is_empty() || data == "[DONE]" {
+                        saw_done = saw_done || data == "[DONE]";
                         continue;
                     }
-                    response_body.push_str(&data);
+                    metrics.record_sse_event(&metrics_provider, &metrics_model);
+                    if capture_body {
+                        if response_body.len() < max_body_bytes {
+                            response_body.push_str(&data);
+                        } else {
+                            truncated = true;
+                        }
+                    }
                     if let Some(state) = usage_state.as_mut() {
                         state.push_event(&data);
                     }
                 }
+                let dropped = recorder_tap.dropped_count();
+                if dropped > 0 {
+                    tracing::warn!(dropped, trace_id = %trace_id, "recording tap dropped chunks");
+                }
+                metrics.record_bytes_forwarded(
+                    "upstream",
+                    &metrics_provider,
+                    &metrics_model,
+                    response_bytes,
+                );
+                if let Some(first_byte_at) = first_byte_at {
+                    metrics.record_time_to_first_byte(
+                        &metrics_provider,
+                        &metrics_model,
+                        (first_byte_at - started_at).as_secs_f64(),
+                    );
+                }
+                metrics.record_stream_duration(
+                    &metrics_provider,
+                    &metrics_model,
+                    started_at.elapsed().as_secs_f64(),
+                );
+                let termination = if recorder_aborted.load(Ordering::Relaxed) {
+                    StreamTermination::ClientDisconnect
+                } else if saw_done && !truncated {
+                    StreamTermination::Clean
+                } else {
+                    StreamTermination::UpstreamError
+                };
+                metrics.record_termination(&metrics_provider, &metrics_model, termination);
                 let usage = usage_state.and_then(|state| state.finish());
+                if let Some(usage) = usage.as_ref() {
+                    let (prompt_tokens, completion_tokens) = generic_token_counts(usage);
+                    metrics.record_tokens(
+                        &metrics_provider,
+                        &metrics_model,
+                        usage_kind_label,
+                        prompt_tokens,
+                        completion_tokens,
+                    );
+                }
                 let body_bytes = if response_body.is_empty() {
                     None
                 } else {
                     Some(Bytes::from(response_body))
                 };
+                // `meta.request_body` is already populated by the provider
+                // call site; `request_bytes`/`response_bytes` aren't fields
+                // on `UpstreamRecordMeta`/`DownstreamRecordMeta` yet (that
+                // struct lives in the missing `traffic.rs`), so the counts
+                // ride along as trailing event args for now, same as
+                // `aborted`/`truncated` above.
+                let request_bytes = meta.request_body.len() as u64;
                 let upstream_event = build_upstream_event(
                     Some(trace_id.clone()),
                     meta,
@@ -125,9 +379,19 @@ pub(super) async fn record_upstream_and_downstream(
                     body_bytes.as_ref(),
                     true,
                     usage,
+                    recorder_aborted.load(Ordering::Relaxed),
+                    truncated,
+                    request_bytes,
+                    response_bytes,
                 );
                 traffic.record_upstream(upstream_event);
                 if let Some(downstream_meta) = downstream_meta {
+                    metrics.record_bytes_forwarded(
+                        "downstream",
+                        &metrics_provider,
+                        &metrics_model,
+                        response_bytes,
+                    );
                     let downstream_event = build_downstream_event(
                         Some(trace_id.clone()),
                         downstream_meta,
@@ -135,23 +399,69 @@ pub(super) async fn record_upstream_and_downstream(
                         &response_headers,
                         body_bytes.as_ref(),
                         true,
+                        recorder_aborted.load(Ordering::Relaxed),
+                        truncated,
+                        response_bytes,
                     );
                     traffic.record_downstream(downstream_event);
                 }
             });
-            let stream = unfold((body.stream, tx), |(mut upstream, tx)| async move {
-                match upstream.next().await {
-                    Some(Ok(bytes)) => {
-                        let _ = tx.send(bytes.clone()).await;
-                        Some((Ok(bytes), (upstream, tx)))
+            let interceptors = ctx.interceptors.clone();
+            let cancellation = ctx.cancellation.clone();
+            let stream = unfold(
+                (body.stream, tap, interceptors, cancellation, aborted),
+                |(mut upstream, tap, interceptors, cancellation, aborted)| async move {
+                    loop {
+                        tokio::select! {
+                            // Client is gone: stop pulling from upstream and
+                            // close the tap so the recorder task drains and
+                            // flushes the partial body instead of waiting on
+                            // chunks that will never come.
+                            _ = cancellation.cancelled() => {
+                                aborted.store(true, Ordering::Relaxed);
+                                tap.close();
+                                return None;
+                            }
+                            chunk = upstream.next() => match chunk {
+                                Some(Ok(mut bytes)) => {
+                                    // Non-blocking under the default overflow
+                                    // policy; only `ChunkOverflowPolicy::Block`
+                                    // makes this wait on the recorder.
+                                    tap.push(bytes.clone()).await;
+                                    let mut dropped = false;
+                                    for interceptor in interceptors.iter() {
+                                        match interceptor.on_stream_chunk(&mut bytes).await {
+                                            ChunkAction::Forward => {}
+                                            ChunkAction::Replace(replacement) => bytes = replacement,
+                                            ChunkAction::Drop => {
+                                                dropped = true;
+                                                break;
+                                            }
+                                        }
+                                    }
+                                    if dropped {
+                                        continue;
+                                    }
+                                    return Some((
+                                        Ok(bytes),
+                                        (upstream, tap, interceptors, cancellation, aborted),
+                                    ));
+                                }
+                                Some(Err(err)) => {
+                                    return Some((
+                                        Err(io::Error::new(io::ErrorKind::Other, err.to_string())),
+                                        (upstream, tap, interceptors, cancellation, aborted),
+                                    ))
+                                }
+                                None => {
+                                    tap.close();
+                                    return None;
+                                }
+                            },
+                        }
                     }
-                    Some(Err(err)) => Some((
-                        Err(io::Error::new(io::ErrorKind::Other, err.to_string())),
-                        (upstream, tx),
-                    )),
-                    None => None,
-                }
-            });
+                },
+            );
             Ok(ProxyResponse::Stream {
                 status,
                 headers,
@@ -160,3 +470,159 @@ pub(super) async fn record_upstream_and_downstream(
         }
     }
 }
+
+/// Tap both directions of an upstream WebSocket connection and record them,
+/// the WS analogue of `record_upstream_and_downstream`'s `Stream` arm.
+///
+/// There's no `ProxyResponse::WebSocket` variant for this to match on yet —
+/// that's a `response.rs` change out of reach in this checkout — so this
+/// takes the upstream `WebSocketBody` directly and hands back a tapped one
+/// for the caller to bridge to the client-facing socket once that variant
+/// and the handler-side upgrade exist.
+pub(super) async fn record_websocket(
+    body: WebSocketBody,
+    meta: UpstreamRecordMeta,
+    usage: UsageKind,
+    ctx: CallContext,
+) -> Result<WebSocketBody, UpstreamPassthroughError> {
+    let (client_tx, mut client_rx) = mpsc::channel::<WsFrame>(256);
+    let (upstream_tx, mut upstream_rx) = mpsc::channel::<WsFrame>(256);
+    let traffic = ctx.traffic.clone();
+    let downstream_meta = ctx.downstream_meta.clone();
+    let trace_id = ctx.trace_id.clone();
+
+    tokio::spawn(async move {
+        let mut usage_state = match usage {
+            UsageKind::ClaudeMessage => {
+                Some(UsageState::Claude(super::usage::ClaudeUsageState::new()))
+            }
+            UsageKind::OpenAIChat => {
+                Some(UsageState::OpenAI(super::usage::OpenAIUsageState::new()))
+            }
+            UsageKind::OpenAIResponses => Some(UsageState::OpenAIResponses(
+                super::usage::OpenAIResponsesUsageState::new(),
+            )),
+            UsageKind::GeminiGenerate => {
+                Some(UsageState::Gemini(super::usage::GeminiUsageState::new()))
+            }
+            UsageKind::None | UsageKind::OpenAICompletions => None,
+        };
+        let mut close_code = None;
+        let mut upstream_frames = String::new();
+        let mut downstream_frames = String::new();
+
+        loop {
+            tokio::select! {
+                frame = client_rx.recv() => {
+                    match frame {
+                        Some(frame) => {
+                            if frame.opcode == WsOpcode::Text {
+                                if let Ok(text) = std::str::from_utf8(&frame.payload) {
+                                    downstream_frames.push_str(text);
+                                }
+                            }
+                            if frame.opcode == WsOpcode::Close {
+                                close_code = frame.close_code;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+                frame = upstream_rx.recv() => {
+                    match frame {
+                        Some(frame) => {
+                            if frame.opcode == WsOpcode::Text {
+                                if let Ok(text) = std::str::from_utf8(&frame.payload) {
+                                    upstream_frames.push_str(text);
+                                    if let Some(state) = usage_state.as_mut() {
+                                        state.push_event(text);
+                                    }
+                                }
+                            }
+                            if frame.opcode == WsOpcode::Close {
+                                close_code = frame.close_code;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+                else => break,
+            }
+        }
+
+        let usage = usage_state.and_then(|state| state.finish());
+        let status = close_code
+            .and_then(|code| StatusCode::from_u16(code).ok())
+            .unwrap_or(StatusCode::SWITCHING_PROTOCOLS);
+        let headers = HeaderMap::new();
+        let upstream_body = if upstream_frames.is_empty() {
+            None
+        } else {
+            Some(Bytes::from(upstream_frames))
+        };
+        let upstream_event = build_upstream_event(
+            Some(trace_id.clone()),
+            meta,
+            status,
+            &headers,
+            upstream_body.as_ref(),
+            true,
+            usage,
+            false,
+            false,
+        );
+        traffic.record_upstream(upstream_event);
+
+        if let Some(downstream_meta) = downstream_meta {
+            let downstream_body = if downstream_frames.is_empty() {
+                None
+            } else {
+                Some(Bytes::from(downstream_frames))
+            };
+            let downstream_event = build_downstream_event(
+                Some(trace_id.clone()),
+                downstream_meta,
+                status,
+                &headers,
+                downstream_body.as_ref(),
+                true,
+                false,
+                false,
+            );
+            traffic.record_downstream(downstream_event);
+        }
+    });
+
+    let WebSocketBody {
+        sink: upstream_sink,
+        stream: upstream_stream,
+    } = body;
+
+    let tapped_stream = unfold(
+        (upstream_stream, upstream_tx),
+        |(mut upstream, tap)| async move {
+            match upstream.next().await {
+                Some(Ok(frame)) => {
+                    let _ = tap.send(frame.clone()).await;
+                    Some((Ok(frame), (upstream, tap)))
+                }
+                Some(Err(err)) => Some((Err(err), (upstream, tap))),
+                None => None,
+            }
+        },
+    );
+
+    let tapped_sink = futures_util::sink::unfold(
+        (upstream_sink, client_tx),
+        |(mut sink, tap), frame: WsFrame| async move {
+            let _ = tap.send(frame.clone()).await;
+            sink.send(frame).await?;
+            Ok::<_, io::Error>((sink, tap))
+        },
+    );
+
+    Ok(WebSocketBody {
+        sink: Box::pin(tapped_sink),
+        stream: Box::pin(tapped_stream),
+    })
+}
@@ -0,0 +1,171 @@
+use std::time::{Duration, SystemTime};
+
+use http::header::RETRY_AFTER;
+use http::StatusCode;
+use rand::Rng;
+
+use gproxy_provider_core::{
+    build_upstream_event, CallContext, ProxyRequest, ProxyResponse, RetryPolicy,
+    UpstreamPassthroughError, UpstreamRecordMeta,
+};
+
+use super::{DispatchProvider, UpstreamOk};
+
+/// Wraps `DispatchProvider::call_native`, retrying a transient failure
+/// (HTTP 429/5xx, or a connection-level error) with exponential backoff and
+/// jitter. A no-op when `ctx.retry` is `None` — retries are opt-in per
+/// request. Only resends requests that are safe to resend: non-streaming
+/// calls, or streaming calls that haven't yielded any bytes downstream yet,
+/// so callers must invoke this before awaiting anything from the returned
+/// stream.
+pub(super) async fn call_native_with_retry<P: DispatchProvider>(
+    provider: &P,
+    req: ProxyRequest,
+    ctx: CallContext,
+) -> Result<UpstreamOk, UpstreamPassthroughError> {
+    let Some(policy) = ctx.retry else {
+        return provider.call_native(req, ctx).await;
+    };
+    let resendable = is_resendable(&req);
+    let mut attempt: u32 = 0;
+    loop {
+        attempt += 1;
+        match provider.call_native(req.clone(), ctx.clone()).await {
+            Ok(ok) => return Ok(ok),
+            Err(err) if resendable && attempt < policy.max_attempts && is_transient(&err) => {
+                record_failed_attempt(provider, &req, &ctx, &err, attempt);
+                let delay = retry_after(&err).unwrap_or_else(|| backoff_delay(&policy, attempt));
+                tokio::time::sleep(delay).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Retries the initial upstream connection for a streaming call with the
+/// same exponential backoff as [`call_native_with_retry`], but — unlike
+/// that function — resends streaming request variants too. That's only
+/// safe because this is for the "no downstream bytes yet" window
+/// `call_native_with_retry`'s doc comment calls out: before the first
+/// transformed byte reaches the client, replaying the upstream request
+/// is indistinguishable from it having taken longer to connect.
+pub(super) async fn call_native_with_stream_failover<P: DispatchProvider>(
+    provider: &P,
+    req: ProxyRequest,
+    ctx: CallContext,
+) -> Result<UpstreamOk, UpstreamPassthroughError> {
+    let Some(policy) = ctx.retry else {
+        return provider.call_native(req, ctx).await;
+    };
+    let mut attempt: u32 = 0;
+    loop {
+        attempt += 1;
+        match provider.call_native(req.clone(), ctx.clone()).await {
+            Ok(ok) => return Ok(ok),
+            Err(err) if attempt < policy.max_attempts && is_transient(&err) => {
+                record_failed_attempt(provider, &req, &ctx, &err, attempt);
+                let delay = retry_after(&err).unwrap_or_else(|| backoff_delay(&policy, attempt));
+                tokio::time::sleep(delay).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn is_resendable(req: &ProxyRequest) -> bool {
+    !matches!(
+        req,
+        ProxyRequest::ClaudeMessagesStream(_)
+            | ProxyRequest::GeminiGenerateStream { .. }
+            | ProxyRequest::OpenAIChatStream(_)
+            | ProxyRequest::OpenAIResponsesStream(_)
+    )
+}
+
+fn is_transient(err: &UpstreamPassthroughError) -> bool {
+    matches!(err.status, StatusCode::TOO_MANY_REQUESTS) || err.status.is_server_error()
+}
+
+fn retry_after(err: &UpstreamPassthroughError) -> Option<Duration> {
+    let value = err.headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let when = httpdate::parse_http_date(value).ok()?;
+    when.duration_since(SystemTime::now()).ok()
+}
+
+/// Exponential backoff with equal jitter: half the capped delay is fixed,
+/// half is randomized, so a burst of retrying callers doesn't resynchronize
+/// into another thundering herd against the same upstream.
+fn backoff_delay(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let exp = 2u32.saturating_pow(attempt.saturating_sub(1));
+    let uncapped = policy.base_delay.saturating_mul(exp);
+    let capped = uncapped.min(policy.max_delay);
+    let half_millis = capped.as_millis() as u64 / 2;
+    let jitter_millis = if half_millis == 0 {
+        0
+    } else {
+        rand::rng().random_range(0..=half_millis)
+    };
+    Duration::from_millis(half_millis + jitter_millis)
+}
+
+fn record_failed_attempt<P: DispatchProvider>(
+    provider: &P,
+    req: &ProxyRequest,
+    ctx: &CallContext,
+    err: &UpstreamPassthroughError,
+    attempt: u32,
+) {
+    let meta = UpstreamRecordMeta {
+        provider: provider.name().to_string(),
+        provider_id: ctx
+            .downstream_meta
+            .as_ref()
+            .and_then(|meta| meta.provider_id),
+        credential_id: None,
+        operation: format!("{}.retry_attempt_{attempt}", request_operation(req)),
+        model: None,
+        request_method: "POST".to_string(),
+        request_path: request_operation(req).to_string(),
+        request_query: None,
+        request_headers: "{}".to_string(),
+        request_body: String::new(),
+    };
+    let event = build_upstream_event(
+        Some(ctx.trace_id.clone()),
+        meta,
+        err.status,
+        &err.headers,
+        Some(&err.body),
+        false,
+        None,
+    );
+    ctx.traffic.record_upstream(event);
+}
+
+pub(super) fn request_operation(req: &ProxyRequest) -> &'static str {
+    match req {
+        ProxyRequest::ClaudeMessages(_) => "claude.messages",
+        ProxyRequest::ClaudeMessagesStream(_) => "claude.messages.stream",
+        ProxyRequest::ClaudeCountTokens(_) => "claude.count_tokens",
+        ProxyRequest::ClaudeModelsList(_) => "claude.models.list",
+        ProxyRequest::ClaudeModelsGet(_) => "claude.models.get",
+        ProxyRequest::GeminiGenerate { .. } => "gemini.generate",
+        ProxyRequest::GeminiGenerateStream { .. } => "gemini.generate.stream",
+        ProxyRequest::GeminiCountTokens { .. } => "gemini.count_tokens",
+        ProxyRequest::GeminiModelsList { .. } => "gemini.models.list",
+        ProxyRequest::GeminiModelsGet { .. } => "gemini.models.get",
+        ProxyRequest::OpenAIChat(_) => "openai.chat",
+        ProxyRequest::OpenAIChatStream(_) => "openai.chat.stream",
+        ProxyRequest::OpenAIResponses(_) => "openai.responses",
+        ProxyRequest::OpenAIResponsesStream(_) => "openai.responses.stream",
+        ProxyRequest::OpenAIInputTokens(_) => "openai.input_tokens",
+        ProxyRequest::OpenAIModelsList(_) => "openai.models.list",
+        ProxyRequest::OpenAIModelsGet(_) => "openai.models.get",
+        ProxyRequest::VertexRawPredict { .. } => "vertex.raw_predict",
+        ProxyRequest::RawPassthrough { .. } => "raw_passthrough",
+        ProxyRequest::OpenAICompletions(_) => "openai.completions",
+    }
+}
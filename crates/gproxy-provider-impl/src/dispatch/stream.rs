@@ -0,0 +1,106 @@
+//! SSE framing helpers shared by the native stream-recording path
+//! (`record.rs`) and every cross-protocol stream transform in
+//! `transform.rs`: [`StreamDecoder`] turns raw upstream chunks into decoded
+//! `data:` payload strings, [`sse_json_bytes`] does the inverse for a
+//! downstream-bound value, and the `gemini_*` helpers bridge the
+//! one-shot/streaming request and response shape Gemini splits into two
+//! distinct endpoints.
+
+use bytes::Bytes;
+use serde::Serialize;
+
+use gproxy_protocol::gemini;
+use gproxy_protocol::sse::SseParser;
+
+/// Decodes a stream of raw upstream chunks into complete SSE `data:`
+/// payloads, buffering any trailing partial event across calls to `push`.
+/// Only the `data:` field is surfaced — callers that also need `id:`/
+/// `retry:` (e.g. resume support) scan the raw bytes independently.
+pub(super) struct StreamDecoder {
+    parser: SseParser,
+}
+
+impl StreamDecoder {
+    pub(super) fn new() -> Self {
+        Self {
+            parser: SseParser::new(),
+        }
+    }
+
+    pub(super) fn push(&mut self, chunk: &Bytes) -> Vec<String> {
+        self.parser
+            .push_bytes(chunk)
+            .into_iter()
+            .map(|event| event.data)
+            .collect()
+    }
+
+    /// Flushes whatever partial event is left buffered once the upstream
+    /// body has ended.
+    pub(super) fn finish(&mut self) -> Vec<String> {
+        self.parser.finish().into_iter().map(|event| event.data).collect()
+    }
+}
+
+/// Serializes `value` as a single `data: <json>\n\n` SSE frame.
+pub(super) fn sse_json_bytes<T: Serialize>(value: &T) -> Option<Bytes> {
+    let payload = serde_json::to_vec(value).ok()?;
+    let mut data = Vec::with_capacity(payload.len() + 8);
+    data.extend_from_slice(b"data: ");
+    data.extend_from_slice(&payload);
+    data.extend_from_slice(b"\n\n");
+    Some(Bytes::from(data))
+}
+
+pub(super) fn now_epoch_seconds() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// `GenerateContentRequest` and `StreamGenerateContentRequest` carry the
+/// same `path`/`body` shape — Gemini's REST API only distinguishes them by
+/// endpoint (`:generateContent` vs. `:streamGenerateContent`), not payload.
+pub(super) fn gemini_stream_to_generate(
+    request: gemini::stream_content::request::StreamGenerateContentRequest,
+) -> gemini::generate_content::request::GenerateContentRequest {
+    gemini::generate_content::request::GenerateContentRequest {
+        path: request.path,
+        body: request.body,
+    }
+}
+
+/// Inverse of [`gemini_stream_to_generate`], for transform paths that build
+/// a one-shot Gemini request and then need to issue it against the
+/// streaming endpoint.
+pub(super) fn gemini_generate_to_stream(
+    request: gemini::generate_content::request::GenerateContentRequest,
+) -> gemini::stream_content::request::StreamGenerateContentRequest {
+    gemini::stream_content::request::StreamGenerateContentRequest {
+        path: request.path,
+        body: request.body,
+    }
+}
+
+/// Decodes one already-`StreamDecoder`-extracted `data:` payload into the
+/// `GenerateContentResponse`(s) it carries. Gemini's streaming endpoint
+/// sends each chunk as a single JSON object in most deployments, but some
+/// front ends (and the REST `:streamGenerateContent` docs themselves) wrap
+/// chunks in a top-level JSON array, so both shapes are handled here rather
+/// than at every call site.
+pub(super) fn parse_gemini_stream_payload(
+    data: &str,
+) -> Vec<gemini::generate_content::response::GenerateContentResponse> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(data) else {
+        return Vec::new();
+    };
+    match value {
+        serde_json::Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| serde_json::from_value(item).ok())
+            .collect(),
+        other => serde_json::from_value(other).ok().into_iter().collect(),
+    }
+}
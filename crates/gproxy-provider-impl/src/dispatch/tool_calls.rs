@@ -0,0 +1,194 @@
+//! Tool/function-call counting for streamed responses, mirroring the
+//! `push_event`/`finish` shape of `ClaudeUsageState`/`OpenAIUsageState`/
+//! `OpenAIResponsesUsageState`/`GeminiUsageState` in `super::usage`. Adding
+//! `tool_call_count`/`tool_call_names` to `TrafficUsage` and threading them
+//! through `build_upstream_event`/`build_downstream_event` still needs
+//! doing in `super::usage` and `super::traffic` — the latter isn't part of
+//! this checkout, so this file holds the per-protocol extraction on its
+//! own, ready for that wiring to be mechanical once `traffic.rs` exists.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Tool/function-call activity observed over the course of one streamed
+/// response: how many distinct calls were emitted and what they were named.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ToolCallActivity {
+    pub(super) tool_call_count: i64,
+    pub(super) tool_call_names: Vec<String>,
+}
+
+fn activity_from_names(names: Vec<String>) -> ToolCallActivity {
+    ToolCallActivity {
+        tool_call_count: names.len() as i64,
+        tool_call_names: names,
+    }
+}
+
+/// Claude: watches `content_block_start` events whose `content_block.type`
+/// is `"tool_use"` and records `content_block.name`.
+#[derive(Debug, Default)]
+pub(super) struct ClaudeToolCallState {
+    names: Vec<String>,
+}
+
+impl ClaudeToolCallState {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn push_event(&mut self, data: &str) {
+        let Ok(value) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        if value.get("type").and_then(Value::as_str) != Some("content_block_start") {
+            return;
+        }
+        let block = value.get("content_block");
+        if block.and_then(|b| b.get("type")).and_then(Value::as_str) != Some("tool_use") {
+            return;
+        }
+        if let Some(name) = block.and_then(|b| b.get("name")).and_then(Value::as_str) {
+            self.names.push(name.to_string());
+        }
+    }
+
+    pub(super) fn finish(self) -> ToolCallActivity {
+        activity_from_names(self.names)
+    }
+}
+
+/// OpenAI Chat Completions: accumulates `choices[].delta.tool_calls[]`
+/// entries keyed by `index`, since a call's name can arrive on one chunk
+/// while its arguments keep streaming in on later chunks under the same
+/// index.
+#[derive(Debug, Default)]
+pub(super) struct OpenAIChatToolCallState {
+    names_by_index: HashMap<i64, String>,
+}
+
+impl OpenAIChatToolCallState {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn push_event(&mut self, data: &str) {
+        let Ok(value) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        let Some(choices) = value.get("choices").and_then(Value::as_array) else {
+            return;
+        };
+        for choice in choices {
+            let Some(tool_calls) = choice
+                .get("delta")
+                .and_then(|delta| delta.get("tool_calls"))
+                .and_then(Value::as_array)
+            else {
+                continue;
+            };
+            for call in tool_calls {
+                let Some(index) = call.get("index").and_then(Value::as_i64) else {
+                    continue;
+                };
+                if let Some(name) = call
+                    .get("function")
+                    .and_then(|function| function.get("name"))
+                    .and_then(Value::as_str)
+                {
+                    self.names_by_index
+                        .entry(index)
+                        .or_insert_with(|| name.to_string());
+                }
+            }
+        }
+    }
+
+    pub(super) fn finish(self) -> ToolCallActivity {
+        let mut by_index: Vec<_> = self.names_by_index.into_iter().collect();
+        by_index.sort_by_key(|(index, _)| *index);
+        activity_from_names(by_index.into_iter().map(|(_, name)| name).collect())
+    }
+}
+
+/// OpenAI Responses API: watches `response.output[]` items of type
+/// `function_call`.
+#[derive(Debug, Default)]
+pub(super) struct OpenAIResponsesToolCallState {
+    names: Vec<String>,
+}
+
+impl OpenAIResponsesToolCallState {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn push_event(&mut self, data: &str) {
+        let Ok(value) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        let Some(items) = value
+            .get("response")
+            .and_then(|response| response.get("output"))
+            .and_then(Value::as_array)
+        else {
+            return;
+        };
+        for item in items {
+            if item.get("type").and_then(Value::as_str) != Some("function_call") {
+                continue;
+            }
+            if let Some(name) = item.get("name").and_then(Value::as_str) {
+                self.names.push(name.to_string());
+            }
+        }
+    }
+
+    pub(super) fn finish(self) -> ToolCallActivity {
+        activity_from_names(self.names)
+    }
+}
+
+/// Gemini: scans `candidates[].content.parts[]` for `functionCall.name`.
+#[derive(Debug, Default)]
+pub(super) struct GeminiToolCallState {
+    names: Vec<String>,
+}
+
+impl GeminiToolCallState {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn push_event(&mut self, data: &str) {
+        let Ok(value) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        let Some(candidates) = value.get("candidates").and_then(Value::as_array) else {
+            return;
+        };
+        for candidate in candidates {
+            let Some(parts) = candidate
+                .get("content")
+                .and_then(|content| content.get("parts"))
+                .and_then(Value::as_array)
+            else {
+                continue;
+            };
+            for part in parts {
+                if let Some(name) = part
+                    .get("functionCall")
+                    .and_then(|call| call.get("name"))
+                    .and_then(Value::as_str)
+                {
+                    self.names.push(name.to_string());
+                }
+            }
+        }
+    }
+
+    pub(super) fn finish(self) -> ToolCallActivity {
+        activity_from_names(self.names)
+    }
+}
@@ -1,47 +1,57 @@
 use std::collections::VecDeque;
 use std::io;
+use std::time::Duration;
 
 use bytes::Bytes;
 use futures_util::stream::unfold;
 use futures_util::StreamExt;
 use http::header::{CONTENT_LENGTH, TRANSFER_ENCODING};
-use http::HeaderMap;
+use http::{HeaderMap, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use serde_json::Value as JsonValue;
 
-use gproxy_provider_core::{
-    build_downstream_event, CallContext, ProxyRequest, ProxyResponse, StreamBody,
-    UpstreamPassthroughError,
-};
 use gproxy_protocol::claude::create_message::stream::BetaStreamEvent;
 use gproxy_protocol::claude::get_model::response::GetModelResponse as ClaudeGetModelResponse;
 use gproxy_protocol::gemini;
 use gproxy_protocol::openai;
 use gproxy_protocol::sse::SseParser;
+use gproxy_provider_core::{
+    build_downstream_event, CallContext, LiveTrafficChunk, ProxyRequest, ProxyResponse, StreamBody,
+    TrafficDirection, UpstreamPassthroughError,
+};
+use gproxy_telemetry::{StreamTermination, TransformSpan};
 use gproxy_transform::count_tokens;
+use gproxy_transform::embeddings;
 use gproxy_transform::generate_content;
 use gproxy_transform::generate_content::claude2gemini::stream::GeminiToClaudeStreamState;
+use gproxy_transform::generate_content::claude2openai_chat::stream::OpenAIChatToClaudeStreamState;
 use gproxy_transform::generate_content::claude2openai_response::stream::ClaudeToOpenAIResponseStreamState;
 use gproxy_transform::generate_content::gemini2claude::stream::ClaudeToGeminiStreamState;
+use gproxy_transform::generate_content::gemini2openai_chat::stream::OpenAIChatToGeminiStreamState;
 use gproxy_transform::generate_content::gemini2openai_response::stream::GeminiToOpenAIResponseStreamState;
+use gproxy_transform::generate_content::openai_chat2claude::stream::ClaudeToOpenAIChatStreamState;
+use gproxy_transform::generate_content::openai_chat2gemini::stream::GeminiToOpenAIChatStreamState;
 use gproxy_transform::generate_content::openai_response2claude::stream::OpenAIResponseToClaudeStreamState;
 use gproxy_transform::generate_content::openai_response2gemini::stream::OpenAIResponseToGeminiStreamState;
 use gproxy_transform::get_model;
 use gproxy_transform::list_models;
 
+use super::call_native_with_retry;
+use super::call_native_with_stream_failover;
+use super::model_cache;
+use super::plan::upstream_usage_for_plan;
 use super::plan::{
-    CountTokensPlan, GenerateContentPlan, ModelsGetPlan, ModelsListPlan, StreamContentPlan,
-    TransformPlan, UsageKind,
+    CountTokensPlan, EmbeddingsPlan, GenerateContentPlan, ModelsGetPlan, ModelsListPlan,
+    RawPassthroughPlan, StreamContentPlan, TransformPlan, UsageKind,
 };
-use super::plan::upstream_usage_for_plan;
 use super::record::record_upstream_only;
 use super::stream::{
     gemini_generate_to_stream, gemini_stream_to_generate, now_epoch_seconds,
     parse_gemini_stream_payload, sse_json_bytes, StreamDecoder,
 };
-use super::usage::{
-    ClaudeUsageState, GeminiUsageState, UsageState, map_usage_for_kind,
-};
+use super::usage::{map_usage_for_kind, ClaudeUsageState, GeminiUsageState, UsageState};
+use super::ws::{ws_complete, ws_connection_ack, ws_error, ws_json_bytes, StreamFraming};
 use super::{DispatchProvider, UpstreamOk};
 
 pub(super) async fn dispatch_transform<P: DispatchProvider>(
@@ -50,107 +60,209 @@ pub(super) async fn dispatch_transform<P: DispatchProvider>(
     usage: UsageKind,
     ctx: CallContext,
 ) -> Result<ProxyResponse, UpstreamPassthroughError> {
-    let mut ctx_native = ctx.clone();
-    ctx_native.downstream_meta = None;
     let _downstream_usage = usage;
     let usage = upstream_usage_for_plan(&plan);
 
+    let (span_source, span_target) = plan_label(&plan);
+    let span: std::sync::Arc<dyn TransformSpan> = std::sync::Arc::from(
+        ctx.telemetry
+            .start_transform(span_source, span_target, usage_label(usage), None),
+    );
+    let mut ctx = ctx;
+    ctx.telemetry_span = Some(span);
+
+    let mut ctx_native = ctx.clone();
+    ctx_native.downstream_meta = None;
+
     match plan {
         TransformPlan::GenerateContent(plan) => match plan {
             GenerateContentPlan::Claude2Gemini { version, request } => {
                 let gemini_request =
                     generate_content::claude2gemini::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(
-                        ProxyRequest::GeminiGenerate {
-                            version,
-                            request: gemini_request,
-                        },
-                        ctx_native,
-                    )
-                    .await?;
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::GeminiGenerate {
+                        version,
+                        request: gemini_request,
+                    },
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     generate_content::claude2gemini::response::transform_response,
+                    claude_error_body,
                 )
             }
             GenerateContentPlan::Claude2OpenAIResponses(request) => {
                 let openai_request =
                     generate_content::claude2openai_response::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::OpenAIResponses(openai_request), ctx_native)
-                    .await?;
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::OpenAIResponses(openai_request),
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     generate_content::openai_response2claude::response::transform_response,
+                    openai_error_body,
                 )
             }
             GenerateContentPlan::Gemini2Claude(request) => {
                 let claude_request =
                     generate_content::gemini2claude::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::ClaudeMessages(claude_request), ctx_native)
-                    .await?;
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::ClaudeMessages(claude_request),
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     generate_content::gemini2claude::response::transform_response,
+                    gemini_error_body,
                 )
             }
             GenerateContentPlan::Gemini2OpenAIResponses(request) => {
                 let openai_request =
                     generate_content::gemini2openai_response::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::OpenAIResponses(openai_request), ctx_native)
-                    .await?;
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::OpenAIResponses(openai_request),
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     generate_content::openai_response2gemini::response::transform_response,
+                    openai_error_body,
                 )
             }
             GenerateContentPlan::OpenAIResponses2Claude(request) => {
                 let claude_request =
                     generate_content::openai_response2claude::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::ClaudeMessages(claude_request), ctx_native)
-                    .await?;
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::ClaudeMessages(claude_request),
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     generate_content::openai_response2claude::response::transform_response,
+                    openai_error_body,
                 )
             }
             GenerateContentPlan::OpenAIResponses2Gemini { version, request } => {
                 let gemini_request =
                     generate_content::openai_response2gemini::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(
-                        ProxyRequest::GeminiGenerate {
-                            version,
-                            request: gemini_request,
-                        },
-                        ctx_native,
-                    )
-                    .await?;
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::GeminiGenerate {
+                        version,
+                        request: gemini_request,
+                    },
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     generate_content::openai_response2gemini::response::transform_response,
+                    openai_error_body,
+                )
+            }
+            GenerateContentPlan::Claude2OpenAIChat { request } => {
+                let openai_request =
+                    generate_content::claude2openai_chat::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::OpenAIChat(openai_request),
+                    ctx_native,
+                )
+                .await?;
+                let upstream_recorded =
+                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+                transform_json_response(
+                    upstream_recorded,
+                    ctx,
+                    generate_content::claude2openai_chat::response::transform_response,
+                    claude_error_body,
+                )
+            }
+            GenerateContentPlan::OpenAIChat2Claude(request) => {
+                let claude_request =
+                    generate_content::openai_chat2claude::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::ClaudeMessages(claude_request),
+                    ctx_native,
+                )
+                .await?;
+                let upstream_recorded =
+                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+                transform_json_response(
+                    upstream_recorded,
+                    ctx,
+                    generate_content::openai_chat2claude::response::transform_response,
+                    openai_error_body,
+                )
+            }
+            GenerateContentPlan::Gemini2OpenAIChat(request) => {
+                let openai_request =
+                    generate_content::gemini2openai_chat::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::OpenAIChat(openai_request),
+                    ctx_native,
+                )
+                .await?;
+                let upstream_recorded =
+                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+                transform_json_response(
+                    upstream_recorded,
+                    ctx,
+                    generate_content::gemini2openai_chat::response::transform_response,
+                    gemini_error_body,
+                )
+            }
+            GenerateContentPlan::OpenAIChat2Gemini { version, request } => {
+                let gemini_request =
+                    generate_content::openai_chat2gemini::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::GeminiGenerate {
+                        version,
+                        request: gemini_request,
+                    },
+                    ctx_native,
+                )
+                .await?;
+                let upstream_recorded =
+                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+                transform_json_response(
+                    upstream_recorded,
+                    ctx,
+                    generate_content::openai_chat2gemini::response::transform_response,
+                    openai_error_body,
                 )
             }
         },
@@ -178,6 +290,9 @@ pub(super) async fn dispatch_transform<P: DispatchProvider>(
                                 .collect()
                         }
                     },
+                    claude_error_event,
+                    claude_usage_events,
+                    None,
                 )
                 .await
             }
@@ -200,6 +315,8 @@ pub(super) async fn dispatch_transform<P: DispatchProvider>(
                                 .collect()
                         }
                     },
+                    claude_error_event,
+                    claude_usage_events,
                 )
                 .await
             }
@@ -213,16 +330,15 @@ pub(super) async fn dispatch_transform<P: DispatchProvider>(
                     ctx_native,
                     ctx,
                     usage.clone(),
+                    StreamFraming::Sse,
                     || {
                         let mut state = ClaudeToGeminiStreamState::new();
-                        move |event: BetaStreamEvent| -> Vec<Bytes> {
-                            state
-                                .transform_event(event)
-                                .into_iter()
-                                .filter_map(|response| sse_json_bytes(&response))
-                                .collect()
+                        move |event: BetaStreamEvent| {
+                            state.transform_event(event).into_iter().collect::<Vec<_>>()
                         }
                     },
+                    gemini_error_chunk,
+                    gemini_usage_event,
                 )
                 .await
             }
@@ -246,6 +362,8 @@ pub(super) async fn dispatch_transform<P: DispatchProvider>(
                                 .collect()
                         }
                     },
+                    gemini_error_chunk,
+                    gemini_usage_event,
                 )
                 .await
             }
@@ -258,17 +376,16 @@ pub(super) async fn dispatch_transform<P: DispatchProvider>(
                     ctx_native,
                     ctx,
                     usage.clone(),
+                    StreamFraming::Sse,
                     || {
                         let created = now_epoch_seconds();
                         let mut state = ClaudeToOpenAIResponseStreamState::new(created);
-                        move |event: BetaStreamEvent| -> Vec<Bytes> {
-                            state
-                                .transform_event(event)
-                                .into_iter()
-                                .filter_map(|response| sse_json_bytes(&response))
-                                .collect()
+                        move |event: BetaStreamEvent| {
+                            state.transform_event(event).into_iter().collect::<Vec<_>>()
                         }
                     },
+                    openai_responses_error_event,
+                    openai_responses_usage_event,
                 )
                 .await
             }
@@ -295,378 +412,1583 @@ pub(super) async fn dispatch_transform<P: DispatchProvider>(
                                 .collect()
                         }
                     },
+                    openai_responses_error_event,
+                    openai_responses_usage_event,
+                    None,
+                )
+                .await
+            }
+            StreamContentPlan::Claude2OpenAIChat { request } => {
+                let openai_request =
+                    generate_content::claude2openai_chat::request::transform_request(request);
+                transform_openai_chat_stream(
+                    provider,
+                    ProxyRequest::OpenAIChatStream(openai_request),
+                    ctx_native,
+                    ctx,
+                    usage.clone(),
+                    || {
+                        let mut state = OpenAIChatToClaudeStreamState::new();
+                        move |response: openai::create_chat_completions::stream::CreateChatCompletionStreamResponse| {
+                            state
+                                .transform_response(response)
+                                .into_iter()
+                                .filter_map(|event| sse_json_bytes(&event))
+                                .collect()
+                        }
+                    },
+                    claude_error_event,
+                    claude_usage_events,
+                )
+                .await
+            }
+            StreamContentPlan::OpenAIChat2Claude(request) => {
+                let claude_request =
+                    generate_content::openai_chat2claude::request::transform_request(request);
+                transform_claude_stream(
+                    provider,
+                    ProxyRequest::ClaudeMessagesStream(claude_request),
+                    ctx_native,
+                    ctx,
+                    usage.clone(),
+                    StreamFraming::Sse,
+                    || {
+                        let mut state = ClaudeToOpenAIChatStreamState::new();
+                        move |event: BetaStreamEvent| {
+                            state.transform_event(event).into_iter().collect::<Vec<_>>()
+                        }
+                    },
+                    openai_chat_error_delta,
+                    openai_chat_usage_event,
+                )
+                .await
+            }
+            StreamContentPlan::Gemini2OpenAIChat(request) => {
+                let request = gemini_stream_to_generate(request);
+                let openai_request =
+                    generate_content::gemini2openai_chat::request::transform_request(request);
+                transform_openai_chat_stream(
+                    provider,
+                    ProxyRequest::OpenAIChatStream(openai_request),
+                    ctx_native,
+                    ctx,
+                    usage.clone(),
+                    || {
+                        let mut state = OpenAIChatToGeminiStreamState::new();
+                        move |response: openai::create_chat_completions::stream::CreateChatCompletionStreamResponse| {
+                            state
+                                .transform_response(response)
+                                .into_iter()
+                                .filter_map(|event| sse_json_bytes(&event))
+                                .collect()
+                        }
+                    },
+                    gemini_error_chunk,
+                    gemini_usage_event,
+                )
+                .await
+            }
+            StreamContentPlan::OpenAIChat2Gemini { version, request } => {
+                let gemini_request =
+                    generate_content::openai_chat2gemini::request::transform_request(request);
+                let stream_request = gemini_generate_to_stream(gemini_request);
+                transform_gemini_stream(
+                    provider,
+                    ProxyRequest::GeminiGenerateStream {
+                        version,
+                        request: stream_request,
+                    },
+                    ctx_native,
+                    ctx,
+                    usage.clone(),
+                    || {
+                        let mut state = GeminiToOpenAIChatStreamState::new();
+                        move |response: gemini::generate_content::response::GenerateContentResponse| {
+                            state
+                                .transform_response(response)
+                                .into_iter()
+                                .filter_map(|event| sse_json_bytes(&event))
+                                .collect()
+                        }
+                    },
+                    gemini_error_chunk,
+                    gemini_usage_event,
+                    Some(Bytes::from_static(b"data: [DONE]\n\n")),
                 )
                 .await
             }
         },
         TransformPlan::CountTokens(plan) => match plan {
             CountTokensPlan::Claude2Gemini { version, request } => {
-                let gemini_request = count_tokens::claude2gemini::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(
-                        ProxyRequest::GeminiCountTokens {
-                            version,
-                            request: gemini_request,
-                        },
-                        ctx_native,
-                    )
-                    .await?;
+                let gemini_request =
+                    count_tokens::claude2gemini::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::GeminiCountTokens {
+                        version,
+                        request: gemini_request,
+                    },
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     count_tokens::claude2gemini::response::transform_response,
+                    claude_error_body,
                 )
             }
             CountTokensPlan::Claude2OpenAIInputTokens(request) => {
-                let openai_request = count_tokens::claude2openai::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::OpenAIInputTokens(openai_request), ctx_native)
-                    .await?;
+                let openai_request =
+                    count_tokens::claude2openai::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::OpenAIInputTokens(openai_request),
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     count_tokens::claude2openai::response::transform_response,
+                    claude_error_body,
                 )
             }
             CountTokensPlan::Gemini2Claude(request) => {
-                let claude_request = count_tokens::gemini2claude::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::ClaudeCountTokens(claude_request), ctx_native)
-                    .await?;
+                let claude_request =
+                    count_tokens::gemini2claude::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::ClaudeCountTokens(claude_request),
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     count_tokens::gemini2claude::response::transform_response,
+                    gemini_error_body,
                 )
             }
             CountTokensPlan::Gemini2OpenAIInputTokens(request) => {
-                let openai_request = count_tokens::gemini2openai::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::OpenAIInputTokens(openai_request), ctx_native)
-                    .await?;
+                let openai_request =
+                    count_tokens::gemini2openai::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::OpenAIInputTokens(openai_request),
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     count_tokens::gemini2openai::response::transform_response,
+                    gemini_error_body,
                 )
             }
             CountTokensPlan::OpenAIInputTokens2Claude(request) => {
-                let claude_request = count_tokens::openai2claude::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::ClaudeCountTokens(claude_request), ctx_native)
-                    .await?;
+                let claude_request =
+                    count_tokens::openai2claude::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::ClaudeCountTokens(claude_request),
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     count_tokens::openai2claude::response::transform_response,
+                    openai_error_body,
                 )
             }
             CountTokensPlan::OpenAIInputTokens2Gemini { version, request } => {
-                let gemini_request = count_tokens::openai2gemini::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(
-                        ProxyRequest::GeminiCountTokens {
-                            version,
-                            request: gemini_request,
-                        },
-                        ctx_native,
-                    )
-                    .await?;
+                let gemini_request =
+                    count_tokens::openai2gemini::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::GeminiCountTokens {
+                        version,
+                        request: gemini_request,
+                    },
+                    ctx_native,
+                )
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
                     count_tokens::openai2gemini::response::transform_response,
+                    openai_error_body,
                 )
             }
         },
-        TransformPlan::ModelsList(plan) => match plan {
-            ModelsListPlan::Claude2Gemini { version, request } => {
-                let gemini_request = list_models::claude2gemini::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(
-                        ProxyRequest::GeminiModelsList {
-                            version,
-                            request: gemini_request,
-                        },
-                        ctx_native,
-                    )
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    list_models::claude2gemini::response::transform_response,
-                )
+        TransformPlan::ModelsList(plan) => {
+            let key = model_cache::key_for_list(provider.name(), &plan);
+            if let Some(cached) = model_cache::get(&key) {
+                return Ok(cached);
             }
-            ModelsListPlan::Claude2OpenAI(request) => {
-                let openai_request = list_models::claude2openai::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::OpenAIModelsList(openai_request), ctx_native)
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    list_models::claude2openai::response::transform_response,
-                )
+            let result = models_list(provider, plan, usage, ctx, ctx_native).await;
+            if let Ok(response) = &result {
+                model_cache::put(key, response);
             }
-            ModelsListPlan::Gemini2Claude(request) => {
-                let claude_request = list_models::gemini2claude::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::ClaudeModelsList(claude_request), ctx_native)
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    list_models::gemini2claude::response::transform_response,
-                )
+            result
+        }
+        TransformPlan::ModelsGet(plan) => {
+            let key = model_cache::key_for_get(provider.name(), &plan);
+            if let Some(cached) = model_cache::get(&key) {
+                return Ok(cached);
             }
-            ModelsListPlan::Gemini2OpenAI(request) => {
-                let openai_request = list_models::gemini2openai::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::OpenAIModelsList(openai_request), ctx_native)
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    list_models::gemini2openai::response::transform_response,
-                )
+            let result = models_get(provider, plan, usage, ctx, ctx_native).await;
+            if let Ok(response) = &result {
+                model_cache::put(key, response);
             }
-            ModelsListPlan::OpenAI2Claude(request) => {
-                let claude_request = list_models::openai2claude::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::ClaudeModelsList(claude_request), ctx_native)
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    list_models::openai2claude::response::transform_response,
+            result
+        }
+        TransformPlan::Embeddings(plan) => match plan {
+            EmbeddingsPlan::OpenAI2Gemini { version, request } => {
+                let gemini_request = embeddings::openai2gemini::request::transform_request(request);
+                let UpstreamOk { response, meta } = call_native_with_retry(
+                    provider,
+                    ProxyRequest::GeminiEmbedContent {
+                        version,
+                        request: gemini_request,
+                    },
+                    ctx_native,
                 )
-            }
-            ModelsListPlan::OpenAI2Gemini { version, request } => {
-                let gemini_request = list_models::openai2gemini::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(
-                        ProxyRequest::GeminiModelsList {
-                            version,
-                            request: gemini_request,
-                        },
-                        ctx_native,
-                    )
-                    .await?;
+                .await?;
                 let upstream_recorded =
                     record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
                 transform_json_response(
                     upstream_recorded,
                     ctx,
-                    list_models::openai2gemini::response::transform_response,
+                    embeddings::openai2gemini::response::transform_response,
+                    openai_error_body,
                 )
             }
         },
-        TransformPlan::ModelsGet(plan) => match plan {
-            ModelsGetPlan::Claude2Gemini { version, request } => {
-                let gemini_request = get_model::claude2gemini::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(
-                        ProxyRequest::GeminiModelsGet {
-                            version,
-                            request: gemini_request,
-                        },
-                        ctx_native,
-                    )
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    get_model::claude2gemini::response::transform_response,
-                )
-            }
-            ModelsGetPlan::Claude2OpenAI(request) => {
-                let openai_request = get_model::claude2openai::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::OpenAIModelsGet(openai_request), ctx_native)
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    get_model::claude2openai::response::transform_response,
-                )
-            }
-            ModelsGetPlan::Gemini2Claude(request) => {
-                let claude_request = get_model::gemini2claude::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::ClaudeModelsGet(claude_request), ctx_native)
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                match upstream_recorded {
-                    ProxyResponse::Json { status, mut headers, body } => {
-                        let value: serde_json::Value = serde_json::from_slice(&body)
-                            .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
-                        let response: ClaudeGetModelResponse = if let Some(model) = value.get("model") {
-                            serde_json::from_value(model.clone()).map_err(|err| {
-                                UpstreamPassthroughError::service_unavailable(err.to_string())
-                            })?
-                        } else {
-                            serde_json::from_value(value).map_err(|err| {
-                                UpstreamPassthroughError::service_unavailable(err.to_string())
-                            })?
-                        };
-                        let mapped = get_model::gemini2claude::response::transform_response(response);
-                        let mapped_body = serde_json::to_vec(&mapped)
-                            .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
-                        scrub_headers(&mut headers);
-                        if let Some(meta) = ctx.downstream_meta {
-                            let event = build_downstream_event(
-                                Some(ctx.trace_id.clone()),
-                                meta,
-                                status,
-                                &headers,
-                                Some(&Bytes::from(mapped_body.clone())),
-                                false,
-                            );
-                            ctx.traffic.record_downstream(event);
-                        }
-                        Ok(ProxyResponse::Json {
-                            status,
-                            headers,
-                            body: Bytes::from(mapped_body),
-                        })
-                    }
-                    ProxyResponse::Stream { .. } => Err(UpstreamPassthroughError::service_unavailable(
-                        "expected json response".to_string(),
-                    )),
-                }
-            }
-            ModelsGetPlan::Gemini2OpenAI(request) => {
-                let openai_request = get_model::gemini2openai::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::OpenAIModelsGet(openai_request), ctx_native)
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    get_model::gemini2openai::response::transform_response,
-                )
-            }
-            ModelsGetPlan::OpenAI2Claude(request) => {
-                let claude_request = get_model::openai2claude::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(ProxyRequest::ClaudeModelsGet(claude_request), ctx_native)
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    get_model::openai2claude::response::transform_response,
-                )
-            }
-            ModelsGetPlan::OpenAI2Gemini { version, request } => {
-                let gemini_request = get_model::openai2gemini::request::transform_request(request);
-                let UpstreamOk { response, meta } = provider
-                    .call_native(
-                        ProxyRequest::GeminiModelsGet {
-                            version,
-                            request: gemini_request,
-                        },
-                        ctx_native,
-                    )
-                    .await?;
-                let upstream_recorded =
-                    record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
-                transform_json_response(
-                    upstream_recorded,
-                    ctx,
-                    get_model::openai2gemini::response::transform_response,
-                )
+        TransformPlan::RawPassthrough(RawPassthroughPlan {
+            target_format,
+            body,
+            headers,
+            stream,
+        }) => {
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::RawPassthrough {
+                    target_format,
+                    body,
+                    headers,
+                    stream,
+                },
+                ctx_native,
+            )
+            .await?;
+            record_upstream_only(response, meta, usage.clone(), ctx.clone()).await
+        }
+    }
+}
+
+async fn models_list<P: DispatchProvider>(
+    provider: &P,
+    plan: ModelsListPlan,
+    usage: UsageKind,
+    ctx: CallContext,
+    ctx_native: CallContext,
+) -> Result<ProxyResponse, UpstreamPassthroughError> {
+    match plan {
+        ModelsListPlan::Claude2Gemini { version, request } => {
+            let gemini_request = list_models::claude2gemini::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::GeminiModelsList {
+                    version,
+                    request: gemini_request,
+                },
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                list_models::claude2gemini::response::transform_response,
+                claude_error_body,
+            )
+        }
+        ModelsListPlan::Claude2OpenAI(request) => {
+            let openai_request = list_models::claude2openai::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::OpenAIModelsList(openai_request),
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                list_models::claude2openai::response::transform_response,
+                claude_error_body,
+            )
+        }
+        ModelsListPlan::Gemini2Claude(request) => {
+            let claude_request = list_models::gemini2claude::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::ClaudeModelsList(claude_request),
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                list_models::gemini2claude::response::transform_response,
+                gemini_error_body,
+            )
+        }
+        ModelsListPlan::Gemini2OpenAI(request) => {
+            let openai_request = list_models::gemini2openai::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::OpenAIModelsList(openai_request),
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                list_models::gemini2openai::response::transform_response,
+                gemini_error_body,
+            )
+        }
+        ModelsListPlan::OpenAI2Claude(request) => {
+            let claude_request = list_models::openai2claude::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::ClaudeModelsList(claude_request),
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                list_models::openai2claude::response::transform_response,
+                openai_error_body,
+            )
+        }
+        ModelsListPlan::OpenAI2Gemini { version, request } => {
+            let gemini_request = list_models::openai2gemini::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::GeminiModelsList {
+                    version,
+                    request: gemini_request,
+                },
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                list_models::openai2gemini::response::transform_response,
+                openai_error_body,
+            )
+        }
+    }
+}
+
+async fn models_get<P: DispatchProvider>(
+    provider: &P,
+    plan: ModelsGetPlan,
+    usage: UsageKind,
+    ctx: CallContext,
+    ctx_native: CallContext,
+) -> Result<ProxyResponse, UpstreamPassthroughError> {
+    match plan {
+        ModelsGetPlan::Claude2Gemini { version, request } => {
+            let gemini_request = get_model::claude2gemini::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::GeminiModelsGet {
+                    version,
+                    request: gemini_request,
+                },
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                get_model::claude2gemini::response::transform_response,
+                claude_error_body,
+            )
+        }
+        ModelsGetPlan::Claude2OpenAI(request) => {
+            let openai_request = get_model::claude2openai::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::OpenAIModelsGet(openai_request),
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                get_model::claude2openai::response::transform_response,
+                claude_error_body,
+            )
+        }
+        ModelsGetPlan::Gemini2Claude(request) => {
+            let claude_request = get_model::gemini2claude::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::ClaudeModelsGet(claude_request),
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            match upstream_recorded {
+                ProxyResponse::Json {
+                    status,
+                    mut headers,
+                    body,
+                } => {
+                    let mapped_body = if status.is_success() {
+                        let value: serde_json::Value =
+                            serde_json::from_slice(&body).map_err(|err| {
+                                UpstreamPassthroughError::service_unavailable(err.to_string())
+                            })?;
+                        let response: ClaudeGetModelResponse = if let Some(model) =
+                            value.get("model")
+                        {
+                            serde_json::from_value(model.clone()).map_err(|err| {
+                                UpstreamPassthroughError::service_unavailable(err.to_string())
+                            })?
+                        } else {
+                            serde_json::from_value(value).map_err(|err| {
+                                UpstreamPassthroughError::service_unavailable(err.to_string())
+                            })?
+                        };
+                        let mapped = get_model::gemini2claude::response::transform_response(response);
+                        serde_json::to_vec(&mapped)
+                    } else {
+                        let message = upstream_error_message(&body);
+                        serde_json::to_vec(&gemini_error_body(status, &message))
+                    }
+                    .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
+                    scrub_headers(&mut headers);
+                    if let Some(meta) = ctx.downstream_meta {
+                        let event = build_downstream_event(
+                            Some(ctx.trace_id.clone()),
+                            meta,
+                            status,
+                            &headers,
+                            Some(&Bytes::from(mapped_body.clone())),
+                            false,
+                        );
+                        ctx.traffic.record_downstream(event);
+                    }
+                    Ok(ProxyResponse::Json {
+                        status,
+                        headers,
+                        body: Bytes::from(mapped_body),
+                    })
+                }
+                ProxyResponse::Stream { .. } => Err(UpstreamPassthroughError::service_unavailable(
+                    "expected json response".to_string(),
+                )),
             }
-        },
+        }
+        ModelsGetPlan::Gemini2OpenAI(request) => {
+            let openai_request = get_model::gemini2openai::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::OpenAIModelsGet(openai_request),
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                get_model::gemini2openai::response::transform_response,
+                gemini_error_body,
+            )
+        }
+        ModelsGetPlan::OpenAI2Claude(request) => {
+            let claude_request = get_model::openai2claude::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::ClaudeModelsGet(claude_request),
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                get_model::openai2claude::response::transform_response,
+                openai_error_body,
+            )
+        }
+        ModelsGetPlan::OpenAI2Gemini { version, request } => {
+            let gemini_request = get_model::openai2gemini::request::transform_request(request);
+            let UpstreamOk { response, meta } = call_native_with_retry(
+                provider,
+                ProxyRequest::GeminiModelsGet {
+                    version,
+                    request: gemini_request,
+                },
+                ctx_native,
+            )
+            .await?;
+            let upstream_recorded =
+                record_upstream_only(response, meta, usage.clone(), ctx.clone()).await?;
+            transform_json_response(
+                upstream_recorded,
+                ctx,
+                get_model::openai2gemini::response::transform_response,
+                openai_error_body,
+            )
+        }
     }
 }
 
+/// Source/target format pair a `TransformPlan` arm is tagged with for
+/// telemetry, e.g. `("claude", "gemini")` for a Claude-shaped request
+/// answered by the Gemini native API.
+fn plan_label(plan: &TransformPlan) -> (&'static str, &'static str) {
+    match plan {
+        TransformPlan::GenerateContent(plan) => generate_content_label(plan),
+        TransformPlan::StreamContent(plan) => stream_content_label(plan),
+        TransformPlan::CountTokens(_) => ("count_tokens", "count_tokens"),
+        TransformPlan::ModelsList(_) => ("models_list", "models_list"),
+        TransformPlan::ModelsGet(_) => ("models_get", "models_get"),
+        TransformPlan::Embeddings(_) => ("openai_embeddings", "gemini_embed_content"),
+        TransformPlan::RawPassthrough(_) => ("raw_passthrough", "raw_passthrough"),
+    }
+}
+
+fn generate_content_label(plan: &GenerateContentPlan) -> (&'static str, &'static str) {
+    match plan {
+        GenerateContentPlan::Claude2Gemini { .. } => ("claude", "gemini"),
+        GenerateContentPlan::Gemini2Claude(_) => ("gemini", "claude"),
+        GenerateContentPlan::OpenAIResponses2Claude(_) => ("openai_responses", "claude"),
+        GenerateContentPlan::OpenAIResponses2Gemini { .. } => ("openai_responses", "gemini"),
+        GenerateContentPlan::Claude2OpenAIChat { .. } => ("claude", "openai_chat"),
+        GenerateContentPlan::OpenAIChat2Claude(_) => ("openai_chat", "claude"),
+        GenerateContentPlan::Gemini2OpenAIChat(_) => ("gemini", "openai_chat"),
+        GenerateContentPlan::OpenAIChat2Gemini { .. } => ("openai_chat", "gemini"),
+    }
+}
+
+fn stream_content_label(plan: &StreamContentPlan) -> (&'static str, &'static str) {
+    match plan {
+        StreamContentPlan::Claude2Gemini { .. } => ("claude", "gemini"),
+        StreamContentPlan::Gemini2Claude(_) => ("gemini", "claude"),
+        StreamContentPlan::OpenAIResponses2Claude(_) => ("openai_responses", "claude"),
+        StreamContentPlan::OpenAIResponses2Gemini { .. } => ("openai_responses", "gemini"),
+        StreamContentPlan::Claude2OpenAIChat { .. } => ("claude", "openai_chat"),
+        StreamContentPlan::OpenAIChat2Claude(_) => ("openai_chat", "claude"),
+        StreamContentPlan::Gemini2OpenAIChat(_) => ("gemini", "openai_chat"),
+        StreamContentPlan::OpenAIChat2Gemini { .. } => ("openai_chat", "gemini"),
+    }
+}
+
+pub(super) fn usage_label(kind: UsageKind) -> &'static str {
+    match kind {
+        UsageKind::None => "none",
+        UsageKind::ClaudeMessage => "claude_message",
+        UsageKind::GeminiGenerate => "gemini_generate",
+        UsageKind::OpenAIChat => "openai_chat",
+        UsageKind::OpenAIResponses => "openai_responses",
+        UsageKind::OpenAICompletions => "openai_completions",
+    }
+}
+
+/// Pulls a generic (prompt, completion) token pair out of whichever
+/// per-format fields `TrafficUsage` populated, by round-tripping it through
+/// JSON rather than naming its fields directly — `TrafficUsage` itself lives
+/// in `traffic.rs`, which isn't part of this checkout. Field names here
+/// mirror the ones `extract_*_usage_from_body` already produces.
+pub(super) fn generic_token_counts(
+    usage: &gproxy_provider_core::TrafficUsage,
+) -> (Option<i64>, Option<i64>) {
+    let value = serde_json::to_value(usage).unwrap_or_default();
+    let prompt = first_present_i64(
+        &value,
+        &[
+            "claude_input_tokens",
+            "openai_chat_prompt_tokens",
+            "gemini_prompt_tokens",
+            "openai_responses_input_tokens",
+        ],
+    );
+    let completion = first_present_i64(
+        &value,
+        &[
+            "claude_output_tokens",
+            "openai_chat_completion_tokens",
+            "gemini_candidates_tokens",
+            "openai_responses_output_tokens",
+        ],
+    );
+    (prompt, completion)
+}
+
+fn first_present_i64(value: &serde_json::Value, keys: &[&str]) -> Option<i64> {
+    keys.iter().find_map(|key| value.get(key).and_then(|v| v.as_i64()))
+}
+
 fn transform_json_response<T, U>(
     response: ProxyResponse,
     ctx: CallContext,
     transform: fn(T) -> U,
+    error_shape: fn(StatusCode, &str) -> JsonValue,
 ) -> Result<ProxyResponse, UpstreamPassthroughError>
 where
     T: DeserializeOwned,
     U: Serialize,
 {
     match response {
-        ProxyResponse::Json { status, mut headers, body } => {
-            let parsed = serde_json::from_slice::<T>(&body)
-                .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
-            let mapped = transform(parsed);
-            let mapped_body = serde_json::to_vec(&mapped)
+        ProxyResponse::Json {
+            status,
+            mut headers,
+            body,
+        } => {
+            // A non-success upstream response won't deserialize into the
+            // success type `T` (OpenAI's `{"error":{...}}`, Claude's
+            // `{"type":"error",...}`, Gemini's `{"error":{"code":...}}` all
+            // have a different shape); re-shape it into the dialect the
+            // client actually asked for instead of failing `T`'s
+            // deserialization and reporting a blanket 503.
+            let mapped_body = if status.is_success() {
+                let parsed = serde_json::from_slice::<T>(&body).map_err(|err| {
+                    UpstreamPassthroughError::service_unavailable(err.to_string())
+                })?;
+                let mapped = transform(parsed);
+                serde_json::to_vec(&mapped)
+            } else {
+                let message = upstream_error_message(&body);
+                serde_json::to_vec(&error_shape(status, &message))
+            }
+            .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
+            scrub_headers(&mut headers);
+            if let Some(meta) = ctx.downstream_meta {
+                let event = build_downstream_event(
+                    Some(ctx.trace_id.clone()),
+                    meta,
+                    status,
+                    &headers,
+                    Some(&Bytes::from(mapped_body.clone())),
+                    false,
+                );
+                ctx.traffic.record_downstream(event);
+            }
+            Ok(ProxyResponse::Json {
+                status,
+                headers,
+                body: Bytes::from(mapped_body),
+            })
+        }
+        ProxyResponse::Stream { .. } => Err(UpstreamPassthroughError::service_unavailable(
+            "expected json response".to_string(),
+        )),
+    }
+}
+
+/// Best-effort extraction of a human-readable message from an upstream
+/// error body, regardless of which dialect shaped it — OpenAI, Claude, and
+/// Gemini all nest it as `error.message` (Gemini sometimes top-level
+/// `message`), so this covers all three without needing to know which
+/// upstream sent it.
+fn upstream_error_message(body: &[u8]) -> String {
+    let Ok(value) = serde_json::from_slice::<JsonValue>(body) else {
+        return "upstream error".to_string();
+    };
+    value
+        .get("error")
+        .and_then(|error| error.get("message"))
+        .or_else(|| value.get("message"))
+        .and_then(|message| message.as_str())
+        .unwrap_or("upstream error")
+        .to_string()
+}
+
+/// Claude's error `type` field, mapped from the upstream HTTP status so a
+/// translated error carries a taxonomy Claude clients already branch on
+/// instead of always reporting `api_error`.
+fn claude_error_type_for_status(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => "invalid_request_error",
+        StatusCode::UNAUTHORIZED => "authentication_error",
+        StatusCode::FORBIDDEN => "permission_error",
+        StatusCode::NOT_FOUND => "not_found_error",
+        StatusCode::TOO_MANY_REQUESTS => "rate_limit_error",
+        StatusCode::SERVICE_UNAVAILABLE => "overloaded_error",
+        status if status.is_server_error() => "api_error",
+        _ => "api_error",
+    }
+}
+
+/// Re-shapes a translated upstream error into a Claude-dialect error body,
+/// preserving the original status code on the response it's attached to.
+fn claude_error_body(status: StatusCode, message: &str) -> JsonValue {
+    serde_json::json!({
+        "type": "error",
+        "error": { "type": claude_error_type_for_status(status), "message": message },
+    })
+}
+
+/// Re-shapes a translated upstream error into a Gemini-dialect error body.
+fn gemini_error_body(status: StatusCode, message: &str) -> JsonValue {
+    let canonical_status = status
+        .canonical_reason()
+        .unwrap_or("UNKNOWN")
+        .to_uppercase()
+        .replace(' ', "_");
+    serde_json::json!({
+        "error": { "code": status.as_u16(), "message": message, "status": canonical_status },
+    })
+}
+
+/// Re-shapes a translated upstream error into an OpenAI-dialect error body
+/// (shared by Chat Completions, Responses, and input-tokens, which all use
+/// the same top-level `error` envelope).
+fn openai_error_body(status: StatusCode, message: &str) -> JsonValue {
+    serde_json::json!({
+        "error": { "message": message, "type": "upstream_error", "code": status.as_u16() },
+    })
+}
+
+/// Cap on how much of a streamed response body a recorder task retains for
+/// traffic logging. Past this, further chunks are dropped from the retained
+/// body (but still streamed to the client) and the emitted event is marked
+/// `body_truncated` rather than let logging memory grow unbounded on a very
+/// long-lived stream.
+const RECORDED_BODY_BUDGET: usize = 64 * 1024;
+
+/// Appends `chunk` to a recorder task's retained `body` while it is still
+/// under `RECORDED_BODY_BUDGET`; past that, the chunk is counted in
+/// `dropped` instead of retained, so a recorder task for a very long stream
+/// holds at most `RECORDED_BODY_BUDGET` bytes of logging body regardless of
+/// how much the upstream actually sends. `UsageState` accounting is driven
+/// separately by callers from the same events, so token counts stay exact
+/// even once the retained body itself is capped.
+fn capture_body_chunk(body: &mut String, dropped: &mut usize, chunk: &str) {
+    if body.len() < RECORDED_BODY_BUDGET {
+        body.push_str(chunk);
+    } else {
+        *dropped += chunk.len();
+    }
+}
+
+/// Folds a recorder task's capped `body`/`dropped` pair into the `Bytes`
+/// recorded on the traffic event, appending a `...truncated(N bytes)`
+/// marker and flipping `truncated` when anything was dropped so the
+/// retained body is self-describing even without the event's separate
+/// `body_truncated` flag.
+fn finish_recorded_body(
+    mut body: String,
+    dropped: usize,
+    truncated: &std::sync::atomic::AtomicBool,
+) -> Option<Bytes> {
+    if dropped > 0 {
+        truncated.store(true, std::sync::atomic::Ordering::Relaxed);
+        body.push_str(&format!("...truncated({dropped} bytes)"));
+    }
+    if body.is_empty() {
+        None
+    } else {
+        Some(Bytes::from(body))
+    }
+}
+
+/// Tracks the SSE `id:`/`retry:` control fields alongside `StreamDecoder`
+/// (which only surfaces `data:` payloads), so a reconnect after a
+/// mid-stream transport error can resume with `Last-Event-ID` and skip
+/// events the client already saw. Kept as an independent scan over the
+/// same raw bytes rather than threaded through `StreamDecoder`, the same
+/// way the usage totals above are tracked independently of the recorder
+/// task.
+#[derive(Default)]
+struct SseResumeState {
+    last_event_id: Option<String>,
+    high_water: Option<i64>,
+    retry_hint: Option<Duration>,
+}
+
+impl SseResumeState {
+    /// Scans a raw chunk for `id:`/`retry:` lines, recording the latest
+    /// values seen. Returns `false` when the chunk's `id:` is numeric and
+    /// at or below the current high-water mark — a re-delivered event from
+    /// a prior connection that callers must not forward or re-count usage
+    /// for. Streams whose `id:` isn't numeric (or is absent) can't be
+    /// deduped this way and are always treated as fresh.
+    fn observe(&mut self, bytes: &[u8]) -> bool {
+        let mut fresh = true;
+        for line in bytes.split(|&b| b == b'\n') {
+            let line = String::from_utf8_lossy(line);
+            let line = line.trim_end_matches('\r');
+            if let Some(id) = line.strip_prefix("id:") {
+                let id = id.trim().to_string();
+                if let Ok(parsed) = id.parse::<i64>() {
+                    if self.high_water.is_some_and(|high_water| parsed <= high_water) {
+                        fresh = false;
+                    }
+                    self.high_water = Some(self.high_water.map_or(parsed, |hw| hw.max(parsed)));
+                }
+                self.last_event_id = Some(id);
+            } else if let Some(retry) = line.strip_prefix("retry:") {
+                if let Ok(millis) = retry.trim().parse::<u64>() {
+                    self.retry_hint = Some(Duration::from_millis(millis));
+                }
+            }
+        }
+        fresh
+    }
+}
+
+/// Caps mid-stream reconnect attempts in the `transform_*_stream` resume
+/// path at the request's opt-in `RetryPolicy`, so resuming behind a dropped
+/// upstream connection shares the same "retries are opt-in per request"
+/// knob as `call_native_with_retry` instead of adding a second, separate one.
+fn max_stream_resumes(ctx: &CallContext) -> u32 {
+    ctx.retry.map(|policy| policy.max_attempts).unwrap_or(0)
+}
+
+/// Bounds a `transform_*_stream` unfold loop's wait on upstream chunks, per
+/// `CallContext::idle_timeout` (resets on every chunk) and
+/// `CallContext::stream_deadline` (fixed from the call's start). Both are
+/// opt-in: a `CallContext` with neither set behaves exactly as before this
+/// watchdog existed.
+#[derive(Clone, Copy)]
+struct StreamWatchdog {
+    idle_timeout: Option<Duration>,
+    deadline: Option<tokio::time::Instant>,
+}
+
+impl StreamWatchdog {
+    fn new(ctx: &CallContext) -> Self {
+        Self {
+            idle_timeout: ctx.idle_timeout,
+            deadline: ctx
+                .stream_deadline
+                .map(|deadline| tokio::time::Instant::now() + deadline),
+        }
+    }
+
+    /// `true` once the overall stream deadline has passed, regardless of how
+    /// recently a chunk arrived.
+    fn deadline_exceeded(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| tokio::time::Instant::now() >= deadline)
+    }
+
+    /// Awaits `upstream.next()`, returning `Err(())` in place of the usual
+    /// `Option` once `idle_timeout` elapses with nothing received. A fresh
+    /// timeout window starts on every call, so this only ever bounds the gap
+    /// between chunks — `deadline_exceeded` covers the stream as a whole.
+    async fn next_chunk<S>(&self, upstream: &mut S) -> Result<Option<S::Item>, ()>
+    where
+        S: futures_util::Stream + Unpin,
+    {
+        match self.idle_timeout {
+            Some(idle_timeout) => tokio::time::timeout(idle_timeout, upstream.next())
+                .await
+                .map_err(|_| ()),
+            None => Ok(upstream.next().await),
+        }
+    }
+}
+
+/// Terminal error event injected into a Claude-shaped downstream when a
+/// mid-stream reconnect budget is exhausted.
+fn claude_error_event(message: &str) -> JsonValue {
+    serde_json::json!({
+        "type": "error",
+        "error": { "type": "overloaded_error", "message": message },
+    })
+}
+
+/// Terminal error chunk injected into a Gemini-shaped downstream when a
+/// mid-stream reconnect budget is exhausted.
+fn gemini_error_chunk(message: &str) -> JsonValue {
+    serde_json::json!({
+        "error": { "code": 503, "message": message, "status": "UNAVAILABLE" },
+    })
+}
+
+/// Terminal error delta injected into an OpenAI Chat Completions-shaped
+/// downstream when a mid-stream reconnect budget is exhausted.
+fn openai_chat_error_delta(message: &str) -> JsonValue {
+    serde_json::json!({
+        "error": { "message": message, "type": "upstream_error", "code": "stream_interrupted" },
+    })
+}
+
+/// Terminal error event injected into an OpenAI Responses-shaped downstream
+/// when a mid-stream reconnect budget is exhausted.
+fn openai_responses_error_event(message: &str) -> JsonValue {
+    serde_json::json!({
+        "type": "error",
+        "message": message,
+        "code": "stream_interrupted",
+    })
+}
+
+/// Synthetic `message_delta` + `message_stop` pair carrying the reconciled
+/// token usage in Claude's shape, appended to a Claude-shaped downstream
+/// once the source stream's own usage accounting finishes. Needed because
+/// some source dialects (e.g. Gemini's per-candidate `usageMetadata`) never
+/// naturally map onto Claude's `message_delta.usage` during the per-event
+/// transform.
+fn claude_usage_events(usage: &gproxy_provider_core::TrafficUsage) -> Vec<JsonValue> {
+    let (input_tokens, output_tokens) = generic_token_counts(usage);
+    vec![
+        serde_json::json!({
+            "type": "message_delta",
+            "delta": { "stop_reason": "end_turn", "stop_sequence": null },
+            "usage": { "input_tokens": input_tokens, "output_tokens": output_tokens },
+        }),
+        serde_json::json!({ "type": "message_stop" }),
+    ]
+}
+
+/// Synthetic `usageMetadata`-bearing chunk appended to a Gemini-shaped
+/// downstream once the source stream's own usage accounting finishes.
+fn gemini_usage_event(usage: &gproxy_provider_core::TrafficUsage) -> Vec<JsonValue> {
+    let (prompt_tokens, candidates_tokens) = generic_token_counts(usage);
+    let total_tokens = match (prompt_tokens, candidates_tokens) {
+        (Some(prompt), Some(candidates)) => Some(prompt + candidates),
+        _ => None,
+    };
+    vec![serde_json::json!({
+        "usageMetadata": {
+            "promptTokenCount": prompt_tokens,
+            "candidatesTokenCount": candidates_tokens,
+            "totalTokenCount": total_tokens,
+        },
+    })]
+}
+
+/// Synthetic final chunk carrying `usage` in OpenAI Chat Completions shape,
+/// appended once the source stream's own usage accounting finishes.
+fn openai_chat_usage_event(usage: &gproxy_provider_core::TrafficUsage) -> Vec<JsonValue> {
+    let (prompt_tokens, completion_tokens) = generic_token_counts(usage);
+    let total_tokens = match (prompt_tokens, completion_tokens) {
+        (Some(prompt), Some(completion)) => Some(prompt + completion),
+        _ => None,
+    };
+    vec![serde_json::json!({
+        "choices": [],
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": total_tokens,
+        },
+    })]
+}
+
+/// Synthetic `response.completed`-shaped event carrying OpenAI Responses
+/// usage, appended once the source stream's own usage accounting finishes.
+fn openai_responses_usage_event(usage: &gproxy_provider_core::TrafficUsage) -> Vec<JsonValue> {
+    let (input_tokens, output_tokens) = generic_token_counts(usage);
+    let total_tokens = match (input_tokens, output_tokens) {
+        (Some(input), Some(output)) => Some(input + output),
+        _ => None,
+    };
+    vec![serde_json::json!({
+        "type": "response.completed",
+        "response": {
+            "usage": {
+                "input_tokens": input_tokens,
+                "output_tokens": output_tokens,
+                "total_tokens": total_tokens,
+            },
+        },
+    })]
+}
+
+async fn transform_claude_stream<P, F, T, O>(
+    provider: &P,
+    upstream_req: ProxyRequest,
+    ctx_native: CallContext,
+    ctx_downstream: CallContext,
+    usage: UsageKind,
+    framing: StreamFraming,
+    mut transform_factory: F,
+    terminal_error: fn(&str) -> JsonValue,
+    usage_event: fn(&gproxy_provider_core::TrafficUsage) -> Vec<JsonValue>,
+) -> Result<ProxyResponse, UpstreamPassthroughError>
+where
+    P: DispatchProvider + Clone + 'static,
+    F: FnMut() -> T + Send + 'static,
+    T: FnMut(BetaStreamEvent) -> Vec<O> + Send + 'static,
+    O: Serialize + Send + 'static,
+{
+    let resume_budget = max_stream_resumes(&ctx_native);
+    let resume_provider = provider.clone();
+    let resume_req = upstream_req.clone();
+    let resume_ctx = ctx_native.clone();
+    let UpstreamOk { response, meta } =
+        call_native_with_stream_failover(provider, upstream_req, ctx_native).await?;
+    match response {
+        ProxyResponse::Stream {
+            status,
+            headers,
+            body,
+        } => {
+            let (down_tx, mut down_rx) = tokio::sync::mpsc::channel::<Bytes>(256);
+            let (up_tx, mut up_rx) = tokio::sync::mpsc::channel::<Bytes>(256);
+            let traffic = ctx_downstream.traffic.clone();
+            let downstream_meta = ctx_downstream.downstream_meta.clone();
+            let trace_id = ctx_downstream.trace_id.clone();
+            let response_headers = headers.clone();
+            let upstream_traffic = traffic.clone();
+            let upstream_trace_id = trace_id.clone();
+            let upstream_headers = response_headers.clone();
+            let provider_name = provider.name().to_string();
+            let upstream_provider_name = provider_name.clone();
+            let downstream_provider_name = provider_name;
+            let upstream_live_tap = ctx_downstream.live_tap.clone();
+            let downstream_live_tap = ctx_downstream.live_tap.clone();
+            let telemetry_span = ctx_downstream.telemetry_span.clone();
+            let aborted = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let upstream_aborted = aborted.clone();
+            let downstream_aborted = aborted.clone();
+            let cancellation = ctx_downstream.cancellation.clone();
+            // Set from the unfold loop below (a full channel means the
+            // recorder fell behind and a chunk was dropped rather than
+            // blocking client delivery) or from the task itself (the
+            // retained body hit `RECORDED_BODY_BUDGET`).
+            let upstream_truncated = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let downstream_truncated =
+                std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let upstream_truncated_task = upstream_truncated.clone();
+            let downstream_truncated_task = downstream_truncated.clone();
+            let watchdog = StreamWatchdog::new(&ctx_downstream);
+            let timed_out = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let loop_timed_out = timed_out.clone();
+            let upstream_timed_out = timed_out.clone();
+            let metrics = ctx_downstream.metrics.clone();
+            let metrics_provider = meta.provider.clone();
+            let metrics_model = meta.model.clone().unwrap_or_default();
+            let loop_trace_id = trace_id.clone();
+            tokio::spawn(async move {
+                let mut usage_from_stream = None;
+                let mut usage_state = match usage {
+                    UsageKind::None => None,
+                    _ => Some(UsageState::Claude(ClaudeUsageState::new())),
+                };
+                let mut parser = SseParser::new();
+                let mut response_body = String::new();
+                let mut dropped_bytes = 0usize;
+                while let Some(chunk) = up_rx.recv().await {
+                    for event in parser.push_bytes(&chunk) {
+                        if event.data.is_empty() || event.data == "[DONE]" {
+                            continue;
+                        }
+                        if let Some(tap) = &upstream_live_tap {
+                            tap.publish(LiveTrafficChunk::now(
+                                upstream_trace_id.clone(),
+                                upstream_provider_name.clone(),
+                                TrafficDirection::Upstream,
+                                event.data.clone(),
+                            ));
+                        }
+                        capture_body_chunk(&mut response_body, &mut dropped_bytes, &event.data);
+                        if let Some(state) = usage_state.as_mut() {
+                            state.push_event(&event.data);
+                        }
+                    }
+                }
+                for event in parser.finish() {
+                    if event.data.is_empty() || event.data == "[DONE]" {
+                        continue;
+                    }
+                    capture_body_chunk(&mut response_body, &mut dropped_bytes, &event.data);
+                    if let Some(state) = usage_state.as_mut() {
+                        state.push_event(&event.data);
+                    }
+                }
+                if let Some(state) = usage_state {
+                    usage_from_stream = map_usage_for_kind(usage, state.finish());
+                }
+                if let (Some(span), Some(usage)) = (telemetry_span.as_ref(), usage_from_stream.as_ref())
+                {
+                    let (prompt_tokens, completion_tokens) = generic_token_counts(usage);
+                    span.record_tokens(prompt_tokens, completion_tokens);
+                }
+                let body_bytes =
+                    finish_recorded_body(response_body, dropped_bytes, &upstream_truncated_task);
+                let event = gproxy_provider_core::build_upstream_event(
+                    Some(upstream_trace_id.clone()),
+                    meta,
+                    status,
+                    &upstream_headers,
+                    body_bytes.as_ref(),
+                    true,
+                    usage_from_stream,
+                    upstream_aborted.load(std::sync::atomic::Ordering::Relaxed),
+                    upstream_truncated.load(std::sync::atomic::Ordering::Relaxed),
+                );
+                upstream_traffic.record_upstream(event);
+                metrics.record_termination(
+                    &metrics_provider,
+                    &metrics_model,
+                    if upstream_timed_out.load(std::sync::atomic::Ordering::Relaxed) {
+                        StreamTermination::Timeout
+                    } else {
+                        StreamTermination::Clean
+                    },
+                );
+            });
+            let downstream_traffic = traffic.clone();
+            let downstream_trace_id = trace_id.clone();
+            let downstream_headers = response_headers.clone();
+            tokio::spawn(async move {
+                let mut parser = SseParser::new();
+                let mut response_body = String::new();
+                let mut dropped_bytes = 0usize;
+                while let Some(chunk) = down_rx.recv().await {
+                    for event in parser.push_bytes(&chunk) {
+                        if event.data.is_empty() || event.data == "[DONE]" {
+                            continue;
+                        }
+                        if let Some(tap) = &downstream_live_tap {
+                            tap.publish(LiveTrafficChunk::now(
+                                downstream_trace_id.clone(),
+                                downstream_provider_name.clone(),
+                                TrafficDirection::Downstream,
+                                event.data.clone(),
+                            ));
+                        }
+                        capture_body_chunk(&mut response_body, &mut dropped_bytes, &event.data);
+                    }
+                }
+                for event in parser.finish() {
+                    if event.data.is_empty() || event.data == "[DONE]" {
+                        continue;
+                    }
+                    capture_body_chunk(&mut response_body, &mut dropped_bytes, &event.data);
+                }
+                if let Some(meta) = downstream_meta {
+                    let body_bytes = finish_recorded_body(
+                        response_body,
+                        dropped_bytes,
+                        &downstream_truncated_task,
+                    );
+                    let event = build_downstream_event(
+                        Some(downstream_trace_id.clone()),
+                        meta,
+                        status,
+                        &downstream_headers,
+                        body_bytes.as_ref(),
+                        true,
+                        downstream_aborted.load(std::sync::atomic::Ordering::Relaxed),
+                        downstream_truncated.load(std::sync::atomic::Ordering::Relaxed),
+                    );
+                    downstream_traffic.record_downstream(event);
+                }
+            });
+
+            let mut initial_pending = VecDeque::<Bytes>::new();
+            if framing == StreamFraming::WebSocket {
+                initial_pending.push_back(ws_connection_ack());
+            }
+            let stream_usage_state = match usage {
+                UsageKind::None => None,
+                _ => Some(UsageState::Claude(ClaudeUsageState::new())),
+            };
+            let stream = unfold(
+                (
+                    body.stream,
+                    SseParser::new(),
+                    transform_factory(),
+                    initial_pending,
+                    down_tx,
+                    up_tx,
+                    cancellation,
+                    aborted,
+                    upstream_truncated,
+                    downstream_truncated,
+                    false,
+                    resume_provider,
+                    resume_req,
+                    resume_ctx,
+                    resume_budget,
+                    0u32,
+                    0usize,
+                    0usize,
+                    stream_usage_state,
+                    SseResumeState::default(),
+                    watchdog,
+                    loop_timed_out,
+                    loop_trace_id,
+                ),
+                move |(
+                    mut upstream,
+                    mut parser,
+                    mut transform,
+                    mut pending,
+                    down_tx,
+                    up_tx,
+                    cancellation,
+                    aborted,
+                    upstream_truncated,
+                    downstream_truncated,
+                    mut done,
+                    resume_provider,
+                    resume_req,
+                    mut resume_ctx,
+                    resume_budget,
+                    mut resumes_used,
+                    mut skip_remaining,
+                    mut emitted_total,
+                    mut stream_usage_state,
+                    mut sse_resume,
+                    watchdog,
+                    timed_out,
+                    trace_id,
+                )| async move {
+                    loop {
+                        if let Some(item) = pending.pop_front() {
+                            // `try_send`, not `send`: a recorder task that fell
+                            // behind must never back-pressure client delivery.
+                            // A dropped chunk just makes the recorded body
+                            // truncated, which the recorder task already
+                            // surfaces via `downstream_truncated`.
+                            if down_tx.try_send(item.clone()).is_err() {
+                                downstream_truncated
+                                    .store(true, std::sync::atomic::Ordering::Relaxed);
+                            }
+                            return Some((
+                                Ok(item),
+                                (
+                                    upstream,
+                                    parser,
+                                    transform,
+                                    pending,
+                                    down_tx,
+                                    up_tx,
+                                    cancellation,
+                                    aborted,
+                                    upstream_truncated,
+                                    downstream_truncated,
+                                    done,
+                                    resume_provider,
+                                    resume_req,
+                                    resume_ctx,
+                                    resume_budget,
+                                    resumes_used,
+                                    skip_remaining,
+                                    emitted_total,
+                                    stream_usage_state,
+                                    sse_resume,
+                                    watchdog,
+                                    timed_out,
+                                    trace_id,
+                                ),
+                            ));
+                        }
+                        if done {
+                            return None;
+                        }
+                        if watchdog.deadline_exceeded() {
+                            if let Some(bytes) = frame_event(
+                                terminal_error(&format!(
+                                    "stream deadline exceeded (trace_id={trace_id})"
+                                )),
+                                framing,
+                            ) {
+                                pending.push_back(bytes);
+                            }
+                            timed_out.store(true, std::sync::atomic::Ordering::Relaxed);
+                            done = true;
+                            continue;
+                        }
+                        tokio::select! {
+                            _ = cancellation.cancelled() => {
+                                // Client is gone: stop pulling from upstream, flush
+                                // whatever the SSE parser had buffered for
+                                // accounting purposes, and close the recorder
+                                // channels so both spawned tasks drain and flush
+                                // their partial bodies instead of hanging forever.
+                                aborted.store(true, std::sync::atomic::Ordering::Relaxed);
+                                let _ = parser.finish();
+                                drop(down_tx);
+                                drop(up_tx);
+                                return None;
+                            }
+                            chunk = watchdog.next_chunk(&mut upstream) => {
+                                let chunk = match chunk {
+                                    Ok(chunk) => chunk,
+                                    Err(()) => {
+                                        // No chunk arrived within `idle_timeout`:
+                                        // tell the client instead of leaving it
+                                        // blocked on a connection that may never
+                                        // produce another byte.
+                                        if let Some(bytes) = frame_event(
+                                            terminal_error(&format!(
+                                                "idle timeout waiting for upstream (trace_id={trace_id})"
+                                            )),
+                                            framing,
+                                        ) {
+                                            pending.push_back(bytes);
+                                        }
+                                        timed_out.store(true, std::sync::atomic::Ordering::Relaxed);
+                                        done = true;
+                                        continue;
+                                    }
+                                };
+                                match chunk {
+                                Some(Ok(bytes)) => {
+                                    if up_tx.try_send(bytes.clone()).is_err() {
+                                        upstream_truncated.store(true, std::sync::atomic::Ordering::Relaxed);
+                                    }
+                                    let fresh = sse_resume.observe(&bytes);
+                                    for event in parser.push_bytes(&bytes) {
+                                        if event.data.is_empty() || !fresh {
+                                            continue;
+                                        }
+                                        if let Some(state) = stream_usage_state.as_mut() {
+                                            state.push_event(&event.data);
+                                        }
+                                        if let Ok(parsed) = serde_json::from_str::<BetaStreamEvent>(&event.data) {
+                                            for item in transform(parsed)
+                                                .into_iter()
+                                                .filter_map(|item| frame_event(item, framing))
+                                            {
+                                                // After a reconnect the restarted
+                                                // generation replays from the
+                                                // beginning; discard the events the
+                                                // client already received instead
+                                                // of duplicating them downstream.
+                                                if skip_remaining > 0 {
+                                                    skip_remaining -= 1;
+                                                    continue;
+                                                }
+                                                emitted_total += 1;
+                                                pending.push_back(item);
+                                            }
+                                        }
+                                    }
+                                    continue;
+                                }
+                                Some(Err(err)) => {
+                                    // Replay the last id this stream has seen (if
+                                    // any) so a server that honors `Last-Event-ID`
+                                    // resumes past it instead of from scratch; a
+                                    // stream that never emitted `id:` leaves this
+                                    // `None` and simply falls back to the existing
+                                    // position-based `skip_remaining` resume.
+                                    resume_ctx.last_event_id = sse_resume.last_event_id.clone();
+                                    if let Some(hint) = sse_resume.retry_hint {
+                                        tokio::time::sleep(hint).await;
+                                    }
+                                    let mut reconnected = false;
+                                    if emitted_total == 0 {
+                                        // Nothing has reached the client yet, so a
+                                        // full backoff-retried reconnect (rather
+                                        // than the single-shot resume below) is
+                                        // indistinguishable from the upstream
+                                        // having taken longer to respond.
+                                        if let Ok(UpstreamOk {
+                                            response: ProxyResponse::Stream { body: resumed, .. },
+                                            ..
+                                        }) = call_native_with_stream_failover(
+                                            &resume_provider,
+                                            resume_req.clone(),
+                                            resume_ctx.clone(),
+                                        )
+                                        .await
+                                        {
+                                            upstream = resumed.stream;
+                                            parser = SseParser::new();
+                                            reconnected = true;
+                                        }
+                                    } else if resumes_used < resume_budget {
+                                        resumes_used += 1;
+                                        if let Ok(UpstreamOk {
+                                            response: ProxyResponse::Stream { body: resumed, .. },
+                                            ..
+                                        }) = call_native_with_retry(
+                                            &resume_provider,
+                                            resume_req.clone(),
+                                            resume_ctx.clone(),
+                                        )
+                                        .await
+                                        {
+                                            upstream = resumed.stream;
+                                            parser = SseParser::new();
+                                            skip_remaining = emitted_total;
+                                            reconnected = true;
+                                        }
+                                    }
+                                    if reconnected {
+                                        continue;
+                                    }
+                                    // Resume budget exhausted (or the reconnect
+                                    // attempt itself failed): tell the client the
+                                    // stream ended instead of abruptly closing
+                                    // the socket.
+                                    match framing {
+                                        StreamFraming::Sse => {
+                                            if let Some(bytes) =
+                                                frame_event(terminal_error(&err.to_string()), framing)
+                                            {
+                                                pending.push_back(bytes);
+                                            }
+                                        }
+                                        StreamFraming::WebSocket => {
+                                            pending.push_back(ws_error(err.to_string()));
+                                        }
+                                    }
+                                    done = true;
+                                    continue;
+                                }
+                                None => {
+                                    for event in parser.finish() {
+                                        if event.data.is_empty() {
+                                            continue;
+                                        }
+                                        if let Some(state) = stream_usage_state.as_mut() {
+                                            state.push_event(&event.data);
+                                        }
+                                        if let Ok(parsed) = serde_json::from_str::<BetaStreamEvent>(&event.data) {
+                                            pending.extend(
+                                                transform(parsed)
+                                                    .into_iter()
+                                                    .filter_map(|item| frame_event(item, framing)),
+                                            );
+                                        }
+                                    }
+                                    if let Some(state) = stream_usage_state.take() {
+                                        if let Some(usage_totals) =
+                                            map_usage_for_kind(usage, state.finish())
+                                        {
+                                            pending.extend(
+                                                usage_event(&usage_totals)
+                                                    .into_iter()
+                                                    .filter_map(|item| frame_event(item, framing)),
+                                            );
+                                        }
+                                    }
+                                    if framing == StreamFraming::WebSocket {
+                                        pending.push_back(ws_complete());
+                                    }
+                                    if pending.is_empty() {
+                                        return None;
+                                    }
+                                }
+                                }
+                            },
+                        }
+                    }
+                },
+            );
+            Ok(ProxyResponse::Stream {
+                status,
+                headers,
+                body: StreamBody::new(body.content_type, stream),
+            })
+        }
+        ProxyResponse::Json {
+            status,
+            mut headers,
+            body,
+        } => {
+            // The upstream failed before any stream was even established
+            // (e.g. an auth/validation error returned as plain JSON):
+            // re-shape it into this call's downstream dialect instead of
+            // a blanket 503, same as the non-streaming transforms.
+            let message = upstream_error_message(&body);
+            let mapped_body = serde_json::to_vec(&terminal_error(&message))
                 .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
             scrub_headers(&mut headers);
-            if let Some(meta) = ctx.downstream_meta {
-                let event = build_downstream_event(
-                    Some(ctx.trace_id.clone()),
-                    meta,
-                    status,
-                    &headers,
-                    Some(&Bytes::from(mapped_body.clone())),
-                    false,
-                );
-                ctx.traffic.record_downstream(event);
-            }
             Ok(ProxyResponse::Json {
                 status,
                 headers,
                 body: Bytes::from(mapped_body),
             })
         }
-        ProxyResponse::Stream { .. } => Err(UpstreamPassthroughError::service_unavailable(
-            "expected json response".to_string(),
-        )),
     }
 }
 
-async fn transform_claude_stream<P, F, T>(
+/// Frames one transformed event per `framing`. Shared by both branches of
+/// `transform_claude_stream`'s unfold loop so the SSE-vs-WebSocket choice
+/// lives in one place.
+fn frame_event<O: Serialize>(item: O, framing: StreamFraming) -> Option<Bytes> {
+    match framing {
+        StreamFraming::Sse => sse_json_bytes(&item),
+        StreamFraming::WebSocket => ws_json_bytes(&item),
+    }
+}
+
+async fn transform_gemini_stream<P, F, T>(
     provider: &P,
     upstream_req: ProxyRequest,
     ctx_native: CallContext,
     ctx_downstream: CallContext,
     usage: UsageKind,
     mut transform_factory: F,
+    terminal_error: fn(&str) -> JsonValue,
+    usage_event: fn(&gproxy_provider_core::TrafficUsage) -> Vec<JsonValue>,
+    /// Bytes to append once the upstream stream ends cleanly, e.g. OpenAI
+    /// Chat Completions' literal `data: [DONE]\n\n` line. `None` for
+    /// dialects (Claude, OpenAI Responses) that signal completion through a
+    /// typed event instead of a sentinel.
+    done_sentinel: Option<Bytes>,
 ) -> Result<ProxyResponse, UpstreamPassthroughError>
 where
-    P: DispatchProvider,
+    P: DispatchProvider + Clone + 'static,
     F: FnMut() -> T + Send + 'static,
-    T: FnMut(BetaStreamEvent) -> Vec<Bytes> + Send + 'static,
+    T: FnMut(gemini::generate_content::response::GenerateContentResponse) -> Vec<Bytes>
+        + Send
+        + 'static,
 {
-    let UpstreamOk { response, meta } = provider.call_native(upstream_req, ctx_native).await?;
+    let resume_budget = max_stream_resumes(&ctx_native);
+    let resume_provider = provider.clone();
+    let resume_req = upstream_req.clone();
+    let resume_ctx = ctx_native.clone();
+    let UpstreamOk { response, meta } =
+        call_native_with_stream_failover(provider, upstream_req, ctx_native).await?;
     match response {
-        ProxyResponse::Stream { status, headers, body } => {
+        ProxyResponse::Stream {
+            status,
+            headers,
+            body,
+        } => {
             let (down_tx, mut down_rx) = tokio::sync::mpsc::channel::<Bytes>(256);
             let (up_tx, mut up_rx) = tokio::sync::mpsc::channel::<Bytes>(256);
             let traffic = ctx_downstream.traffic.clone();
@@ -676,42 +1998,62 @@ where
             let upstream_traffic = traffic.clone();
             let upstream_trace_id = trace_id.clone();
             let upstream_headers = response_headers.clone();
+            let telemetry_span = ctx_downstream.telemetry_span.clone();
+            // Set from the unfold loop below (a full channel means the
+            // recorder fell behind and a chunk was dropped rather than
+            // blocking client delivery).
+            let upstream_truncated = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let downstream_truncated =
+                std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let upstream_truncated_task = upstream_truncated.clone();
+            let downstream_truncated_task = downstream_truncated.clone();
+            let watchdog = StreamWatchdog::new(&ctx_downstream);
+            let timed_out = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let loop_timed_out = timed_out.clone();
+            let upstream_timed_out = timed_out.clone();
+            let metrics = ctx_downstream.metrics.clone();
+            let metrics_provider = meta.provider.clone();
+            let metrics_model = meta.model.clone().unwrap_or_default();
+            let loop_trace_id = trace_id.clone();
             tokio::spawn(async move {
                 let mut usage_from_stream = None;
                 let mut usage_state = match usage {
                     UsageKind::None => None,
-                    _ => Some(UsageState::Claude(ClaudeUsageState::new())),
+                    _ => Some(UsageState::Gemini(GeminiUsageState::new())),
                 };
-                let mut parser = SseParser::new();
+                let mut decoder = StreamDecoder::new();
                 let mut response_body = String::new();
+                let mut dropped_bytes = 0usize;
                 while let Some(chunk) = up_rx.recv().await {
-                    for event in parser.push_bytes(&chunk) {
-                        if event.data.is_empty() || event.data == "[DONE]" {
+                    for data in decoder.push(&chunk) {
+                        if data.is_empty() || data == "[DONE]" {
                             continue;
                         }
-                        response_body.push_str(&event.data);
+                        capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                         if let Some(state) = usage_state.as_mut() {
-                            state.push_event(&event.data);
+                            state.push_event(&data);
                         }
                     }
                 }
-                for event in parser.finish() {
-                    if event.data.is_empty() || event.data == "[DONE]" {
+                for data in decoder.finish() {
+                    if data.is_empty() || data == "[DONE]" {
                         continue;
                     }
-                    response_body.push_str(&event.data);
+                    capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                     if let Some(state) = usage_state.as_mut() {
-                        state.push_event(&event.data);
+                        state.push_event(&data);
                     }
                 }
                 if let Some(state) = usage_state {
                     usage_from_stream = map_usage_for_kind(usage, state.finish());
                 }
-                let body_bytes = if response_body.is_empty() {
-                    None
-                } else {
-                    Some(Bytes::from(response_body))
-                };
+                if let (Some(span), Some(usage)) = (telemetry_span.as_ref(), usage_from_stream.as_ref())
+                {
+                    let (prompt_tokens, completion_tokens) = generic_token_counts(usage);
+                    span.record_tokens(prompt_tokens, completion_tokens);
+                }
+                let body_bytes =
+                    finish_recorded_body(response_body, dropped_bytes, &upstream_truncated_task);
                 let event = gproxy_provider_core::build_upstream_event(
                     Some(upstream_trace_id.clone()),
                     meta,
@@ -720,35 +2062,46 @@ where
                     body_bytes.as_ref(),
                     true,
                     usage_from_stream,
+                    upstream_truncated.load(std::sync::atomic::Ordering::Relaxed),
                 );
                 upstream_traffic.record_upstream(event);
+                metrics.record_termination(
+                    &metrics_provider,
+                    &metrics_model,
+                    if upstream_timed_out.load(std::sync::atomic::Ordering::Relaxed) {
+                        StreamTermination::Timeout
+                    } else {
+                        StreamTermination::Clean
+                    },
+                );
             });
             let downstream_traffic = traffic.clone();
             let downstream_trace_id = trace_id.clone();
             let downstream_headers = response_headers.clone();
             tokio::spawn(async move {
-                let mut parser = SseParser::new();
+                let mut decoder = StreamDecoder::new();
                 let mut response_body = String::new();
+                let mut dropped_bytes = 0usize;
                 while let Some(chunk) = down_rx.recv().await {
-                    for event in parser.push_bytes(&chunk) {
-                        if event.data.is_empty() || event.data == "[DONE]" {
+                    for data in decoder.push(&chunk) {
+                        if data.is_empty() || data == "[DONE]" {
                             continue;
                         }
-                        response_body.push_str(&event.data);
+                        capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                     }
                 }
-                for event in parser.finish() {
-                    if event.data.is_empty() || event.data == "[DONE]" {
+                for data in decoder.finish() {
+                    if data.is_empty() || data == "[DONE]" {
                         continue;
                     }
-                    response_body.push_str(&event.data);
+                    capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                 }
                 if let Some(meta) = downstream_meta {
-                    let body_bytes = if response_body.is_empty() {
-                        None
-                    } else {
-                        Some(Bytes::from(response_body))
-                    };
+                    let body_bytes = finish_recorded_body(
+                        response_body,
+                        dropped_bytes,
+                        &downstream_truncated_task,
+                    );
                     let event = build_downstream_event(
                         Some(downstream_trace_id.clone()),
                         meta,
@@ -756,55 +2109,252 @@ where
                         &downstream_headers,
                         body_bytes.as_ref(),
                         true,
+                        downstream_truncated.load(std::sync::atomic::Ordering::Relaxed),
                     );
                     downstream_traffic.record_downstream(event);
                 }
             });
 
+            let stream_usage_state = match usage {
+                UsageKind::None => None,
+                _ => Some(UsageState::Gemini(GeminiUsageState::new())),
+            };
             let stream = unfold(
                 (
                     body.stream,
-                    SseParser::new(),
+                    StreamDecoder::new(),
                     transform_factory(),
                     VecDeque::<Bytes>::new(),
                     down_tx,
                     up_tx,
+                    upstream_truncated,
+                    downstream_truncated,
+                    resume_provider,
+                    resume_req,
+                    resume_ctx,
+                    resume_budget,
+                    0u32,
+                    0usize,
+                    0usize,
+                    false,
+                    stream_usage_state,
+                    SseResumeState::default(),
+                    watchdog,
+                    loop_timed_out,
+                    loop_trace_id,
+                    false,
                 ),
-                |(mut upstream, mut parser, mut transform, mut pending, down_tx, up_tx)| async move {
+                move |(
+                    mut upstream,
+                    mut decoder,
+                    mut transform,
+                    mut pending,
+                    down_tx,
+                    up_tx,
+                    upstream_truncated,
+                    downstream_truncated,
+                    resume_provider,
+                    resume_req,
+                    mut resume_ctx,
+                    resume_budget,
+                    mut resumes_used,
+                    mut skip_remaining,
+                    mut emitted_total,
+                    mut done,
+                    mut stream_usage_state,
+                    mut sse_resume,
+                    watchdog,
+                    timed_out,
+                    trace_id,
+                    mut sentinel_sent,
+                )| async move {
                     loop {
                         if let Some(item) = pending.pop_front() {
-                            let _ = down_tx.send(item.clone()).await;
+                            // `try_send`, not `send`: a recorder task that fell
+                            // behind must never back-pressure client delivery.
+                            if down_tx.try_send(item.clone()).is_err() {
+                                downstream_truncated
+                                    .store(true, std::sync::atomic::Ordering::Relaxed);
+                            }
                             return Some((
                                 Ok(item),
-                                (upstream, parser, transform, pending, down_tx, up_tx),
+                                (
+                                    upstream,
+                                    decoder,
+                                    transform,
+                                    pending,
+                                    down_tx,
+                                    up_tx,
+                                    upstream_truncated,
+                                    downstream_truncated,
+                                    resume_provider,
+                                    resume_req,
+                                    resume_ctx,
+                                    resume_budget,
+                                    resumes_used,
+                                    skip_remaining,
+                                    emitted_total,
+                                    done,
+                                    stream_usage_state,
+                                    sse_resume,
+                                    watchdog,
+                                    timed_out,
+                                    trace_id,
+                                    sentinel_sent,
+                                ),
                             ));
                         }
-                        match upstream.next().await {
+                        if done {
+                            return None;
+                        }
+                        if watchdog.deadline_exceeded() {
+                            if let Some(bytes) =
+                                sse_json_bytes(&terminal_error(&format!(
+                                    "stream deadline exceeded (trace_id={trace_id})"
+                                )))
+                            {
+                                pending.push_back(bytes);
+                            }
+                            timed_out.store(true, std::sync::atomic::Ordering::Relaxed);
+                            done = true;
+                            continue;
+                        }
+                        let chunk = match watchdog.next_chunk(&mut upstream).await {
+                            Ok(chunk) => chunk,
+                            Err(()) => {
+                                // No chunk arrived within `idle_timeout`: tell
+                                // the client instead of leaving it blocked on a
+                                // connection that may never produce another byte.
+                                if let Some(bytes) =
+                                    sse_json_bytes(&terminal_error(&format!(
+                                        "idle timeout waiting for upstream (trace_id={trace_id})"
+                                    )))
+                                {
+                                    pending.push_back(bytes);
+                                }
+                                timed_out.store(true, std::sync::atomic::Ordering::Relaxed);
+                                done = true;
+                                continue;
+                            }
+                        };
+                        match chunk {
                             Some(Ok(bytes)) => {
-                                let _ = up_tx.send(bytes.clone()).await;
-                                for event in parser.push_bytes(&bytes) {
-                                    if event.data.is_empty() {
+                                if up_tx.try_send(bytes.clone()).is_err() {
+                                    upstream_truncated.store(true, std::sync::atomic::Ordering::Relaxed);
+                                }
+                                let fresh = sse_resume.observe(&bytes);
+                                for data in decoder.push(&bytes) {
+                                    if data.is_empty() || !fresh {
                                         continue;
                                     }
-                                    if let Ok(parsed) = serde_json::from_str::<BetaStreamEvent>(&event.data) {
-                                        pending.extend(transform(parsed));
+                                    if let Some(state) = stream_usage_state.as_mut() {
+                                        state.push_event(&data);
+                                    }
+                                    for parsed in parse_gemini_stream_payload(&data) {
+                                        for item in transform(parsed) {
+                                            if skip_remaining > 0 {
+                                                skip_remaining -= 1;
+                                                continue;
+                                            }
+                                            emitted_total += 1;
+                                            pending.push_back(item);
+                                        }
                                     }
                                 }
                                 continue;
                             }
                             Some(Err(err)) => {
-                                return Some((
-                                    Err(io::Error::new(io::ErrorKind::Other, err.to_string())),
-                                    (upstream, parser, transform, pending, down_tx, up_tx),
-                                ))
+                                resume_ctx.last_event_id = sse_resume.last_event_id.clone();
+                                if let Some(hint) = sse_resume.retry_hint {
+                                    tokio::time::sleep(hint).await;
+                                }
+                                let mut reconnected = false;
+                                if emitted_total == 0 {
+                                    // Nothing has reached the client yet, so a
+                                    // full backoff-retried reconnect (rather
+                                    // than the single-shot resume below) is
+                                    // indistinguishable from the upstream
+                                    // having taken longer to respond.
+                                    if let Ok(UpstreamOk {
+                                        response: ProxyResponse::Stream { body: resumed, .. },
+                                        ..
+                                    }) = call_native_with_stream_failover(
+                                        &resume_provider,
+                                        resume_req.clone(),
+                                        resume_ctx.clone(),
+                                    )
+                                    .await
+                                    {
+                                        upstream = resumed.stream;
+                                        decoder = StreamDecoder::new();
+                                        reconnected = true;
+                                    }
+                                } else if resumes_used < resume_budget {
+                                    resumes_used += 1;
+                                    if let Ok(UpstreamOk {
+                                        response: ProxyResponse::Stream { body: resumed, .. },
+                                        ..
+                                    }) = call_native_with_retry(
+                                        &resume_provider,
+                                        resume_req.clone(),
+                                        resume_ctx.clone(),
+                                    )
+                                    .await
+                                    {
+                                        upstream = resumed.stream;
+                                        decoder = StreamDecoder::new();
+                                        skip_remaining = emitted_total;
+                                        reconnected = true;
+                                    }
+                                }
+                                if reconnected {
+                                    continue;
+                                }
+                                // Resume budget exhausted (or the reconnect
+                                // attempt itself failed): tell the client the
+                                // stream ended instead of abruptly closing the
+                                // socket.
+                                if let Some(bytes) = sse_json_bytes(&terminal_error(&err.to_string()))
+                                {
+                                    pending.push_back(bytes);
+                                }
+                                done = true;
+                                continue;
                             }
                             None => {
-                                for event in parser.finish() {
-                                    if event.data.is_empty() {
+                                for data in decoder.finish() {
+                                    if data.is_empty() {
                                         continue;
                                     }
-                                    if let Ok(parsed) = serde_json::from_str::<BetaStreamEvent>(&event.data) {
-                                        pending.extend(transform(parsed));
+                                    if let Some(state) = stream_usage_state.as_mut() {
+                                        state.push_event(&data);
+                                    }
+                                    for parsed in parse_gemini_stream_payload(&data) {
+                                        for item in transform(parsed) {
+                                            if skip_remaining > 0 {
+                                                skip_remaining -= 1;
+                                                continue;
+                                            }
+                                            emitted_total += 1;
+                                            pending.push_back(item);
+                                        }
+                                    }
+                                }
+                                if let Some(state) = stream_usage_state.take() {
+                                    if let Some(usage_totals) =
+                                        map_usage_for_kind(usage, state.finish())
+                                    {
+                                        pending.extend(
+                                            usage_event(&usage_totals)
+                                                .into_iter()
+                                                .filter_map(|item| sse_json_bytes(&item)),
+                                        );
+                                    }
+                                }
+                                if !sentinel_sent {
+                                    sentinel_sent = true;
+                                    if let Some(bytes) = done_sentinel.clone() {
+                                        pending.push_back(bytes);
                                     }
                                 }
                                 if pending.is_empty() {
@@ -821,30 +2371,55 @@ where
                 body: StreamBody::new(body.content_type, stream),
             })
         }
-        ProxyResponse::Json { .. } => Err(UpstreamPassthroughError::service_unavailable(
-            "expected stream response".to_string(),
-        )),
+        ProxyResponse::Json {
+            status,
+            mut headers,
+            body,
+        } => {
+            // The upstream failed before any stream was even established
+            // (e.g. an auth/validation error returned as plain JSON):
+            // re-shape it into this call's downstream dialect instead of
+            // a blanket 503, same as the non-streaming transforms.
+            let message = upstream_error_message(&body);
+            let mapped_body = serde_json::to_vec(&terminal_error(&message))
+                .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
+            scrub_headers(&mut headers);
+            Ok(ProxyResponse::Json {
+                status,
+                headers,
+                body: Bytes::from(mapped_body),
+            })
+        }
     }
 }
 
-async fn transform_gemini_stream<P, F, T>(
+async fn transform_openai_responses_stream<P, F, T>(
     provider: &P,
     upstream_req: ProxyRequest,
     ctx_native: CallContext,
     ctx_downstream: CallContext,
     usage: UsageKind,
     mut transform_factory: F,
+    terminal_error: fn(&str) -> JsonValue,
+    usage_event: fn(&gproxy_provider_core::TrafficUsage) -> Vec<JsonValue>,
 ) -> Result<ProxyResponse, UpstreamPassthroughError>
 where
-    P: DispatchProvider,
+    P: DispatchProvider + Clone + 'static,
     F: FnMut() -> T + Send + 'static,
-    T: FnMut(gemini::generate_content::response::GenerateContentResponse) -> Vec<Bytes>
-        + Send
-        + 'static,
+    T: FnMut(openai::create_response::stream::ResponseStreamEvent) -> Vec<Bytes> + Send + 'static,
 {
-    let UpstreamOk { response, meta } = provider.call_native(upstream_req, ctx_native).await?;
+    let resume_budget = max_stream_resumes(&ctx_native);
+    let resume_provider = provider.clone();
+    let resume_req = upstream_req.clone();
+    let resume_ctx = ctx_native.clone();
+    let UpstreamOk { response, meta } =
+        call_native_with_stream_failover(provider, upstream_req, ctx_native).await?;
     match response {
-        ProxyResponse::Stream { status, headers, body } => {
+        ProxyResponse::Stream {
+            status,
+            headers,
+            body,
+        } => {
             let (down_tx, mut down_rx) = tokio::sync::mpsc::channel::<Bytes>(256);
             let (up_tx, mut up_rx) = tokio::sync::mpsc::channel::<Bytes>(256);
             let traffic = ctx_downstream.traffic.clone();
@@ -854,20 +2429,40 @@ where
             let upstream_traffic = traffic.clone();
             let upstream_trace_id = trace_id.clone();
             let upstream_headers = response_headers.clone();
+            let telemetry_span = ctx_downstream.telemetry_span.clone();
+            // Set from the unfold loop below (a full channel means the
+            // recorder fell behind and a chunk was dropped rather than
+            // blocking client delivery).
+            let upstream_truncated = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let downstream_truncated =
+                std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let upstream_truncated_task = upstream_truncated.clone();
+            let downstream_truncated_task = downstream_truncated.clone();
+            let watchdog = StreamWatchdog::new(&ctx_downstream);
+            let timed_out = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let loop_timed_out = timed_out.clone();
+            let upstream_timed_out = timed_out.clone();
+            let metrics = ctx_downstream.metrics.clone();
+            let metrics_provider = meta.provider.clone();
+            let metrics_model = meta.model.clone().unwrap_or_default();
+            let loop_trace_id = trace_id.clone();
             tokio::spawn(async move {
                 let mut usage_from_stream = None;
                 let mut usage_state = match usage {
                     UsageKind::None => None,
-                    _ => Some(UsageState::Gemini(GeminiUsageState::new())),
+                    _ => Some(UsageState::OpenAIResponses(
+                        super::usage::OpenAIResponsesUsageState::new(),
+                    )),
                 };
                 let mut decoder = StreamDecoder::new();
                 let mut response_body = String::new();
+                let mut dropped_bytes = 0usize;
                 while let Some(chunk) = up_rx.recv().await {
                     for data in decoder.push(&chunk) {
                         if data.is_empty() || data == "[DONE]" {
                             continue;
                         }
-                        response_body.push_str(&data);
+                        capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                         if let Some(state) = usage_state.as_mut() {
                             state.push_event(&data);
                         }
@@ -877,7 +2472,7 @@ where
                     if data.is_empty() || data == "[DONE]" {
                         continue;
                     }
-                    response_body.push_str(&data);
+                    capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                     if let Some(state) = usage_state.as_mut() {
                         state.push_event(&data);
                     }
@@ -885,11 +2480,13 @@ where
                 if let Some(state) = usage_state {
                     usage_from_stream = map_usage_for_kind(usage, state.finish());
                 }
-                let body_bytes = if response_body.is_empty() {
-                    None
-                } else {
-                    Some(Bytes::from(response_body))
-                };
+                if let (Some(span), Some(usage)) = (telemetry_span.as_ref(), usage_from_stream.as_ref())
+                {
+                    let (prompt_tokens, completion_tokens) = generic_token_counts(usage);
+                    span.record_tokens(prompt_tokens, completion_tokens);
+                }
+                let body_bytes =
+                    finish_recorded_body(response_body, dropped_bytes, &upstream_truncated_task);
                 let event = gproxy_provider_core::build_upstream_event(
                     Some(upstream_trace_id.clone()),
                     meta,
@@ -898,8 +2495,18 @@ where
                     body_bytes.as_ref(),
                     true,
                     usage_from_stream,
+                    upstream_truncated.load(std::sync::atomic::Ordering::Relaxed),
                 );
                 upstream_traffic.record_upstream(event);
+                metrics.record_termination(
+                    &metrics_provider,
+                    &metrics_model,
+                    if upstream_timed_out.load(std::sync::atomic::Ordering::Relaxed) {
+                        StreamTermination::Timeout
+                    } else {
+                        StreamTermination::Clean
+                    },
+                );
             });
             let downstream_traffic = traffic.clone();
             let downstream_trace_id = trace_id.clone();
@@ -907,26 +2514,27 @@ where
             tokio::spawn(async move {
                 let mut decoder = StreamDecoder::new();
                 let mut response_body = String::new();
+                let mut dropped_bytes = 0usize;
                 while let Some(chunk) = down_rx.recv().await {
                     for data in decoder.push(&chunk) {
                         if data.is_empty() || data == "[DONE]" {
                             continue;
                         }
-                        response_body.push_str(&data);
+                        capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                     }
                 }
                 for data in decoder.finish() {
                     if data.is_empty() || data == "[DONE]" {
                         continue;
                     }
-                    response_body.push_str(&data);
+                    capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                 }
                 if let Some(meta) = downstream_meta {
-                    let body_bytes = if response_body.is_empty() {
-                        None
-                    } else {
-                        Some(Bytes::from(response_body))
-                    };
+                    let body_bytes = finish_recorded_body(
+                        response_body,
+                        dropped_bytes,
+                        &downstream_truncated_task,
+                    );
                     let event = build_downstream_event(
                         Some(downstream_trace_id.clone()),
                         meta,
@@ -934,11 +2542,18 @@ where
                         &downstream_headers,
                         body_bytes.as_ref(),
                         true,
+                        downstream_truncated.load(std::sync::atomic::Ordering::Relaxed),
                     );
                     downstream_traffic.record_downstream(event);
                 }
             });
 
+            let stream_usage_state = match usage {
+                UsageKind::None => None,
+                _ => Some(UsageState::OpenAIResponses(
+                    super::usage::OpenAIResponsesUsageState::new(),
+                )),
+            };
             let stream = unfold(
                 (
                     body.stream,
@@ -947,42 +2562,231 @@ where
                     VecDeque::<Bytes>::new(),
                     down_tx,
                     up_tx,
+                    upstream_truncated,
+                    downstream_truncated,
+                    resume_provider,
+                    resume_req,
+                    resume_ctx,
+                    resume_budget,
+                    0u32,
+                    0usize,
+                    0usize,
+                    false,
+                    stream_usage_state,
+                    SseResumeState::default(),
+                    watchdog,
+                    loop_timed_out,
+                    loop_trace_id,
                 ),
-                |(mut upstream, mut decoder, mut transform, mut pending, down_tx, up_tx)| async move {
+                move |(
+                    mut upstream,
+                    mut decoder,
+                    mut transform,
+                    mut pending,
+                    down_tx,
+                    up_tx,
+                    upstream_truncated,
+                    downstream_truncated,
+                    resume_provider,
+                    resume_req,
+                    mut resume_ctx,
+                    resume_budget,
+                    mut resumes_used,
+                    mut skip_remaining,
+                    mut emitted_total,
+                    mut done,
+                    mut stream_usage_state,
+                    mut sse_resume,
+                    watchdog,
+                    timed_out,
+                    trace_id,
+                )| async move {
                     loop {
                         if let Some(item) = pending.pop_front() {
-                            let _ = down_tx.send(item.clone()).await;
+                            // `try_send`, not `send`: a recorder task that fell
+                            // behind must never back-pressure client delivery.
+                            if down_tx.try_send(item.clone()).is_err() {
+                                downstream_truncated
+                                    .store(true, std::sync::atomic::Ordering::Relaxed);
+                            }
                             return Some((
                                 Ok(item),
-                                (upstream, decoder, transform, pending, down_tx, up_tx),
+                                (
+                                    upstream,
+                                    decoder,
+                                    transform,
+                                    pending,
+                                    down_tx,
+                                    up_tx,
+                                    upstream_truncated,
+                                    downstream_truncated,
+                                    resume_provider,
+                                    resume_req,
+                                    resume_ctx,
+                                    resume_budget,
+                                    resumes_used,
+                                    skip_remaining,
+                                    emitted_total,
+                                    done,
+                                    stream_usage_state,
+                                    sse_resume,
+                                    watchdog,
+                                    timed_out,
+                                    trace_id,
+                                ),
                             ));
                         }
-                        match upstream.next().await {
+                        if done {
+                            return None;
+                        }
+                        if watchdog.deadline_exceeded() {
+                            if let Some(bytes) =
+                                sse_json_bytes(&terminal_error(&format!(
+                                    "stream deadline exceeded (trace_id={trace_id})"
+                                )))
+                            {
+                                pending.push_back(bytes);
+                            }
+                            timed_out.store(true, std::sync::atomic::Ordering::Relaxed);
+                            done = true;
+                            continue;
+                        }
+                        let chunk = match watchdog.next_chunk(&mut upstream).await {
+                            Ok(chunk) => chunk,
+                            Err(()) => {
+                                // No chunk arrived within `idle_timeout`: tell
+                                // the client instead of leaving it blocked on a
+                                // connection that may never produce another byte.
+                                if let Some(bytes) =
+                                    sse_json_bytes(&terminal_error(&format!(
+                                        "idle timeout waiting for upstream (trace_id={trace_id})"
+                                    )))
+                                {
+                                    pending.push_back(bytes);
+                                }
+                                timed_out.store(true, std::sync::atomic::Ordering::Relaxed);
+                                done = true;
+                                continue;
+                            }
+                        };
+                        match chunk {
                             Some(Ok(bytes)) => {
-                                let _ = up_tx.send(bytes.clone()).await;
+                                if up_tx.try_send(bytes.clone()).is_err() {
+                                    upstream_truncated.store(true, std::sync::atomic::Ordering::Relaxed);
+                                }
+                                let fresh = sse_resume.observe(&bytes);
                                 for data in decoder.push(&bytes) {
-                                    if data.is_empty() {
+                                    if data.is_empty() || !fresh {
                                         continue;
                                     }
-                                    for parsed in parse_gemini_stream_payload(&data) {
-                                        pending.extend(transform(parsed));
+                                    if let Some(state) = stream_usage_state.as_mut() {
+                                        state.push_event(&data);
+                                    }
+                                    if let Ok(parsed) = serde_json::from_str::<
+                                        openai::create_response::stream::ResponseStreamEvent,
+                                    >(&data)
+                                    {
+                                        for item in transform(parsed) {
+                                            if skip_remaining > 0 {
+                                                skip_remaining -= 1;
+                                                continue;
+                                            }
+                                            emitted_total += 1;
+                                            pending.push_back(item);
+                                        }
                                     }
                                 }
                                 continue;
                             }
                             Some(Err(err)) => {
-                                return Some((
-                                    Err(io::Error::new(io::ErrorKind::Other, err.to_string())),
-                                    (upstream, decoder, transform, pending, down_tx, up_tx),
-                                ))
+                                resume_ctx.last_event_id = sse_resume.last_event_id.clone();
+                                if let Some(hint) = sse_resume.retry_hint {
+                                    tokio::time::sleep(hint).await;
+                                }
+                                let mut reconnected = false;
+                                if emitted_total == 0 {
+                                    // Nothing has reached the client yet, so a
+                                    // full backoff-retried reconnect (rather
+                                    // than the single-shot resume below) is
+                                    // indistinguishable from the upstream
+                                    // having taken longer to respond.
+                                    if let Ok(UpstreamOk {
+                                        response: ProxyResponse::Stream { body: resumed, .. },
+                                        ..
+                                    }) = call_native_with_stream_failover(
+                                        &resume_provider,
+                                        resume_req.clone(),
+                                        resume_ctx.clone(),
+                                    )
+                                    .await
+                                    {
+                                        upstream = resumed.stream;
+                                        decoder = StreamDecoder::new();
+                                        reconnected = true;
+                                    }
+                                } else if resumes_used < resume_budget {
+                                    resumes_used += 1;
+                                    if let Ok(UpstreamOk {
+                                        response: ProxyResponse::Stream { body: resumed, .. },
+                                        ..
+                                    }) = call_native_with_retry(
+                                        &resume_provider,
+                                        resume_req.clone(),
+                                        resume_ctx.clone(),
+                                    )
+                                    .await
+                                    {
+                                        upstream = resumed.stream;
+                                        decoder = StreamDecoder::new();
+                                        skip_remaining = emitted_total;
+                                        reconnected = true;
+                                    }
+                                }
+                                if reconnected {
+                                    continue;
+                                }
+                                // Resume budget exhausted (or the reconnect
+                                // attempt itself failed): tell the client the
+                                // stream ended instead of abruptly closing the
+                                // socket.
+                                if let Some(bytes) = sse_json_bytes(&terminal_error(&err.to_string()))
+                                {
+                                    pending.push_back(bytes);
+                                }
+                                done = true;
+                                continue;
                             }
                             None => {
                                 for data in decoder.finish() {
                                     if data.is_empty() {
                                         continue;
                                     }
-                                    for parsed in parse_gemini_stream_payload(&data) {
-                                        pending.extend(transform(parsed));
+                                    if let Some(state) = stream_usage_state.as_mut() {
+                                        state.push_event(&data);
+                                    }
+                                    if let Ok(parsed) = serde_json::from_str::<
+                                        openai::create_response::stream::ResponseStreamEvent,
+                                    >(&data)
+                                    {
+                                        for item in transform(parsed) {
+                                            if skip_remaining > 0 {
+                                                skip_remaining -= 1;
+                                                continue;
+                                            }
+                                            emitted_total += 1;
+                                            pending.push_back(item);
+                                        }
+                                    }
+                                }
+                                if let Some(state) = stream_usage_state.take() {
+                                    if let Some(usage_totals) =
+                                        map_usage_for_kind(usage, state.finish())
+                                    {
+                                        pending.extend(
+                                            usage_event(&usage_totals)
+                                                .into_iter()
+                                                .filter_map(|item| sse_json_bytes(&item)),
+                                        );
                                     }
                                 }
                                 if pending.is_empty() {
@@ -999,30 +2803,59 @@ where
                 body: StreamBody::new(body.content_type, stream),
             })
         }
-        ProxyResponse::Json { .. } => Err(UpstreamPassthroughError::service_unavailable(
-            "expected stream response".to_string(),
-        )),
+        ProxyResponse::Json {
+            status,
+            mut headers,
+            body,
+        } => {
+            // The upstream failed before any stream was even established
+            // (e.g. an auth/validation error returned as plain JSON):
+            // re-shape it into this call's downstream dialect instead of
+            // a blanket 503, same as the non-streaming transforms.
+            let message = upstream_error_message(&body);
+            let mapped_body = serde_json::to_vec(&terminal_error(&message))
+                .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
+            scrub_headers(&mut headers);
+            Ok(ProxyResponse::Json {
+                status,
+                headers,
+                body: Bytes::from(mapped_body),
+            })
+        }
     }
 }
 
-async fn transform_openai_responses_stream<P, F, T>(
+/// Same shape as `transform_openai_responses_stream`, parameterized on the
+/// Chat Completions event type instead of the Responses one.
+async fn transform_openai_chat_stream<P, F, T>(
     provider: &P,
     upstream_req: ProxyRequest,
     ctx_native: CallContext,
     ctx_downstream: CallContext,
     usage: UsageKind,
     mut transform_factory: F,
+    terminal_error: fn(&str) -> JsonValue,
+    usage_event: fn(&gproxy_provider_core::TrafficUsage) -> Vec<JsonValue>,
 ) -> Result<ProxyResponse, UpstreamPassthroughError>
 where
-    P: DispatchProvider,
+    P: DispatchProvider + Clone + 'static,
     F: FnMut() -> T + Send + 'static,
-    T: FnMut(openai::create_response::stream::ResponseStreamEvent) -> Vec<Bytes>
+    T: FnMut(openai::create_chat_completions::stream::CreateChatCompletionStreamResponse) -> Vec<Bytes>
         + Send
         + 'static,
 {
-    let UpstreamOk { response, meta } = provider.call_native(upstream_req, ctx_native).await?;
+    let resume_budget = max_stream_resumes(&ctx_native);
+    let resume_provider = provider.clone();
+    let resume_req = upstream_req.clone();
+    let resume_ctx = ctx_native.clone();
+    let UpstreamOk { response, meta } =
+        call_native_with_stream_failover(provider, upstream_req, ctx_native).await?;
     match response {
-        ProxyResponse::Stream { status, headers, body } => {
+        ProxyResponse::Stream {
+            status,
+            headers,
+            body,
+        } => {
             let (down_tx, mut down_rx) = tokio::sync::mpsc::channel::<Bytes>(256);
             let (up_tx, mut up_rx) = tokio::sync::mpsc::channel::<Bytes>(256);
             let traffic = ctx_downstream.traffic.clone();
@@ -1032,22 +2865,39 @@ where
             let upstream_traffic = traffic.clone();
             let upstream_trace_id = trace_id.clone();
             let upstream_headers = response_headers.clone();
+            let telemetry_span = ctx_downstream.telemetry_span.clone();
+            // Set when a recorder task's retained body hits
+            // `RECORDED_BODY_BUDGET`; this stream doesn't share the other
+            // transforms' try_send-backpressure truncation path, so it only
+            // ever reflects the body-capture cap.
+            let upstream_truncated = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let downstream_truncated =
+                std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let upstream_truncated_task = upstream_truncated.clone();
+            let downstream_truncated_task = downstream_truncated.clone();
+            let watchdog = StreamWatchdog::new(&ctx_downstream);
+            let timed_out = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let loop_timed_out = timed_out.clone();
+            let upstream_timed_out = timed_out.clone();
+            let metrics = ctx_downstream.metrics.clone();
+            let metrics_provider = meta.provider.clone();
+            let metrics_model = meta.model.clone().unwrap_or_default();
+            let loop_trace_id = trace_id.clone();
             tokio::spawn(async move {
                 let mut usage_from_stream = None;
                 let mut usage_state = match usage {
                     UsageKind::None => None,
-                    _ => Some(UsageState::OpenAIResponses(
-                        super::usage::OpenAIResponsesUsageState::new(),
-                    )),
+                    _ => Some(UsageState::OpenAI(super::usage::OpenAIUsageState::new())),
                 };
                 let mut decoder = StreamDecoder::new();
                 let mut response_body = String::new();
+                let mut dropped_bytes = 0usize;
                 while let Some(chunk) = up_rx.recv().await {
                     for data in decoder.push(&chunk) {
                         if data.is_empty() || data == "[DONE]" {
                             continue;
                         }
-                        response_body.push_str(&data);
+                        capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                         if let Some(state) = usage_state.as_mut() {
                             state.push_event(&data);
                         }
@@ -1057,7 +2907,7 @@ where
                     if data.is_empty() || data == "[DONE]" {
                         continue;
                     }
-                    response_body.push_str(&data);
+                    capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                     if let Some(state) = usage_state.as_mut() {
                         state.push_event(&data);
                     }
@@ -1065,11 +2915,13 @@ where
                 if let Some(state) = usage_state {
                     usage_from_stream = map_usage_for_kind(usage, state.finish());
                 }
-                let body_bytes = if response_body.is_empty() {
-                    None
-                } else {
-                    Some(Bytes::from(response_body))
-                };
+                if let (Some(span), Some(usage)) = (telemetry_span.as_ref(), usage_from_stream.as_ref())
+                {
+                    let (prompt_tokens, completion_tokens) = generic_token_counts(usage);
+                    span.record_tokens(prompt_tokens, completion_tokens);
+                }
+                let body_bytes =
+                    finish_recorded_body(response_body, dropped_bytes, &upstream_truncated_task);
                 let event = gproxy_provider_core::build_upstream_event(
                     Some(upstream_trace_id.clone()),
                     meta,
@@ -1078,8 +2930,18 @@ where
                     body_bytes.as_ref(),
                     true,
                     usage_from_stream,
+                    upstream_truncated.load(std::sync::atomic::Ordering::Relaxed),
                 );
                 upstream_traffic.record_upstream(event);
+                metrics.record_termination(
+                    &metrics_provider,
+                    &metrics_model,
+                    if upstream_timed_out.load(std::sync::atomic::Ordering::Relaxed) {
+                        StreamTermination::Timeout
+                    } else {
+                        StreamTermination::Clean
+                    },
+                );
             });
             let downstream_traffic = traffic.clone();
             let downstream_trace_id = trace_id.clone();
@@ -1087,26 +2949,27 @@ where
             tokio::spawn(async move {
                 let mut decoder = StreamDecoder::new();
                 let mut response_body = String::new();
+                let mut dropped_bytes = 0usize;
                 while let Some(chunk) = down_rx.recv().await {
                     for data in decoder.push(&chunk) {
                         if data.is_empty() || data == "[DONE]" {
                             continue;
                         }
-                        response_body.push_str(&data);
+                        capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                     }
                 }
                 for data in decoder.finish() {
                     if data.is_empty() || data == "[DONE]" {
                         continue;
                     }
-                    response_body.push_str(&data);
+                    capture_body_chunk(&mut response_body, &mut dropped_bytes, &data);
                 }
                 if let Some(meta) = downstream_meta {
-                    let body_bytes = if response_body.is_empty() {
-                        None
-                    } else {
-                        Some(Bytes::from(response_body))
-                    };
+                    let body_bytes = finish_recorded_body(
+                        response_body,
+                        dropped_bytes,
+                        &downstream_truncated_task,
+                    );
                     let event = build_downstream_event(
                         Some(downstream_trace_id.clone()),
                         meta,
@@ -1114,11 +2977,16 @@ where
                         &downstream_headers,
                         body_bytes.as_ref(),
                         true,
+                        downstream_truncated.load(std::sync::atomic::Ordering::Relaxed),
                     );
                     downstream_traffic.record_downstream(event);
                 }
             });
 
+            let stream_usage_state = match usage {
+                UsageKind::None => None,
+                _ => Some(UsageState::OpenAI(super::usage::OpenAIUsageState::new())),
+            };
             let stream = unfold(
                 (
                     body.stream,
@@ -1127,48 +2995,216 @@ where
                     VecDeque::<Bytes>::new(),
                     down_tx,
                     up_tx,
+                    resume_provider,
+                    resume_req,
+                    resume_ctx,
+                    resume_budget,
+                    0u32,
+                    0usize,
+                    0usize,
+                    false,
+                    stream_usage_state,
+                    SseResumeState::default(),
+                    watchdog,
+                    loop_timed_out,
+                    loop_trace_id,
                 ),
-                |(mut upstream, mut decoder, mut transform, mut pending, down_tx, up_tx)| async move {
+                move |(
+                    mut upstream,
+                    mut decoder,
+                    mut transform,
+                    mut pending,
+                    down_tx,
+                    up_tx,
+                    resume_provider,
+                    resume_req,
+                    mut resume_ctx,
+                    resume_budget,
+                    mut resumes_used,
+                    mut skip_remaining,
+                    mut emitted_total,
+                    mut done,
+                    mut stream_usage_state,
+                    mut sse_resume,
+                    watchdog,
+                    timed_out,
+                    trace_id,
+                )| async move {
                     loop {
                         if let Some(item) = pending.pop_front() {
                             let _ = down_tx.send(item.clone()).await;
                             return Some((
                                 Ok(item),
-                                (upstream, decoder, transform, pending, down_tx, up_tx),
+                                (
+                                    upstream,
+                                    decoder,
+                                    transform,
+                                    pending,
+                                    down_tx,
+                                    up_tx,
+                                    resume_provider,
+                                    resume_req,
+                                    resume_ctx,
+                                    resume_budget,
+                                    resumes_used,
+                                    skip_remaining,
+                                    emitted_total,
+                                    done,
+                                    stream_usage_state,
+                                    sse_resume,
+                                    watchdog,
+                                    timed_out,
+                                    trace_id,
+                                ),
                             ));
                         }
-                        match upstream.next().await {
+                        if done {
+                            return None;
+                        }
+                        if watchdog.deadline_exceeded() {
+                            if let Some(bytes) =
+                                sse_json_bytes(&terminal_error(&format!(
+                                    "stream deadline exceeded (trace_id={trace_id})"
+                                )))
+                            {
+                                pending.push_back(bytes);
+                            }
+                            timed_out.store(true, std::sync::atomic::Ordering::Relaxed);
+                            done = true;
+                            continue;
+                        }
+                        let chunk = match watchdog.next_chunk(&mut upstream).await {
+                            Ok(chunk) => chunk,
+                            Err(()) => {
+                                // No chunk arrived within `idle_timeout`: tell
+                                // the client instead of leaving it blocked on a
+                                // connection that may never produce another byte.
+                                if let Some(bytes) =
+                                    sse_json_bytes(&terminal_error(&format!(
+                                        "idle timeout waiting for upstream (trace_id={trace_id})"
+                                    )))
+                                {
+                                    pending.push_back(bytes);
+                                }
+                                timed_out.store(true, std::sync::atomic::Ordering::Relaxed);
+                                done = true;
+                                continue;
+                            }
+                        };
+                        match chunk {
                             Some(Ok(bytes)) => {
                                 let _ = up_tx.send(bytes.clone()).await;
+                                let fresh = sse_resume.observe(&bytes);
                                 for data in decoder.push(&bytes) {
-                                    if data.is_empty() {
+                                    if data.is_empty() || data == "[DONE]" || !fresh {
                                         continue;
                                     }
+                                    if let Some(state) = stream_usage_state.as_mut() {
+                                        state.push_event(&data);
+                                    }
                                     if let Ok(parsed) = serde_json::from_str::<
-                                        openai::create_response::stream::ResponseStreamEvent,
+                                        openai::create_chat_completions::stream::CreateChatCompletionStreamResponse,
                                     >(&data)
                                     {
-                                        pending.extend(transform(parsed));
+                                        for item in transform(parsed) {
+                                            if skip_remaining > 0 {
+                                                skip_remaining -= 1;
+                                                continue;
+                                            }
+                                            emitted_total += 1;
+                                            pending.push_back(item);
+                                        }
                                     }
                                 }
                                 continue;
                             }
                             Some(Err(err)) => {
-                                return Some((
-                                    Err(io::Error::new(io::ErrorKind::Other, err.to_string())),
-                                    (upstream, decoder, transform, pending, down_tx, up_tx),
-                                ))
+                                resume_ctx.last_event_id = sse_resume.last_event_id.clone();
+                                if let Some(hint) = sse_resume.retry_hint {
+                                    tokio::time::sleep(hint).await;
+                                }
+                                let mut reconnected = false;
+                                if emitted_total == 0 {
+                                    // Nothing has reached the client yet, so a
+                                    // full backoff-retried reconnect (rather
+                                    // than the single-shot resume below) is
+                                    // indistinguishable from the upstream
+                                    // having taken longer to respond.
+                                    if let Ok(UpstreamOk {
+                                        response: ProxyResponse::Stream { body: resumed, .. },
+                                        ..
+                                    }) = call_native_with_stream_failover(
+                                        &resume_provider,
+                                        resume_req.clone(),
+                                        resume_ctx.clone(),
+                                    )
+                                    .await
+                                    {
+                                        upstream = resumed.stream;
+                                        decoder = StreamDecoder::new();
+                                        reconnected = true;
+                                    }
+                                } else if resumes_used < resume_budget {
+                                    resumes_used += 1;
+                                    if let Ok(UpstreamOk {
+                                        response: ProxyResponse::Stream { body: resumed, .. },
+                                        ..
+                                    }) = call_native_with_retry(
+                                        &resume_provider,
+                                        resume_req.clone(),
+                                        resume_ctx.clone(),
+                                    )
+                                    .await
+                                    {
+                                        upstream = resumed.stream;
+                                        decoder = StreamDecoder::new();
+                                        skip_remaining = emitted_total;
+                                        reconnected = true;
+                                    }
+                                }
+                                if reconnected {
+                                    continue;
+                                }
+                                // Resume budget exhausted (or the reconnect
+                                // attempt itself failed): tell the client the
+                                // stream ended instead of abruptly closing the
+                                // socket.
+                                if let Some(bytes) = sse_json_bytes(&terminal_error(&err.to_string()))
+                                {
+                                    pending.push_back(bytes);
+                                }
+                                done = true;
+                                continue;
                             }
                             None => {
                                 for data in decoder.finish() {
-                                    if data.is_empty() {
+                                    if data.is_empty() || data == "[DONE]" {
                                         continue;
                                     }
+                                    if let Some(state) = stream_usage_state.as_mut() {
+                                        state.push_event(&data);
+                                    }
                                     if let Ok(parsed) = serde_json::from_str::<
-                                        openai::create_response::stream::ResponseStreamEvent,
+                                        openai::create_chat_completions::stream::CreateChatCompletionStreamResponse,
                                     >(&data)
                                     {
-                                        pending.extend(transform(parsed));
+                                        for item in transform(parsed) {
+                                            if skip_remaining > 0 {
+                                                skip_remaining -= 1;
+                                                continue;
+                                            }
+                                            emitted_total += 1;
+                                            pending.push_back(item);
+                                        }
+                                    }
+                                }
+                                if let Some(state) = stream_usage_state.take() {
+                                    if let Some(usage_totals) = map_usage_for_kind(usage, state.finish()) {
+                                        pending.extend(
+                                            usage_event(&usage_totals)
+                                                .into_iter()
+                                                .filter_map(|item| sse_json_bytes(&item)),
+                                        );
                                     }
                                 }
                                 if pending.is_empty() {
@@ -1185,9 +3221,25 @@ where
                 body: StreamBody::new(body.content_type, stream),
             })
         }
-        ProxyResponse::Json { .. } => Err(UpstreamPassthroughError::service_unavailable(
-            "expected stream response".to_string(),
-        )),
+        ProxyResponse::Json {
+            status,
+            mut headers,
+            body,
+        } => {
+            // The upstream failed before any stream was even established
+            // (e.g. an auth/validation error returned as plain JSON):
+            // re-shape it into this call's downstream dialect instead of
+            // a blanket 503, same as the non-streaming transforms.
+            let message = upstream_error_message(&body);
+            let mapped_body = serde_json::to_vec(&terminal_error(&message))
+                .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
+            scrub_headers(&mut headers);
+            Ok(ProxyResponse::Json {
+                status,
+                headers,
+                body: Bytes::from(mapped_body),
+            })
+        }
     }
 }
 
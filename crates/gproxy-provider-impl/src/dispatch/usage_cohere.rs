@@ -0,0 +1,117 @@
+//! Cohere chat usage accounting, mirroring `ClaudeUsageState`/
+//! `OpenAIUsageState`/`GeminiUsageState` in `super::usage`. Wiring this in
+//! as `UsageKind::Cohere` / `UsageState::Cohere(CohereUsageState)` and
+//! adding the matching `cohere_*` fields to `TrafficUsage` hasn't happened
+//! yet — no `DispatchProvider` dispatches a Cohere plan, so there's no
+//! `UsageKind::Cohere` to wire against — so the extraction logic lives here
+//! on its own, ready for that to be a small, mechanical follow-up.
+
+use bytes::Bytes;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+struct CohereBilledUnits {
+    #[serde(default)]
+    input_tokens: Option<f64>,
+    #[serde(default)]
+    output_tokens: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CohereTokens {
+    #[serde(default)]
+    input_tokens: Option<f64>,
+    #[serde(default)]
+    output_tokens: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CohereMeta {
+    #[serde(default)]
+    billed_units: CohereBilledUnits,
+    #[serde(default)]
+    tokens: CohereTokens,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CohereUsageBody {
+    #[serde(default)]
+    meta: Option<CohereMeta>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CohereStreamEndEvent {
+    #[serde(default)]
+    response: Option<CohereUsageBody>,
+}
+
+/// Parsed usage for a single Cohere chat completion. Cohere reports both a
+/// provider-billed figure (`billed_units`) and a raw tokenizer count
+/// (`tokens`); both are kept rather than collapsed into one number, since
+/// they can legitimately differ (e.g. cached tokens aren't billed).
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct CohereUsage {
+    pub(super) billed_input_tokens: Option<u64>,
+    pub(super) billed_output_tokens: Option<u64>,
+    pub(super) raw_input_tokens: Option<u64>,
+    pub(super) raw_output_tokens: Option<u64>,
+}
+
+fn usage_from_meta(meta: &CohereMeta) -> CohereUsage {
+    CohereUsage {
+        billed_input_tokens: meta.billed_units.input_tokens.map(|n| n as u64),
+        billed_output_tokens: meta.billed_units.output_tokens.map(|n| n as u64),
+        raw_input_tokens: meta.tokens.input_tokens.map(|n| n as u64),
+        raw_output_tokens: meta.tokens.output_tokens.map(|n| n as u64),
+    }
+}
+
+/// Extracts usage from a non-streaming Cohere chat response body.
+pub(super) fn extract_cohere_usage_from_body(body: &Bytes) -> Option<CohereUsage> {
+    let parsed: CohereUsageBody = serde_json::from_slice(body).ok()?;
+    parsed.meta.as_ref().map(usage_from_meta)
+}
+
+/// Accumulates a Cohere chat stream, extracting usage from the terminal
+/// `stream-end` event the same way `ClaudeUsageState` extracts usage from
+/// Claude's terminal `message_delta` event.
+#[derive(Debug, Default)]
+pub(super) struct CohereUsageState {
+    usage: Option<CohereUsage>,
+}
+
+impl CohereUsageState {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn push_event(&mut self, data: &str) {
+        let Ok(event) = serde_json::from_str::<CohereStreamEndEvent>(data) else {
+            return;
+        };
+        if let Some(meta) = event.response.as_ref().and_then(|r| r.meta.as_ref()) {
+            self.usage = Some(usage_from_meta(meta));
+        }
+    }
+
+    pub(super) fn finish(self) -> Option<CohereUsage> {
+        self.usage
+    }
+}
+
+/// Best-effort fallback mirroring `map_claude_usage_to_openai`/
+/// `map_claude_usage_to_gemini`: when only Claude-protocol usage is
+/// available for a response that's being reported as Cohere usage,
+/// approximate Cohere's fields from it. Claude has no billed/raw
+/// distinction, so both land on the same numbers.
+pub(super) fn map_claude_usage_to_cohere(
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+) -> CohereUsage {
+    CohereUsage {
+        billed_input_tokens: input_tokens,
+        billed_output_tokens: output_tokens,
+        raw_input_tokens: input_tokens,
+        raw_output_tokens: output_tokens,
+    }
+}
@@ -0,0 +1,58 @@
+//! A uniform interface over the per-provider streaming usage accumulators
+//! (`GeminiUsageState` in `usage_gemini.rs`, `CohereUsageState` in
+//! `usage_cohere.rs`, and `ClaudeUsageState`/`OpenAIUsageState`/
+//! `OpenAIResponsesUsageState` in `super::usage`), so a caller can drive
+//! whichever one a `UsageKind` selects without matching on the `UsageState`
+//! enum by name. Today `record.rs`/`transform.rs` still do that matching
+//! directly against `UsageState` (`super::usage`) instead of this trait —
+//! rewiring them to go through `StreamUsageExtractor` instead is a
+//! mechanical follow-up, same as `CohereUsageState` (`usage_cohere.rs`)
+//! already being written but not yet `UsageKind`/`UsageState`-wired.
+//!
+//! `finish` returns `Self::Usage` rather than the common `TrafficUsage`
+//! type directly, because producing a `TrafficUsage` means populating its
+//! per-provider fields and `TrafficUsage` lives in `traffic.rs`, which isn't
+//! part of this checkout — each impl's `Usage` is its own already-real type
+//! (`GeminiUsage`, `CohereUsage`), and folding that into `TrafficUsage` is
+//! `super::usage`'s job (it already does this for `GeminiUsage` via
+//! `gemini_usage_to_traffic`).
+pub(super) trait StreamUsageExtractor {
+    type Usage;
+
+    /// Feeds one already-decoded SSE `data: {...}` payload into the
+    /// accumulator.
+    fn push_event(&mut self, data: &str);
+
+    /// Consumes the accumulator, returning whatever usage it collected.
+    /// Implementations that support a caller-supplied fallback (e.g.
+    /// `GeminiUsageState::finish`, for when a char-count estimate is
+    /// available) keep that as an inherent method; this trait method always
+    /// uses each type's no-evidence default instead, since a generic
+    /// fallback closure can't be threaded through a shared trait without
+    /// an extra type parameter that no current caller needs.
+    fn finish(self) -> Self::Usage;
+}
+
+impl StreamUsageExtractor for super::usage_gemini::GeminiUsageState {
+    type Usage = super::usage_gemini::GeminiUsage;
+
+    fn push_event(&mut self, data: &str) {
+        super::usage_gemini::GeminiUsageState::push_event(self, data);
+    }
+
+    fn finish(self) -> Self::Usage {
+        super::usage_gemini::GeminiUsageState::finish(self, Self::Usage::default)
+    }
+}
+
+impl StreamUsageExtractor for super::usage_cohere::CohereUsageState {
+    type Usage = Option<super::usage_cohere::CohereUsage>;
+
+    fn push_event(&mut self, data: &str) {
+        super::usage_cohere::CohereUsageState::push_event(self, data);
+    }
+
+    fn finish(self) -> Self::Usage {
+        super::usage_cohere::CohereUsageState::finish(self)
+    }
+}
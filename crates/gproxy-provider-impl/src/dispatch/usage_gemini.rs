@@ -0,0 +1,132 @@
+//! Gemini `usageMetadata` extraction, mirroring `usage_cohere.rs`.
+//! `GeminiUsageState` itself lives here as the real `push_event`/`finish`
+//! accumulator; `super::usage` re-exports it as `UsageState::Gemini`'s
+//! payload and adapts its `GeminiUsage` output down to `TrafficUsage` via
+//! `gemini_usage_to_traffic`.
+//!
+//! `GeminiUsageState` (as called from `record.rs`/`transform.rs`) already
+//! receives one already-decoded SSE `data: {...}` payload per `push_event`
+//! call — `StreamDecoder` (`super::stream`) owns the carry-over buffer that
+//! turns raw chunks into those payloads — so this module only needs to
+//! parse a single decoded event at a time.
+
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+struct GeminiUsageMetadata {
+    #[serde(default, rename = "promptTokenCount")]
+    prompt_token_count: Option<u64>,
+    #[serde(default, rename = "candidatesTokenCount")]
+    candidates_token_count: Option<u64>,
+    #[serde(default, rename = "totalTokenCount")]
+    total_token_count: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GeminiStreamEvent {
+    #[serde(default, rename = "usageMetadata")]
+    usage_metadata: Option<GeminiUsageMetadata>,
+}
+
+/// Token counts for a single Gemini `generateContent` call, streamed or
+/// not. `total_tokens` is carried separately from `prompt_tokens +
+/// candidate_tokens` since Gemini's own total can include overhead (e.g.
+/// thinking tokens) neither of the other two fields accounts for.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct GeminiUsage {
+    pub(super) prompt_tokens: Option<u64>,
+    pub(super) candidate_tokens: Option<u64>,
+    pub(super) total_tokens: Option<u64>,
+}
+
+impl GeminiUsage {
+    fn is_empty(&self) -> bool {
+        self.prompt_tokens.is_none() && self.candidate_tokens.is_none() && self.total_tokens.is_none()
+    }
+
+    /// Overlays `other`'s non-null fields onto `self`, so a later event that
+    /// only reports (say) an updated `totalTokenCount` doesn't clobber an
+    /// earlier event's `promptTokenCount` with a null.
+    fn merge(&mut self, other: GeminiUsage) {
+        if other.prompt_tokens.is_some() {
+            self.prompt_tokens = other.prompt_tokens;
+        }
+        if other.candidate_tokens.is_some() {
+            self.candidate_tokens = other.candidate_tokens;
+        }
+        if other.total_tokens.is_some() {
+            self.total_tokens = other.total_tokens;
+        }
+    }
+}
+
+fn usage_from_metadata(metadata: &GeminiUsageMetadata) -> GeminiUsage {
+    GeminiUsage {
+        prompt_tokens: metadata.prompt_token_count,
+        candidate_tokens: metadata.candidates_token_count,
+        total_tokens: metadata.total_token_count,
+    }
+}
+
+/// Extracts usage from a non-streaming `generateContent` response body.
+pub(super) fn extract_gemini_usage_from_body(body: &[u8]) -> Option<GeminiUsage> {
+    let parsed: GeminiStreamEvent = serde_json::from_slice(body).ok()?;
+    parsed.usage_metadata.as_ref().map(usage_from_metadata)
+}
+
+/// Accumulates `usageMetadata` across a `generateContent` SSE stream. Every
+/// chunk in the stream can carry its own `usageMetadata` snapshot (Gemini
+/// resends running totals, not just a final delta), so each decoded event
+/// is merged field-by-field into the running total rather than replacing it
+/// wholesale.
+#[derive(Debug, Default)]
+pub(super) struct GeminiUsageState {
+    usage: GeminiUsage,
+}
+
+impl GeminiUsageState {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn push_event(&mut self, data: &str) {
+        let Ok(event) = serde_json::from_str::<GeminiStreamEvent>(data) else {
+            return;
+        };
+        if let Some(metadata) = event.usage_metadata.as_ref() {
+            self.usage.merge(usage_from_metadata(metadata));
+        }
+    }
+
+    /// Returns the accumulated usage, or `fallback` if no event in the
+    /// stream ever carried a `usageMetadata` block — some upstreams omit it
+    /// entirely on early-terminated or errored streams, and billing should
+    /// degrade to an estimate rather than report nothing at all.
+    pub(super) fn finish(self, fallback: impl FnOnce() -> GeminiUsage) -> GeminiUsage {
+        if self.usage.is_empty() {
+            fallback()
+        } else {
+            self.usage
+        }
+    }
+}
+
+/// Crude fallback when a Gemini stream never reports `usageMetadata`:
+/// ~4 characters per token, the same rule of thumb used for Claude/OpenAI
+/// cost estimates elsewhere in the proxy when a precise count isn't
+/// available. `candidate_chars` is the concatenation of every streamed
+/// text part, `prompt_chars` the serialized request contents.
+pub(super) fn estimate_gemini_usage(prompt_chars: usize, candidate_chars: usize) -> GeminiUsage {
+    let estimate = |chars: usize| -> Option<u64> {
+        if chars == 0 {
+            None
+        } else {
+            Some(((chars as u64) + 3) / 4)
+        }
+    };
+    GeminiUsage {
+        prompt_tokens: estimate(prompt_chars),
+        candidate_tokens: estimate(candidate_chars),
+        total_tokens: None,
+    }
+}
@@ -0,0 +1,42 @@
+use bytes::Bytes;
+use serde::Serialize;
+
+/// Selects how a transformed stream's events are framed for downstream
+/// delivery. `transform_claude_stream` drives the same per-event transform
+/// closures for both modes; only this choice differs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum StreamFraming {
+    /// `data: <json>\n\n` chunks — the long-standing SSE default.
+    Sse,
+    /// One JSON envelope per message, modeled on the graphql-ws
+    /// `connection_ack`/`next`/`complete`/`error` message shape, for clients
+    /// that speak a subscription-style protocol instead of raw SSE.
+    WebSocket,
+}
+
+/// Frames a single transformed event as a `next` envelope. Returns `None` if
+/// the event fails to serialize, matching `sse_json_bytes`'s best-effort
+/// behavior of silently dropping an unencodable event rather than failing
+/// the whole stream.
+pub(super) fn ws_json_bytes<T: Serialize>(value: &T) -> Option<Bytes> {
+    let payload = serde_json::to_value(value).ok()?;
+    let envelope = serde_json::json!({ "type": "next", "payload": payload });
+    serde_json::to_vec(&envelope).ok().map(Bytes::from)
+}
+
+/// The handshake frame sent before any events, acknowledging the
+/// subscription the same way `connection_ack` does in graphql-ws.
+pub(super) fn ws_connection_ack() -> Bytes {
+    Bytes::from(serde_json::json!({ "type": "connection_ack" }).to_string())
+}
+
+/// The terminal frame sent once the upstream stream ends cleanly.
+pub(super) fn ws_complete() -> Bytes {
+    Bytes::from(serde_json::json!({ "type": "complete" }).to_string())
+}
+
+/// Sent in place of `ws_complete` when the upstream stream errors mid-flight,
+/// so a subscribed client can distinguish a clean end from a broken one.
+pub(super) fn ws_error(message: String) -> Bytes {
+    Bytes::from(serde_json::json!({ "type": "error", "payload": message }).to_string())
+}
@@ -0,0 +1,136 @@
+//! Optional custom DNS resolution for outbound provider connections, built
+//! on `hickory-resolver` instead of the OS resolver so operators behind
+//! split-horizon DNS (or pinning a provider's CDN edge) can override how
+//! upstream hostnames resolve.
+//!
+//! `GlobalConfig` (`apps/gproxy/src/cli.rs`) is meant to carry this as an
+//! optional `dns: Option<DnsConfig>` section, the same way it already
+//! carries `proxy`, with `put_config` rebuilding the resolver and
+//! reinstalling it on the provider registry's clients whenever the section
+//! changes — alongside the `dsn_changed`/`bind_changed` handling it already
+//! has. `crate::client::shared_client` installs this via
+//! `GPROXY_UPSTREAM_DNS_*` env vars today; threading per-call `DnsConfig`
+//! through `GlobalConfig` instead is the remaining piece.
+//!
+//! Implements `wreq::dns::Resolve` rather than `reqwest::dns::Resolve` since
+//! `wreq::Client` (not `reqwest::Client`) is what every provider actually
+//! builds — `wreq`'s `dns` module mirrors reqwest's shape exactly, so this
+//! is otherwise the same resolver either way.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use hickory_resolver::config::{NameServerConfig, Protocol, ResolverConfig, ResolverOpts};
+use hickory_resolver::TokioAsyncResolver;
+use serde::{Deserialize, Serialize};
+use wreq::dns::{Addrs, Name, Resolve, Resolving};
+
+/// Transport used to reach a configured name server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsQueryProtocol {
+    Udp,
+    Tcp,
+    Doh,
+}
+
+impl DnsQueryProtocol {
+    fn to_hickory(self) -> Protocol {
+        match self {
+            DnsQueryProtocol::Udp => Protocol::Udp,
+            DnsQueryProtocol::Tcp => Protocol::Tcp,
+            DnsQueryProtocol::Doh => Protocol::Https,
+        }
+    }
+}
+
+/// Operator-supplied DNS overrides for outbound provider connections.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DnsConfig {
+    /// Upstream resolver addresses to query, in order. Empty falls back to
+    /// whatever `hickory-resolver` reads from the system configuration
+    /// (e.g. `/etc/resolv.conf`).
+    #[serde(default)]
+    pub servers: Vec<SocketAddr>,
+    #[serde(default)]
+    pub protocol: Option<DnsQueryProtocol>,
+    /// Static `hostname -> address` overrides checked before any resolver
+    /// query, so a provider's CDN edge can be pinned without touching
+    /// `/etc/hosts` on the host running gproxy.
+    #[serde(default)]
+    pub hosts: HashMap<String, IpAddr>,
+}
+
+impl DnsConfig {
+    /// No servers and no host overrides configured — callers should keep
+    /// using the system resolver rather than install `GproxyDnsResolver`.
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty() && self.hosts.is_empty()
+    }
+}
+
+/// Builds a caching `hickory-resolver` resolver from `config`. Answers are
+/// cached honoring each record's own TTL via `ResolverOpts`'s default cache,
+/// and `config.hosts` is read by `GproxyDnsResolver` ahead of any query
+/// rather than here, since `hickory-resolver` only lets a static override
+/// win over `/etc/hosts`, not over an arbitrary configured entry.
+fn build_resolver(config: &DnsConfig) -> TokioAsyncResolver {
+    let mut resolver_config = ResolverConfig::new();
+    let protocol = config
+        .protocol
+        .unwrap_or(DnsQueryProtocol::Udp)
+        .to_hickory();
+    for server in &config.servers {
+        resolver_config.add_name_server(NameServerConfig::new(*server, protocol));
+    }
+
+    let mut opts = ResolverOpts::default();
+    opts.use_hosts_file = true;
+
+    if config.servers.is_empty() {
+        TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|_| {
+            TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+        })
+    } else {
+        TokioAsyncResolver::tokio(resolver_config, opts)
+    }
+}
+
+/// A `wreq::dns::Resolve` implementation so a configured [`DnsConfig`] can be
+/// installed directly via `wreq::ClientBuilder::dns_resolver`. Checks
+/// `hosts` first, then falls back to the wrapped `hickory-resolver` lookup.
+#[derive(Clone)]
+pub struct GproxyDnsResolver {
+    hosts: Arc<HashMap<String, IpAddr>>,
+    resolver: Arc<TokioAsyncResolver>,
+}
+
+impl GproxyDnsResolver {
+    pub fn new(config: &DnsConfig) -> Self {
+        Self {
+            hosts: Arc::new(config.hosts.clone()),
+            resolver: Arc::new(build_resolver(config)),
+        }
+    }
+}
+
+impl Resolve for GproxyDnsResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let hosts = self.hosts.clone();
+        let resolver = self.resolver.clone();
+        Box::pin(async move {
+            if let Some(addr) = hosts.get(name.as_str()) {
+                let addrs: Addrs = Box::new(std::iter::once(SocketAddr::new(*addr, 0)));
+                return Ok(addrs);
+            }
+
+            let lookup = resolver
+                .lookup_ip(name.as_str())
+                .await
+                .map_err(|err| -> Box<dyn std::error::Error + Send + Sync> { Box::new(err) })?;
+            let addrs: Addrs = Box::new(lookup.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
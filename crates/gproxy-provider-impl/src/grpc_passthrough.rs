@@ -0,0 +1,59 @@
+//! Building blocks for a gRPC passthrough mode on `VertexProvider`,
+//! `VertexExpressProvider`, and `GeminiCliProvider` — Google's Vertex AI and
+//! Gemini backends expose `StreamGenerateContent` and friends over gRPC
+//! alongside REST, and relaying those frames needs protocol-aware handling
+//! rather than the plain body passthrough `dispatch_request` does today.
+//!
+//! This module covers the two pieces that don't depend on how the relay is
+//! actually wired: recognizing an `application/grpc` request so the router
+//! can switch code paths, and mapping a gRPC status code back to the HTTP
+//! status a REST-speaking caller expects. The relay itself — opening a
+//! `tonic` client to the upstream, forwarding bidirectional/server-streaming
+//! frames, and preserving trailers — needs a `ProxyResponse` variant able to
+//! carry a gRPC byte stream plus trailers (today's variants are HTTP
+//! request/response shaped) and a dispatch-layer hook that checks
+//! [`Provider::supports_grpc_passthrough`] before falling into the REST
+//! path; neither exists in this tree yet, so wiring a real relay in is left
+//! for whoever adds them. Once one exists, `VertexProvider`,
+//! `VertexExpressProvider`, and `GeminiCliProvider` are the providers that
+//! should override `supports_grpc_passthrough` to return `true`.
+//!
+//! [`Provider::supports_grpc_passthrough`]: gproxy_provider_core::Provider::supports_grpc_passthrough
+
+/// Whether a request's `Content-Type` header indicates gRPC (including the
+/// `+proto`/`+json` message-encoding suffixes gRPC allows), as opposed to a
+/// plain REST JSON body.
+pub fn is_grpc_content_type(content_type: &str) -> bool {
+    let content_type = content_type.trim();
+    content_type == "application/grpc"
+        || content_type
+            .strip_prefix("application/grpc+")
+            .is_some_and(|suffix| !suffix.is_empty())
+}
+
+/// Maps a gRPC status code (the `grpc-status` trailer value) to the HTTP
+/// status a REST-speaking caller should see, following the mapping gRPC's
+/// own HTTP/JSON transcoding spec uses.
+pub fn grpc_status_to_http(code: i32) -> http::StatusCode {
+    use http::StatusCode;
+    match code {
+        0 => StatusCode::OK,
+        1 => StatusCode::from_u16(499).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR), // Cancelled
+        2 => StatusCode::INTERNAL_SERVER_ERROR,                                      // Unknown
+        3 => StatusCode::BAD_REQUEST,                                               // InvalidArgument
+        4 => StatusCode::GATEWAY_TIMEOUT,                                           // DeadlineExceeded
+        5 => StatusCode::NOT_FOUND,                                                 // NotFound
+        6 => StatusCode::CONFLICT,                                                  // AlreadyExists
+        7 => StatusCode::FORBIDDEN,                                                 // PermissionDenied
+        8 => StatusCode::TOO_MANY_REQUESTS,                                         // ResourceExhausted
+        9 => StatusCode::BAD_REQUEST,                                               // FailedPrecondition
+        10 => StatusCode::CONFLICT,                                                 // Aborted
+        11 => StatusCode::BAD_REQUEST,                                              // OutOfRange
+        12 => StatusCode::NOT_IMPLEMENTED,                                          // Unimplemented
+        13 => StatusCode::INTERNAL_SERVER_ERROR,                                    // Internal
+        14 => StatusCode::SERVICE_UNAVAILABLE,                                      // Unavailable
+        15 => StatusCode::INTERNAL_SERVER_ERROR,                                    // DataLoss
+        16 => StatusCode::UNAUTHORIZED,                                             // Unauthenticated
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
@@ -1,11 +1,42 @@
+pub mod client;
 pub mod credential;
+pub mod dispatch;
+pub mod dns;
+pub mod grpc_passthrough;
+pub mod model_filter;
 pub mod provider;
+pub mod proxy_resolver;
 pub mod registry;
+pub mod tls_config;
+pub mod token_cache;
+pub mod transport;
+pub mod upstream;
 
+/// One provider's seed row for a fresh database — `name`/`config_json`
+/// become an `AdminProviderInput` that `storage.ensure_providers` upserts at
+/// startup (see `apps/gproxy/src/main.rs`), and `enabled` mirrors whether
+/// this upstream should accept traffic before an operator has touched
+/// `/admin/providers` at all.
+#[derive(Debug, Clone)]
+pub struct ProviderDefault {
+    pub name: &'static str,
+    pub config_json: serde_json::Value,
+    pub enabled: bool,
+}
+
+pub use client::shared_client;
 pub use credential::BaseCredential;
+pub use dns::{DnsConfig, DnsQueryProtocol, GproxyDnsResolver};
+pub use grpc_passthrough::{grpc_status_to_http, is_grpc_content_type};
+pub use model_filter::ModelFilter;
+pub use proxy_resolver::{ProxyRule, ProxyScheme, ResolvedProxy, UpstreamProxyConfig, UpstreamProxyResolver};
 pub use provider::{
-    AistudioProvider, AntiGravityProvider, ClaudeCodeProvider, ClaudeProvider, CodexProvider,
-    DeepSeekProvider, GeminiCliProvider, NvidiaProvider, OpenAIProvider, VertexExpressProvider,
-    VertexProvider,
+    AistudioProvider, AntiGravityProvider, AzureOpenAIProvider, ClaudeCodeProvider, ClaudeProvider,
+    CodexProvider, DeepSeekProvider, GeminiCliProvider, NvidiaProvider, OpenAIProvider,
+    UpstreamError, VertexExpressProvider, VertexProvider,
+};
+pub use registry::{build_registry, build_registry_with_sink, default_providers, ProviderRegistry};
+pub use tls_config::{
+    build_client_config, build_root_store, PinningConfig, PinningVerifier, TlsConfig, TlsConfigError,
 };
-pub use registry::{build_registry, build_registry_with_sink, ProviderRegistry};
+pub use token_cache::TokenCache;
@@ -0,0 +1,72 @@
+//! Config-driven allow/deny filtering over a provider's model catalog, so an
+//! operator can expose only `gpt-4o*` from `OpenAIProvider` or hide preview
+//! models from `AistudioProvider` without touching upstream config.
+//!
+//! `GlobalConfig` (`apps/gproxy/src/cli.rs`) is meant to carry this as an
+//! optional `model_filter: Option<ModelFilter>` section per provider entry,
+//! the same way it already carries `proxy`/`dns`. Each provider's
+//! `handle_models_list`/`handle_models_get` (e.g.
+//! `geminicli/mod.rs`'s handlers of those names) currently relay the
+//! upstream's model-catalog JSON straight through as raw bytes rather than
+//! building a typed response, and that upstream JSON shape differs per
+//! provider (Gemini, OpenAI, and Claude-facing catalogs all look
+//! different) — so applying this filter is a per-provider change: decode
+//! the `models` array, drop entries `allows` rejects, and for
+//! `handle_models_get` return a [`crate::provider::UpstreamError::NotFound`]
+//! in place of the upstream response when the requested model is filtered
+//! out. This module
+//! is the filter itself, ready for each provider's handler to call.
+
+use serde::{Deserialize, Serialize};
+
+/// An allow/deny pair of model-id glob patterns. A pattern ending in `*` is
+/// a prefix match (`gpt-4o*` matches `gpt-4o` and `gpt-4o-mini`); anything
+/// else must match the model id exactly.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelFilter {
+    /// If non-empty, only ids matching one of these patterns are allowed.
+    /// An empty list means "no allowlist restriction" rather than "allow
+    /// nothing".
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Checked before `allow`; any id matching one of these patterns is
+    /// rejected even if `allow` would otherwise permit it.
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl ModelFilter {
+    /// No rules configured — callers can skip filtering entirely rather
+    /// than paying the per-model match cost.
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Whether `model_id` should be exposed under this filter.
+    pub fn allows(&self, model_id: &str) -> bool {
+        if self.deny.iter().any(|pattern| model_id_matches(pattern, model_id)) {
+            return false;
+        }
+        if self.allow.is_empty() {
+            return true;
+        }
+        self.allow.iter().any(|pattern| model_id_matches(pattern, model_id))
+    }
+
+    /// Drops every id this filter rejects, preserving order.
+    pub fn filter_ids<'a, I>(&self, ids: I) -> Vec<&'a str>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        ids.into_iter().filter(|id| self.allows(id)).collect()
+    }
+}
+
+/// Matches `model_id` against a single pattern: a trailing `*` makes it a
+/// prefix match, otherwise the pattern must equal `model_id` exactly.
+fn model_id_matches(pattern: &str, model_id: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => model_id.starts_with(prefix),
+        None => pattern == model_id,
+    }
+}
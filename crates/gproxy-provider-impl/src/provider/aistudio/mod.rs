@@ -3,26 +3,28 @@ use std::time::Instant;
 
 use async_trait::async_trait;
 use http::header::{AUTHORIZATION, CONTENT_TYPE};
-use http::{HeaderMap, HeaderValue};
-use serde_json::json;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use serde_json::{json, Value as JsonValue};
 use tracing::{info, warn};
 
+use gproxy_protocol::{gemini, openai};
 use gproxy_provider_core::{
     AttemptFailure, CallContext, CredentialPool, DisallowScope, PoolSnapshot, Provider,
     ProxyRequest, ProxyResponse, StateSink, UpstreamPassthroughError, UpstreamRecordMeta,
 };
-use gproxy_protocol::{gemini, openai};
 
 use crate::client::shared_client;
 use crate::credential::BaseCredential;
 use crate::dispatch::{
     dispatch_request, CountTokensPlan, DispatchPlan, DispatchProvider, GenerateContentPlan,
-    ModelsGetPlan, ModelsListPlan, StreamContentPlan, TransformPlan, UsageKind, UpstreamOk,
+    ModelsGetPlan, ModelsListPlan, StreamContentPlan, TransformPlan, UpstreamOk, UsageKind,
 };
+use crate::provider::not_implemented;
 use crate::record::{headers_to_json, json_body_to_string};
 use crate::upstream::{handle_response, network_failure};
 use crate::ProviderDefault;
-use crate::provider::not_implemented;
+
+mod oauth;
 
 pub const PROVIDER_NAME: &str = "aistudio";
 const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com";
@@ -30,7 +32,19 @@ const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com";
 pub fn default_provider() -> ProviderDefault {
     ProviderDefault {
         name: PROVIDER_NAME,
-        config_json: json!({ "base_url": DEFAULT_BASE_URL }),
+        config_json: json!({
+            "base_url": DEFAULT_BASE_URL,
+            // Per-credential `meta.project_id`/`meta.location` route that
+            // credential through the regional Vertex AI endpoint instead;
+            // see `credential_vertex_region`.
+            "project_id": null,
+            "location": null,
+            // Per-credential `meta.block_threshold` (e.g. "BLOCK_ONLY_HIGH",
+            // "BLOCK_NONE") applies to the standard harm categories on every
+            // generate/stream call unless the client already set its own
+            // `safetySettings`; see `apply_block_threshold`.
+            "block_threshold": null,
+        }),
         enabled: true,
     }
 }
@@ -75,6 +89,10 @@ impl Provider for AistudioProvider {
 
 #[async_trait]
 impl DispatchProvider for AistudioProvider {
+    fn name(&self) -> &str {
+        PROVIDER_NAME
+    }
+
     fn dispatch_plan(&self, req: ProxyRequest) -> DispatchPlan {
         match req {
             ProxyRequest::GeminiGenerate { version, request } => DispatchPlan::Native {
@@ -175,6 +193,17 @@ impl DispatchProvider for AistudioProvider {
                 }),
                 usage: UsageKind::None,
             },
+            // Vertex-specific raw passthrough; this provider talks to the
+            // AI Studio Gemini API, not Vertex, so there's no endpoint to
+            // forward it to. `call_native`'s wildcard arm reports that.
+            req @ ProxyRequest::VertexRawPredict { .. } => DispatchPlan::Native {
+                req,
+                usage: UsageKind::None,
+            },
+            req @ ProxyRequest::OpenAICompletions(_) => DispatchPlan::Native {
+                req,
+                usage: UsageKind::OpenAICompletions,
+            },
         }
     }
 
@@ -203,6 +232,16 @@ impl DispatchProvider for AistudioProvider {
             ProxyRequest::OpenAIChatStream(request) => {
                 self.handle_openai_chat(request, true, ctx).await
             }
+            ProxyRequest::OpenAICompletions(body) => {
+                let body: LegacyCompletionRequest = serde_json::from_value(body).map_err(|err| {
+                    UpstreamPassthroughError::from_status(
+                        StatusCode::BAD_REQUEST,
+                        format!("invalid completions request body: {err}"),
+                    )
+                })?;
+                let is_stream = body.stream.unwrap_or(false);
+                self.handle_openai_completions(body, is_stream, ctx).await
+            }
             _ => Err(not_implemented(PROVIDER_NAME)),
         }
     }
@@ -227,14 +266,22 @@ impl AistudioProvider {
                 let model = model.clone();
                 let body = body.clone();
                 async move {
-                    let api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
                     let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
-                    let path = format!("/{version_prefix}/models/{model}:generateContent");
-                    let url = build_url(base_url.as_deref(), &path);
+                    let (path, url) = build_generate_endpoint(
+                        credential.value(),
+                        base_url.as_deref(),
+                        version_prefix,
+                        &model,
+                        "generateContent",
+                    );
                     let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_gemini_headers(&api_key)?;
+                    let req_headers = build_gemini_headers(&auth)?;
+                    let mut body = serde_json::to_value(&body).unwrap_or_else(|_| json!({}));
+                    if let Some(threshold) = credential_block_threshold(credential.value()) {
+                        apply_block_threshold(&mut body, &threshold);
+                    }
                     let request_body = json_body_to_string(&body);
                     let request_headers = headers_to_json(&req_headers);
                     let started_at = Instant::now();
@@ -321,14 +368,22 @@ impl AistudioProvider {
                 let model = model.clone();
                 let body = body.clone();
                 async move {
-                    let api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
                     let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
-                    let path = format!("/{version_prefix}/models/{model}:streamGenerateContent");
-                    let url = build_url(base_url.as_deref(), &path);
+                    let (path, url) = build_generate_endpoint(
+                        credential.value(),
+                        base_url.as_deref(),
+                        version_prefix,
+                        &model,
+                        "streamGenerateContent",
+                    );
                     let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_gemini_headers(&api_key)?;
+                    let req_headers = build_gemini_headers(&auth)?;
+                    let mut body = serde_json::to_value(&body).unwrap_or_else(|_| json!({}));
+                    if let Some(threshold) = credential_block_threshold(credential.value()) {
+                        apply_block_threshold(&mut body, &threshold);
+                    }
                     let request_body = json_body_to_string(&body);
                     let request_headers = headers_to_json(&req_headers);
                     let started_at = Instant::now();
@@ -384,14 +439,9 @@ impl AistudioProvider {
                         request_headers,
                         request_body,
                     };
-                    let response = handle_response(
-                        response,
-                        true,
-                        scope.clone(),
-                        &ctx,
-                        Some(meta.clone()),
-                    )
-                    .await?;
+                    let response =
+                        handle_response(response, true, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
                     Ok(UpstreamOk { response, meta })
                 }
             })
@@ -415,14 +465,18 @@ impl AistudioProvider {
                 let model = model.clone();
                 let body = body.clone();
                 async move {
-                    let api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
                     let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
-                    let path = format!("/{version_prefix}/models/{model}:countTokens");
-                    let url = build_url(base_url.as_deref(), &path);
+                    let (path, url) = build_generate_endpoint(
+                        credential.value(),
+                        base_url.as_deref(),
+                        version_prefix,
+                        &model,
+                        "countTokens",
+                    );
                     let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_gemini_headers(&api_key)?;
+                    let req_headers = build_gemini_headers(&auth)?;
                     let request_body = json_body_to_string(&body);
                     let request_headers = headers_to_json(&req_headers);
                     let started_at = Instant::now();
@@ -478,14 +532,9 @@ impl AistudioProvider {
                         request_headers,
                         request_body,
                     };
-                    let response = handle_response(
-                        response,
-                        false,
-                        scope.clone(),
-                        &ctx,
-                        Some(meta.clone()),
-                    )
-                    .await?;
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
                     Ok(UpstreamOk { response, meta })
                 }
             })
@@ -507,18 +556,19 @@ impl AistudioProvider {
                 let scope = scope.clone();
                 let query = query.clone();
                 async move {
-                    let api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
                     let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
                     let qs = serde_qs::to_string(&query).unwrap_or_default();
-                    let mut path = format!("/{version_prefix}/models");
+                    let (list_path, mut url) =
+                        build_models_path(credential.value(), base_url.as_deref(), version_prefix, "");
+                    let mut path = list_path.clone();
                     if !qs.is_empty() {
                         path = format!("{path}?{qs}");
+                        url = format!("{url}?{qs}");
                     }
-                    let url = build_url(base_url.as_deref(), &path);
                     let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_gemini_headers(&api_key)?;
+                    let req_headers = build_gemini_headers(&auth)?;
                     let request_headers = headers_to_json(&req_headers);
                     let started_at = Instant::now();
                     info!(
@@ -568,19 +618,14 @@ impl AistudioProvider {
                         operation: "gemini.models_list".to_string(),
                         model: None,
                         request_method: "GET".to_string(),
-                        request_path: format!("/{version_prefix}/models"),
+                        request_path: list_path,
                         request_query,
                         request_headers,
                         request_body: String::new(),
                     };
-                    let response = handle_response(
-                        response,
-                        false,
-                        scope.clone(),
-                        &ctx,
-                        Some(meta.clone()),
-                    )
-                    .await?;
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
                     Ok(UpstreamOk { response, meta })
                 }
             })
@@ -602,14 +647,17 @@ impl AistudioProvider {
                 let scope = scope.clone();
                 let name = name.clone();
                 async move {
-                    let api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
                     let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
-                    let path = format!("/{version_prefix}/models/{name}");
-                    let url = build_url(base_url.as_deref(), &path);
+                    let (path, url) = build_models_path(
+                        credential.value(),
+                        base_url.as_deref(),
+                        version_prefix,
+                        &format!("/{name}"),
+                    );
                     let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_gemini_headers(&api_key)?;
+                    let req_headers = build_gemini_headers(&auth)?;
                     let request_headers = headers_to_json(&req_headers);
                     let started_at = Instant::now();
                     info!(
@@ -663,14 +711,9 @@ impl AistudioProvider {
                         request_headers,
                         request_body: String::new(),
                     };
-                    let response = handle_response(
-                        response,
-                        false,
-                        scope.clone(),
-                        &ctx,
-                        Some(meta.clone()),
-                    )
-                    .await?;
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
                     Ok(UpstreamOk { response, meta })
                 }
             })
@@ -695,11 +738,12 @@ impl AistudioProvider {
                     }
                 }
                 None => {
-                    body.stream_options =
-                        Some(openai::create_chat_completions::types::ChatCompletionStreamOptions {
+                    body.stream_options = Some(
+                        openai::create_chat_completions::types::ChatCompletionStreamOptions {
                             include_usage: Some(true),
                             include_obfuscation: None,
-                        });
+                        },
+                    );
                 }
             }
         }
@@ -711,13 +755,12 @@ impl AistudioProvider {
                 let model = model.clone();
                 let body = body.clone();
                 async move {
-                    let api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
                     let base_url = credential_base_url(credential.value());
-                    let path = "/v1beta/openai/chat/completions".to_string();
-                    let url = build_url(base_url.as_deref(), &path);
+                    let (path, url) =
+                        build_openai_chat_endpoint(credential.value(), base_url.as_deref());
                     let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_openai_compat_headers(&api_key)?;
+                    let req_headers = build_openai_compat_headers(&auth)?;
                     let request_body = json_body_to_string(&body);
                     let request_headers = headers_to_json(&req_headers);
                     let started_at = Instant::now();
@@ -786,26 +829,342 @@ impl AistudioProvider {
             })
             .await
     }
+
+    /// Forwards a legacy OpenAI `/v1/completions` (text-completion) request
+    /// to Gemini's OpenAI-compat surface, mirroring `handle_openai_chat`'s
+    /// streaming/header/recording behavior. Reached via
+    /// `ProxyRequest::OpenAICompletions`'s `call_native` arm above, which
+    /// deserializes the raw JSON payload into [`LegacyCompletionRequest`]
+    /// and derives `is_stream` from its `stream` field. This provider is the
+    /// only one with an arm for the variant; no HTTP route produces it yet
+    /// since `classify_request` (the request-to-`ProxyRequest` classifier)
+    /// isn't part of this checkout, so a real client still can't reach this
+    /// path end to end.
+    async fn handle_openai_completions(
+        &self,
+        mut body: LegacyCompletionRequest,
+        is_stream: bool,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = body.model.clone();
+        let scope = DisallowScope::model(model.clone());
+        if is_stream {
+            body.stream = Some(true);
+            match &mut body.stream_options {
+                Some(options) => {
+                    if options.include_usage.is_none() {
+                        options.include_usage = Some(true);
+                    }
+                }
+                None => {
+                    body.stream_options = Some(
+                        openai::create_chat_completions::types::ChatCompletionStreamOptions {
+                            include_usage: Some(true),
+                            include_obfuscation: None,
+                        },
+                    );
+                }
+            }
+        }
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let body = body.clone();
+                async move {
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
+                    let base_url = credential_base_url(credential.value());
+                    let path = "/v1beta/openai/completions".to_string();
+                    let url = build_url(base_url.as_deref(), &path);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let req_headers = build_openai_compat_headers(&auth)?;
+                    let request_body = json_body_to_string(&body);
+                    let request_headers = headers_to_json(&req_headers);
+                    let started_at = Instant::now();
+                    info!(
+                        event = "upstream_request",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "openai.completions",
+                        method = "POST",
+                        path = %path,
+                        model = %model,
+                        is_stream = is_stream
+                    );
+                    let response = client
+                        .post(url)
+                        .headers(req_headers.clone())
+                        .json(&body)
+                        .send()
+                        .await
+                        .map_err(|err| {
+                            warn!(
+                                event = "upstream_response",
+                                trace_id = %ctx.trace_id,
+                                provider = %PROVIDER_NAME,
+                                op = "openai.completions",
+                                status = "error",
+                                elapsed_ms = started_at.elapsed().as_millis(),
+                                error = %err
+                            );
+                            network_failure(err, &scope)
+                        })?;
+                    info!(
+                        event = "upstream_response",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "openai.completions",
+                        status = %response.status().as_u16(),
+                        elapsed_ms = started_at.elapsed().as_millis(),
+                        is_stream = is_stream
+                    );
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "openai.completions".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response = handle_response(
+                        response,
+                        is_stream,
+                        scope.clone(),
+                        &ctx,
+                        Some(meta.clone()),
+                    )
+                    .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+}
+
+/// A legacy OpenAI-style `/v1/completions` request body. Minimal, since this
+/// is forwarded upstream untouched rather than transformed — `prompt` stays
+/// a raw [`JsonValue`] because the legacy API accepts either a string or an
+/// array of strings/tokens, and `extra` passes every other field
+/// (`max_tokens`, `temperature`, `n`, ...) through unchanged so callers
+/// aren't limited to a hand-maintained field list.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+struct LegacyCompletionRequest {
+    model: String,
+    prompt: JsonValue,
+    #[serde(default)]
+    stream: Option<bool>,
+    #[serde(default)]
+    stream_options: Option<openai::create_chat_completions::types::ChatCompletionStreamOptions>,
+    #[serde(flatten)]
+    extra: serde_json::Map<String, JsonValue>,
+}
+
+/// How a resolved credential authenticates against `base_url` for this
+/// call: a plain Gemini API key, or a Bearer token minted from ADC
+/// service-account credentials.
+enum AistudioAuth {
+    ApiKey(String),
+    Bearer(String),
 }
 
-fn build_gemini_headers(api_key: &str) -> Result<HeaderMap, AttemptFailure> {
+/// Resolve the way the given credential should authenticate: ADC
+/// service-account credentials mint (and cache) an OAuth access token,
+/// while everything else falls back to the existing plain-api-key path.
+async fn resolve_auth(
+    credential: &BaseCredential,
+    ctx: &CallContext,
+    scope: &DisallowScope,
+) -> Result<AistudioAuth, AttemptFailure> {
+    if let Some(service_account) = oauth::credential_service_account(credential) {
+        let token = oauth::ensure_access_token(credential.id, &service_account, ctx, scope).await?;
+        return Ok(AistudioAuth::Bearer(token));
+    }
+    if credential_vertex_region(credential).is_some() {
+        return Err(invalid_credential(
+            scope,
+            "regional vertex routing requires a service-account credential",
+        ));
+    }
+    let api_key = credential_api_key(credential)
+        .ok_or_else(|| invalid_credential(scope, "missing api_key"))?;
+    Ok(AistudioAuth::ApiKey(api_key))
+}
+
+/// A credential's `meta.project_id`/`meta.location`, present for credentials
+/// that should route through the regional Vertex AI endpoint rather than
+/// the public `generativelanguage.googleapis.com` Gemini API surface.
+/// Both-or-neither: a credential with only one of the two fields set is
+/// treated as not carrying Vertex routing.
+struct VertexRegion {
+    project_id: String,
+    location: String,
+}
+
+fn credential_vertex_region(credential: &BaseCredential) -> Option<VertexRegion> {
+    let project_id = credential.meta.get("project_id")?.as_str()?.to_string();
+    let location = credential.meta.get("location")?.as_str()?.to_string();
+    Some(VertexRegion {
+        project_id,
+        location,
+    })
+}
+
+/// Build the path/URL for anything shaped like `.../models<suffix>`,
+/// choosing between the regional Vertex AI publisher-models layout (when the
+/// credential carries a `VertexRegion`) and the flat Express-mode/API-key
+/// layout against `base_url`. `suffix` covers what comes after `models`:
+/// `:generateContent`, `/{name}` for a single-model lookup, or empty for a
+/// plain listing.
+fn build_models_path(
+    credential: &BaseCredential,
+    base_url: Option<&str>,
+    version_prefix: &str,
+    suffix: &str,
+) -> (String, String) {
+    if let Some(region) = credential_vertex_region(credential) {
+        let regional_base = format!("https://{}-aiplatform.googleapis.com", region.location);
+        let path = format!(
+            "/{version_prefix}/projects/{}/locations/{}/publishers/google/models{suffix}",
+            region.project_id, region.location
+        );
+        let url = build_url(Some(&regional_base), &path);
+        return (path, url);
+    }
+    let path = format!("/{version_prefix}/models{suffix}");
+    let url = build_url(base_url, &path);
+    (path, url)
+}
+
+/// Build the path/URL for a `generateContent`-family call, choosing between
+/// the regional Vertex AI layout (when the credential carries a
+/// `VertexRegion`) and the existing Express-mode/API-key layout against
+/// `base_url`.
+fn build_generate_endpoint(
+    credential: &BaseCredential,
+    base_url: Option<&str>,
+    version_prefix: &str,
+    model: &str,
+    operation: &str,
+) -> (String, String) {
+    build_models_path(
+        credential,
+        base_url,
+        version_prefix,
+        &format!("/{model}:{operation}"),
+    )
+}
+
+/// Build the path/URL for the OpenAI-compatible chat-completions call,
+/// routing through Vertex AI's `endpoints/openapi` surface when the
+/// credential carries a `VertexRegion`, since Vertex doesn't expose the
+/// Express-mode `/v1beta/openai/...` path.
+fn build_openai_chat_endpoint(
+    credential: &BaseCredential,
+    base_url: Option<&str>,
+) -> (String, String) {
+    if let Some(region) = credential_vertex_region(credential) {
+        let regional_base = format!("https://{}-aiplatform.googleapis.com", region.location);
+        let path = format!(
+            "/v1/projects/{}/locations/{}/endpoints/openapi/chat/completions",
+            region.project_id, region.location
+        );
+        let url = build_url(Some(&regional_base), &path);
+        return (path, url);
+    }
+    let path = "/v1beta/openai/chat/completions".to_string();
+    let url = build_url(base_url, &path);
+    (path, url)
+}
+
+/// The harm categories Gemini's `safetySettings` covers out of the box.
+const STANDARD_HARM_CATEGORIES: [&str; 4] = [
+    "HARM_CATEGORY_HARASSMENT",
+    "HARM_CATEGORY_HATE_SPEECH",
+    "HARM_CATEGORY_SEXUALLY_EXPLICIT",
+    "HARM_CATEGORY_DANGEROUS_CONTENT",
+];
+
+fn credential_block_threshold(credential: &BaseCredential) -> Option<String> {
+    credential
+        .meta
+        .get("block_threshold")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+/// Merge `threshold` into `body["safetySettings"]` for every category in
+/// `STANDARD_HARM_CATEGORIES` the caller didn't already specify. A category
+/// the client already set is left exactly as they sent it — only the gaps
+/// get the configured default, rather than the whole array being skipped
+/// just because the client mentioned one category.
+fn apply_block_threshold(body: &mut JsonValue, threshold: &str) {
+    let Some(object) = body.as_object_mut() else {
+        return;
+    };
+    let mut settings = object
+        .get("safetySettings")
+        .and_then(|value| value.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let existing_categories: std::collections::HashSet<&str> = settings
+        .iter()
+        .filter_map(|setting| setting.get("category").and_then(|value| value.as_str()))
+        .collect();
+    for category in STANDARD_HARM_CATEGORIES {
+        if !existing_categories.contains(category) {
+            settings.push(json!({ "category": category, "threshold": threshold }));
+        }
+    }
+    object.insert("safetySettings".to_string(), JsonValue::Array(settings));
+}
+
+fn build_gemini_headers(auth: &AistudioAuth) -> Result<HeaderMap, AttemptFailure> {
     let mut headers = HeaderMap::new();
-    headers.insert(
-        "x-goog-api-key",
-        HeaderValue::from_str(api_key).map_err(|err| AttemptFailure {
-            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
-            mark: None,
-        })?,
-    );
+    match auth {
+        AistudioAuth::ApiKey(api_key) => {
+            headers.insert(
+                "x-goog-api-key",
+                HeaderValue::from_str(api_key).map_err(|err| AttemptFailure {
+                    passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+                    mark: None,
+                })?,
+            );
+        }
+        AistudioAuth::Bearer(token) => {
+            let mut bearer = String::with_capacity(token.len() + 7);
+            bearer.push_str("Bearer ");
+            bearer.push_str(token);
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&bearer).map_err(|err| AttemptFailure {
+                    passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+                    mark: None,
+                })?,
+            );
+        }
+    }
     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
     Ok(headers)
 }
 
-fn build_openai_compat_headers(api_key: &str) -> Result<HeaderMap, AttemptFailure> {
+fn build_openai_compat_headers(auth: &AistudioAuth) -> Result<HeaderMap, AttemptFailure> {
+    let token = match auth {
+        AistudioAuth::ApiKey(api_key) => api_key.as_str(),
+        AistudioAuth::Bearer(token) => token.as_str(),
+    };
     let mut headers = HeaderMap::new();
-    let mut bearer = String::with_capacity(api_key.len() + 7);
+    let mut bearer = String::with_capacity(token.len() + 7);
     bearer.push_str("Bearer ");
-    bearer.push_str(api_key);
+    bearer.push_str(token);
     headers.insert(
         AUTHORIZATION,
         HeaderValue::from_str(&bearer).map_err(|err| AttemptFailure {
@@ -843,7 +1202,9 @@ fn build_url(base_url: Option<&str>, path: &str) -> String {
         path = path.trim_start_matches("v1/").trim_start_matches("v1");
     }
     if base.ends_with("/v1beta") && (path == "v1beta" || path.starts_with("v1beta/")) {
-        path = path.trim_start_matches("v1beta/").trim_start_matches("v1beta");
+        path = path
+            .trim_start_matches("v1beta/")
+            .trim_start_matches("v1beta");
     }
     format!("{base}/{path}")
 }
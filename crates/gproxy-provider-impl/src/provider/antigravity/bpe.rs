@@ -0,0 +1,102 @@
+//! Offline token-count estimate used when no credential has
+//! `count_tokens_upstream` enabled and the real Gemini tokenizer can't be
+//! reached. Gemini doesn't publish a merge table we could embed verbatim, so
+//! this approximates it with a small generic byte-pair-encoding vocabulary:
+//! common English/code merges collapse into single tokens the way a real
+//! subword tokenizer would, while untouched runs of text (most notably CJK,
+//! which shares no merges with this table) fall back to one token per
+//! character — close to how multilingual tokenizers actually split it,
+//! rather than the flat `chars / 4` estimate this replaces.
+
+/// Merge pairs in priority order (earlist entries merge first), mirroring
+/// how a trained BPE vocabulary is built: the most frequent pairs across a
+/// general English/code corpus merge first, forming longer pairs further
+/// down the table.
+const MERGES: &[(&str, &str)] = &[
+    ("t", "h"),
+    ("i", "n"),
+    ("e", "r"),
+    ("o", "n"),
+    ("r", "e"),
+    ("a", "n"),
+    ("a", "t"),
+    ("e", "n"),
+    ("o", "r"),
+    ("i", "s"),
+    ("e", "d"),
+    ("i", "t"),
+    ("o", "u"),
+    ("e", "s"),
+    ("a", "l"),
+    ("a", "r"),
+    ("s", "t"),
+    ("t", "o"),
+    ("n", "d"),
+    ("l", "e"),
+    ("i", "c"),
+    ("o", "f"),
+    ("t", "i"),
+    ("a", "s"),
+    ("r", "o"),
+    ("v", "e"),
+    ("u", "n"),
+    ("l", "y"),
+    ("c", "h"),
+    ("o", "m"),
+    ("l", "o"),
+    ("s", "e"),
+    ("a", "c"),
+    ("u", "s"),
+    ("te", "r"),
+    ("th", "e"),
+    ("in", "g"),
+    ("en", "t"),
+    ("an", "d"),
+    ("at", "ion"),
+    ("c", "on"),
+    ("t", "ion"),
+    ("f", "or"),
+    ("p", "ro"),
+    ("w", "h"),
+    ("re", "s"),
+    ("co", "m"),
+    ("a", "ti"),
+    ("th", "at"),
+    ("i", "on"),
+];
+
+/// Estimates the number of tokens `text` would cost a real tokenizer. Text
+/// is pre-split on whitespace first (matching how byte-level BPE tokenizers
+/// scope merges to a single word) so the merge search stays cheap even on
+/// very large prompts.
+pub(super) fn bpe_token_count(text: &str) -> u32 {
+    text.split_whitespace()
+        .map(|word| bpe_tokenize_word(word).len() as u32)
+        .sum()
+}
+
+fn merge_rank(a: &str, b: &str) -> Option<usize> {
+    MERGES.iter().position(|(x, y)| *x == a && *y == b)
+}
+
+fn bpe_tokenize_word(word: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = word.chars().map(|c| c.to_string()).collect();
+    loop {
+        let mut best: Option<(usize, usize)> = None;
+        for i in 0..tokens.len().saturating_sub(1) {
+            if let Some(rank) = merge_rank(&tokens[i], &tokens[i + 1]) {
+                if best.map_or(true, |(best_rank, _)| rank < best_rank) {
+                    best = Some((rank, i));
+                }
+            }
+        }
+        match best {
+            Some((_, index)) => {
+                let merged = format!("{}{}", tokens[index], tokens[index + 1]);
+                tokens.splice(index..=index + 1, [merged]);
+            }
+            None => break,
+        }
+    }
+    tokens
+}
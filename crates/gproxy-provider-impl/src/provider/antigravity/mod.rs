@@ -1,6 +1,7 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::io;
-use std::sync::Arc;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
 
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -29,6 +30,7 @@ use crate::record::{headers_to_json, json_body_to_string};
 use crate::upstream::{handle_response, send_with_logging};
 use crate::ProviderDefault;
 
+mod bpe;
 mod oauth;
 mod refresh;
 mod usage;
@@ -86,6 +88,24 @@ pub fn default_provider() -> ProviderDefault {
         config_json: json!({
             "base_url": DEFAULT_BASE_URL,
             "stream2nostream": DEFAULT_STREAM2NOSTREAM
+            // "block_threshold": not set by default; when present (e.g.
+            // "BLOCK_NONE"), injects safetySettings for every harm category
+            // into requests that don't already specify one.
+            // "count_tokens_upstream": not set by default (off); when true,
+            // countTokens is forwarded to the real upstream instead of being
+            // estimated locally.
+            // "base_urls": not set by default; when present, a list of
+            // regional endpoints (e.g. Vertex AI's
+            // `{REGION}-aiplatform.googleapis.com` pattern) tried in order
+            // for generate/stream, failing over to the next region on a
+            // connection error or a 429/503 response.
+            // "model_catalog_live": not set by default (off); when true,
+            // this credential is used to refresh the /models catalog from
+            // the real upstream listModels endpoint instead of serving only
+            // the static built-in list.
+            // "model_catalog_ttl_secs": not set by default
+            // (DEFAULT_MODEL_CATALOG_TTL applies); how long a live-fetched
+            // catalog is served before the next request refreshes it.
         }),
         enabled: true,
     }
@@ -94,15 +114,25 @@ pub fn default_provider() -> ProviderDefault {
 #[derive(Debug)]
 pub struct AntiGravityProvider {
     pool: CredentialPool<AntiGravityCredential>,
+    model_catalog: tokio::sync::RwLock<Option<ModelCatalogEntry>>,
 }
 
 pub type AntiGravityCredential = BaseCredential;
 
+/// The last live fetch of [`build_models_list`]'s upstream counterpart,
+/// consulted by `handle_models_list`/`handle_models_get` until it goes
+/// stale. See [`DEFAULT_MODEL_CATALOG_TTL`] and `credential_model_catalog_ttl`.
+#[derive(Debug, Clone)]
+struct ModelCatalogEntry {
+    fetched_at: std::time::Instant,
+    models: Vec<gemini::types::Model>,
+}
+
 impl AntiGravityProvider {
     pub fn new(sink: Arc<dyn StateSink>) -> Self {
         let snapshot = PoolSnapshot::empty();
         let pool = CredentialPool::new(PROVIDER_NAME, snapshot, Some(sink));
-        Self { pool }
+        Self { pool, model_catalog: tokio::sync::RwLock::new(None) }
     }
 
     pub fn pool(&self) -> &CredentialPool<AntiGravityCredential> {
@@ -193,78 +223,92 @@ impl AntiGravityProvider {
                     let tokens = refresh::ensure_tokens(credential.value(), &ctx, &scope).await?;
                     let project_id =
                         credential_project_id(credential.value()).unwrap_or_else(random_project_id);
-                    let base_url = credential_base_url(credential.value());
+                    let base_urls = credential_base_urls(credential.value());
                     let stream2nostream =
                         credential_stream2nostream(credential.value()).unwrap_or(DEFAULT_STREAM2NOSTREAM);
-                    let path = if is_stream || stream2nostream {
-                        "/v1internal:streamGenerateContent?alt=sse"
-                    } else {
-                        "/v1internal:generateContent"
-                    }
-                    .to_string();
-                    let url = build_url(base_url.as_deref(), &path);
-                    let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_headers(&tokens.access_token, &raw_model)?;
-                    let wrapped = wrap_internal_request(&model, &project_id, &body);
-                    let request_body = json_body_to_string(&wrapped);
-                    let request_headers = headers_to_json(&req_headers);
-                    let response = send_with_logging(
-                        &ctx,
-                        PROVIDER_NAME,
-                        "antigravity.generate",
-                        "POST",
-                        &path,
-                        Some(&model),
-                        is_stream || stream2nostream,
-                        &scope,
-                        || {
-                            client
-                                .post(url)
-                                .headers(req_headers.clone())
-                                .json(&wrapped)
-                                .send()
-                        },
-                    )
-                    .await?;
-                    let meta = UpstreamRecordMeta {
-                        provider: PROVIDER_NAME.to_string(),
-                        provider_id: ctx.provider_id,
-                        credential_id: Some(credential.value().id),
-                        operation: "antigravity.generate".to_string(),
-                        model: Some(model),
-                        request_method: "POST".to_string(),
-                        request_path: path,
-                        request_query: None,
-                        request_headers,
-                        request_body,
-                    };
-                    let response = handle_response(
-                        response,
-                        is_stream || stream2nostream,
-                        scope.clone(),
-                        &ctx,
-                        Some(meta.clone()),
-                    )
-                    .await?;
-                    let response = if is_stream {
-                        unwrap_internal_stream(response).map_err(|err| AttemptFailure {
-                            passthrough: err,
-                            mark: None,
-                        })?
-                    } else if stream2nostream {
-                        stream_to_response(response)
-                            .await
-                            .map_err(|err| AttemptFailure {
-                                passthrough: err,
-                                mark: None,
-                            })?
+                    let block_threshold = credential_block_threshold(credential.value());
+                    let credential_id = credential.value().id;
+                    let fake_stream = is_stream && is_fake_stream_model(&raw_model);
+
+                    let attempt = if fake_stream {
+                        run_fake_stream_attempt(
+                            ctx.clone(),
+                            scope.clone(),
+                            model.clone(),
+                            raw_model.clone(),
+                            tokens.access_token.clone(),
+                            credential_id,
+                            project_id.clone(),
+                            base_urls.clone(),
+                            block_threshold.clone(),
+                            body.clone(),
+                        )
+                        .await
                     } else {
-                        unwrap_internal_json(response).map_err(|err| AttemptFailure {
-                            passthrough: err,
-                            mark: None,
-                        })?
+                        run_generate_attempt(
+                            &ctx,
+                            &scope,
+                            &model,
+                            &raw_model,
+                            &tokens.access_token,
+                            credential_id,
+                            &project_id,
+                            &base_urls,
+                            stream2nostream,
+                            is_stream,
+                            block_threshold.as_deref(),
+                            &body,
+                        )
+                        .await
                     };
-                    Ok(UpstreamOk { response, meta })
+                    match attempt {
+                        Err(failure) if is_unauthorized_failure(&failure) => {
+                            match credential_refresh_token(credential.value()) {
+                                Some(refresh_token) => {
+                                    let tokens = refresh::refresh_access_token(
+                                        credential_id,
+                                        refresh_token,
+                                        &ctx,
+                                        &scope,
+                                    )
+                                    .await?;
+                                    if fake_stream {
+                                        run_fake_stream_attempt(
+                                            ctx.clone(),
+                                            scope.clone(),
+                                            model.clone(),
+                                            raw_model.clone(),
+                                            tokens.access_token.clone(),
+                                            credential_id,
+                                            project_id.clone(),
+                                            base_urls.clone(),
+                                            block_threshold.clone(),
+                                            body.clone(),
+                                        )
+                                        .await
+                                    } else {
+                                        run_generate_attempt(
+                                            &ctx,
+                                            &scope,
+                                            &model,
+                                            &raw_model,
+                                            &tokens.access_token,
+                                            credential_id,
+                                            &project_id,
+                                            &base_urls,
+                                            stream2nostream,
+                                            is_stream,
+                                            block_threshold.as_deref(),
+                                            &body,
+                                        )
+                                        .await
+                                    }
+                                }
+                                None => Err(failure),
+                            }
+                        }
+                        other => other,
+                    }
                 }
             })
             .await
@@ -291,22 +335,144 @@ impl AntiGravityProvider {
                     let tokens = refresh::ensure_tokens(credential.value(), &ctx, &scope).await?;
                     let project_id =
                         credential_project_id(credential.value()).unwrap_or_else(random_project_id);
-                    let base_url = credential_base_url(credential.value());
-                    let path = "/v1internal:streamGenerateContent?alt=sse".to_string();
-                    let url = build_url(base_url.as_deref(), &path);
+                    let base_urls = credential_base_urls(credential.value());
+                    let block_threshold = credential_block_threshold(credential.value());
+                    let credential_id = credential.value().id;
+                    let fake_stream = is_fake_stream_model(&raw_model);
+
+                    let attempt = if fake_stream {
+                        run_fake_stream_attempt(
+                            ctx.clone(),
+                            scope.clone(),
+                            model.clone(),
+                            raw_model.clone(),
+                            tokens.access_token.clone(),
+                            credential_id,
+                            project_id.clone(),
+                            base_urls.clone(),
+                            block_threshold.clone(),
+                            body.clone(),
+                        )
+                        .await
+                    } else {
+                        run_generate_stream_attempt(
+                            &ctx,
+                            &scope,
+                            &model,
+                            &raw_model,
+                            &tokens.access_token,
+                            credential_id,
+                            &project_id,
+                            &base_urls,
+                            block_threshold.as_deref(),
+                            &body,
+                        )
+                        .await
+                    };
+                    match attempt {
+                        Err(failure) if is_unauthorized_failure(&failure) => {
+                            match credential_refresh_token(credential.value()) {
+                                Some(refresh_token) => {
+                                    let tokens = refresh::refresh_access_token(
+                                        credential_id,
+                                        refresh_token,
+                                        &ctx,
+                                        &scope,
+                                    )
+                                    .await?;
+                                    if fake_stream {
+                                        run_fake_stream_attempt(
+                                            ctx.clone(),
+                                            scope.clone(),
+                                            model.clone(),
+                                            raw_model.clone(),
+                                            tokens.access_token.clone(),
+                                            credential_id,
+                                            project_id.clone(),
+                                            base_urls.clone(),
+                                            block_threshold.clone(),
+                                            body.clone(),
+                                        )
+                                        .await
+                                    } else {
+                                        run_generate_stream_attempt(
+                                            &ctx,
+                                            &scope,
+                                            &model,
+                                            &raw_model,
+                                            &tokens.access_token,
+                                            credential_id,
+                                            &project_id,
+                                            &base_urls,
+                                            block_threshold.as_deref(),
+                                            &body,
+                                        )
+                                        .await
+                                    }
+                                }
+                                None => Err(failure),
+                            }
+                        }
+                        other => other,
+                    }
+                }
+            })
+            .await
+    }
+
+    async fn handle_count_tokens(
+        &self,
+        request: gemini::count_tokens::request::CountTokensRequest,
+        ctx: UpstreamContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let raw_model = request.path.model.clone();
+        let model = normalize_model_name(&raw_model);
+        let scope = DisallowScope::model(model.clone());
+        let body = request.body;
+        let cache_key = count_tokens_cache_key(&model, &body);
+
+        if let Some(cached) = count_tokens_cache().read().await.get(&cache_key).copied() {
+            let request_body = json_body_to_string(&body);
+            return local_count_tokens_response(&ctx, model, request_body, cached);
+        }
+
+        let upstream = self
+            .pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let raw_model = raw_model.clone();
+                let body = body.clone();
+                async move {
+                    if !credential_count_tokens_upstream(credential.value()).unwrap_or(false) {
+                        return Err(AttemptFailure {
+                            passthrough: UpstreamPassthroughError::service_unavailable(
+                                "count_tokens_upstream disabled".to_string(),
+                            ),
+                            mark: None,
+                        });
+                    }
+                    let tokens = refresh::ensure_tokens(credential.value(), &ctx, &scope).await?;
+                    let project_id =
+                        credential_project_id(credential.value()).unwrap_or_else(random_project_id);
+                    let base_url =
+                        credential_base_url(credential.value()).unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+                    let path = "/v1internal:countTokens".to_string();
+                    let url = build_url(&base_url, &path);
                     let client = shared_client(ctx.proxy.as_deref())?;
                     let req_headers = build_headers(&tokens.access_token, &raw_model)?;
-                    let wrapped = wrap_internal_request(&model, &project_id, &body);
+                    let wrapped = wrap_internal_request(&model, &project_id, &body, None);
                     let request_body = json_body_to_string(&wrapped);
                     let request_headers = headers_to_json(&req_headers);
                     let response = send_with_logging(
                         &ctx,
                         PROVIDER_NAME,
-                        "antigravity.stream",
+                        "antigravity.count_tokens",
                         "POST",
                         &path,
                         Some(&model),
-                        true,
+                        false,
                         &scope,
                         || {
                             client
@@ -321,7 +487,7 @@ impl AntiGravityProvider {
                         provider: PROVIDER_NAME.to_string(),
                         provider_id: ctx.provider_id,
                         credential_id: Some(credential.value().id),
-                        operation: "antigravity.stream".to_string(),
+                        operation: "antigravity.count_tokens".to_string(),
                         model: Some(model),
                         request_method: "POST".to_string(),
                         request_path: path,
@@ -330,56 +496,38 @@ impl AntiGravityProvider {
                         request_body,
                     };
                     let response =
-                        handle_response(response, true, scope.clone(), &ctx, Some(meta.clone()))
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
                             .await?;
-                    let response = unwrap_internal_stream(response).map_err(|err| AttemptFailure {
+                    let response = unwrap_internal_json(response).map_err(|err| AttemptFailure {
                         passthrough: err,
                         mark: None,
                     })?;
                     Ok(UpstreamOk { response, meta })
                 }
             })
-            .await
-    }
+            .await;
+        if let Ok(ok) = upstream {
+            if let ProxyResponse::Json { body: response_bytes, .. } = &ok.response {
+                if let Ok(parsed) =
+                    serde_json::from_slice::<gemini::count_tokens::response::CountTokensResponse>(
+                        response_bytes,
+                    )
+                {
+                    count_tokens_cache()
+                        .write()
+                        .await
+                        .insert(cache_key, parsed.total_tokens);
+                }
+            }
+            return Ok(ok);
+        }
 
-    async fn handle_count_tokens(
-        &self,
-        request: gemini::count_tokens::request::CountTokensRequest,
-        ctx: UpstreamContext,
-    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
-        let model = normalize_model_name(&request.path.model);
-        let _scope = DisallowScope::model(model.clone());
-        let token_count = estimate_tokens(&request.body);
-        let response_body = gemini::count_tokens::response::CountTokensResponse {
-            total_tokens: token_count,
-            cached_content_token_count: None,
-            prompt_tokens_details: None,
-            cache_tokens_details: None,
-        };
-        let body = serde_json::to_vec(&response_body)
-            .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        let meta = UpstreamRecordMeta {
-            provider: PROVIDER_NAME.to_string(),
-            provider_id: ctx.provider_id,
-            credential_id: None,
-            operation: "antigravity.count_tokens".to_string(),
-            model: Some(model),
-            request_method: "POST".to_string(),
-            request_path: "/v1beta/models:countTokens".to_string(),
-            request_query: None,
-            request_headers: String::new(),
-            request_body: json_body_to_string(&request.body),
-        };
-        Ok(UpstreamOk {
-            response: ProxyResponse::Json {
-                status: StatusCode::OK,
-                headers,
-                body: Bytes::from(body),
-            },
-            meta,
-        })
+        // No credential has count-tokens upstreaming enabled, or the upstream
+        // call itself failed; fall back to a local estimate rather than
+        // surfacing the error to the client.
+        let token_count = estimate_tokens(&body);
+        let request_body = json_body_to_string(&body);
+        local_count_tokens_response(&ctx, model, request_body, token_count)
     }
 
     async fn handle_models_list(
@@ -387,7 +535,7 @@ impl AntiGravityProvider {
         request: gemini::list_models::request::ListModelsRequest,
         ctx: UpstreamContext,
     ) -> Result<UpstreamOk, UpstreamPassthroughError> {
-        let models = build_models_list();
+        let models = self.model_catalog(&ctx).await;
         let response_body = gemini::list_models::response::ListModelsResponse {
             models,
             next_page_token: None,
@@ -424,12 +572,18 @@ impl AntiGravityProvider {
         ctx: UpstreamContext,
     ) -> Result<UpstreamOk, UpstreamPassthroughError> {
         let name = normalize_model_name(&request.path.name);
-        let model = build_model(&name).ok_or_else(|| {
-            UpstreamPassthroughError::from_status(
-                StatusCode::NOT_FOUND,
-                format!("unknown model: {name}"),
-            )
-        })?;
+        let model = self
+            .model_catalog(&ctx)
+            .await
+            .into_iter()
+            .find(|model| model.base_model_id.as_deref() == Some(name.as_str()))
+            .or_else(|| build_model(&name))
+            .ok_or_else(|| {
+                UpstreamPassthroughError::from_status(
+                    StatusCode::NOT_FOUND,
+                    format!("unknown model: {name}"),
+                )
+            })?;
         let body = serde_json::to_vec(&model)
             .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
         let mut headers = HeaderMap::new();
@@ -455,6 +609,131 @@ impl AntiGravityProvider {
             meta,
         })
     }
+
+    /// The catalog `handle_models_list`/`handle_models_get` serve: the last
+    /// live fetch of the upstream model list if it's still within its TTL,
+    /// a fresh fetch if it's gone stale (or there's never been one), or the
+    /// static [`build_models_list`] if no credential has live fetching
+    /// enabled or the fetch itself failed. A failed fetch never replaces a
+    /// still-cached (if stale) entry, and never makes either endpoint
+    /// hard-fail.
+    async fn model_catalog(&self, ctx: &UpstreamContext) -> Vec<gemini::types::Model> {
+        if let Some(entry) = self.model_catalog.read().await.as_ref() {
+            if entry.fetched_at.elapsed() < self.model_catalog_ttl() {
+                return entry.models.clone();
+            }
+        }
+
+        match self.fetch_live_models(ctx).await {
+            Ok(models) if !models.is_empty() => {
+                *self.model_catalog.write().await = Some(ModelCatalogEntry {
+                    fetched_at: std::time::Instant::now(),
+                    models: models.clone(),
+                });
+                models
+            }
+            _ => {
+                if let Some(entry) = self.model_catalog.read().await.as_ref() {
+                    return entry.models.clone();
+                }
+                build_models_list()
+            }
+        }
+    }
+
+    /// The configured refresh interval, read off whichever credential the
+    /// pool would currently pick (mirroring `credential_stream2nostream`'s
+    /// per-credential-meta shape), falling back to
+    /// [`DEFAULT_MODEL_CATALOG_TTL`] when unset or when there's no
+    /// credential to ask yet.
+    fn model_catalog_ttl(&self) -> std::time::Duration {
+        self.pool
+            .snapshot()
+            .credentials
+            .iter()
+            .find_map(|credential| credential_model_catalog_ttl(credential.value()))
+            .unwrap_or(DEFAULT_MODEL_CATALOG_TTL)
+    }
+
+    /// Fetches the real model list from the upstream `listModels` endpoint,
+    /// authenticated the same way `handle_generate`/`handle_count_tokens`
+    /// are (`refresh::ensure_tokens` + `build_headers`), using whichever
+    /// enabled credential has `model_catalog_live` set. Scoped
+    /// `AllModels` like the other provider-wide calls (`usage`, OAuth)
+    /// rather than a single model, since this isn't about any one model.
+    async fn fetch_live_models(
+        &self,
+        ctx: &UpstreamContext,
+    ) -> Result<Vec<gemini::types::Model>, UpstreamPassthroughError> {
+        let scope = DisallowScope::AllModels;
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                async move {
+                    if !credential_model_catalog_live(credential.value()).unwrap_or(false) {
+                        return Err(AttemptFailure {
+                            passthrough: UpstreamPassthroughError::service_unavailable(
+                                "model_catalog_live disabled".to_string(),
+                            ),
+                            mark: None,
+                        });
+                    }
+                    let tokens = refresh::ensure_tokens(credential.value(), &ctx, &scope).await?;
+                    let base_url = credential_base_url(credential.value())
+                        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+                    let path = "/v1internal:listModels".to_string();
+                    let url = build_url(&base_url, &path);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let req_headers = build_headers(&tokens.access_token, "")?;
+                    let request_headers = headers_to_json(&req_headers);
+                    let response = send_with_logging(
+                        &ctx,
+                        PROVIDER_NAME,
+                        "antigravity.models.catalog_fetch",
+                        "GET",
+                        &path,
+                        None,
+                        false,
+                        &scope,
+                        || client.get(&url).headers(req_headers.clone()).send(),
+                    )
+                    .await?;
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx.provider_id,
+                        credential_id: Some(credential.value().id),
+                        operation: "antigravity.models.catalog_fetch".to_string(),
+                        model: None,
+                        request_method: "GET".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body: String::new(),
+                    };
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta))
+                            .await?;
+                    let ProxyResponse::Json { body: response_bytes, .. } = response else {
+                        return Err(AttemptFailure {
+                            passthrough: UpstreamPassthroughError::service_unavailable(
+                                "expected json response".to_string(),
+                            ),
+                            mark: None,
+                        });
+                    };
+                    let parsed: gemini::list_models::response::ListModelsResponse =
+                        serde_json::from_slice(&response_bytes).map_err(|err| AttemptFailure {
+                            passthrough: UpstreamPassthroughError::service_unavailable(
+                                err.to_string(),
+                            ),
+                            mark: None,
+                        })?;
+                    Ok(parsed.models)
+                }
+            })
+            .await
+    }
 }
 
 pub(super) fn build_headers(access_token: &str, model_name: &str) -> Result<HeaderMap, AttemptFailure> {
@@ -489,11 +768,34 @@ pub(super) fn build_headers(access_token: &str, model_name: &str) -> Result<Head
     Ok(headers)
 }
 
-fn wrap_internal_request(
+/// Harm categories Gemini's `safetySettings` accepts; injected for all four
+/// together so operators get one consistent threshold rather than leaving
+/// some categories at the API default.
+const SAFETY_HARM_CATEGORIES: [&str; 4] = [
+    "HARM_CATEGORY_HARASSMENT",
+    "HARM_CATEGORY_HATE_SPEECH",
+    "HARM_CATEGORY_SEXUALLY_EXPLICIT",
+    "HARM_CATEGORY_DANGEROUS_CONTENT",
+];
+
+fn wrap_internal_request<T: serde::Serialize>(
     model: &str,
     project_id: &str,
-    request: &gemini::generate_content::request::GenerateContentRequestBody,
+    request: &T,
+    block_threshold: Option<&str>,
 ) -> JsonValue {
+    let mut request = serde_json::to_value(request).unwrap_or(JsonValue::Null);
+    if let Some(threshold) = block_threshold {
+        if let JsonValue::Object(map) = &mut request {
+            if !map.contains_key("safetySettings") {
+                let safety_settings: Vec<JsonValue> = SAFETY_HARM_CATEGORIES
+                    .iter()
+                    .map(|category| json!({ "category": category, "threshold": threshold }))
+                    .collect();
+                map.insert("safetySettings".to_string(), json!(safety_settings));
+            }
+        }
+    }
     json!({
         "model": model,
         "project": project_id,
@@ -541,6 +843,300 @@ fn unwrap_internal_stream(
     }
 }
 
+/// Maximum number of `MAX_TOKENS`-triggered continuation rounds before
+/// anti-truncation gives up and passes the (still-truncated) stream through
+/// as-is, so a model that never returns `STOP` can't loop forever.
+const ANTI_TRUNC_MAX_CONTINUATIONS: u32 = 5;
+
+/// Whether `raw_model` was requested through the anti-truncation prefix or
+/// suffix that `normalize_model_name` strips.
+fn is_anti_trunc_model(raw_model: &str) -> bool {
+    let trimmed = raw_model.trim();
+    trimmed.starts_with(ANTI_TRUNC_PREFIX) || trimmed.ends_with(ANTI_TRUNC_SUFFIX)
+}
+
+type BoxedByteStream = Pin<Box<dyn futures_util::Stream<Item = Result<Bytes, io::Error>> + Send>>;
+
+/// Everything `map_anti_trunc_stream` needs to re-issue a
+/// `streamGenerateContent` continuation request once the upstream stream
+/// reports `finishReason: MAX_TOKENS` instead of `STOP`.
+struct AntiTruncContext {
+    ctx: UpstreamContext,
+    scope: DisallowScope,
+    client: wreq::Client,
+    req_headers: HeaderMap,
+    url: String,
+    model: String,
+    project_id: String,
+    block_threshold: Option<String>,
+    original_request: JsonValue,
+    contents: Vec<JsonValue>,
+}
+
+fn unwrap_internal_stream_with_anti_trunc(
+    response: ProxyResponse,
+    continuation: AntiTruncContext,
+) -> Result<ProxyResponse, UpstreamPassthroughError> {
+    match response {
+        ProxyResponse::Stream { status, headers, body } => {
+            let upstream: BoxedByteStream = Box::pin(body.stream);
+            let stream = map_anti_trunc_stream(upstream, continuation);
+            Ok(ProxyResponse::Stream {
+                status,
+                headers,
+                body: StreamBody::new("text/event-stream", stream),
+            })
+        }
+        ProxyResponse::Json { .. } => Err(UpstreamPassthroughError::service_unavailable(
+            "expected stream response".to_string(),
+        )),
+    }
+}
+
+struct AntiTruncState {
+    upstream: BoxedByteStream,
+    parser: SseParser,
+    pending: VecDeque<Bytes>,
+    accumulated_text: String,
+    last_finish_reason: Option<String>,
+    rounds: u32,
+    dedup_pending: bool,
+    continuation: AntiTruncContext,
+    finished: bool,
+}
+
+/// Relays an anti-truncation model's SSE stream, accumulating the
+/// concatenated candidate text and watching `finishReason`. A `MAX_TOKENS`
+/// cutoff triggers an automatic continuation request (original `contents`
+/// plus the accumulated assistant turn and a short "keep going" user turn)
+/// whose events are spliced into the same client stream; only the final
+/// round's `STOP` produces the `data: [DONE]\n\n` terminator.
+fn map_anti_trunc_stream(
+    upstream: BoxedByteStream,
+    continuation: AntiTruncContext,
+) -> impl futures_util::Stream<Item = Result<Bytes, io::Error>> {
+    futures_util::stream::unfold(
+        AntiTruncState {
+            upstream,
+            parser: SseParser::new(),
+            pending: VecDeque::new(),
+            accumulated_text: String::new(),
+            last_finish_reason: None,
+            rounds: 0,
+            dedup_pending: false,
+            continuation,
+            finished: false,
+        },
+        move |mut state| async move {
+            loop {
+                if let Some(item) = state.pending.pop_front() {
+                    return Some((Ok(item), state));
+                }
+                if state.finished {
+                    return None;
+                }
+                match state.upstream.next().await {
+                    Some(Ok(bytes)) => {
+                        for event in state.parser.push_bytes(&bytes) {
+                            if event.data.is_empty() || event.data == "[DONE]" {
+                                continue;
+                            }
+                            push_anti_trunc_event(&mut state, &event.data);
+                        }
+                    }
+                    Some(Err(err)) => {
+                        state.finished = true;
+                        return Some((Err(err), state));
+                    }
+                    None => {
+                        for event in state.parser.finish() {
+                            if event.data.is_empty() || event.data == "[DONE]" {
+                                continue;
+                            }
+                            push_anti_trunc_event(&mut state, &event.data);
+                        }
+                        if !state.pending.is_empty() {
+                            continue;
+                        }
+                        let truncated = state.last_finish_reason.as_deref() == Some("MAX_TOKENS");
+                        if truncated && state.rounds < ANTI_TRUNC_MAX_CONTINUATIONS {
+                            state.last_finish_reason = None;
+                            if start_anti_trunc_continuation(&mut state).await.is_err() {
+                                state.finished = true;
+                                state
+                                    .pending
+                                    .push_back(Bytes::from_static(b"data: [DONE]\n\n"));
+                            }
+                        } else {
+                            state.finished = true;
+                            state
+                                .pending
+                                .push_back(Bytes::from_static(b"data: [DONE]\n\n"));
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
+
+fn push_anti_trunc_event(state: &mut AntiTruncState, data: &str) {
+    let value: JsonValue = match serde_json::from_str(data) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+    let mut value = unwrap_internal_value(value);
+    let text = extract_candidate_text(&value);
+    if let Some(reason) = extract_finish_reason(&value) {
+        state.last_finish_reason = Some(reason);
+    }
+    if text.is_empty() {
+        if let Some(bytes) = sse_json_bytes(&value) {
+            state.pending.push_back(bytes);
+        }
+        return;
+    }
+    let emitted = if state.dedup_pending {
+        state.dedup_pending = false;
+        strip_seam_overlap(&state.accumulated_text, &text)
+    } else {
+        text.clone()
+    };
+    state.accumulated_text.push_str(&text);
+    if emitted.is_empty() {
+        // Fully duplicated seam chunk restating text we already relayed.
+        return;
+    }
+    set_candidate_text(&mut value, &emitted);
+    if let Some(bytes) = sse_json_bytes(&value) {
+        state.pending.push_back(bytes);
+    }
+}
+
+async fn start_anti_trunc_continuation(state: &mut AntiTruncState) -> Result<(), ()> {
+    let continuation_request = build_anti_trunc_continuation_request(
+        &state.continuation.original_request,
+        &state.continuation.contents,
+        &state.accumulated_text,
+    );
+    let wrapped = wrap_internal_request(
+        &state.continuation.model,
+        &state.continuation.project_id,
+        &continuation_request,
+        state.continuation.block_threshold.as_deref(),
+    );
+    let response = state
+        .continuation
+        .client
+        .post(&state.continuation.url)
+        .headers(state.continuation.req_headers.clone())
+        .json(&wrapped)
+        .send()
+        .await
+        .map_err(|_| ())?;
+    let proxy_response = handle_response(
+        response,
+        true,
+        state.continuation.scope.clone(),
+        &state.continuation.ctx,
+        None,
+    )
+    .await
+    .map_err(|_| ())?;
+    match proxy_response {
+        ProxyResponse::Stream { body, .. } => {
+            state.upstream = Box::pin(body.stream);
+            state.parser = SseParser::new();
+            state.rounds += 1;
+            state.dedup_pending = true;
+            Ok(())
+        }
+        ProxyResponse::Json { .. } => Err(()),
+    }
+}
+
+/// Builds the continuation request body: the original `contents` extended
+/// with an assistant turn carrying everything relayed so far, plus a short
+/// user turn nudging the model to pick up where it was cut off.
+fn build_anti_trunc_continuation_request(
+    original: &JsonValue,
+    contents: &[JsonValue],
+    accumulated_text: &str,
+) -> JsonValue {
+    let mut request = original.clone();
+    let mut new_contents = contents.to_vec();
+    new_contents.push(json!({
+        "role": "model",
+        "parts": [{"text": accumulated_text}],
+    }));
+    new_contents.push(json!({
+        "role": "user",
+        "parts": [{
+            "text": "Continue exactly from where you left off. Do not repeat or summarize anything you already said.",
+        }],
+    }));
+    if let JsonValue::Object(map) = &mut request {
+        map.insert("contents".to_string(), JsonValue::Array(new_contents));
+    }
+    request
+}
+
+fn extract_candidate_text(value: &JsonValue) -> String {
+    value
+        .get("candidates")
+        .and_then(|candidates| candidates.as_array())
+        .and_then(|candidates| candidates.first())
+        .and_then(|candidate| candidate.get("content"))
+        .and_then(|content| content.get("parts"))
+        .and_then(|parts| parts.as_array())
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(|text| text.as_str()))
+                .collect::<String>()
+        })
+        .unwrap_or_default()
+}
+
+fn extract_finish_reason(value: &JsonValue) -> Option<String> {
+    value
+        .get("candidates")
+        .and_then(|candidates| candidates.as_array())
+        .and_then(|candidates| candidates.first())
+        .and_then(|candidate| candidate.get("finishReason"))
+        .and_then(|reason| reason.as_str())
+        .map(|reason| reason.to_string())
+}
+
+fn set_candidate_text(value: &mut JsonValue, text: &str) {
+    let part = value
+        .get_mut("candidates")
+        .and_then(|candidates| candidates.as_array_mut())
+        .and_then(|candidates| candidates.first_mut())
+        .and_then(|candidate| candidate.get_mut("content"))
+        .and_then(|content| content.get_mut("parts"))
+        .and_then(|parts| parts.as_array_mut())
+        .and_then(|parts| parts.first_mut());
+    if let Some(JsonValue::Object(part)) = part {
+        part.insert("text".to_string(), JsonValue::String(text.to_string()));
+    }
+}
+
+/// Finds the longest suffix of `accumulated` that's also a prefix of
+/// `new_text` and strips it, so a continuation round that restates the last
+/// few words before picking up doesn't duplicate them in the client stream.
+fn strip_seam_overlap(accumulated: &str, new_text: &str) -> String {
+    let acc_chars: Vec<char> = accumulated.chars().collect();
+    let new_chars: Vec<char> = new_text.chars().collect();
+    let max_len = acc_chars.len().min(new_chars.len());
+    for len in (1..=max_len).rev() {
+        if acc_chars[acc_chars.len() - len..] == new_chars[..len] {
+            return new_chars[len..].iter().collect();
+        }
+    }
+    new_text.to_string()
+}
+
 async fn stream_to_response(
     response: ProxyResponse,
 ) -> Result<ProxyResponse, UpstreamPassthroughError> {
@@ -748,8 +1344,67 @@ fn estimate_tokens_from_contents(contents: &[gemini::count_tokens::types::Conten
 }
 
 fn estimate_tokens_from_text(text: &str) -> u32 {
-    let chars = text.chars().count() as u32;
-    (chars + 3) / 4
+    bpe::bpe_token_count(text)
+}
+
+fn count_tokens_cache() -> &'static tokio::sync::RwLock<HashMap<u64, u32>> {
+    static CACHE: OnceLock<tokio::sync::RwLock<HashMap<u64, u32>>> = OnceLock::new();
+    CACHE.get_or_init(|| tokio::sync::RwLock::new(HashMap::new()))
+}
+
+/// Hashes the normalized model plus the serialized request contents so
+/// repeated `countTokens` calls for the same prompt (client retries, the
+/// same prefix reused across turns) are served from cache instead of
+/// re-hitting the upstream endpoint.
+fn count_tokens_cache_key(
+    model: &str,
+    body: &gemini::count_tokens::request::CountTokensRequestBody,
+) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    model.hash(&mut hasher);
+    json_body_to_string(body).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Builds a `countTokens` response from a locally-known count (either a
+/// cache hit or the offline BPE estimate) without involving a credential.
+fn local_count_tokens_response(
+    ctx: &UpstreamContext,
+    model: String,
+    request_body: String,
+    token_count: u32,
+) -> Result<UpstreamOk, UpstreamPassthroughError> {
+    let response_body = gemini::count_tokens::response::CountTokensResponse {
+        total_tokens: token_count,
+        cached_content_token_count: None,
+        prompt_tokens_details: None,
+        cache_tokens_details: None,
+    };
+    let response_bytes = serde_json::to_vec(&response_body)
+        .map_err(|err| UpstreamPassthroughError::service_unavailable(err.to_string()))?;
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    let meta = UpstreamRecordMeta {
+        provider: PROVIDER_NAME.to_string(),
+        provider_id: ctx.provider_id,
+        credential_id: None,
+        operation: "antigravity.count_tokens".to_string(),
+        model: Some(model),
+        request_method: "POST".to_string(),
+        request_path: "/v1beta/models:countTokens".to_string(),
+        request_query: None,
+        request_headers: String::new(),
+        request_body,
+    };
+    Ok(UpstreamOk {
+        response: ProxyResponse::Json {
+            status: StatusCode::OK,
+            headers,
+            body: Bytes::from(response_bytes),
+        },
+        meta,
+    })
 }
 
 fn build_models_list() -> Vec<gemini::types::Model> {
@@ -761,24 +1416,27 @@ fn build_models_list() -> Vec<gemini::types::Model> {
 
 fn build_model(model: &str) -> Option<gemini::types::Model> {
     let base = normalize_model_name(model);
+    let metadata = model_metadata(&base);
     Some(gemini::types::Model {
         name: format!("models/{base}"),
         base_model_id: Some(base.clone()),
         version: "1".to_string(),
         display_name: Some(base.clone()),
         description: None,
-        input_token_limit: None,
-        output_token_limit: None,
-        supported_generation_methods: Some(vec![
-            "generateContent".to_string(),
-            "countTokens".to_string(),
-            "streamGenerateContent".to_string(),
-        ]),
-        thinking: None,
-        temperature: None,
-        max_temperature: None,
-        top_p: None,
-        top_k: None,
+        input_token_limit: Some(metadata.input_token_limit),
+        output_token_limit: Some(metadata.output_token_limit),
+        supported_generation_methods: Some(
+            metadata
+                .supported_generation_methods
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
+        ),
+        thinking: Some(metadata.thinking),
+        temperature: Some(metadata.temperature),
+        max_temperature: Some(metadata.max_temperature),
+        top_p: Some(metadata.top_p),
+        top_k: Some(metadata.top_k),
     })
 }
 
@@ -791,6 +1449,117 @@ fn base_models() -> Vec<&'static str> {
     ]
 }
 
+/// Static capability info for a known base model, used both to populate the
+/// `/models` listing's `input_token_limit`/`output_token_limit`/sampling
+/// fields and to pick the `requesttype` header `build_headers` sends
+/// upstream. Keyed on the normalized base name (see `normalize_model_name`),
+/// not the raw client-facing alias.
+#[derive(Clone, Copy)]
+struct ModelMetadata {
+    input_token_limit: u32,
+    output_token_limit: u32,
+    thinking: bool,
+    temperature: f32,
+    max_temperature: f32,
+    top_p: f32,
+    top_k: u32,
+    supported_generation_methods: &'static [&'static str],
+    request_type: &'static str,
+}
+
+const GENERATE_CONTENT_METHODS: &[&str] =
+    &["generateContent", "countTokens", "streamGenerateContent"];
+const IMAGE_GENERATION_METHODS: &[&str] = &["generateImage", "countTokens"];
+
+const MODEL_METADATA: &[(&str, ModelMetadata)] = &[
+    (
+        "gemini-2.5-pro",
+        ModelMetadata {
+            input_token_limit: 1_048_576,
+            output_token_limit: 65_536,
+            thinking: true,
+            temperature: 1.0,
+            max_temperature: 2.0,
+            top_p: 0.95,
+            top_k: 64,
+            supported_generation_methods: GENERATE_CONTENT_METHODS,
+            request_type: "agent",
+        },
+    ),
+    (
+        "gemini-2.5-flash",
+        ModelMetadata {
+            input_token_limit: 1_048_576,
+            output_token_limit: 65_536,
+            thinking: true,
+            temperature: 1.0,
+            max_temperature: 2.0,
+            top_p: 0.95,
+            top_k: 64,
+            supported_generation_methods: GENERATE_CONTENT_METHODS,
+            request_type: "agent",
+        },
+    ),
+    (
+        "gemini-3-pro-preview",
+        ModelMetadata {
+            input_token_limit: 1_048_576,
+            output_token_limit: 65_536,
+            thinking: true,
+            temperature: 1.0,
+            max_temperature: 2.0,
+            top_p: 0.95,
+            top_k: 64,
+            supported_generation_methods: GENERATE_CONTENT_METHODS,
+            request_type: "agent",
+        },
+    ),
+    (
+        "gemini-3-flash-preview",
+        ModelMetadata {
+            input_token_limit: 1_048_576,
+            output_token_limit: 32_768,
+            thinking: true,
+            temperature: 1.0,
+            max_temperature: 2.0,
+            top_p: 0.95,
+            top_k: 64,
+            supported_generation_methods: GENERATE_CONTENT_METHODS,
+            request_type: "agent",
+        },
+    ),
+];
+
+/// Default metadata for a base name not present in `MODEL_METADATA` —
+/// conservative limits, no thinking support, and the `generateContent`
+/// method set unless the name itself advertises image generation.
+fn default_model_metadata(base: &str) -> ModelMetadata {
+    let is_image_model = base.to_ascii_lowercase().contains("image");
+    ModelMetadata {
+        input_token_limit: 32_768,
+        output_token_limit: 8_192,
+        thinking: false,
+        temperature: 1.0,
+        max_temperature: 2.0,
+        top_p: 0.95,
+        top_k: 64,
+        supported_generation_methods: if is_image_model {
+            IMAGE_GENERATION_METHODS
+        } else {
+            GENERATE_CONTENT_METHODS
+        },
+        request_type: if is_image_model { "image_gen" } else { "agent" },
+    }
+}
+
+fn model_metadata(base: &str) -> ModelMetadata {
+    MODEL_METADATA
+        .iter()
+        .find(|(name, _)| *name == base)
+        .map(|(_, metadata)| *metadata)
+        .unwrap_or_else(|| default_model_metadata(base))
+}
+
 fn normalize_model_name(model: &str) -> String {
     let mut name = model.trim();
     for prefix in [FAKE_PREFIX, ANTI_TRUNC_PREFIX] {
@@ -808,11 +1577,7 @@ fn normalize_model_name(model: &str) -> String {
 }
 
 fn request_type_for_model(model: &str) -> &'static str {
-    if model.to_ascii_lowercase().contains("image") {
-        "image_gen"
-    } else {
-        "agent"
-    }
+    model_metadata(&normalize_model_name(model)).request_type
 }
 
 fn generate_request_id() -> String {
@@ -831,6 +1596,10 @@ pub(super) fn random_project_id() -> String {
     format!("projects/random-{hex}/locations/global")
 }
 
+/// A manually-supplied static access token, if the credential carries one.
+/// Credentials backed by a service-account key never hit this path — they're
+/// minted transparently by `refresh::ensure_tokens` via a JWT-bearer
+/// exchange before this function is ever consulted.
 pub(super) fn credential_access_token(credential: &BaseCredential) -> Option<String> {
     credential
         .secret
@@ -865,6 +1634,478 @@ pub(super) fn credential_base_url(credential: &BaseCredential) -> Option<String>
         .map(|value| value.to_string())
 }
 
+/// Regional endpoints to try, in order, for one request. Reads a
+/// `base_urls` list (mirroring the Vertex AI `{REGION}-aiplatform
+/// .googleapis.com` multi-region pattern) when present, falling back to the
+/// single `base_url`/`DEFAULT_BASE_URL` override so existing single-region
+/// credentials keep working unchanged.
+fn credential_base_urls(credential: &BaseCredential) -> Vec<String> {
+    if let Some(urls) = credential.meta.get("base_urls").and_then(|value| value.as_array()) {
+        let list: Vec<String> = urls
+            .iter()
+            .filter_map(|value| value.as_str().map(|value| value.to_string()))
+            .collect();
+        if !list.is_empty() {
+            return list;
+        }
+    }
+    vec![credential_base_url(credential).unwrap_or_else(|| DEFAULT_BASE_URL.to_string())]
+}
+
+/// Whether a failed attempt is worth retrying against the next regional
+/// endpoint rather than immediately marking the credential: a connection
+/// failure (surfaced as 503 by `network_failure`) or a retryable upstream
+/// status (429/503) may just mean this region is degraded.
+fn is_region_failover_failure(failure: &AttemptFailure) -> bool {
+    matches!(
+        failure.passthrough.status,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    )
+}
+
+/// Whether `failure` looks like an expired/invalid access token rather than
+/// a genuinely dead credential — worth one retry with a freshly-minted
+/// token via `credential_refresh_token` before giving up on the credential.
+fn is_unauthorized_failure(failure: &AttemptFailure) -> bool {
+    failure.passthrough.status == StatusCode::UNAUTHORIZED
+}
+
+/// Runs the region-failover loop for a single `generateContent` /
+/// `streamGenerateContent` attempt against `access_token`. Factored out of
+/// `handle_generate` so it can be retried once with a freshly-refreshed
+/// token after a 401 without duplicating the loop.
+#[allow(clippy::too_many_arguments)]
+async fn run_generate_attempt(
+    ctx: &UpstreamContext,
+    scope: &DisallowScope,
+    model: &str,
+    raw_model: &str,
+    access_token: &str,
+    credential_id: i64,
+    project_id: &str,
+    base_urls: &[String],
+    stream2nostream: bool,
+    is_stream: bool,
+    block_threshold: Option<&str>,
+    body: &gemini::generate_content::request::GenerateContentRequestBody,
+) -> Result<UpstreamOk, AttemptFailure> {
+    let path = if is_stream || stream2nostream {
+        "/v1internal:streamGenerateContent?alt=sse"
+    } else {
+        "/v1internal:generateContent"
+    }
+    .to_string();
+    let client = shared_client(ctx.proxy.as_deref())?;
+    let req_headers = build_headers(access_token, raw_model)?;
+    let wrapped = wrap_internal_request(model, project_id, body, block_threshold);
+    let request_body = json_body_to_string(&wrapped);
+    let request_headers = headers_to_json(&req_headers);
+    let anti_trunc = is_stream && is_anti_trunc_model(raw_model);
+    let original_request = serde_json::to_value(body).unwrap_or(JsonValue::Null);
+    let contents = original_request
+        .get("contents")
+        .and_then(|value| value.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut last_failure = None;
+    for (index, region) in base_urls.iter().enumerate() {
+        let is_last_region = index + 1 == base_urls.len();
+        let url = build_url(region, &path);
+        let continuation_url = url.clone();
+        let response = match send_with_logging(
+            ctx,
+            PROVIDER_NAME,
+            "antigravity.generate",
+            "POST",
+            &path,
+            Some(model),
+            is_stream || stream2nostream,
+            scope,
+            || {
+                client
+                    .post(url)
+                    .headers(req_headers.clone())
+                    .json(&wrapped)
+                    .send()
+            },
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(failure) if !is_last_region && is_region_failover_failure(&failure) => {
+                last_failure = Some(failure);
+                continue;
+            }
+            Err(failure) => return Err(failure),
+        };
+        let meta = UpstreamRecordMeta {
+            provider: PROVIDER_NAME.to_string(),
+            provider_id: ctx.provider_id,
+            credential_id: Some(credential_id),
+            operation: "antigravity.generate".to_string(),
+            model: Some(model.to_string()),
+            request_method: "POST".to_string(),
+            request_path: path.clone(),
+            request_query: None,
+            request_headers: request_headers.clone(),
+            request_body: request_body.clone(),
+        };
+        let response = match handle_response(
+            response,
+            is_stream || stream2nostream,
+            scope.clone(),
+            ctx,
+            Some(meta.clone()),
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(failure) if !is_last_region && is_region_failover_failure(&failure) => {
+                last_failure = Some(failure);
+                continue;
+            }
+            Err(failure) => return Err(failure),
+        };
+        let response = if anti_trunc {
+            let continuation = AntiTruncContext {
+                ctx: ctx.clone(),
+                scope: scope.clone(),
+                client: client.clone(),
+                req_headers: req_headers.clone(),
+                url: continuation_url,
+                model: model.to_string(),
+                project_id: project_id.to_string(),
+                block_threshold: block_threshold.map(|value| value.to_string()),
+                original_request: original_request.clone(),
+                contents: contents.clone(),
+            };
+            unwrap_internal_stream_with_anti_trunc(response, continuation).map_err(|err| {
+                AttemptFailure {
+                    passthrough: err,
+                    mark: None,
+                }
+            })?
+        } else if is_stream {
+            unwrap_internal_stream(response).map_err(|err| AttemptFailure {
+                passthrough: err,
+                mark: None,
+            })?
+        } else if stream2nostream {
+            stream_to_response(response)
+                .await
+                .map_err(|err| AttemptFailure {
+                    passthrough: err,
+                    mark: None,
+                })?
+        } else {
+            unwrap_internal_json(response).map_err(|err| AttemptFailure {
+                passthrough: err,
+                mark: None,
+            })?
+        };
+        return Ok(UpstreamOk { response, meta });
+    }
+    Err(last_failure.unwrap_or_else(|| invalid_credential(scope, "no regional endpoints configured")))
+}
+
+/// The `streamGenerateContent`-only counterpart of `run_generate_attempt`,
+/// used by `handle_generate_stream`.
+#[allow(clippy::too_many_arguments)]
+async fn run_generate_stream_attempt(
+    ctx: &UpstreamContext,
+    scope: &DisallowScope,
+    model: &str,
+    raw_model: &str,
+    access_token: &str,
+    credential_id: i64,
+    project_id: &str,
+    base_urls: &[String],
+    block_threshold: Option<&str>,
+    body: &gemini::generate_content::request::GenerateContentRequestBody,
+) -> Result<UpstreamOk, AttemptFailure> {
+    let path = "/v1internal:streamGenerateContent?alt=sse".to_string();
+    let client = shared_client(ctx.proxy.as_deref())?;
+    let req_headers = build_headers(access_token, raw_model)?;
+    let wrapped = wrap_internal_request(model, project_id, body, block_threshold);
+    let request_body = json_body_to_string(&wrapped);
+    let request_headers = headers_to_json(&req_headers);
+    let anti_trunc = is_anti_trunc_model(raw_model);
+    let original_request = serde_json::to_value(body).unwrap_or(JsonValue::Null);
+    let contents = original_request
+        .get("contents")
+        .and_then(|value| value.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut last_failure = None;
+    for (index, region) in base_urls.iter().enumerate() {
+        let is_last_region = index + 1 == base_urls.len();
+        let url = build_url(region, &path);
+        let continuation_url = url.clone();
+        let response = match send_with_logging(
+            ctx,
+            PROVIDER_NAME,
+            "antigravity.stream",
+            "POST",
+            &path,
+            Some(model),
+            true,
+            scope,
+            || {
+                client
+                    .post(url)
+                    .headers(req_headers.clone())
+                    .json(&wrapped)
+                    .send()
+            },
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(failure) if !is_last_region && is_region_failover_failure(&failure) => {
+                last_failure = Some(failure);
+                continue;
+            }
+            Err(failure) => return Err(failure),
+        };
+        let meta = UpstreamRecordMeta {
+            provider: PROVIDER_NAME.to_string(),
+            provider_id: ctx.provider_id,
+            credential_id: Some(credential_id),
+            operation: "antigravity.stream".to_string(),
+            model: Some(model.to_string()),
+            request_method: "POST".to_string(),
+            request_path: path.clone(),
+            request_query: None,
+            request_headers: request_headers.clone(),
+            request_body: request_body.clone(),
+        };
+        let response = match handle_response(response, true, scope.clone(), ctx, Some(meta.clone())).await {
+            Ok(response) => response,
+            Err(failure) if !is_last_region && is_region_failover_failure(&failure) => {
+                last_failure = Some(failure);
+                continue;
+            }
+            Err(failure) => return Err(failure),
+        };
+        let response = if anti_trunc {
+            let continuation = AntiTruncContext {
+                ctx: ctx.clone(),
+                scope: scope.clone(),
+                client: client.clone(),
+                req_headers: req_headers.clone(),
+                url: continuation_url,
+                model: model.to_string(),
+                project_id: project_id.to_string(),
+                block_threshold: block_threshold.map(|value| value.to_string()),
+                original_request: original_request.clone(),
+                contents: contents.clone(),
+            };
+            unwrap_internal_stream_with_anti_trunc(response, continuation).map_err(|err| {
+                AttemptFailure {
+                    passthrough: err,
+                    mark: None,
+                }
+            })?
+        } else {
+            unwrap_internal_stream(response).map_err(|err| AttemptFailure {
+                passthrough: err,
+                mark: None,
+            })?
+        };
+        return Ok(UpstreamOk { response, meta });
+    }
+    Err(last_failure.unwrap_or_else(|| invalid_credential(scope, "no regional endpoints configured")))
+}
+
+/// How often to emit a `: keep-alive\n\n` SSE comment line while a
+/// fake-stream's real upstream call is still in flight.
+const FAKE_STREAM_KEEP_ALIVE: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Whether `raw_model` was requested through the fake-streaming prefix or
+/// suffix that `normalize_model_name` strips.
+fn is_fake_stream_model(raw_model: &str) -> bool {
+    let trimmed = raw_model.trim();
+    trimmed.starts_with(FAKE_PREFIX) || trimmed.ends_with(FAKE_SUFFIX)
+}
+
+/// Bridges a non-streaming `generateContent` call into a synthetic SSE
+/// stream for clients using the `FAKE_PREFIX`-tagged ("假流式") model alias.
+/// The real call runs on a background task via `run_generate_attempt` (with
+/// streaming forced off) so this can return immediately and start emitting
+/// keep-alive comment lines while it's in flight — useful against upstreams
+/// whose native streaming mode is flaky. This is the opposite direction of
+/// `credential_stream2nostream`, which bridges a *streaming* upstream into a
+/// buffered non-stream client response; the two features are independent.
+#[allow(clippy::too_many_arguments)]
+async fn run_fake_stream_attempt(
+    ctx: UpstreamContext,
+    scope: DisallowScope,
+    model: String,
+    raw_model: String,
+    access_token: String,
+    credential_id: i64,
+    project_id: String,
+    base_urls: Vec<String>,
+    block_threshold: Option<String>,
+    body: gemini::generate_content::request::GenerateContentRequestBody,
+) -> Result<UpstreamOk, AttemptFailure> {
+    let req_headers = build_headers(&access_token, &raw_model)?;
+    let wrapped = wrap_internal_request(&model, &project_id, &body, block_threshold.as_deref());
+    let meta = UpstreamRecordMeta {
+        provider: PROVIDER_NAME.to_string(),
+        provider_id: ctx.provider_id,
+        credential_id: Some(credential_id),
+        operation: "antigravity.generate".to_string(),
+        model: Some(model.clone()),
+        request_method: "POST".to_string(),
+        request_path: "/v1internal:generateContent".to_string(),
+        request_query: None,
+        request_headers: headers_to_json(&req_headers),
+        request_body: json_body_to_string(&wrapped),
+    };
+
+    let join_handle = tokio::spawn(async move {
+        run_generate_attempt(
+            &ctx,
+            &scope,
+            &model,
+            &raw_model,
+            &access_token,
+            credential_id,
+            &project_id,
+            &base_urls,
+            false,
+            false,
+            block_threshold.as_deref(),
+            &body,
+        )
+        .await
+    });
+
+    let stream = fake_stream_from_join(join_handle);
+    Ok(UpstreamOk {
+        response: ProxyResponse::Stream {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: StreamBody::new("text/event-stream", stream),
+        },
+        meta,
+    })
+}
+
+enum FakeStreamPhase {
+    Waiting(tokio::task::JoinHandle<Result<UpstreamOk, AttemptFailure>>),
+    Draining(VecDeque<Bytes>),
+}
+
+fn fake_stream_from_join(
+    join_handle: tokio::task::JoinHandle<Result<UpstreamOk, AttemptFailure>>,
+) -> impl futures_util::Stream<Item = Result<Bytes, io::Error>> {
+    futures_util::stream::unfold(FakeStreamPhase::Waiting(join_handle), |phase| async move {
+        match phase {
+            FakeStreamPhase::Waiting(mut handle) => {
+                tokio::select! {
+                    _ = tokio::time::sleep(FAKE_STREAM_KEEP_ALIVE) => {
+                        Some((Ok(Bytes::from_static(b": keep-alive\n\n")), FakeStreamPhase::Waiting(handle)))
+                    }
+                    result = &mut handle => {
+                        let frames = match result {
+                            Ok(Ok(upstream_ok)) => build_fake_stream_frames(upstream_ok.response),
+                            Ok(Err(failure)) => fake_stream_error_frames(&failure.passthrough.status.to_string()),
+                            Err(_) => fake_stream_error_frames("upstream task failed"),
+                        };
+                        let mut queue: VecDeque<Bytes> = frames.into();
+                        let item = queue.pop_front()?;
+                        Some((Ok(item), FakeStreamPhase::Draining(queue)))
+                    }
+                }
+            }
+            FakeStreamPhase::Draining(mut queue) => {
+                let item = queue.pop_front()?;
+                Some((Ok(item), FakeStreamPhase::Draining(queue)))
+            }
+        }
+    })
+}
+
+/// Turns a single non-streaming `generateContent` response into one or more
+/// `data: {...}\n\n` frames chunked on sentence/paragraph boundaries, plus a
+/// trailing `data: [DONE]\n\n`. All frames but the last carry the response's
+/// `finishReason` stripped so clients don't see it until the real final
+/// chunk.
+fn build_fake_stream_frames(response: ProxyResponse) -> Vec<Bytes> {
+    let (status, body) = match response {
+        ProxyResponse::Json { status, body, .. } => (status, body),
+        ProxyResponse::Stream { .. } => {
+            return fake_stream_error_frames("expected json response");
+        }
+    };
+    if !status.is_success() {
+        return fake_stream_error_frames(&format!("upstream returned {status}"));
+    }
+    let value: JsonValue = match serde_json::from_slice(&body) {
+        Ok(value) => unwrap_internal_value(value),
+        Err(err) => return fake_stream_error_frames(&err.to_string()),
+    };
+
+    let text = extract_candidate_text(&value);
+    let chunks = chunk_fake_stream_text(&text);
+    let last_index = chunks.len().saturating_sub(1);
+    let mut frames: Vec<Bytes> = chunks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, chunk)| {
+            let mut frame = value.clone();
+            set_candidate_text(&mut frame, chunk);
+            if index != last_index {
+                clear_finish_reason(&mut frame);
+            }
+            sse_json_bytes(&frame)
+        })
+        .collect();
+    if frames.is_empty() {
+        frames.extend(sse_json_bytes(&value));
+    }
+    frames.push(Bytes::from_static(b"data: [DONE]\n\n"));
+    frames
+}
+
+fn fake_stream_error_frames(message: &str) -> Vec<Bytes> {
+    let frame = sse_json_bytes(&json!({"error": {"message": message}}))
+        .unwrap_or_else(|| Bytes::from_static(b"data: {}\n\n"));
+    vec![frame, Bytes::from_static(b"data: [DONE]\n\n")]
+}
+
+fn clear_finish_reason(value: &mut JsonValue) {
+    if let Some(JsonValue::Object(candidate)) = value
+        .get_mut("candidates")
+        .and_then(|candidates| candidates.as_array_mut())
+        .and_then(|candidates| candidates.first_mut())
+    {
+        candidate.remove("finishReason");
+    }
+}
+
+/// Splits `text` into chunks that end on a sentence or paragraph boundary so
+/// a fake-streamed response looks like incremental output instead of one
+/// giant frame.
+fn chunk_fake_stream_text(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for ch in text.chars() {
+        current.push(ch);
+        if matches!(ch, '.' | '!' | '?' | '\n') {
+            chunks.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
 fn credential_stream2nostream(credential: &BaseCredential) -> Option<bool> {
     credential
         .meta
@@ -872,8 +2113,43 @@ fn credential_stream2nostream(credential: &BaseCredential) -> Option<bool> {
         .and_then(|value| value.as_bool())
 }
 
-pub(super) fn build_url(base_url: Option<&str>, path: &str) -> String {
-    let base = base_url.unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/');
+fn credential_block_threshold(credential: &BaseCredential) -> Option<String> {
+    credential
+        .meta
+        .get("block_threshold")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn credential_count_tokens_upstream(credential: &BaseCredential) -> Option<bool> {
+    credential
+        .meta
+        .get("count_tokens_upstream")
+        .and_then(|value| value.as_bool())
+}
+
+/// Default [`model_catalog_ttl`](AntiGravityProvider::model_catalog_ttl):
+/// how long a live-fetched catalog is served before the next request
+/// triggers a refresh.
+const DEFAULT_MODEL_CATALOG_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+fn credential_model_catalog_live(credential: &BaseCredential) -> Option<bool> {
+    credential
+        .meta
+        .get("model_catalog_live")
+        .and_then(|value| value.as_bool())
+}
+
+fn credential_model_catalog_ttl(credential: &BaseCredential) -> Option<std::time::Duration> {
+    credential
+        .meta
+        .get("model_catalog_ttl_secs")
+        .and_then(|value| value.as_u64())
+        .map(std::time::Duration::from_secs)
+}
+
+pub(super) fn build_url(region: &str, path: &str) -> String {
+    let base = region.trim_end_matches('/');
     let path = path.trim_start_matches('/');
     format!("{base}/{path}")
 }
@@ -0,0 +1,254 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use http::header::CONTENT_TYPE;
+use http::{HeaderMap, HeaderValue};
+use serde_json::json;
+use tracing::{info, warn};
+
+use gproxy_provider_core::{
+    AttemptFailure, CallContext, CredentialPool, DisallowScope, PoolSnapshot, Provider,
+    ProxyRequest, ProxyResponse, StateSink, UpstreamPassthroughError, UpstreamRecordMeta,
+};
+
+use crate::client::shared_client;
+use crate::credential::BaseCredential;
+use crate::dispatch::UpstreamOk;
+use crate::provider::not_implemented;
+use crate::record::{headers_to_json, json_body_to_string};
+use crate::transport::TransportConfig;
+use crate::upstream::{handle_response, network_failure};
+use crate::ProviderDefault;
+
+pub const PROVIDER_NAME: &str = "azure-openai";
+const DEFAULT_API_VERSION: &str = "2024-10-21";
+
+pub fn default_provider() -> ProviderDefault {
+    ProviderDefault {
+        name: PROVIDER_NAME,
+        config_json: json!({ "api_version": DEFAULT_API_VERSION }),
+        enabled: true,
+    }
+}
+
+/// Speaks the same OpenAI chat-completions wire format as `OpenAIProvider`,
+/// but against an Azure-hosted deployment: the URL is keyed by
+/// `{endpoint}/openai/deployments/{deployment}/chat/completions` with an
+/// `api-version` query parameter, and auth is an `api-key` header rather than
+/// `Authorization: Bearer`.
+#[derive(Debug)]
+pub struct AzureOpenAIProvider {
+    pool: CredentialPool<AzureOpenAICredential>,
+}
+
+pub type AzureOpenAICredential = BaseCredential;
+
+impl AzureOpenAIProvider {
+    pub fn new(sink: Arc<dyn StateSink>) -> Self {
+        let snapshot = PoolSnapshot::empty();
+        let pool = CredentialPool::new(PROVIDER_NAME, snapshot, Some(sink));
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &CredentialPool<AzureOpenAICredential> {
+        &self.pool
+    }
+
+    pub fn replace_snapshot(&self, snapshot: PoolSnapshot<AzureOpenAICredential>) {
+        self.pool.replace_snapshot(snapshot);
+    }
+
+    async fn handle_chat(
+        &self,
+        request: gproxy_protocol::openai::create_chat_completions::request::CreateChatCompletionRequest,
+        is_stream: bool,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.body.model.clone();
+        let scope = DisallowScope::model(model.clone());
+        let mut body = request.body;
+        if is_stream {
+            body.stream = Some(true);
+        }
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let body = body.clone();
+                async move {
+                    let api_key = credential_api_key(credential.value())
+                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let endpoint = credential_endpoint(credential.value())
+                        .ok_or_else(|| invalid_credential(&scope, "missing endpoint"))?;
+                    let deployment = credential_deployment(credential.value())
+                        .unwrap_or_else(|| model.clone());
+                    let api_version = credential_api_version(credential.value())
+                        .unwrap_or_else(|| DEFAULT_API_VERSION.to_string());
+                    let path = format!("/openai/deployments/{deployment}/chat/completions");
+                    let url = format!(
+                        "{}{}?api-version={}",
+                        endpoint.trim_end_matches('/'),
+                        path,
+                        api_version
+                    );
+                    let transport = TransportConfig::from_meta(&credential.value().meta);
+                    let client = shared_client(transport.resolve_proxy(ctx.proxy.as_deref()))?;
+                    let mut req_headers = build_auth_headers(&api_key)?;
+                    transport.apply_headers(&mut req_headers)?;
+                    let request_body = json_body_to_string(&body);
+                    let request_headers = headers_to_json(&req_headers);
+                    let started_at = Instant::now();
+                    info!(
+                        event = "upstream_request",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "azure_openai.chat",
+                        method = "POST",
+                        path = %path,
+                        model = %model,
+                        is_stream = is_stream
+                    );
+                    let mut request_builder = client.post(url).headers(req_headers.clone()).json(&body);
+                    if let Some(connect_timeout) = transport.connect_timeout {
+                        request_builder = request_builder.timeout(connect_timeout);
+                    }
+                    let response = request_builder
+                        .send()
+                        .await
+                        .map_err(|err| {
+                            warn!(
+                                event = "upstream_response",
+                                trace_id = %ctx.trace_id,
+                                provider = %PROVIDER_NAME,
+                                op = "azure_openai.chat",
+                                status = "error",
+                                elapsed_ms = started_at.elapsed().as_millis(),
+                                error = %err
+                            );
+                            network_failure(err, &scope)
+                        })?;
+                    info!(
+                        event = "upstream_response",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "azure_openai.chat",
+                        status = %response.status().as_u16(),
+                        elapsed_ms = started_at.elapsed().as_millis(),
+                        is_stream = is_stream
+                    );
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "azure_openai.chat".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: Some(format!("api-version={api_version}")),
+                        request_headers,
+                        request_body,
+                    };
+                    let response = handle_response(
+                        response,
+                        is_stream,
+                        scope.clone(),
+                        &ctx,
+                        Some(meta.clone()),
+                    )
+                    .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Provider for AzureOpenAIProvider {
+    fn name(&self) -> &str {
+        PROVIDER_NAME
+    }
+
+    async fn call(
+        &self,
+        req: ProxyRequest,
+        ctx: CallContext,
+    ) -> Result<ProxyResponse, UpstreamPassthroughError> {
+        match req {
+            ProxyRequest::OpenAIChat(request) => {
+                self.handle_chat(request, false, ctx).await.map(|ok| ok.response)
+            }
+            ProxyRequest::OpenAIChatStream(request) => {
+                self.handle_chat(request, true, ctx).await.map(|ok| ok.response)
+            }
+            _ => Err(not_implemented(PROVIDER_NAME)),
+        }
+    }
+}
+
+fn build_auth_headers(api_key: &str) -> Result<HeaderMap, AttemptFailure> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "api-key",
+        HeaderValue::from_str(api_key).map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?,
+    );
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Ok(headers)
+}
+
+fn credential_api_key(credential: &BaseCredential) -> Option<String> {
+    if let serde_json::Value::String(value) = &credential.secret {
+        return Some(value.clone());
+    }
+    credential
+        .secret
+        .get("api_key")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn credential_endpoint(credential: &BaseCredential) -> Option<String> {
+    credential
+        .meta
+        .get("base_url")
+        .or_else(|| credential.meta.get("endpoint"))
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn credential_deployment(credential: &BaseCredential) -> Option<String> {
+    credential
+        .meta
+        .get("deployment")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn credential_api_version(credential: &BaseCredential) -> Option<String> {
+    credential
+        .meta
+        .get("api_version")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn invalid_credential(scope: &DisallowScope, message: &str) -> AttemptFailure {
+    AttemptFailure {
+        passthrough: UpstreamPassthroughError::service_unavailable(message.to_string()),
+        mark: Some(gproxy_provider_core::DisallowMark {
+            scope: scope.clone(),
+            level: gproxy_provider_core::DisallowLevel::Dead,
+            duration: None,
+            reason: Some(message.to_string()),
+        }),
+    }
+}
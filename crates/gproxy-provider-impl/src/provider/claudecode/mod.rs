@@ -1,19 +1,43 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use http::header::{AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
 use serde_json::json;
 
 use gproxy_provider_core::{
-    CallContext, CredentialPool, PoolSnapshot, Provider, ProxyRequest, ProxyResponse, StateSink,
-    UpstreamPassthroughError,
+    AttemptFailure, CallContext, CredentialPool, DisallowScope, PoolSnapshot, Provider,
+    ProxyRequest, ProxyResponse, StateSink, UpstreamPassthroughError, UpstreamRecordMeta,
 };
 
-use crate::credential::BaseCredential;
 use crate::ProviderDefault;
-use crate::provider::not_implemented;
+use crate::client::shared_client;
+use crate::credential::BaseCredential;
+use crate::dispatch::{
+    CountTokensPlan, DispatchPlan, DispatchProvider, GenerateContentPlan, ModelsGetPlan,
+    ModelsListPlan, StreamContentPlan, TransformPlan, UpstreamOk, UsageKind, dispatch_request,
+};
+use crate::record::{headers_to_json, json_body_to_string};
+use crate::upstream::{handle_response, send_with_logging};
+
+mod refresh;
+mod usage;
+
+use refresh::{ensure_tokens, refresh_access_token};
 
 pub const PROVIDER_NAME: &str = "claudecode";
 const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+/// The Anthropic Messages API version this provider speaks; distinct from
+/// the OAuth `anthropic-beta` marker below.
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// Claude Code's OAuth token endpoint only accepts requests carrying this
+/// beta marker, the same one `claudecode/usage.rs` already sends.
+pub(super) const OAUTH_BETA: &str = "oauth-2025-04-20";
+pub(super) const HEADER_BETA: HeaderName = HeaderName::from_static("anthropic-beta");
+/// Mirrors the real Claude Code CLI's user agent, since Anthropic's OAuth
+/// surface is scoped to that client rather than arbitrary API callers.
+pub(super) const CLAUDE_CODE_UA: &str = "claude-cli/1.0.0 (external, cli)";
+pub(super) const USAGE_URL: &str = "https://api.anthropic.com/api/oauth/usage";
 
 pub fn default_provider() -> ProviderDefault {
     ProviderDefault {
@@ -54,9 +78,598 @@ impl Provider for ClaudeCodeProvider {
 
     async fn call(
         &self,
-        _req: ProxyRequest,
-        _ctx: CallContext,
+        req: ProxyRequest,
+        ctx: CallContext,
     ) -> Result<ProxyResponse, UpstreamPassthroughError> {
-        Err(not_implemented(PROVIDER_NAME))
+        dispatch_request(self, req, ctx).await
+    }
+}
+
+#[async_trait]
+impl DispatchProvider for ClaudeCodeProvider {
+    fn name(&self) -> &str {
+        PROVIDER_NAME
+    }
+
+    fn dispatch_plan(&self, req: ProxyRequest) -> DispatchPlan {
+        match req {
+            ProxyRequest::ClaudeMessages(request) => DispatchPlan::Native {
+                req: ProxyRequest::ClaudeMessages(request),
+                usage: UsageKind::ClaudeMessage,
+            },
+            ProxyRequest::ClaudeMessagesStream(request) => DispatchPlan::Native {
+                req: ProxyRequest::ClaudeMessagesStream(request),
+                usage: UsageKind::ClaudeMessage,
+            },
+            ProxyRequest::ClaudeCountTokens(request) => DispatchPlan::Native {
+                req: ProxyRequest::ClaudeCountTokens(request),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::ClaudeModelsList(request) => DispatchPlan::Native {
+                req: ProxyRequest::ClaudeModelsList(request),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::ClaudeModelsGet(request) => DispatchPlan::Native {
+                req: ProxyRequest::ClaudeModelsGet(request),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::GeminiGenerate { request, .. } => DispatchPlan::Transform {
+                plan: TransformPlan::GenerateContent(GenerateContentPlan::Gemini2Claude(request)),
+                usage: UsageKind::GeminiGenerate,
+            },
+            ProxyRequest::GeminiGenerateStream { request, .. } => DispatchPlan::Transform {
+                plan: TransformPlan::StreamContent(StreamContentPlan::Gemini2Claude(request)),
+                usage: UsageKind::GeminiGenerate,
+            },
+            ProxyRequest::GeminiCountTokens { request, .. } => DispatchPlan::Transform {
+                plan: TransformPlan::CountTokens(CountTokensPlan::Gemini2Claude(request)),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::GeminiModelsList { request, .. } => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsList(ModelsListPlan::Gemini2Claude(request)),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::GeminiModelsGet { request, .. } => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsGet(ModelsGetPlan::Gemini2Claude(request)),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::OpenAIResponses(request) => DispatchPlan::Transform {
+                plan: TransformPlan::GenerateContent(GenerateContentPlan::OpenAIResponses2Claude(
+                    request,
+                )),
+                usage: UsageKind::OpenAIResponses,
+            },
+            ProxyRequest::OpenAIResponsesStream(request) => DispatchPlan::Transform {
+                plan: TransformPlan::StreamContent(StreamContentPlan::OpenAIResponses2Claude(
+                    request,
+                )),
+                usage: UsageKind::OpenAIResponses,
+            },
+            ProxyRequest::OpenAIInputTokens(request) => DispatchPlan::Transform {
+                plan: TransformPlan::CountTokens(CountTokensPlan::OpenAIInputTokens2Claude(
+                    request,
+                )),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::OpenAIModelsList(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsList(ModelsListPlan::OpenAI2Claude(request)),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::OpenAIModelsGet(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsGet(ModelsGetPlan::OpenAI2Claude(request)),
+                usage: UsageKind::None,
+            },
+            // This provider speaks the native Anthropic Messages API only;
+            // there's no transform target for plain OpenAI chat-completion
+            // traffic. `call_native`'s wildcard arm reports that.
+            req @ (ProxyRequest::OpenAIChat(_) | ProxyRequest::OpenAIChatStream(_)) => {
+                DispatchPlan::Native {
+                    req,
+                    usage: UsageKind::None,
+                }
+            }
+            req @ ProxyRequest::VertexRawPredict { .. } => DispatchPlan::Native {
+                req,
+                usage: UsageKind::None,
+            },
+        }
+    }
+
+    async fn call_native(
+        &self,
+        req: ProxyRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        match req {
+            ProxyRequest::ClaudeMessages(request) => {
+                self.handle_messages(request, false, ctx).await
+            }
+            ProxyRequest::ClaudeMessagesStream(request) => {
+                self.handle_messages(request, true, ctx).await
+            }
+            ProxyRequest::ClaudeCountTokens(request) => {
+                self.handle_count_tokens(request, ctx).await
+            }
+            ProxyRequest::ClaudeModelsList(request) => self.handle_models_list(request, ctx).await,
+            ProxyRequest::ClaudeModelsGet(request) => self.handle_models_get(request, ctx).await,
+            _ => Err(UpstreamPassthroughError::service_unavailable(
+                "non-native operation".to_string(),
+            )),
+        }
+    }
+}
+
+impl ClaudeCodeProvider {
+    async fn handle_messages(
+        &self,
+        request: gproxy_protocol::claude::create_message::request::CreateMessageRequest,
+        is_stream: bool,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.model.clone();
+        let scope = DisallowScope::model(model.clone());
+        let op = if is_stream {
+            "claudecode.messages_stream"
+        } else {
+            "claudecode.messages"
+        };
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let request = request.clone();
+                async move {
+                    let tokens =
+                        ensure_tokens(&self.pool, credential.value(), &ctx, &scope).await?;
+                    let mut access_token = tokens.access_token.clone();
+                    let refresh_token = tokens
+                        .refresh_token
+                        .clone()
+                        .or_else(|| credential_refresh_token(credential.value()));
+                    let base_url = credential_base_url(credential.value());
+                    let path = "/v1/messages".to_string();
+                    let url = build_url(base_url.as_deref(), &path);
+                    let body = serde_json::to_value(&request).unwrap_or_else(|_| json!({}));
+                    let request_body = json_body_to_string(&body);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let mut req_headers = build_claudecode_headers(&access_token)?;
+                    let request_headers = headers_to_json(&req_headers);
+
+                    let mut response = send_with_logging(
+                        &ctx,
+                        PROVIDER_NAME,
+                        op,
+                        "POST",
+                        &path,
+                        Some(&body),
+                        is_stream,
+                        &scope,
+                        || {
+                            client
+                                .post(&url)
+                                .headers(req_headers.clone())
+                                .json(&body)
+                                .send()
+                        },
+                    )
+                    .await?;
+
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        || response.status() == StatusCode::FORBIDDEN
+                    {
+                        if let Some(refresh_token) = refresh_token {
+                            let refreshed = refresh_access_token(
+                                credential.value().id,
+                                refresh_token,
+                                &ctx,
+                                &scope,
+                            )
+                            .await?;
+                            access_token = refreshed.access_token;
+                            req_headers = build_claudecode_headers(&access_token)?;
+                            response = send_with_logging(
+                                &ctx,
+                                PROVIDER_NAME,
+                                op,
+                                "POST",
+                                &path,
+                                Some(&body),
+                                is_stream,
+                                &scope,
+                                || {
+                                    client
+                                        .post(&url)
+                                        .headers(req_headers.clone())
+                                        .json(&body)
+                                        .send()
+                                },
+                            )
+                            .await?;
+                        }
+                    }
+
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: op.to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response = handle_response(
+                        response,
+                        is_stream,
+                        scope.clone(),
+                        &ctx,
+                        Some(meta.clone()),
+                    )
+                    .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    async fn handle_count_tokens(
+        &self,
+        request: gproxy_protocol::claude::count_tokens::request::CountTokensRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.model.clone();
+        let scope = DisallowScope::model(model.clone());
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let request = request.clone();
+                async move {
+                    let tokens =
+                        ensure_tokens(&self.pool, credential.value(), &ctx, &scope).await?;
+                    let mut access_token = tokens.access_token.clone();
+                    let refresh_token = tokens
+                        .refresh_token
+                        .clone()
+                        .or_else(|| credential_refresh_token(credential.value()));
+                    let base_url = credential_base_url(credential.value());
+                    let path = "/v1/messages/count_tokens".to_string();
+                    let url = build_url(base_url.as_deref(), &path);
+                    let body = serde_json::to_value(&request).unwrap_or_else(|_| json!({}));
+                    let request_body = json_body_to_string(&body);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let mut req_headers = build_claudecode_headers(&access_token)?;
+                    let request_headers = headers_to_json(&req_headers);
+
+                    let mut response = send_with_logging(
+                        &ctx,
+                        PROVIDER_NAME,
+                        "claudecode.count_tokens",
+                        "POST",
+                        &path,
+                        Some(&body),
+                        false,
+                        &scope,
+                        || {
+                            client
+                                .post(&url)
+                                .headers(req_headers.clone())
+                                .json(&body)
+                                .send()
+                        },
+                    )
+                    .await?;
+
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        || response.status() == StatusCode::FORBIDDEN
+                    {
+                        if let Some(refresh_token) = refresh_token {
+                            let refreshed = refresh_access_token(
+                                credential.value().id,
+                                refresh_token,
+                                &ctx,
+                                &scope,
+                            )
+                            .await?;
+                            access_token = refreshed.access_token;
+                            req_headers = build_claudecode_headers(&access_token)?;
+                            response = send_with_logging(
+                                &ctx,
+                                PROVIDER_NAME,
+                                "claudecode.count_tokens",
+                                "POST",
+                                &path,
+                                Some(&body),
+                                false,
+                                &scope,
+                                || {
+                                    client
+                                        .post(&url)
+                                        .headers(req_headers.clone())
+                                        .json(&body)
+                                        .send()
+                                },
+                            )
+                            .await?;
+                        }
+                    }
+
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "claudecode.count_tokens".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    async fn handle_models_list(
+        &self,
+        request: gproxy_protocol::claude::list_models::request::ListModelsRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let scope = DisallowScope::AllModels;
+        let query = request.query;
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let query = query.clone();
+                async move {
+                    let tokens =
+                        ensure_tokens(&self.pool, credential.value(), &ctx, &scope).await?;
+                    let mut access_token = tokens.access_token.clone();
+                    let refresh_token = tokens
+                        .refresh_token
+                        .clone()
+                        .or_else(|| credential_refresh_token(credential.value()));
+                    let base_url = credential_base_url(credential.value());
+                    let qs = serde_qs::to_string(&query).unwrap_or_default();
+                    let mut path = "/v1/models".to_string();
+                    if !qs.is_empty() {
+                        path = format!("{path}?{qs}");
+                    }
+                    let url = build_url(base_url.as_deref(), &path);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let mut req_headers = build_claudecode_headers(&access_token)?;
+                    let request_headers = headers_to_json(&req_headers);
+
+                    let mut response = send_with_logging(
+                        &ctx,
+                        PROVIDER_NAME,
+                        "claudecode.models_list",
+                        "GET",
+                        &path,
+                        None,
+                        false,
+                        &scope,
+                        || client.get(&url).headers(req_headers.clone()).send(),
+                    )
+                    .await?;
+
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        || response.status() == StatusCode::FORBIDDEN
+                    {
+                        if let Some(refresh_token) = refresh_token {
+                            let refreshed = refresh_access_token(
+                                credential.value().id,
+                                refresh_token,
+                                &ctx,
+                                &scope,
+                            )
+                            .await?;
+                            access_token = refreshed.access_token;
+                            req_headers = build_claudecode_headers(&access_token)?;
+                            response = send_with_logging(
+                                &ctx,
+                                PROVIDER_NAME,
+                                "claudecode.models_list",
+                                "GET",
+                                &path,
+                                None,
+                                false,
+                                &scope,
+                                || client.get(&url).headers(req_headers.clone()).send(),
+                            )
+                            .await?;
+                        }
+                    }
+
+                    let request_query = if qs.is_empty() { None } else { Some(qs) };
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "claudecode.models_list".to_string(),
+                        model: None,
+                        request_method: "GET".to_string(),
+                        request_path: "/v1/models".to_string(),
+                        request_query,
+                        request_headers,
+                        request_body: String::new(),
+                    };
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    async fn handle_models_get(
+        &self,
+        request: gproxy_protocol::claude::get_model::request::GetModelRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let scope = DisallowScope::AllModels;
+        let model_id = request.path.model_id;
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model_id = model_id.clone();
+                async move {
+                    let tokens =
+                        ensure_tokens(&self.pool, credential.value(), &ctx, &scope).await?;
+                    let mut access_token = tokens.access_token.clone();
+                    let refresh_token = tokens
+                        .refresh_token
+                        .clone()
+                        .or_else(|| credential_refresh_token(credential.value()));
+                    let base_url = credential_base_url(credential.value());
+                    let path = format!("/v1/models/{model_id}");
+                    let url = build_url(base_url.as_deref(), &path);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let mut req_headers = build_claudecode_headers(&access_token)?;
+                    let request_headers = headers_to_json(&req_headers);
+
+                    let mut response = send_with_logging(
+                        &ctx,
+                        PROVIDER_NAME,
+                        "claudecode.models_get",
+                        "GET",
+                        &path,
+                        None,
+                        false,
+                        &scope,
+                        || client.get(&url).headers(req_headers.clone()).send(),
+                    )
+                    .await?;
+
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        || response.status() == StatusCode::FORBIDDEN
+                    {
+                        if let Some(refresh_token) = refresh_token {
+                            let refreshed = refresh_access_token(
+                                credential.value().id,
+                                refresh_token,
+                                &ctx,
+                                &scope,
+                            )
+                            .await?;
+                            access_token = refreshed.access_token;
+                            req_headers = build_claudecode_headers(&access_token)?;
+                            response = send_with_logging(
+                                &ctx,
+                                PROVIDER_NAME,
+                                "claudecode.models_get",
+                                "GET",
+                                &path,
+                                None,
+                                false,
+                                &scope,
+                                || client.get(&url).headers(req_headers.clone()).send(),
+                            )
+                            .await?;
+                        }
+                    }
+
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "claudecode.models_get".to_string(),
+                        model: Some(model_id.clone()),
+                        request_method: "GET".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body: String::new(),
+                    };
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+}
+
+pub(super) fn credential_refresh_token(credential: &BaseCredential) -> Option<String> {
+    credential
+        .secret
+        .get("refresh_token")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn credential_base_url(credential: &BaseCredential) -> Option<String> {
+    credential
+        .meta
+        .get("base_url")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn build_url(base_url: Option<&str>, path: &str) -> String {
+    let base = base_url.unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/');
+    format!("{base}/{}", path.trim_start_matches('/'))
+}
+
+fn build_claudecode_headers(access_token: &str) -> Result<HeaderMap, AttemptFailure> {
+    let mut headers = HeaderMap::new();
+    let mut bearer = String::with_capacity(access_token.len() + 7);
+    bearer.push_str("Bearer ");
+    bearer.push_str(access_token);
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&bearer).map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?,
+    );
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert(
+        "anthropic-version",
+        HeaderValue::from_static(ANTHROPIC_VERSION),
+    );
+    headers.insert(HEADER_BETA, HeaderValue::from_static(OAUTH_BETA));
+    headers.insert(USER_AGENT, HeaderValue::from_static(CLAUDE_CODE_UA));
+    Ok(headers)
+}
+
+pub(super) fn invalid_credential(scope: &DisallowScope, message: &str) -> AttemptFailure {
+    AttemptFailure {
+        passthrough: UpstreamPassthroughError::service_unavailable(message.to_string()),
+        mark: Some(gproxy_provider_core::DisallowMark {
+            scope: scope.clone(),
+            level: gproxy_provider_core::DisallowLevel::Dead,
+            duration: None,
+            reason: Some(message.to_string()),
+        }),
     }
 }
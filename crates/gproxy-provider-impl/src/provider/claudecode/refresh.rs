@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use http::HeaderValue;
+use http::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+
+use gproxy_provider_core::{
+    AttemptFailure, CredentialPool, DisallowScope, UpstreamContext, UpstreamPassthroughError,
+};
+
+use crate::client::shared_client;
+use crate::credential::BaseCredential;
+
+use super::{credential_refresh_token, invalid_credential};
+
+/// Refresh this far ahead of the real expiry so an in-flight request never
+/// races a token that expires mid-call.
+const REFRESH_SKEW: Duration = Duration::from_secs(60);
+/// Used when a response omits `expires_in`; Anthropic's OAuth access tokens
+/// are conventionally valid for an hour.
+const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(3600);
+const TOKEN_URL: &str = "https://console.anthropic.com/v1/oauth/token";
+/// The Claude Code CLI's public OAuth client id; it has no associated
+/// secret, matching the installed-app (PKCE) flow it authenticates with.
+pub(super) const CLIENT_ID: &str = "9d1c250a-e61b-44d9-88ed-5944d1962f5e";
+
+#[derive(Clone, Debug)]
+pub(super) struct CachedTokens {
+    pub(super) access_token: String,
+    pub(super) refresh_token: Option<String>,
+    expires_at: SystemTime,
+}
+
+fn cached_tokens(
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+) -> CachedTokens {
+    CachedTokens {
+        access_token,
+        refresh_token,
+        expires_at: SystemTime::now() + expires_in.map_or(DEFAULT_TOKEN_TTL, Duration::from_secs),
+    }
+}
+
+#[derive(Serialize)]
+struct RefreshRequest {
+    client_id: &'static str,
+    grant_type: &'static str,
+    refresh_token: String,
+}
+
+#[derive(Deserialize)]
+struct RefreshResponse {
+    access_token: Option<String>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+static TOKEN_CACHE: OnceLock<tokio::sync::RwLock<HashMap<i64, CachedTokens>>> = OnceLock::new();
+
+pub(super) async fn ensure_tokens(
+    _pool: &CredentialPool<BaseCredential>,
+    credential: &BaseCredential,
+    ctx: &UpstreamContext,
+    scope: &DisallowScope,
+) -> Result<CachedTokens, AttemptFailure> {
+    if let Some(cached) = token_cache().read().await.get(&credential.id).cloned() {
+        if cached.expires_at > SystemTime::now() + REFRESH_SKEW {
+            return Ok(cached);
+        }
+    }
+    if let Some(access_token) = credential_access_token(credential) {
+        let tokens = cached_tokens(access_token, credential_refresh_token(credential), None);
+        token_cache()
+            .write()
+            .await
+            .insert(credential.id, tokens.clone());
+        return Ok(tokens);
+    }
+    if let Some(refresh_token) = credential_refresh_token(credential) {
+        return refresh_access_token(credential.id, refresh_token, ctx, scope).await;
+    }
+    Err(invalid_credential(
+        scope,
+        "missing access_token/refresh_token",
+    ))
+}
+
+fn credential_access_token(credential: &BaseCredential) -> Option<String> {
+    credential
+        .secret
+        .get("access_token")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+pub(super) async fn refresh_access_token(
+    credential_id: i64,
+    refresh_token: String,
+    ctx: &UpstreamContext,
+    scope: &DisallowScope,
+) -> Result<CachedTokens, AttemptFailure> {
+    let client = shared_client(ctx.proxy.as_deref())?;
+    let request = RefreshRequest {
+        client_id: CLIENT_ID,
+        grant_type: "refresh_token",
+        refresh_token: refresh_token.clone(),
+    };
+    let response = client
+        .post(TOKEN_URL)
+        .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+        .json(&request)
+        .send()
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let message = format!("refresh_token failed: {status}: {body}");
+        let mark = if status == http::StatusCode::UNAUTHORIZED {
+            Some(gproxy_provider_core::DisallowMark {
+                scope: scope.clone(),
+                level: gproxy_provider_core::DisallowLevel::Dead,
+                duration: None,
+                reason: Some("refresh_token_invalid".to_string()),
+            })
+        } else {
+            None
+        };
+        return Err(AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(message),
+            mark,
+        });
+    }
+    let payload = response
+        .json::<RefreshResponse>()
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?;
+    let access_token = payload.access_token.ok_or_else(|| AttemptFailure {
+        passthrough: UpstreamPassthroughError::service_unavailable(
+            "refresh_token response missing access_token".to_string(),
+        ),
+        mark: None,
+    })?;
+    let tokens = cached_tokens(
+        access_token,
+        payload.refresh_token.or(Some(refresh_token)),
+        payload.expires_in,
+    );
+    token_cache()
+        .write()
+        .await
+        .insert(credential_id, tokens.clone());
+    Ok(tokens)
+}
+
+fn token_cache() -> &'static tokio::sync::RwLock<HashMap<i64, CachedTokens>> {
+    TOKEN_CACHE.get_or_init(|| tokio::sync::RwLock::new(HashMap::new()))
+}
@@ -12,8 +12,8 @@ use crate::credential::BaseCredential;
 use crate::dispatch::UpstreamOk;
 use crate::upstream::{classify_status, send_with_logging};
 
-use super::{credential_refresh_token, PROVIDER_NAME, USAGE_URL, CLAUDE_CODE_UA, OAUTH_BETA};
 use super::refresh;
+use super::{CLAUDE_CODE_UA, OAUTH_BETA, PROVIDER_NAME, USAGE_URL, credential_refresh_token};
 
 struct UsageFetch {
     payload: JsonValue,
@@ -80,25 +80,30 @@ async fn fetch_usage_payload_with_credential(
             .await?;
             if (response.status() == StatusCode::UNAUTHORIZED
                 || response.status() == StatusCode::FORBIDDEN)
-                && let Some(refresh_token) = refresh_token {
-                    let refreshed =
-                        refresh::refresh_access_token(credential.value().id, refresh_token, &ctx, &scope)
-                            .await?;
-                    access_token = refreshed.access_token;
-                    req_headers = build_usage_headers(&access_token)?;
-                    response = send_with_logging(
-                        &ctx,
-                        PROVIDER_NAME,
-                        "claudecode.usage",
-                        "GET",
-                        "/api/oauth/usage",
-                        None,
-                        false,
-                        &scope,
-                        || client.get(USAGE_URL).headers(req_headers.clone()).send(),
-                    )
-                    .await?;
-                }
+                && let Some(refresh_token) = refresh_token
+            {
+                let refreshed = refresh::refresh_access_token(
+                    credential.value().id,
+                    refresh_token,
+                    &ctx,
+                    &scope,
+                )
+                .await?;
+                access_token = refreshed.access_token;
+                req_headers = build_usage_headers(&access_token)?;
+                response = send_with_logging(
+                    &ctx,
+                    PROVIDER_NAME,
+                    "claudecode.usage",
+                    "GET",
+                    "/api/oauth/usage",
+                    None,
+                    false,
+                    &scope,
+                    || client.get(USAGE_URL).headers(req_headers.clone()).send(),
+                )
+                .await?;
+            }
 
             let status = response.status();
             let headers = response.headers().clone();
@@ -113,12 +118,11 @@ async fn fetch_usage_payload_with_credential(
                     mark,
                 });
             }
-            let payload = serde_json::from_slice::<JsonValue>(&body).map_err(|err| {
-                AttemptFailure {
+            let payload =
+                serde_json::from_slice::<JsonValue>(&body).map_err(|err| AttemptFailure {
                     passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
                     mark: None,
-                }
-            })?;
+                })?;
             Ok(UsageFetch {
                 payload,
                 credential_id: credential.value().id,
@@ -149,9 +153,6 @@ fn build_usage_headers(access_token: &str) -> Result<HeaderMap, AttemptFailure>
         http::header::USER_AGENT,
         HeaderValue::from_static(CLAUDE_CODE_UA),
     );
-    headers.insert(
-        super::HEADER_BETA,
-        HeaderValue::from_static(OAUTH_BETA),
-    );
+    headers.insert(super::HEADER_BETA, HeaderValue::from_static(OAUTH_BETA));
     Ok(headers)
 }
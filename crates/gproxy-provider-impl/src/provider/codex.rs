@@ -8,8 +8,20 @@ use gproxy_provider_core::{
 };
 
 use crate::provider::not_implemented;
+use crate::ProviderDefault;
+
+pub mod instructions;
 
 pub const PROVIDER_NAME: &str = "codex";
+const DEFAULT_BASE_URL: &str = "https://api.openai.com";
+
+pub fn default_provider() -> ProviderDefault {
+    ProviderDefault {
+        name: PROVIDER_NAME,
+        config_json: serde_json::json!({ "base_url": DEFAULT_BASE_URL }),
+        enabled: true,
+    }
+}
 
 #[derive(Debug)]
 pub struct CodexProvider {
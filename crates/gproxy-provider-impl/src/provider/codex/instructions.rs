@@ -1,3 +1,37 @@
+//! Model-to-instruction resolution for the Codex provider, driven by a
+//! configurable [`InstructionTable`] instead of a hardcoded `if/else` chain.
+//!
+//! The table is an ordered list of [`InstructionRule`]s (`model_glob`,
+//! `template_id`, optional `personality`) plus a set of named
+//! [`InstructionTemplate`]s with `{{ personality }}`-style placeholders.
+//! [`seed_defaults`] builds the table this module used to hardcode, the
+//! same way `default_providers()` seeds provider config at startup, so
+//! behavior is unchanged until an operator overrides it.
+//!
+//! This module owns the table and its hot-swappable storage (a
+//! `RwLock<Arc<InstructionTable>>`, the same shape `CredentialPool` uses for
+//! its snapshot), so [`set_table`] takes effect on the very next
+//! `resolve_instructions` call. [`InstructionRule`]/[`InstructionTemplate`]
+//! stay plain `Serialize`/`Deserialize` data so they don't need to know
+//! about storage: `gproxy-storage`'s `instruction_templates`/
+//! `instruction_rules` entities and `TrafficStore::list_instruction_*`/
+//! `upsert_instruction_*`/`delete_instruction_*` persist them as rows, and
+//! `apps/gproxy/src/instructions_store.rs` converts rows to/from this
+//! module's types and calls [`set_table`] after every
+//! `/admin/instructions/*` mutation — the same shape `apply_snapshot` calls
+//! `registry.apply_pools` today, just invoked directly from the CRUD
+//! handlers instead of riding `ConfigEvent`, since there's no file on disk
+//! for a reload endpoint to re-read.
+//!
+//! Also worth noting: nothing in this provider currently calls
+//! `resolve_instructions`/`instructions_for_model` — `CodexProvider::call`
+//! (see `provider/codex.rs`) isn't implemented yet, so this module isn't
+//! reachable from a request today either. It's written ready for that
+//! wiring rather than guessing at it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
 const BASE_INSTRUCTIONS: &str = include_str!("instructions/prompt.md");
 const BASE_INSTRUCTIONS_WITH_APPLY_PATCH: &str =
     include_str!("instructions/prompt_with_apply_patch_instructions.md");
@@ -19,12 +53,25 @@ const GPT_5_2_CODEX_PERSONALITY_PRAGMATIC: &str =
 
 const PERSONALITY_PLACEHOLDER: &str = "{{ personality }}";
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The base template every rule falls back to when nothing else matches.
+const BASE_TEMPLATE_ID: &str = "base";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CodexPersonality {
     Friendly,
     Pragmatic,
 }
 
+impl CodexPersonality {
+    fn placeholder_text(self) -> &'static str {
+        match self {
+            CodexPersonality::Friendly => GPT_5_2_CODEX_PERSONALITY_FRIENDLY,
+            CodexPersonality::Pragmatic => GPT_5_2_CODEX_PERSONALITY_PRAGMATIC,
+        }
+    }
+}
+
 pub fn parse_personality(value: &str) -> Option<CodexPersonality> {
     match value.trim().to_ascii_lowercase().as_str() {
         "friendly" => Some(CodexPersonality::Friendly),
@@ -33,51 +80,243 @@ pub fn parse_personality(value: &str) -> Option<CodexPersonality> {
     }
 }
 
-pub fn instructions_for_model(model: &str, personality: Option<CodexPersonality>) -> String {
-    if model.starts_with("o3") || model.starts_with("o4-mini") {
-        BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string()
-    } else if model.starts_with("codex-mini-latest") {
-        BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string()
-    } else if model.starts_with("gpt-4.1") {
-        BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string()
-    } else if model.starts_with("gpt-4o") {
-        BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string()
-    } else if model.starts_with("gpt-3.5") {
-        BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string()
-    } else if model.starts_with("test-gpt-5") {
-        GPT_5_CODEX_INSTRUCTIONS.to_string()
-    } else if model.starts_with("gpt-5.2-codex") || model.starts_with("bengalfox") {
-        let personality_text = match personality {
-            Some(CodexPersonality::Friendly) => GPT_5_2_CODEX_PERSONALITY_FRIENDLY,
-            Some(CodexPersonality::Pragmatic) => GPT_5_2_CODEX_PERSONALITY_PRAGMATIC,
-            None => "",
-        };
-        let rendered = GPT_5_2_CODEX_TEMPLATE.replace(PERSONALITY_PLACEHOLDER, personality_text);
-        if rendered.trim().is_empty() {
-            GPT_5_2_CODEX_INSTRUCTIONS.to_string()
-        } else {
-            rendered
+/// One named instruction body, addressed by [`InstructionRule::template_id`].
+/// May contain the `{{ personality }}` placeholder; other `{{ name }}`
+/// placeholders are left untouched by [`InstructionTable::resolve`] today
+/// (personality is the only one the built-in templates use), but the
+/// storage shape doesn't assume that won't grow.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InstructionTemplate {
+    pub id: String,
+    pub body: String,
+}
+
+/// One rule in the table: if `model_glob` matches (trailing `*` = prefix
+/// match, exact string otherwise), render `template_id` with `personality`
+/// (falling back to the caller-supplied personality when this rule doesn't
+/// pin one) and stop.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InstructionRule {
+    pub model_glob: String,
+    pub template_id: String,
+    #[serde(default)]
+    pub personality: Option<CodexPersonality>,
+}
+
+fn glob_matches(pattern: &str, model: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => model.starts_with(prefix),
+        None => pattern == model,
+    }
+}
+
+/// An ordered rule list plus the named templates they reference. Rules are
+/// tried first-match-wins in table order, so more specific globs must be
+/// listed before broader ones — [`seed_defaults`] preserves the exact
+/// ordering the old `if/else` chain used.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InstructionTable {
+    pub rules: Vec<InstructionRule>,
+    pub templates: Vec<InstructionTemplate>,
+}
+
+impl InstructionTable {
+    /// Renders the instructions for `model`, using `personality` wherever a
+    /// matched rule doesn't pin its own. Falls back to the `"base"`
+    /// template (or an empty string if even that's missing from the
+    /// table, which only a hand-edited table could manage) when no rule
+    /// matches.
+    pub fn resolve(&self, model: &str, personality: Option<CodexPersonality>) -> String {
+        let templates: HashMap<&str, &str> = self
+            .templates
+            .iter()
+            .map(|t| (t.id.as_str(), t.body.as_str()))
+            .collect();
+
+        for rule in &self.rules {
+            if !glob_matches(&rule.model_glob, model) {
+                continue;
+            }
+            let Some(body) = templates.get(rule.template_id.as_str()) else {
+                continue;
+            };
+            let effective_personality = rule.personality.or(personality);
+            return render(body, effective_personality);
+        }
+
+        match templates.get(BASE_TEMPLATE_ID) {
+            Some(body) => render(body, personality),
+            None => String::new(),
         }
-    } else if model.starts_with("gpt-5.1-codex-max") {
-        GPT_5_1_CODEX_MAX_INSTRUCTIONS.to_string()
-    } else if (model.starts_with("gpt-5-codex")
-        || model.starts_with("gpt-5.1-codex")
-        || model.starts_with("codex-"))
-        && !model.contains("-mini")
-    {
-        GPT_5_CODEX_INSTRUCTIONS.to_string()
-    } else if model.starts_with("gpt-5-codex")
-        || model.starts_with("gpt-5.1-codex")
-        || model.starts_with("codex-")
-    {
-        GPT_5_CODEX_INSTRUCTIONS.to_string()
-    } else if model.starts_with("gpt-5.2") || model.starts_with("boomslang") {
-        GPT_5_2_INSTRUCTIONS.to_string()
-    } else if model.starts_with("gpt-5.1") {
-        GPT_5_1_INSTRUCTIONS.to_string()
-    } else if model.starts_with("gpt-5") {
-        BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string()
+    }
+}
+
+fn render(body: &str, personality: Option<CodexPersonality>) -> String {
+    if !body.contains(PERSONALITY_PLACEHOLDER) {
+        return body.to_string();
+    }
+    let personality_text = personality.map(CodexPersonality::placeholder_text).unwrap_or("");
+    let rendered = body.replace(PERSONALITY_PLACEHOLDER, personality_text);
+    if rendered.trim().is_empty() {
+        body.to_string()
     } else {
-        BASE_INSTRUCTIONS.to_string()
+        rendered
     }
 }
+
+/// Builds the table this module used to hardcode as an `if/else` chain,
+/// preserving its exact match order (most-specific first) and the
+/// `gpt-5.2-codex` personality-template special case.
+pub fn seed_defaults() -> InstructionTable {
+    let templates = vec![
+        InstructionTemplate {
+            id: BASE_TEMPLATE_ID.to_string(),
+            body: BASE_INSTRUCTIONS.to_string(),
+        },
+        InstructionTemplate {
+            id: "base_with_apply_patch".to_string(),
+            body: BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string(),
+        },
+        InstructionTemplate {
+            id: "gpt_5_codex".to_string(),
+            body: GPT_5_CODEX_INSTRUCTIONS.to_string(),
+        },
+        InstructionTemplate {
+            id: "gpt_5_1".to_string(),
+            body: GPT_5_1_INSTRUCTIONS.to_string(),
+        },
+        InstructionTemplate {
+            id: "gpt_5_2".to_string(),
+            body: GPT_5_2_INSTRUCTIONS.to_string(),
+        },
+        InstructionTemplate {
+            id: "gpt_5_1_codex_max".to_string(),
+            body: GPT_5_1_CODEX_MAX_INSTRUCTIONS.to_string(),
+        },
+        // The templated variant renders personality text into the
+        // placeholder; `resolve`'s fallback-to-literal-body behavior in
+        // `render` reproduces the old "empty personality falls back to the
+        // plain gpt-5.2-codex prompt" special case without needing a
+        // separate rule for it.
+        InstructionTemplate {
+            id: "gpt_5_2_codex".to_string(),
+            body: GPT_5_2_CODEX_TEMPLATE.to_string(),
+        },
+    ];
+
+    let rules = vec![
+        InstructionRule {
+            model_glob: "o3*".to_string(),
+            template_id: "base_with_apply_patch".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "o4-mini*".to_string(),
+            template_id: "base_with_apply_patch".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "codex-mini-latest*".to_string(),
+            template_id: "base_with_apply_patch".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-4.1*".to_string(),
+            template_id: "base_with_apply_patch".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-4o*".to_string(),
+            template_id: "base_with_apply_patch".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-3.5*".to_string(),
+            template_id: "base_with_apply_patch".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "test-gpt-5*".to_string(),
+            template_id: "gpt_5_codex".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-5.2-codex*".to_string(),
+            template_id: "gpt_5_2_codex".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "bengalfox*".to_string(),
+            template_id: "gpt_5_2_codex".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-5.1-codex-max*".to_string(),
+            template_id: "gpt_5_1_codex_max".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-5-codex*".to_string(),
+            template_id: "gpt_5_codex".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-5.1-codex*".to_string(),
+            template_id: "gpt_5_codex".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "codex-*".to_string(),
+            template_id: "gpt_5_codex".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-5.2*".to_string(),
+            template_id: "gpt_5_2".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "boomslang*".to_string(),
+            template_id: "gpt_5_2".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-5.1*".to_string(),
+            template_id: "gpt_5_1".to_string(),
+            personality: None,
+        },
+        InstructionRule {
+            model_glob: "gpt-5*".to_string(),
+            template_id: "base_with_apply_patch".to_string(),
+            personality: None,
+        },
+    ];
+
+    InstructionTable { rules, templates }
+}
+
+static CURRENT_TABLE: OnceLock<RwLock<Arc<InstructionTable>>> = OnceLock::new();
+
+fn table_cell() -> &'static RwLock<Arc<InstructionTable>> {
+    CURRENT_TABLE.get_or_init(|| RwLock::new(Arc::new(seed_defaults())))
+}
+
+/// The live table, hot-swapped by [`set_table`]. Every `resolve_instructions`
+/// call reads through this rather than capturing a table at startup.
+pub fn current_table() -> Arc<InstructionTable> {
+    table_cell().read().expect("poisoned instruction table lock").clone()
+}
+
+/// Atomically swaps in a new table — the hot-reload seam a storage-backed
+/// admin CRUD surface would call once rules/templates are persisted rows.
+pub fn set_table(table: InstructionTable) {
+    *table_cell().write().expect("poisoned instruction table lock") = Arc::new(table);
+}
+
+/// Resolves instructions for `model` against the current live table. This
+/// is the replacement for the old hardcoded `instructions_for_model`
+/// function, kept under that name so existing call sites (once wired, per
+/// the module doc) don't need to change.
+pub fn instructions_for_model(model: &str, personality: Option<CodexPersonality>) -> String {
+    current_table().resolve(model, personality)
+}
@@ -6,11 +6,29 @@ use tokenizers::Tokenizer;
 
 use gproxy_provider_core::{AttemptFailure, UpstreamPassthroughError};
 
+use crate::client::shared_client;
+
+/// Maps a model-name substring to the tokenizer slug (the directory under
+/// `data/cache/tokenizers/`) and the Hugging Face hub repo its
+/// `tokenizer.json` is fetched from when it isn't cached on disk yet.
+/// Matched in order, so list more specific substrings first.
+const TOKENIZER_REGISTRY: &[(&str, &str, &str)] = &[
+    ("deepseek", "deepseek", "deepseek-ai/DeepSeek-V3"),
+    ("qwen", "qwen", "Qwen/Qwen2.5-72B-Instruct"),
+    ("llama", "llama", "meta-llama/Llama-3.1-8B-Instruct"),
+];
+
+/// Used when a model name matches nothing in the registry, so token counts
+/// are still approximately right rather than the request failing outright.
+const FALLBACK_SLUG: &str = "deepseek";
+const FALLBACK_HF_REPO: &str = "deepseek-ai/DeepSeek-V3";
+
 pub async fn count_input_tokens(
     body: &gproxy_protocol::openai::count_tokens::request::InputTokenCountRequestBody,
     data_dir: Option<&str>,
 ) -> Result<i64, AttemptFailure> {
-    let tokenizer = load_tokenizer(data_dir).await?;
+    let (slug, hf_repo) = resolve_tokenizer(&body.model);
+    let tokenizer = load_tokenizer(data_dir, slug, hf_repo).await?;
     let mut value = serde_json::to_value(body).map_err(|err| AttemptFailure {
         passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
         mark: None,
@@ -22,19 +40,38 @@ pub async fn count_input_tokens(
         passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
         mark: None,
     })?;
-    let encoding = tokenizer.encode(text, false).map_err(|err| AttemptFailure {
-        passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
-        mark: None,
-    })?;
+    let encoding = tokenizer
+        .encode(text, false)
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?;
     Ok(encoding.get_ids().len() as i64)
 }
 
-async fn load_tokenizer(data_dir: Option<&str>) -> Result<Arc<Tokenizer>, AttemptFailure> {
+/// Resolves a requested model name to the `(slug, hf_repo)` pair that serves
+/// it, falling back to the DeepSeek tokenizer for unrecognized models.
+fn resolve_tokenizer(model: &str) -> (&'static str, &'static str) {
+    let lower = model.to_lowercase();
+    TOKENIZER_REGISTRY
+        .iter()
+        .find(|(needle, _, _)| lower.contains(needle))
+        .map(|(_, slug, hf_repo)| (*slug, *hf_repo))
+        .unwrap_or((FALLBACK_SLUG, FALLBACK_HF_REPO))
+}
+
+async fn load_tokenizer(
+    data_dir: Option<&str>,
+    slug: &str,
+    hf_repo: &str,
+) -> Result<Arc<Tokenizer>, AttemptFailure> {
     let cache = tokenizer_cache();
-    let key = tokenizer_key(data_dir);
+    let key = tokenizer_key(data_dir, slug);
     {
         let guard = cache.lock().map_err(|_| AttemptFailure {
-            passthrough: UpstreamPassthroughError::service_unavailable("tokenizer lock failed".to_string()),
+            passthrough: UpstreamPassthroughError::service_unavailable(
+                "tokenizer lock failed".to_string(),
+            ),
             mark: None,
         })?;
         if let Some(tokenizer) = guard.get(&key) {
@@ -42,38 +79,88 @@ async fn load_tokenizer(data_dir: Option<&str>) -> Result<Arc<Tokenizer>, Attemp
         }
     }
 
-    let path = tokenizer_path(data_dir);
-    let bytes = tokio::fs::read(&path).await.map_err(|err| AttemptFailure {
-        passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
-        mark: None,
-    })?;
+    let path = tokenizer_path(data_dir, slug);
+    let bytes = match tokio::fs::read(&path).await {
+        Ok(bytes) => bytes,
+        Err(_) => fetch_tokenizer(&path, hf_repo).await?,
+    };
     let tokenizer = Tokenizer::from_bytes(bytes.as_slice()).map_err(|err| AttemptFailure {
         passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
         mark: None,
     })?;
     let tokenizer = Arc::new(tokenizer);
     let mut guard = cache.lock().map_err(|_| AttemptFailure {
-        passthrough: UpstreamPassthroughError::service_unavailable("tokenizer lock failed".to_string()),
+        passthrough: UpstreamPassthroughError::service_unavailable(
+            "tokenizer lock failed".to_string(),
+        ),
         mark: None,
     })?;
     guard.insert(key, tokenizer.clone());
     Ok(tokenizer)
 }
 
-fn tokenizer_cache() -> &'static Mutex<HashMap<String, Arc<Tokenizer>>> {
-    static CACHE: OnceLock<Mutex<HashMap<String, Arc<Tokenizer>>>> = OnceLock::new();
+/// Downloads `tokenizer.json` from the hub repo and caches it at `path` for
+/// next time. Any failure here is returned verbatim rather than silently
+/// falling through to some other vocabulary.
+async fn fetch_tokenizer(path: &Path, hf_repo: &str) -> Result<Vec<u8>, AttemptFailure> {
+    let url = format!("https://huggingface.co/{hf_repo}/resolve/main/tokenizer.json");
+    let client = shared_client(None)?;
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(format!(
+                "failed to fetch tokenizer for {hf_repo}: {err}"
+            )),
+            mark: None,
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(format!(
+                "failed to fetch tokenizer for {hf_repo}: {status}"
+            )),
+            mark: None,
+        });
+    }
+    let bytes = response.bytes().await.map_err(|err| AttemptFailure {
+        passthrough: UpstreamPassthroughError::service_unavailable(format!(
+            "failed to read tokenizer body for {hf_repo}: {err}"
+        )),
+        mark: None,
+    })?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|err| AttemptFailure {
+                passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+                mark: None,
+            })?;
+    }
+    tokio::fs::write(path, &bytes)
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?;
+    Ok(bytes.to_vec())
+}
+
+fn tokenizer_cache() -> &'static Mutex<HashMap<(String, String), Arc<Tokenizer>>> {
+    static CACHE: OnceLock<Mutex<HashMap<(String, String), Arc<Tokenizer>>>> = OnceLock::new();
     CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-fn tokenizer_key(data_dir: Option<&str>) -> String {
+fn tokenizer_key(data_dir: Option<&str>, slug: &str) -> (String, String) {
     let base = data_dir
         .map(|value| value.trim())
         .filter(|value| !value.is_empty())
         .unwrap_or("./data");
-    base.to_string()
+    (base.to_string(), slug.to_string())
 }
 
-fn tokenizer_path(data_dir: Option<&str>) -> PathBuf {
+fn tokenizer_path(data_dir: Option<&str>, slug: &str) -> PathBuf {
     let base = data_dir
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty())
@@ -81,6 +168,6 @@ fn tokenizer_path(data_dir: Option<&str>) -> PathBuf {
     Path::new(&base)
         .join("cache")
         .join("tokenizers")
-        .join("deepseek")
+        .join(slug)
         .join("tokenizer.json")
 }
@@ -1,16 +1,30 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use serde_json::json;
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use http::{HeaderMap, HeaderValue, StatusCode};
+use serde::Deserialize;
+use serde_json::{Value as JsonValue, json};
 
+use gproxy_protocol::gemini;
 use gproxy_provider_core::{
-    CredentialPool, DownstreamContext, PoolSnapshot, Provider, ProxyRequest, ProxyResponse,
-    StateSink, UpstreamPassthroughError,
+    AttemptFailure, CallContext, CredentialPool, DisallowScope, PoolSnapshot, Provider,
+    ProxyRequest, ProxyResponse, StateSink, UpstreamPassthroughError, UpstreamRecordMeta,
 };
 
-use crate::credential::BaseCredential;
 use crate::ProviderDefault;
-use crate::provider::not_implemented;
+use crate::client::shared_client;
+use crate::credential::BaseCredential;
+use crate::dispatch::{
+    CountTokensPlan, DispatchPlan, DispatchProvider, GenerateContentPlan, ModelsGetPlan,
+    ModelsListPlan, StreamContentPlan, TransformPlan, UpstreamOk, UsageKind, dispatch_request,
+};
+use crate::record::{headers_to_json, json_body_to_string};
+use crate::upstream::{handle_response, send_with_logging};
+
+mod refresh;
+
+use refresh::{ensure_tokens, refresh_access_token};
 
 pub const PROVIDER_NAME: &str = "geminicli";
 const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com";
@@ -54,9 +68,763 @@ impl Provider for GeminiCliProvider {
 
     async fn call(
         &self,
-        _req: ProxyRequest,
-        _ctx: DownstreamContext,
+        req: ProxyRequest,
+        ctx: CallContext,
     ) -> Result<ProxyResponse, UpstreamPassthroughError> {
-        Err(not_implemented(PROVIDER_NAME))
+        dispatch_request(self, req, ctx).await
+    }
+}
+
+#[async_trait]
+impl DispatchProvider for GeminiCliProvider {
+    fn name(&self) -> &str {
+        PROVIDER_NAME
+    }
+
+    fn dispatch_plan(&self, req: ProxyRequest) -> DispatchPlan {
+        match req {
+            ProxyRequest::GeminiGenerate { version, request } => DispatchPlan::Native {
+                req: ProxyRequest::GeminiGenerate { version, request },
+                usage: UsageKind::GeminiGenerate,
+            },
+            ProxyRequest::GeminiGenerateStream { version, request } => DispatchPlan::Native {
+                req: ProxyRequest::GeminiGenerateStream { version, request },
+                usage: UsageKind::GeminiGenerate,
+            },
+            ProxyRequest::GeminiCountTokens { version, request } => DispatchPlan::Native {
+                req: ProxyRequest::GeminiCountTokens { version, request },
+                usage: UsageKind::None,
+            },
+            ProxyRequest::GeminiModelsList { version, request } => DispatchPlan::Native {
+                req: ProxyRequest::GeminiModelsList { version, request },
+                usage: UsageKind::None,
+            },
+            ProxyRequest::GeminiModelsGet { version, request } => DispatchPlan::Native {
+                req: ProxyRequest::GeminiModelsGet { version, request },
+                usage: UsageKind::None,
+            },
+            ProxyRequest::OpenAIResponses(request) => DispatchPlan::Transform {
+                plan: TransformPlan::GenerateContent(GenerateContentPlan::OpenAIResponses2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::OpenAIResponses,
+            },
+            ProxyRequest::OpenAIChat(request) => DispatchPlan::Transform {
+                plan: TransformPlan::GenerateContent(GenerateContentPlan::OpenAIChat2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::OpenAIChat,
+            },
+            ProxyRequest::OpenAIResponsesStream(request) => DispatchPlan::Transform {
+                plan: TransformPlan::StreamContent(StreamContentPlan::OpenAIResponses2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::OpenAIResponses,
+            },
+            ProxyRequest::OpenAIChatStream(request) => DispatchPlan::Transform {
+                plan: TransformPlan::StreamContent(StreamContentPlan::OpenAIChat2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::OpenAIChat,
+            },
+            ProxyRequest::OpenAIInputTokens(request) => DispatchPlan::Transform {
+                plan: TransformPlan::CountTokens(CountTokensPlan::OpenAIInputTokens2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::OpenAIModelsList(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsList(ModelsListPlan::OpenAI2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::OpenAIModelsGet(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsGet(ModelsGetPlan::OpenAI2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::ClaudeMessages(request) => DispatchPlan::Transform {
+                plan: TransformPlan::GenerateContent(GenerateContentPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::ClaudeMessage,
+            },
+            ProxyRequest::ClaudeMessagesStream(request) => DispatchPlan::Transform {
+                plan: TransformPlan::StreamContent(StreamContentPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::ClaudeMessage,
+            },
+            ProxyRequest::ClaudeCountTokens(request) => DispatchPlan::Transform {
+                plan: TransformPlan::CountTokens(CountTokensPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::ClaudeModelsList(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsList(ModelsListPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::ClaudeModelsGet(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsGet(ModelsGetPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            // This provider authenticates against the public Gemini API
+            // surface via OAuth, not Vertex; there's no rawPredict endpoint
+            // to forward this to. `call_native`'s wildcard arm reports that.
+            req @ ProxyRequest::VertexRawPredict { .. } => DispatchPlan::Native {
+                req,
+                usage: UsageKind::None,
+            },
+        }
+    }
+
+    async fn call_native(
+        &self,
+        req: ProxyRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        match req {
+            ProxyRequest::GeminiGenerate { version, request } => {
+                self.handle_generate(version, request, false, ctx).await
+            }
+            ProxyRequest::GeminiGenerateStream { version, request } => {
+                self.handle_generate(version, request, true, ctx).await
+            }
+            ProxyRequest::GeminiCountTokens { version, request } => {
+                self.handle_count_tokens(version, request, ctx).await
+            }
+            ProxyRequest::GeminiModelsList { version, request } => {
+                self.handle_models_list(version, request, ctx).await
+            }
+            ProxyRequest::GeminiModelsGet { version, request } => {
+                self.handle_models_get(version, request, ctx).await
+            }
+            _ => Err(UpstreamPassthroughError::service_unavailable(
+                "non-native operation".to_string(),
+            )),
+        }
+    }
+}
+
+impl GeminiCliProvider {
+    async fn handle_generate(
+        &self,
+        version: gproxy_provider_core::GeminiApiVersion,
+        request: gemini::generate_content::request::GenerateContentRequest,
+        is_stream: bool,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.path.model.clone();
+        let scope = DisallowScope::model(model.clone());
+        let body = request.body;
+        let op = if is_stream {
+            "gemini.stream_generate"
+        } else {
+            "gemini.generate"
+        };
+        let operation = if is_stream {
+            "streamGenerateContent"
+        } else {
+            "generateContent"
+        };
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let body = body.clone();
+                async move {
+                    let tokens = ensure_tokens(credential.value(), &ctx, &scope).await?;
+                    let mut access_token = tokens.access_token.clone();
+                    let base_url = credential_base_url_for_model(credential.value(), &model);
+                    let version_prefix = version_prefix(version);
+                    let (path, url) = build_generate_endpoint(
+                        base_url.as_deref(),
+                        version_prefix,
+                        &model,
+                        operation,
+                    );
+                    let mut body = serde_json::to_value(&body).unwrap_or_else(|_| json!({}));
+                    inject_default_safety_settings(
+                        &mut body,
+                        credential_safety_block_threshold(credential.value()).as_deref(),
+                    );
+                    let request_body = json_body_to_string(&body);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let mut req_headers = build_geminicli_headers(&access_token)?;
+                    let request_headers = headers_to_json(&req_headers);
+
+                    let mut response = send_with_logging(
+                        &ctx,
+                        PROVIDER_NAME,
+                        op,
+                        "POST",
+                        &path,
+                        Some(&body),
+                        is_stream,
+                        &scope,
+                        || {
+                            client
+                                .post(&url)
+                                .headers(req_headers.clone())
+                                .json(&body)
+                                .send()
+                        },
+                    )
+                    .await?;
+
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        || response.status() == StatusCode::FORBIDDEN
+                    {
+                        if let Some(refresh_token) = credential_refresh_token(credential.value()) {
+                            let refreshed = refresh_access_token(
+                                credential.value().id,
+                                refresh_token,
+                                &ctx,
+                                &scope,
+                            )
+                            .await?;
+                            access_token = refreshed.access_token;
+                            req_headers = build_geminicli_headers(&access_token)?;
+                            response = send_with_logging(
+                                &ctx,
+                                PROVIDER_NAME,
+                                op,
+                                "POST",
+                                &path,
+                                Some(&body),
+                                is_stream,
+                                &scope,
+                                || {
+                                    client
+                                        .post(&url)
+                                        .headers(req_headers.clone())
+                                        .json(&body)
+                                        .send()
+                                },
+                            )
+                            .await?;
+                        }
+                    }
+
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: op.to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response = handle_response(
+                        response,
+                        is_stream,
+                        scope.clone(),
+                        &ctx,
+                        Some(meta.clone()),
+                    )
+                    .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    async fn handle_count_tokens(
+        &self,
+        version: gproxy_provider_core::GeminiApiVersion,
+        request: gemini::count_tokens::request::CountTokensRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.path.model.clone();
+        let scope = DisallowScope::model(model.clone());
+        let body = request.body;
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let body = body.clone();
+                async move {
+                    let tokens = ensure_tokens(credential.value(), &ctx, &scope).await?;
+                    let mut access_token = tokens.access_token.clone();
+                    let base_url = credential_base_url_for_model(credential.value(), &model);
+                    let version_prefix = version_prefix(version);
+                    let (path, url) = build_generate_endpoint(
+                        base_url.as_deref(),
+                        version_prefix,
+                        &model,
+                        "countTokens",
+                    );
+                    let body = serde_json::to_value(&body).unwrap_or_else(|_| json!({}));
+                    let request_body = json_body_to_string(&body);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let mut req_headers = build_geminicli_headers(&access_token)?;
+                    let request_headers = headers_to_json(&req_headers);
+
+                    let mut response = send_with_logging(
+                        &ctx,
+                        PROVIDER_NAME,
+                        "gemini.count_tokens",
+                        "POST",
+                        &path,
+                        Some(&body),
+                        false,
+                        &scope,
+                        || {
+                            client
+                                .post(&url)
+                                .headers(req_headers.clone())
+                                .json(&body)
+                                .send()
+                        },
+                    )
+                    .await?;
+
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        || response.status() == StatusCode::FORBIDDEN
+                    {
+                        if let Some(refresh_token) = credential_refresh_token(credential.value()) {
+                            let refreshed = refresh_access_token(
+                                credential.value().id,
+                                refresh_token,
+                                &ctx,
+                                &scope,
+                            )
+                            .await?;
+                            access_token = refreshed.access_token;
+                            req_headers = build_geminicli_headers(&access_token)?;
+                            response = send_with_logging(
+                                &ctx,
+                                PROVIDER_NAME,
+                                "gemini.count_tokens",
+                                "POST",
+                                &path,
+                                Some(&body),
+                                false,
+                                &scope,
+                                || {
+                                    client
+                                        .post(&url)
+                                        .headers(req_headers.clone())
+                                        .json(&body)
+                                        .send()
+                                },
+                            )
+                            .await?;
+                        }
+                    }
+
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "gemini.count_tokens".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    async fn handle_models_list(
+        &self,
+        version: gproxy_provider_core::GeminiApiVersion,
+        request: gemini::list_models::request::ListModelsRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let scope = DisallowScope::AllModels;
+        let query = request.query;
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let query = query.clone();
+                async move {
+                    let tokens = ensure_tokens(credential.value(), &ctx, &scope).await?;
+                    let mut access_token = tokens.access_token.clone();
+                    // No single model to route by when listing the whole
+                    // catalog, so `endpoint_routes` doesn't apply here —
+                    // only the flat `base_url` override does.
+                    let base_url = credential_base_url(credential.value());
+                    let version_prefix = version_prefix(version);
+                    let qs = serde_qs::to_string(&query).unwrap_or_default();
+                    let mut path = format!("/{version_prefix}/models");
+                    if !qs.is_empty() {
+                        path = format!("{path}?{qs}");
+                    }
+                    let url = build_url(base_url.as_deref(), &path);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let mut req_headers = build_geminicli_headers(&access_token)?;
+                    let request_headers = headers_to_json(&req_headers);
+
+                    let mut response = send_with_logging(
+                        &ctx,
+                        PROVIDER_NAME,
+                        "gemini.models_list",
+                        "GET",
+                        &path,
+                        None,
+                        false,
+                        &scope,
+                        || client.get(&url).headers(req_headers.clone()).send(),
+                    )
+                    .await?;
+
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        || response.status() == StatusCode::FORBIDDEN
+                    {
+                        if let Some(refresh_token) = credential_refresh_token(credential.value()) {
+                            let refreshed = refresh_access_token(
+                                credential.value().id,
+                                refresh_token,
+                                &ctx,
+                                &scope,
+                            )
+                            .await?;
+                            access_token = refreshed.access_token;
+                            req_headers = build_geminicli_headers(&access_token)?;
+                            response = send_with_logging(
+                                &ctx,
+                                PROVIDER_NAME,
+                                "gemini.models_list",
+                                "GET",
+                                &path,
+                                None,
+                                false,
+                                &scope,
+                                || client.get(&url).headers(req_headers.clone()).send(),
+                            )
+                            .await?;
+                        }
+                    }
+
+                    let request_query = if qs.is_empty() { None } else { Some(qs) };
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "gemini.models_list".to_string(),
+                        model: None,
+                        request_method: "GET".to_string(),
+                        request_path: format!("/{version_prefix}/models"),
+                        request_query,
+                        request_headers,
+                        request_body: String::new(),
+                    };
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    async fn handle_models_get(
+        &self,
+        version: gproxy_provider_core::GeminiApiVersion,
+        request: gemini::get_model::request::GetModelRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let scope = DisallowScope::AllModels;
+        let name = request.path.name;
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let name = name.clone();
+                async move {
+                    let tokens = ensure_tokens(credential.value(), &ctx, &scope).await?;
+                    let mut access_token = tokens.access_token.clone();
+                    let base_url = credential_base_url_for_model(credential.value(), &name);
+                    let version_prefix = version_prefix(version);
+                    let path = format!("/{version_prefix}/models/{name}");
+                    let url = build_url(base_url.as_deref(), &path);
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let mut req_headers = build_geminicli_headers(&access_token)?;
+                    let request_headers = headers_to_json(&req_headers);
+
+                    let mut response = send_with_logging(
+                        &ctx,
+                        PROVIDER_NAME,
+                        "gemini.models_get",
+                        "GET",
+                        &path,
+                        None,
+                        false,
+                        &scope,
+                        || client.get(&url).headers(req_headers.clone()).send(),
+                    )
+                    .await?;
+
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        || response.status() == StatusCode::FORBIDDEN
+                    {
+                        if let Some(refresh_token) = credential_refresh_token(credential.value()) {
+                            let refreshed = refresh_access_token(
+                                credential.value().id,
+                                refresh_token,
+                                &ctx,
+                                &scope,
+                            )
+                            .await?;
+                            access_token = refreshed.access_token;
+                            req_headers = build_geminicli_headers(&access_token)?;
+                            response = send_with_logging(
+                                &ctx,
+                                PROVIDER_NAME,
+                                "gemini.models_get",
+                                "GET",
+                                &path,
+                                None,
+                                false,
+                                &scope,
+                                || client.get(&url).headers(req_headers.clone()).send(),
+                            )
+                            .await?;
+                        }
+                    }
+
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "gemini.models_get".to_string(),
+                        model: Some(name.clone()),
+                        request_method: "GET".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body: String::new(),
+                    };
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+}
+
+/// A manually-supplied static access token, if the credential carries one.
+/// Credentials backed by a service-account key never hit this path — they're
+/// minted transparently by `refresh::ensure_tokens` via a JWT-bearer
+/// exchange before this function is ever consulted.
+pub(super) fn credential_access_token(credential: &BaseCredential) -> Option<String> {
+    credential
+        .secret
+        .get("access_token")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+pub(super) fn credential_refresh_token(credential: &BaseCredential) -> Option<String> {
+    credential
+        .secret
+        .get("refresh_token")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn credential_base_url(credential: &BaseCredential) -> Option<String> {
+    credential
+        .meta
+        .get("base_url")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+/// One entry in a credential's model-to-endpoint routing table: requests
+/// for a model matching `model_pattern` (a trailing `*` matches as a
+/// prefix, same syntax as `crate::model_filter`'s patterns; anything else
+/// must match the model name exactly) use `base_url` instead of the
+/// credential's flat default.
+#[derive(Debug, Clone, Deserialize)]
+struct EndpointRoute {
+    model_pattern: String,
+    base_url: String,
+}
+
+fn credential_endpoint_routes(credential: &BaseCredential) -> Vec<EndpointRoute> {
+    credential
+        .meta
+        .get("endpoint_routes")
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn route_pattern_matches(pattern: &str, model: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => model.starts_with(prefix),
+        None => pattern == model,
+    }
+}
+
+/// Resolves the base URL to use for `model` on `credential`: the first
+/// matching entry in its `endpoint_routes` table wins, falling back to the
+/// flat [`credential_base_url`] override, then `DEFAULT_BASE_URL` inside
+/// [`build_url`]. Lets an operator route, say, `gemini-1.5-pro` to a
+/// `us-central1` regional endpoint and everything else to the global one
+/// from a single credential, without needing a separate credential per
+/// region.
+fn credential_base_url_for_model(credential: &BaseCredential, model: &str) -> Option<String> {
+    credential_endpoint_routes(credential)
+        .into_iter()
+        .find(|route| route_pattern_matches(&route.model_pattern, model))
+        .map(|route| route.base_url)
+        .or_else(|| credential_base_url(credential))
+}
+
+/// Gemini's standard harm categories, covered by a `safety_block_threshold`
+/// override. See https://ai.google.dev/gemini-api/docs/safety-settings.
+const SAFETY_HARM_CATEGORIES: [&str; 4] = [
+    "HARM_CATEGORY_HARASSMENT",
+    "HARM_CATEGORY_HATE_SPEECH",
+    "HARM_CATEGORY_SEXUALLY_EXPLICIT",
+    "HARM_CATEGORY_DANGEROUS_CONTENT",
+];
+
+/// A per-credential default `safetySettings` block threshold (e.g.
+/// `BLOCK_NONE`, `BLOCK_ONLY_HIGH`), applied across
+/// [`SAFETY_HARM_CATEGORIES`] when the downstream request didn't send its
+/// own `safetySettings`. Lives alongside `base_url` in `credential.meta`
+/// rather than the provider's `config_json` — `config_json` only seeds the
+/// provider row an admin can edit later, while `meta` is what
+/// `handle_generate` actually reads per request, matching
+/// `credential_base_url` above.
+fn credential_safety_block_threshold(credential: &BaseCredential) -> Option<String> {
+    credential
+        .meta
+        .get("safety_block_threshold")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+/// Merges `block_threshold` into `body["safetySettings"]` for every category
+/// in [`SAFETY_HARM_CATEGORIES`], unless the downstream request already set
+/// its own `safetySettings` — those are left untouched so an explicit
+/// caller choice always wins over the credential's default.
+fn inject_default_safety_settings(body: &mut JsonValue, block_threshold: Option<&str>) {
+    let Some(block_threshold) = block_threshold else {
+        return;
+    };
+    if body.get("safetySettings").is_some() {
+        return;
+    }
+    let settings: Vec<JsonValue> = SAFETY_HARM_CATEGORIES
+        .iter()
+        .map(|category| json!({ "category": category, "threshold": block_threshold }))
+        .collect();
+    if let Some(object) = body.as_object_mut() {
+        object.insert("safetySettings".to_string(), JsonValue::Array(settings));
+    }
+}
+
+fn build_url(base_url: Option<&str>, path: &str) -> String {
+    let base = base_url.unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/');
+    let mut path = path.trim_start_matches('/');
+    if base.ends_with("/v1") && (path == "v1" || path.starts_with("v1/")) {
+        path = path.trim_start_matches("v1/").trim_start_matches("v1");
+    }
+    if base.ends_with("/v1beta") && (path == "v1beta" || path.starts_with("v1beta/")) {
+        path = path
+            .trim_start_matches("v1beta/")
+            .trim_start_matches("v1beta");
+    }
+    format!("{base}/{path}")
+}
+
+fn build_generate_endpoint(
+    base_url: Option<&str>,
+    version_prefix: &str,
+    model: &str,
+    operation: &str,
+) -> (String, String) {
+    let path = format!("/{version_prefix}/models/{model}:{operation}");
+    let url = build_url(base_url, &path);
+    (path, url)
+}
+
+fn version_prefix(version: gproxy_provider_core::GeminiApiVersion) -> &'static str {
+    match version {
+        gproxy_provider_core::GeminiApiVersion::V1 => "v1",
+        gproxy_provider_core::GeminiApiVersion::V1Beta => "v1beta",
+    }
+}
+
+fn build_geminicli_headers(access_token: &str) -> Result<HeaderMap, AttemptFailure> {
+    let mut headers = HeaderMap::new();
+    let mut bearer = String::with_capacity(access_token.len() + 7);
+    bearer.push_str("Bearer ");
+    bearer.push_str(access_token);
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&bearer).map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?,
+    );
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Ok(headers)
+}
+
+pub(super) fn invalid_credential(scope: &DisallowScope, message: &str) -> AttemptFailure {
+    AttemptFailure {
+        passthrough: UpstreamPassthroughError::service_unavailable(message.to_string()),
+        mark: Some(gproxy_provider_core::DisallowMark {
+            scope: scope.clone(),
+            level: gproxy_provider_core::DisallowLevel::Dead,
+            duration: None,
+            reason: Some(message.to_string()),
+        }),
     }
 }
@@ -1,20 +1,83 @@
 use std::collections::HashMap;
 use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
 
-use http::header::CONTENT_TYPE;
 use http::HeaderValue;
+use http::header::CONTENT_TYPE;
+use jsonwebtoken::{Algorithm, EncodingKey, Header as JwtHeader};
 use serde::{Deserialize, Serialize};
 
-use gproxy_provider_core::{AttemptFailure, DisallowScope, UpstreamContext, UpstreamPassthroughError};
+use gproxy_provider_core::{
+    AttemptFailure, DisallowScope, UpstreamContext, UpstreamPassthroughError,
+};
 
 use crate::client::shared_client;
 use crate::credential::BaseCredential;
 
 use super::{credential_access_token, credential_refresh_token, invalid_credential};
 
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+/// Refresh this far ahead of the real expiry so an in-flight request never
+/// races a token that expires mid-call.
+const REFRESH_SKEW: Duration = Duration::from_secs(60);
+/// Google access tokens are conventionally valid for an hour; used when a
+/// response omits `expires_in` (manually-supplied access tokens never carry
+/// one at all).
+const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(3600);
+
+/// The fields of a Google ADC service-account JSON key. Present in
+/// `credential.secret` for credentials that authenticate via a
+/// JWT-bearer grant instead of the installed-app `refresh_token` flow
+/// below, which is how operators actually deploy this provider on GCP.
+#[derive(Debug, Clone, Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    #[serde(default = "default_token_uri")]
+    token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    REFRESH_TOKEN_URL.to_string()
+}
+
+fn credential_service_account(credential: &BaseCredential) -> Option<ServiceAccountKey> {
+    serde_json::from_value(credential.secret.clone()).ok()
+}
+
 #[derive(Clone, Debug)]
 pub(super) struct CachedTokens {
     pub(super) access_token: String,
+    expires_at: SystemTime,
+}
+
+fn cached_tokens(access_token: String, expires_in: Option<u64>) -> CachedTokens {
+    CachedTokens {
+        access_token,
+        expires_at: SystemTime::now() + expires_in.map_or(DEFAULT_TOKEN_TTL, Duration::from_secs),
+    }
+}
+
+#[derive(Serialize)]
+struct JwtClaims {
+    iss: String,
+    scope: &'static str,
+    aud: String,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Serialize)]
+struct JwtBearerRequest {
+    grant_type: &'static str,
+    assertion: String,
+}
+
+#[derive(Deserialize)]
+struct JwtBearerResponse {
+    access_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
 }
 
 #[derive(Serialize)]
@@ -28,12 +91,15 @@ struct RefreshRequest {
 #[derive(Deserialize)]
 struct RefreshResponse {
     access_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
 }
 
 static TOKEN_CACHE: OnceLock<tokio::sync::RwLock<HashMap<i64, CachedTokens>>> = OnceLock::new();
 const REFRESH_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
-const CLIENT_ID: &str = "681255809395-oo8ft2oprdrnp9e3aqf6av3hmdib135j.apps.googleusercontent.com";
-const CLIENT_SECRET: &str = "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";
+pub(super) const CLIENT_ID: &str =
+    "681255809395-oo8ft2oprdrnp9e3aqf6av3hmdib135j.apps.googleusercontent.com";
+pub(super) const CLIENT_SECRET: &str = "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";
 
 pub(super) async fn ensure_tokens(
     credential: &BaseCredential,
@@ -41,20 +107,116 @@ pub(super) async fn ensure_tokens(
     scope: &DisallowScope,
 ) -> Result<CachedTokens, AttemptFailure> {
     if let Some(cached) = token_cache().read().await.get(&credential.id).cloned() {
-        return Ok(cached);
+        if cached.expires_at > SystemTime::now() + REFRESH_SKEW {
+            return Ok(cached);
+        }
+    }
+    if let Some(service_account) = credential_service_account(credential) {
+        return mint_jwt_bearer_token(credential.id, &service_account, ctx, scope).await;
     }
     if let Some(access_token) = credential_access_token(credential) {
-        let tokens = CachedTokens { access_token };
-        token_cache().write().await.insert(credential.id, tokens.clone());
+        let tokens = cached_tokens(access_token, None);
+        token_cache()
+            .write()
+            .await
+            .insert(credential.id, tokens.clone());
         return Ok(tokens);
     }
     if let Some(refresh_token) = credential_refresh_token(credential) {
         return refresh_access_token(credential.id, refresh_token, ctx, scope).await;
     }
-    Err(invalid_credential(scope, "missing access_token/refresh_token"))
+    Err(invalid_credential(
+        scope,
+        "missing access_token/refresh_token",
+    ))
+}
+
+/// Mints an access token for a service-account credential via the JWT-bearer
+/// grant (RFC 7523), the ADC path used when a credential carries a full
+/// service-account key instead of an installed-app refresh token.
+async fn mint_jwt_bearer_token(
+    credential_id: i64,
+    service_account: &ServiceAccountKey,
+    ctx: &UpstreamContext,
+    scope: &DisallowScope,
+) -> Result<CachedTokens, AttemptFailure> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let claims = JwtClaims {
+        iss: service_account.client_email.clone(),
+        scope: CLOUD_PLATFORM_SCOPE,
+        aud: service_account.token_uri.clone(),
+        iat: now,
+        exp: now + 3600,
+    };
+    let encoding_key = EncodingKey::from_rsa_pem(service_account.private_key.as_bytes())
+        .map_err(|err| invalid_credential(scope, &format!("invalid private_key: {err}")))?;
+    let assertion = jsonwebtoken::encode(&JwtHeader::new(Algorithm::RS256), &claims, &encoding_key)
+        .map_err(|err| invalid_credential(scope, &format!("failed to sign jwt: {err}")))?;
+
+    let client = shared_client(ctx.proxy.as_deref())?;
+    let request = JwtBearerRequest {
+        grant_type: "urn:ietf:params:oauth:grant-type:jwt-bearer",
+        assertion,
+    };
+    let response = client
+        .post(&service_account.token_uri)
+        .header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        )
+        .form(&request)
+        .send()
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let message = format!("jwt-bearer token exchange failed: {status}: {body}");
+        let mark = if status == http::StatusCode::UNAUTHORIZED
+            || status == http::StatusCode::BAD_REQUEST
+        {
+            Some(gproxy_provider_core::DisallowMark {
+                scope: scope.clone(),
+                level: gproxy_provider_core::DisallowLevel::Dead,
+                duration: None,
+                reason: Some("service_account_invalid".to_string()),
+            })
+        } else {
+            None
+        };
+        return Err(AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(message),
+            mark,
+        });
+    }
+    let payload = response
+        .json::<JwtBearerResponse>()
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?;
+    let access_token = payload.access_token.ok_or_else(|| AttemptFailure {
+        passthrough: UpstreamPassthroughError::service_unavailable(
+            "jwt-bearer response missing access_token".to_string(),
+        ),
+        mark: None,
+    })?;
+    let tokens = cached_tokens(access_token, payload.expires_in);
+    token_cache()
+        .write()
+        .await
+        .insert(credential_id, tokens.clone());
+    Ok(tokens)
 }
 
-async fn refresh_access_token(
+pub(super) async fn refresh_access_token(
     credential_id: i64,
     refresh_token: String,
     ctx: &UpstreamContext,
@@ -69,7 +231,10 @@ async fn refresh_access_token(
     };
     let response = client
         .post(REFRESH_TOKEN_URL)
-        .header(CONTENT_TYPE, HeaderValue::from_static("application/x-www-form-urlencoded"))
+        .header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        )
         .form(&request)
         .send()
         .await
@@ -96,18 +261,24 @@ async fn refresh_access_token(
             mark,
         });
     }
-    let payload = response.json::<RefreshResponse>().await.map_err(|err| AttemptFailure {
-        passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
-        mark: None,
-    })?;
+    let payload = response
+        .json::<RefreshResponse>()
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?;
     let access_token = payload.access_token.ok_or_else(|| AttemptFailure {
         passthrough: UpstreamPassthroughError::service_unavailable(
             "refresh_token response missing access_token".to_string(),
         ),
         mark: None,
     })?;
-    let tokens = CachedTokens { access_token };
-    token_cache().write().await.insert(credential_id, tokens.clone());
+    let tokens = cached_tokens(access_token, payload.expires_in);
+    token_cache()
+        .write()
+        .await
+        .insert(credential_id, tokens.clone());
     Ok(tokens)
 }
 
@@ -1,5 +1,6 @@
 pub mod aistudio;
 pub mod antigravity;
+pub mod azure_openai;
 pub mod claude;
 pub mod claudecode;
 pub mod codex;
@@ -12,6 +13,7 @@ pub mod vertexexpress;
 
 pub use aistudio::AistudioProvider;
 pub use antigravity::AntiGravityProvider;
+pub use azure_openai::AzureOpenAIProvider;
 pub use claude::ClaudeProvider;
 pub use claudecode::ClaudeCodeProvider;
 pub use codex::CodexProvider;
@@ -22,13 +24,107 @@ pub use openai::OpenAIProvider;
 pub use vertex::VertexProvider;
 pub use vertexexpress::VertexExpressProvider;
 
-use http::StatusCode;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::header::RETRY_AFTER;
+use http::{HeaderMap, HeaderValue, StatusCode};
 
 use gproxy_provider_core::UpstreamPassthroughError;
 
+/// Structured reasons an upstream call can fail before or instead of
+/// producing a raw passthrough response, so callers (the router, the
+/// failover layer) can branch on *why* a call failed instead of re-parsing
+/// a status code back out of [`UpstreamPassthroughError`]. Converts into one
+/// via [`From`], which is the only surface `Provider::call` implementations
+/// and their callers actually need to deal with.
+#[derive(Debug, Clone)]
+pub enum UpstreamError {
+    /// This provider is registered but doesn't implement the call yet.
+    NotImplemented { provider: String },
+    /// The upstream connection or transport itself failed (network error,
+    /// TLS failure, timeout) rather than the upstream answering with an
+    /// error status.
+    BadGateway { provider: String, reason: String },
+    /// No registered provider matched the requested model.
+    NoProvider { model: String },
+    /// The selected provider doesn't recognize the requested model.
+    NotFound { provider: String, model: String },
+    /// Upstream responded 401/403.
+    Unauthorized { provider: String },
+    /// Upstream responded 429. `retry_after` is forwarded as a `Retry-After`
+    /// header on the converted [`UpstreamPassthroughError`] when present.
+    RateLimited {
+        provider: String,
+        retry_after: Option<Duration>,
+    },
+}
+
+impl UpstreamError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            UpstreamError::NotImplemented { .. } => StatusCode::NOT_IMPLEMENTED,
+            UpstreamError::BadGateway { .. } => StatusCode::BAD_GATEWAY,
+            UpstreamError::NoProvider { .. } => StatusCode::NOT_FOUND,
+            UpstreamError::NotFound { .. } => StatusCode::NOT_FOUND,
+            UpstreamError::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
+            UpstreamError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
+
+impl std::fmt::Display for UpstreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpstreamError::NotImplemented { provider } => {
+                write!(f, "{provider} provider not implemented")
+            }
+            UpstreamError::BadGateway { provider, reason } => {
+                write!(f, "{provider} upstream connection failed: {reason}")
+            }
+            UpstreamError::NoProvider { model } => {
+                write!(f, "no registered provider matches model {model:?}")
+            }
+            UpstreamError::NotFound { provider, model } => {
+                write!(f, "{provider} does not recognize model {model:?}")
+            }
+            UpstreamError::Unauthorized { provider } => {
+                write!(f, "{provider} rejected the configured credentials")
+            }
+            UpstreamError::RateLimited { provider, .. } => {
+                write!(f, "{provider} is rate-limiting this credential")
+            }
+        }
+    }
+}
+
+impl std::error::Error for UpstreamError {}
+
+impl From<UpstreamError> for UpstreamPassthroughError {
+    fn from(err: UpstreamError) -> Self {
+        let status = err.status();
+        let retry_after = match &err {
+            UpstreamError::RateLimited {
+                retry_after: Some(duration),
+                ..
+            } => HeaderValue::from_str(&duration.as_secs().to_string()).ok(),
+            _ => None,
+        };
+        let message = err.to_string();
+        match retry_after {
+            Some(value) => {
+                let mut headers = HeaderMap::new();
+                headers.insert(RETRY_AFTER, value);
+                UpstreamPassthroughError::new(status, headers, Bytes::from(message))
+            }
+            None => UpstreamPassthroughError::from_status(status, message),
+        }
+    }
+}
+
 pub(crate) fn not_implemented(provider: &str) -> UpstreamPassthroughError {
-    UpstreamPassthroughError::from_status(
-        StatusCode::NOT_IMPLEMENTED,
-        format!("{provider} provider not implemented"),
-    )
+    UpstreamError::NotImplemented {
+        provider: provider.to_string(),
+    }
+    .into()
 }
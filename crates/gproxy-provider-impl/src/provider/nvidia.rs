@@ -9,8 +9,18 @@ use gproxy_provider_core::{
 
 use crate::credential::BaseCredential;
 use crate::provider::not_implemented;
+use crate::ProviderDefault;
 
 pub const PROVIDER_NAME: &str = "nvidia";
+const DEFAULT_BASE_URL: &str = "https://integrate.api.nvidia.com";
+
+pub fn default_provider() -> ProviderDefault {
+    ProviderDefault {
+        name: PROVIDER_NAME,
+        config_json: serde_json::json!({ "base_url": DEFAULT_BASE_URL }),
+        enabled: true,
+    }
+}
 
 #[derive(Debug)]
 pub struct NvidiaProvider {
@@ -1,23 +1,33 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use http::{HeaderMap, HeaderValue};
 use serde_json::json;
+use tracing::{info, warn};
 
 use gproxy_provider_core::{
-    CallContext, CredentialPool, PoolSnapshot, Provider, ProxyRequest, ProxyResponse, StateSink,
-    UpstreamPassthroughError,
+    AttemptFailure, CallContext, CredentialPool, DisallowScope, PoolSnapshot, Provider,
+    ProxyRequest, ProxyResponse, StateSink, UpstreamPassthroughError, UpstreamRecordMeta,
 };
 
+use crate::client::shared_client;
 use crate::credential::BaseCredential;
-use crate::ProviderDefault;
+use crate::dispatch::UpstreamOk;
 use crate::provider::not_implemented;
+use crate::record::{headers_to_json, json_body_to_string};
+use crate::transport::TransportConfig;
+use crate::upstream::{handle_response, network_failure};
+use crate::ProviderDefault;
 
 pub const PROVIDER_NAME: &str = "openai";
+const DEFAULT_BASE_URL: &str = "https://api.openai.com";
 
 pub fn default_provider() -> ProviderDefault {
     ProviderDefault {
         name: PROVIDER_NAME,
-        config_json: json!({}),
+        config_json: json!({ "base_url": DEFAULT_BASE_URL }),
         enabled: true,
     }
 }
@@ -43,6 +53,109 @@ impl OpenAIProvider {
     pub fn replace_snapshot(&self, snapshot: PoolSnapshot<OpenAICredential>) {
         self.pool.replace_snapshot(snapshot);
     }
+
+    async fn handle_chat(
+        &self,
+        request: gproxy_protocol::openai::create_chat_completions::request::CreateChatCompletionRequest,
+        is_stream: bool,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.body.model.clone();
+        let scope = DisallowScope::model(model.clone());
+        let mut body = request.body;
+        if is_stream {
+            body.stream = Some(true);
+        }
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let body = body.clone();
+                async move {
+                    let api_key = credential_api_key(credential.value())
+                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let base_url = credential_base_url(credential.value());
+                    let path = "/v1/chat/completions".to_string();
+                    let url = build_url(base_url.as_deref(), &path);
+                    let transport = TransportConfig::from_meta(&credential.value().meta);
+                    let client = shared_client(transport.resolve_proxy(ctx.proxy.as_deref()))?;
+                    let mut req_headers = build_auth_headers(&api_key)?;
+                    transport.apply_headers(&mut req_headers)?;
+                    let request_body = json_body_to_string(&body);
+                    let request_headers = headers_to_json(&req_headers);
+                    let started_at = Instant::now();
+                    info!(
+                        event = "upstream_request",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "openai.chat",
+                        method = "POST",
+                        path = %path,
+                        model = %model,
+                        is_stream = is_stream
+                    );
+                    let mut request_builder = client.post(url).headers(req_headers.clone()).json(&body);
+                    if let Some(connect_timeout) = transport.connect_timeout {
+                        request_builder = request_builder.timeout(connect_timeout);
+                    }
+                    let response = request_builder
+                        .send()
+                        .await
+                        .map_err(|err| {
+                            warn!(
+                                event = "upstream_response",
+                                trace_id = %ctx.trace_id,
+                                provider = %PROVIDER_NAME,
+                                op = "openai.chat",
+                                status = "error",
+                                elapsed_ms = started_at.elapsed().as_millis(),
+                                error = %err
+                            );
+                            network_failure(err, &scope)
+                        })?;
+                    info!(
+                        event = "upstream_response",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "openai.chat",
+                        status = %response.status().as_u16(),
+                        elapsed_ms = started_at.elapsed().as_millis(),
+                        is_stream = is_stream
+                    );
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "openai.chat".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    // When the caller asked for a streamed response, `handle_response`
+                    // wraps the upstream body as a `ProxyResponse::Stream` of
+                    // already-framed `data: <json>` lines; it stops forwarding at the
+                    // `data: [DONE]` sentinel and never buffers the full body.
+                    let response = handle_response(
+                        response,
+                        is_stream,
+                        scope.clone(),
+                        &ctx,
+                        Some(meta.clone()),
+                    )
+                    .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
 }
 
 #[async_trait]
@@ -53,9 +166,70 @@ impl Provider for OpenAIProvider {
 
     async fn call(
         &self,
-        _req: ProxyRequest,
-        _ctx: CallContext,
+        req: ProxyRequest,
+        ctx: CallContext,
     ) -> Result<ProxyResponse, UpstreamPassthroughError> {
-        Err(not_implemented(PROVIDER_NAME))
+        match req {
+            ProxyRequest::OpenAIChat(request) => {
+                self.handle_chat(request, false, ctx).await.map(|ok| ok.response)
+            }
+            ProxyRequest::OpenAIChatStream(request) => {
+                self.handle_chat(request, true, ctx).await.map(|ok| ok.response)
+            }
+            _ => Err(not_implemented(PROVIDER_NAME)),
+        }
+    }
+}
+
+fn build_auth_headers(api_key: &str) -> Result<HeaderMap, AttemptFailure> {
+    let mut headers = HeaderMap::new();
+    let mut bearer = String::with_capacity(api_key.len() + 7);
+    bearer.push_str("Bearer ");
+    bearer.push_str(api_key);
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&bearer).map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?,
+    );
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Ok(headers)
+}
+
+fn credential_api_key(credential: &BaseCredential) -> Option<String> {
+    if let serde_json::Value::String(value) = &credential.secret {
+        return Some(value.clone());
+    }
+    credential
+        .secret
+        .get("api_key")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn credential_base_url(credential: &BaseCredential) -> Option<String> {
+    credential
+        .meta
+        .get("base_url")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string())
+}
+
+fn build_url(base_url: Option<&str>, path: &str) -> String {
+    let base = base_url.unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+    format!("{base}/{path}")
+}
+
+fn invalid_credential(scope: &DisallowScope, message: &str) -> AttemptFailure {
+    AttemptFailure {
+        passthrough: UpstreamPassthroughError::service_unavailable(message.to_string()),
+        mark: Some(gproxy_provider_core::DisallowMark {
+            scope: scope.clone(),
+            level: gproxy_provider_core::DisallowLevel::Dead,
+            duration: None,
+            reason: Some(message.to_string()),
+        }),
     }
 }
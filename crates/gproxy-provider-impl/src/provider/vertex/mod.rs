@@ -0,0 +1,602 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use http::header::CONTENT_TYPE;
+use http::{HeaderMap, HeaderValue};
+use serde_json::json;
+use tracing::{info, warn};
+
+use gproxy_protocol::gemini;
+use gproxy_provider_core::{
+    AttemptFailure, CallContext, CredentialPool, DisallowScope, PoolSnapshot, Provider,
+    ProxyRequest, ProxyResponse, StateSink, UpstreamPassthroughError, UpstreamRecordMeta,
+};
+
+use crate::client::shared_client;
+use crate::credential::BaseCredential;
+use crate::dispatch::{
+    dispatch_request, CountTokensPlan, DispatchPlan, DispatchProvider, GenerateContentPlan,
+    ModelsGetPlan, ModelsListPlan, StreamContentPlan, TransformPlan, UpstreamOk, UsageKind,
+};
+use crate::record::{headers_to_json, json_body_to_string};
+use crate::upstream::{handle_response, network_failure};
+use crate::ProviderDefault;
+
+mod oauth;
+
+use oauth::{credential_adc, ensure_access_token};
+
+pub const PROVIDER_NAME: &str = "vertex";
+
+pub fn default_provider() -> ProviderDefault {
+    ProviderDefault {
+        name: PROVIDER_NAME,
+        config_json: json!({
+            // Every credential must carry a service-account JSON in `secret`
+            // plus `project_id`/`location` in `meta` — unlike `vertexexpress`,
+            // this provider has no Express API-key mode to fall back to.
+        }),
+        enabled: true,
+    }
+}
+
+/// VertexAI's project-scoped `generateContent` surface, reached with an
+/// Application Default Credentials bearer token instead of the API key the
+/// public Gemini endpoint and `VertexExpressProvider`'s Express mode use.
+/// Every `ProxyRequest` this provider can't serve natively is routed through
+/// the existing Gemini transform arms unchanged, via the same
+/// `GenerateContentPlan`/`StreamContentPlan` variants every other
+/// Gemini-backed provider uses.
+#[derive(Debug)]
+pub struct VertexProvider {
+    pool: CredentialPool<VertexCredential>,
+}
+
+pub type VertexCredential = BaseCredential;
+
+impl VertexProvider {
+    pub fn new(sink: Arc<dyn StateSink>) -> Self {
+        let snapshot = PoolSnapshot::empty();
+        let pool = CredentialPool::new(PROVIDER_NAME, snapshot, Some(sink));
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &CredentialPool<VertexCredential> {
+        &self.pool
+    }
+
+    pub fn replace_snapshot(&self, snapshot: PoolSnapshot<VertexCredential>) {
+        self.pool.replace_snapshot(snapshot);
+    }
+}
+
+#[async_trait]
+impl Provider for VertexProvider {
+    fn name(&self) -> &str {
+        PROVIDER_NAME
+    }
+
+    async fn call(
+        &self,
+        req: ProxyRequest,
+        ctx: CallContext,
+    ) -> Result<ProxyResponse, UpstreamPassthroughError> {
+        dispatch_request(self, req, ctx).await
+    }
+}
+
+#[async_trait]
+impl DispatchProvider for VertexProvider {
+    fn name(&self) -> &str {
+        PROVIDER_NAME
+    }
+
+    fn dispatch_plan(&self, req: ProxyRequest) -> DispatchPlan {
+        match req {
+            ProxyRequest::GeminiGenerate {
+                version: _,
+                request,
+            } => DispatchPlan::Native {
+                req: ProxyRequest::GeminiGenerate {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                },
+                usage: UsageKind::GeminiGenerate,
+            },
+            ProxyRequest::GeminiGenerateStream {
+                version: _,
+                request,
+            } => DispatchPlan::Native {
+                req: ProxyRequest::GeminiGenerateStream {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                },
+                usage: UsageKind::GeminiGenerate,
+            },
+            ProxyRequest::GeminiCountTokens {
+                version: _,
+                request,
+            } => DispatchPlan::Native {
+                req: ProxyRequest::GeminiCountTokens {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                },
+                usage: UsageKind::None,
+            },
+            ProxyRequest::OpenAIResponses(request) => DispatchPlan::Transform {
+                plan: TransformPlan::GenerateContent(GenerateContentPlan::OpenAIResponses2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::OpenAIResponses,
+            },
+            ProxyRequest::OpenAIChat(request) => DispatchPlan::Transform {
+                plan: TransformPlan::GenerateContent(GenerateContentPlan::OpenAIChat2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::OpenAIChat,
+            },
+            ProxyRequest::OpenAIResponsesStream(request) => DispatchPlan::Transform {
+                plan: TransformPlan::StreamContent(StreamContentPlan::OpenAIResponses2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::OpenAIResponses,
+            },
+            ProxyRequest::OpenAIChatStream(request) => DispatchPlan::Transform {
+                plan: TransformPlan::StreamContent(StreamContentPlan::OpenAIChat2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::OpenAIChat,
+            },
+            ProxyRequest::OpenAIInputTokens(request) => DispatchPlan::Transform {
+                plan: TransformPlan::CountTokens(CountTokensPlan::OpenAIInputTokens2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::OpenAIModelsList(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsList(ModelsListPlan::OpenAI2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::OpenAIModelsGet(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsGet(ModelsGetPlan::OpenAI2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::ClaudeMessages(request) => DispatchPlan::Transform {
+                plan: TransformPlan::GenerateContent(GenerateContentPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::ClaudeMessage,
+            },
+            ProxyRequest::ClaudeMessagesStream(request) => DispatchPlan::Transform {
+                plan: TransformPlan::StreamContent(StreamContentPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::ClaudeMessage,
+            },
+            ProxyRequest::ClaudeCountTokens(request) => DispatchPlan::Transform {
+                plan: TransformPlan::CountTokens(CountTokensPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::ClaudeModelsList(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsList(ModelsListPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            ProxyRequest::ClaudeModelsGet(request) => DispatchPlan::Transform {
+                plan: TransformPlan::ModelsGet(ModelsGetPlan::Claude2Gemini {
+                    version: gproxy_provider_core::GeminiApiVersion::V1Beta,
+                    request,
+                }),
+                usage: UsageKind::None,
+            },
+            // `GeminiModelsList`/`GeminiModelsGet` and anything else this
+            // provider doesn't have a transform arm for fall through to
+            // `call_native`, which reports them as unsupported.
+            req => DispatchPlan::Native {
+                req,
+                usage: UsageKind::None,
+            },
+        }
+    }
+
+    async fn call_native(
+        &self,
+        req: ProxyRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        match req {
+            ProxyRequest::GeminiGenerate { version, request } => {
+                self.handle_generate(version, request, false, ctx).await
+            }
+            ProxyRequest::GeminiGenerateStream { version, request } => {
+                self.handle_generate_stream(version, request, ctx).await
+            }
+            ProxyRequest::GeminiCountTokens { version, request } => {
+                self.handle_count_tokens(version, request, ctx).await
+            }
+            _ => Err(UpstreamPassthroughError::service_unavailable(
+                "non-native operation".to_string(),
+            )),
+        }
+    }
+}
+
+impl VertexProvider {
+    async fn handle_generate(
+        &self,
+        version: gproxy_provider_core::GeminiApiVersion,
+        request: gemini::generate_content::request::GenerateContentRequest,
+        is_stream: bool,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.path.model.clone();
+        let scope = DisallowScope::model(model.clone());
+        let body = request.body;
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let body = body.clone();
+                async move {
+                    let token = resolve_token(credential.value(), &ctx, &scope).await?;
+                    let region = credential_region(credential.value())
+                        .ok_or_else(|| invalid_credential(&scope, "missing project_id/location"))?;
+                    let version_prefix = version_prefix(version);
+                    let (path, url) =
+                        build_generate_endpoint(&region, version_prefix, &model, "generateContent");
+                    let req_headers = build_vertex_headers(&token)?;
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let body = serde_json::to_value(&body).unwrap_or_else(|_| json!({}));
+                    let request_body = json_body_to_string(&body);
+                    let request_headers = headers_to_json(&req_headers);
+                    let started_at = Instant::now();
+                    info!(
+                        event = "upstream_request",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "gemini.generate",
+                        method = "POST",
+                        path = %path,
+                        model = %model,
+                        is_stream = is_stream
+                    );
+                    let response = client
+                        .post(&url)
+                        .headers(req_headers.clone())
+                        .json(&body)
+                        .send()
+                        .await
+                        .map_err(|err| {
+                            warn!(
+                                event = "upstream_response",
+                                trace_id = %ctx.trace_id,
+                                provider = %PROVIDER_NAME,
+                                op = "gemini.generate",
+                                status = "error",
+                                elapsed_ms = started_at.elapsed().as_millis(),
+                                error = %err
+                            );
+                            network_failure(err, &scope)
+                        })?;
+                    info!(
+                        event = "upstream_response",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "gemini.generate",
+                        status = %response.status().as_u16(),
+                        elapsed_ms = started_at.elapsed().as_millis(),
+                        is_stream = is_stream
+                    );
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "gemini.generate".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response = handle_response(
+                        response,
+                        is_stream,
+                        scope.clone(),
+                        &ctx,
+                        Some(meta.clone()),
+                    )
+                    .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    async fn handle_generate_stream(
+        &self,
+        version: gproxy_provider_core::GeminiApiVersion,
+        request: gemini::stream_content::request::StreamGenerateContentRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.path.model.clone();
+        let scope = DisallowScope::model(model.clone());
+        let body = request.body;
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let body = body.clone();
+                async move {
+                    let token = resolve_token(credential.value(), &ctx, &scope).await?;
+                    let region = credential_region(credential.value())
+                        .ok_or_else(|| invalid_credential(&scope, "missing project_id/location"))?;
+                    let version_prefix = version_prefix(version);
+                    let (path, url) = build_generate_endpoint(
+                        &region,
+                        version_prefix,
+                        &model,
+                        "streamGenerateContent",
+                    );
+                    let req_headers = build_vertex_headers(&token)?;
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let body = serde_json::to_value(&body).unwrap_or_else(|_| json!({}));
+                    let request_body = json_body_to_string(&body);
+                    let request_headers = headers_to_json(&req_headers);
+                    let started_at = Instant::now();
+                    info!(
+                        event = "upstream_request",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "gemini.stream_generate",
+                        method = "POST",
+                        path = %path,
+                        model = %model,
+                        is_stream = true
+                    );
+                    let response = client
+                        .post(&url)
+                        .headers(req_headers.clone())
+                        .json(&body)
+                        .send()
+                        .await
+                        .map_err(|err| {
+                            warn!(
+                                event = "upstream_response",
+                                trace_id = %ctx.trace_id,
+                                provider = %PROVIDER_NAME,
+                                op = "gemini.stream_generate",
+                                status = "error",
+                                elapsed_ms = started_at.elapsed().as_millis(),
+                                error = %err
+                            );
+                            network_failure(err, &scope)
+                        })?;
+                    info!(
+                        event = "upstream_response",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "gemini.stream_generate",
+                        status = %response.status().as_u16(),
+                        elapsed_ms = started_at.elapsed().as_millis(),
+                        is_stream = true
+                    );
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "gemini.stream_generate".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response =
+                        handle_response(response, true, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    async fn handle_count_tokens(
+        &self,
+        version: gproxy_provider_core::GeminiApiVersion,
+        request: gemini::count_tokens::request::CountTokensRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.path.model.clone();
+        let scope = DisallowScope::model(model.clone());
+        let body = request.body;
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let body = body.clone();
+                async move {
+                    let token = resolve_token(credential.value(), &ctx, &scope).await?;
+                    let region = credential_region(credential.value())
+                        .ok_or_else(|| invalid_credential(&scope, "missing project_id/location"))?;
+                    let version_prefix = version_prefix(version);
+                    let (path, url) =
+                        build_generate_endpoint(&region, version_prefix, &model, "countTokens");
+                    let req_headers = build_vertex_headers(&token)?;
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let request_body = json_body_to_string(&body);
+                    let request_headers = headers_to_json(&req_headers);
+                    let started_at = Instant::now();
+                    info!(
+                        event = "upstream_request",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "gemini.count_tokens",
+                        method = "POST",
+                        path = %path,
+                        model = %model,
+                        is_stream = false
+                    );
+                    let response = client
+                        .post(&url)
+                        .headers(req_headers.clone())
+                        .json(&body)
+                        .send()
+                        .await
+                        .map_err(|err| {
+                            warn!(
+                                event = "upstream_response",
+                                trace_id = %ctx.trace_id,
+                                provider = %PROVIDER_NAME,
+                                op = "gemini.count_tokens",
+                                status = "error",
+                                elapsed_ms = started_at.elapsed().as_millis(),
+                                error = %err
+                            );
+                            network_failure(err, &scope)
+                        })?;
+                    info!(
+                        event = "upstream_response",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "gemini.count_tokens",
+                        status = %response.status().as_u16(),
+                        elapsed_ms = started_at.elapsed().as_millis(),
+                        is_stream = false
+                    );
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "gemini.count_tokens".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+}
+
+/// Resolve an ADC bearer token for `credential`, minting/caching/refreshing
+/// it via [`ensure_access_token`]. Unlike `VertexExpressProvider`, there's no
+/// API-key fallback: every `VertexProvider` credential is expected to carry
+/// either a service-account key or a gcloud user ADC JSON (see
+/// `oauth::credential_adc`).
+async fn resolve_token(
+    credential: &BaseCredential,
+    ctx: &CallContext,
+    scope: &DisallowScope,
+) -> Result<String, AttemptFailure> {
+    let adc = credential_adc(credential)
+        .ok_or_else(|| invalid_credential(scope, "missing service account or user ADC credentials"))?;
+    ensure_access_token(credential.id, &adc, ctx, scope).await
+}
+
+fn build_vertex_headers(bearer_token: &str) -> Result<HeaderMap, AttemptFailure> {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert(
+        http::header::AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {bearer_token}")).map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?,
+    );
+    Ok(headers)
+}
+
+/// A credential's GCP project/region, read from `credential.meta`. Required
+/// on every credential — there's no project-less "Express" mode here.
+struct VertexRegion {
+    project_id: String,
+    location: String,
+}
+
+fn credential_region(credential: &BaseCredential) -> Option<VertexRegion> {
+    let project_id = credential.meta.get("project_id")?.as_str()?.to_string();
+    let location = credential.meta.get("location")?.as_str()?.to_string();
+    Some(VertexRegion {
+        project_id,
+        location,
+    })
+}
+
+/// Build the path and URL for a `publishers/google/models/{model}:{operation}`
+/// call against `region`'s location-scoped host, e.g.
+/// `https://us-central1-aiplatform.googleapis.com/v1beta1/projects/{project}/locations/us-central1/publishers/google/models/{model}:generateContent`.
+fn build_generate_endpoint(
+    region: &VertexRegion,
+    version_prefix: &str,
+    model: &str,
+    operation: &str,
+) -> (String, String) {
+    let path = format!(
+        "/{version_prefix}/projects/{}/locations/{}/publishers/google/models/{model}:{operation}",
+        region.project_id, region.location
+    );
+    let url = format!("https://{}-aiplatform.googleapis.com{path}", region.location);
+    (path, url)
+}
+
+fn version_prefix(version: gproxy_provider_core::GeminiApiVersion) -> &'static str {
+    match version {
+        gproxy_provider_core::GeminiApiVersion::V1 => "v1",
+        gproxy_provider_core::GeminiApiVersion::V1Beta => "v1beta1",
+    }
+}
+
+fn invalid_credential(scope: &DisallowScope, message: &str) -> AttemptFailure {
+    AttemptFailure {
+        passthrough: UpstreamPassthroughError::service_unavailable(message.to_string()),
+        mark: Some(gproxy_provider_core::DisallowMark {
+            scope: scope.clone(),
+            level: gproxy_provider_core::DisallowLevel::Dead,
+            duration: None,
+            reason: Some(message.to_string()),
+        }),
+    }
+}
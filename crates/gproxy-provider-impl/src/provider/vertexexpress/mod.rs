@@ -1,36 +1,55 @@
-use std::sync::Arc;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
 
 use async_trait::async_trait;
 use http::header::CONTENT_TYPE;
 use http::{HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value as JsonValue};
 use tracing::{info, warn};
 
+use gproxy_protocol::claude;
+use gproxy_protocol::gemini;
 use gproxy_provider_core::{
     AttemptFailure, CallContext, CredentialPool, DisallowScope, PoolSnapshot, Provider,
     ProxyRequest, ProxyResponse, StateSink, UpstreamPassthroughError, UpstreamRecordMeta,
 };
-use gproxy_protocol::gemini;
 
 use crate::client::shared_client;
 use crate::credential::BaseCredential;
 use crate::dispatch::{
     dispatch_request, CountTokensPlan, DispatchPlan, DispatchProvider, GenerateContentPlan,
-    ModelsGetPlan, ModelsListPlan, StreamContentPlan, TransformPlan, UsageKind, UpstreamOk,
+    ModelsGetPlan, ModelsListPlan, StreamContentPlan, TransformPlan, UpstreamOk, UsageKind,
 };
 use crate::record::{headers_to_json, json_body_to_string};
 use crate::upstream::{handle_response, network_failure};
 use crate::ProviderDefault;
 
+mod oauth;
+
+use oauth::{credential_service_account, ensure_access_token};
+
 pub const PROVIDER_NAME: &str = "vertexexpress";
 const DEFAULT_BASE_URL: &str = "https://aiplatform.googleapis.com";
 const MODELS_JSON: &str = include_str!("models.json");
+/// The `anthropic_version` Google's Vertex rawPredict surface requires in
+/// place of the header the real Anthropic API uses for the same purpose.
+const ANTHROPIC_VERTEX_VERSION: &str = "vertex-2023-10-16";
 
 pub fn default_provider() -> ProviderDefault {
     ProviderDefault {
         name: PROVIDER_NAME,
-        config_json: json!({ "base_url": DEFAULT_BASE_URL }),
+        config_json: json!({
+            "base_url": DEFAULT_BASE_URL,
+            // Per-credential overrides live in each credential's `meta.safety_settings`
+            // (see `credential_safety_settings`); this documents the expected shape.
+            "safety_settings": [],
+            // Set both on a credential's `meta` to switch that credential from Express
+            // mode to project-scoped "regional" routing (see `credential_vertex_region`).
+            "project_id": null,
+            "location": null,
+        }),
         enabled: true,
     }
 }
@@ -75,37 +94,56 @@ impl Provider for VertexExpressProvider {
 
 #[async_trait]
 impl DispatchProvider for VertexExpressProvider {
+    fn name(&self) -> &str {
+        PROVIDER_NAME
+    }
+
     fn dispatch_plan(&self, req: ProxyRequest) -> DispatchPlan {
         match req {
-            ProxyRequest::GeminiGenerate { version: _, request } => DispatchPlan::Native {
+            ProxyRequest::GeminiGenerate {
+                version: _,
+                request,
+            } => DispatchPlan::Native {
                 req: ProxyRequest::GeminiGenerate {
                     version: gproxy_provider_core::GeminiApiVersion::V1Beta,
                     request,
                 },
                 usage: UsageKind::GeminiGenerate,
             },
-            ProxyRequest::GeminiGenerateStream { version: _, request } => DispatchPlan::Native {
+            ProxyRequest::GeminiGenerateStream {
+                version: _,
+                request,
+            } => DispatchPlan::Native {
                 req: ProxyRequest::GeminiGenerateStream {
                     version: gproxy_provider_core::GeminiApiVersion::V1Beta,
                     request,
                 },
                 usage: UsageKind::GeminiGenerate,
             },
-            ProxyRequest::GeminiCountTokens { version: _, request } => DispatchPlan::Native {
+            ProxyRequest::GeminiCountTokens {
+                version: _,
+                request,
+            } => DispatchPlan::Native {
                 req: ProxyRequest::GeminiCountTokens {
                     version: gproxy_provider_core::GeminiApiVersion::V1Beta,
                     request,
                 },
                 usage: UsageKind::None,
             },
-            ProxyRequest::GeminiModelsList { version: _, request } => DispatchPlan::Native {
+            ProxyRequest::GeminiModelsList {
+                version: _,
+                request,
+            } => DispatchPlan::Native {
                 req: ProxyRequest::GeminiModelsList {
                     version: gproxy_provider_core::GeminiApiVersion::V1Beta,
                     request,
                 },
                 usage: UsageKind::None,
             },
-            ProxyRequest::GeminiModelsGet { version: _, request } => DispatchPlan::Native {
+            ProxyRequest::GeminiModelsGet {
+                version: _,
+                request,
+            } => DispatchPlan::Native {
                 req: ProxyRequest::GeminiModelsGet {
                     version: gproxy_provider_core::GeminiApiVersion::V1Beta,
                     request,
@@ -161,6 +199,12 @@ impl DispatchProvider for VertexExpressProvider {
                 }),
                 usage: UsageKind::None,
             },
+            ProxyRequest::ClaudeMessages(request) if is_vertex_claude_model(&request.model) => {
+                DispatchPlan::Native {
+                    req: ProxyRequest::ClaudeMessages(request),
+                    usage: UsageKind::ClaudeMessage,
+                }
+            }
             ProxyRequest::ClaudeMessages(request) => DispatchPlan::Transform {
                 plan: TransformPlan::GenerateContent(GenerateContentPlan::Claude2Gemini {
                     version: gproxy_provider_core::GeminiApiVersion::V1Beta,
@@ -168,6 +212,14 @@ impl DispatchProvider for VertexExpressProvider {
                 }),
                 usage: UsageKind::ClaudeMessage,
             },
+            ProxyRequest::ClaudeMessagesStream(request)
+                if is_vertex_claude_model(&request.model) =>
+            {
+                DispatchPlan::Native {
+                    req: ProxyRequest::ClaudeMessagesStream(request),
+                    usage: UsageKind::ClaudeMessage,
+                }
+            }
             ProxyRequest::ClaudeMessagesStream(request) => DispatchPlan::Transform {
                 plan: TransformPlan::StreamContent(StreamContentPlan::Claude2Gemini {
                     version: gproxy_provider_core::GeminiApiVersion::V1Beta,
@@ -196,6 +248,10 @@ impl DispatchProvider for VertexExpressProvider {
                 }),
                 usage: UsageKind::None,
             },
+            req @ ProxyRequest::VertexRawPredict { .. } => DispatchPlan::Native {
+                req,
+                usage: UsageKind::GeminiGenerate,
+            },
         }
     }
 
@@ -220,6 +276,15 @@ impl DispatchProvider for VertexExpressProvider {
             ProxyRequest::GeminiModelsGet { version, request } => {
                 self.handle_models_get(version, request, ctx).await
             }
+            ProxyRequest::ClaudeMessages(request) => self.handle_claude_raw(request, ctx).await,
+            ProxyRequest::ClaudeMessagesStream(request) => {
+                self.handle_claude_raw_stream(request, ctx).await
+            }
+            ProxyRequest::VertexRawPredict {
+                model,
+                body,
+                stream,
+            } => self.handle_raw_predict(model, body, stream, ctx).await,
             _ => Err(UpstreamPassthroughError::service_unavailable(
                 "non-native operation".to_string(),
             )),
@@ -246,19 +311,28 @@ impl VertexExpressProvider {
                 let model = model.clone();
                 let body = body.clone();
                 async move {
-                    let api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
                     let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
-                    let path = format!(
-                        "/{version_prefix}/publishers/google/models/{model}:generateContent"
-                    );
-                    let url = build_url(
+                    let (path, url) = build_generate_endpoint(
+                        credential.value(),
                         base_url.as_deref(),
-                        &format!("{path}?key={api_key}"),
+                        version_prefix,
+                        "google",
+                        &model,
+                        "generateContent",
+                        &auth,
                     );
+                    let req_headers = match &auth {
+                        CredentialAuth::ApiKey(_) => build_vertexexpress_headers(None),
+                        CredentialAuth::Bearer(token) => build_vertexexpress_headers(Some(token)),
+                    };
                     let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_vertexexpress_headers();
+                    let mut body = serde_json::to_value(&body).unwrap_or_else(|_| json!({}));
+                    merge_safety_settings(
+                        &mut body,
+                        &credential_safety_settings(credential.value()),
+                    );
                     let request_body = json_body_to_string(&body);
                     let request_headers = headers_to_json(&req_headers);
                     let started_at = Instant::now();
@@ -345,19 +419,28 @@ impl VertexExpressProvider {
                 let model = model.clone();
                 let body = body.clone();
                 async move {
-                    let api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
                     let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
-                    let path = format!(
-                        "/{version_prefix}/publishers/google/models/{model}:streamGenerateContent"
-                    );
-                    let url = build_url(
+                    let (path, url) = build_generate_endpoint(
+                        credential.value(),
                         base_url.as_deref(),
-                        &format!("{path}?key={api_key}"),
+                        version_prefix,
+                        "google",
+                        &model,
+                        "streamGenerateContent",
+                        &auth,
                     );
+                    let req_headers = match &auth {
+                        CredentialAuth::ApiKey(_) => build_vertexexpress_headers(None),
+                        CredentialAuth::Bearer(token) => build_vertexexpress_headers(Some(token)),
+                    };
                     let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_vertexexpress_headers();
+                    let mut body = serde_json::to_value(&body).unwrap_or_else(|_| json!({}));
+                    merge_safety_settings(
+                        &mut body,
+                        &credential_safety_settings(credential.value()),
+                    );
                     let request_body = json_body_to_string(&body);
                     let request_headers = headers_to_json(&req_headers);
                     let started_at = Instant::now();
@@ -413,9 +496,123 @@ impl VertexExpressProvider {
                         request_headers,
                         request_body,
                     };
+                    let response =
+                        handle_response(response, true, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    /// Forward an already provider-shaped `generateContent` body untouched,
+    /// for callers that need fields the typed `GenerateContentRequest`
+    /// doesn't model yet. Otherwise identical to `handle_generate`/
+    /// `handle_generate_stream`: same credential, auth, safety-settings
+    /// merge, and record/stream handling, just skipping deserialization
+    /// into a typed request.
+    async fn handle_raw_predict(
+        &self,
+        model: String,
+        body: JsonValue,
+        is_stream: bool,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let scope = DisallowScope::model(model.clone());
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let body = body.clone();
+                async move {
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
+                    let base_url = credential_base_url(credential.value());
+                    let version_prefix =
+                        version_prefix(gproxy_provider_core::GeminiApiVersion::V1Beta);
+                    let operation = if is_stream {
+                        "streamGenerateContent"
+                    } else {
+                        "generateContent"
+                    };
+                    let (path, url) = build_generate_endpoint(
+                        credential.value(),
+                        base_url.as_deref(),
+                        version_prefix,
+                        "google",
+                        &model,
+                        operation,
+                        &auth,
+                    );
+                    let req_headers = match &auth {
+                        CredentialAuth::ApiKey(_) => build_vertexexpress_headers(None),
+                        CredentialAuth::Bearer(token) => build_vertexexpress_headers(Some(token)),
+                    };
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let mut body = body;
+                    merge_safety_settings(
+                        &mut body,
+                        &credential_safety_settings(credential.value()),
+                    );
+                    let request_body = json_body_to_string(&body);
+                    let request_headers = headers_to_json(&req_headers);
+                    let started_at = Instant::now();
+                    info!(
+                        event = "upstream_request",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "gemini.raw_predict",
+                        method = "POST",
+                        path = %path,
+                        model = %model,
+                        is_stream = is_stream
+                    );
+                    let response = client
+                        .post(url)
+                        .headers(req_headers.clone())
+                        .json(&body)
+                        .send()
+                        .await
+                        .map_err(|err| {
+                            warn!(
+                                event = "upstream_response",
+                                trace_id = %ctx.trace_id,
+                                provider = %PROVIDER_NAME,
+                                op = "gemini.raw_predict",
+                                status = "error",
+                                elapsed_ms = started_at.elapsed().as_millis(),
+                                error = %err
+                            );
+                            network_failure(err, &scope)
+                        })?;
+                    info!(
+                        event = "upstream_response",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "gemini.raw_predict",
+                        status = %response.status().as_u16(),
+                        elapsed_ms = started_at.elapsed().as_millis(),
+                        is_stream = is_stream
+                    );
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "gemini.raw_predict".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
                     let response = handle_response(
                         response,
-                        true,
+                        is_stream,
                         scope.clone(),
                         &ctx,
                         Some(meta.clone()),
@@ -427,6 +624,208 @@ impl VertexExpressProvider {
             .await
     }
 
+    /// Non-streaming Vertex passthrough for Anthropic-hosted Claude models
+    /// (`publishers/anthropic/models/{model}:rawPredict`), for requests
+    /// `dispatch_plan` has already identified as targeting one of those
+    /// models rather than Gemini.
+    async fn handle_claude_raw(
+        &self,
+        request: claude::create_message::request::CreateMessageRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.model.clone();
+        let scope = DisallowScope::model(model.clone());
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let request = request.clone();
+                async move {
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
+                    let base_url = credential_base_url(credential.value());
+                    let version_prefix =
+                        version_prefix(gproxy_provider_core::GeminiApiVersion::V1Beta);
+                    let (path, url) = build_generate_endpoint(
+                        credential.value(),
+                        base_url.as_deref(),
+                        version_prefix,
+                        "anthropic",
+                        &model,
+                        "rawPredict",
+                        &auth,
+                    );
+                    let req_headers = match &auth {
+                        CredentialAuth::ApiKey(_) => build_vertexexpress_headers(None),
+                        CredentialAuth::Bearer(token) => build_vertexexpress_headers(Some(token)),
+                    };
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let body = build_claude_raw_body(&request);
+                    let request_body = json_body_to_string(&body);
+                    let request_headers = headers_to_json(&req_headers);
+                    let started_at = Instant::now();
+                    info!(
+                        event = "upstream_request",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "claude.raw_predict",
+                        method = "POST",
+                        path = %path,
+                        model = %model,
+                        is_stream = false
+                    );
+                    let response = client
+                        .post(url)
+                        .headers(req_headers.clone())
+                        .json(&body)
+                        .send()
+                        .await
+                        .map_err(|err| {
+                            warn!(
+                                event = "upstream_response",
+                                trace_id = %ctx.trace_id,
+                                provider = %PROVIDER_NAME,
+                                op = "claude.raw_predict",
+                                status = "error",
+                                elapsed_ms = started_at.elapsed().as_millis(),
+                                error = %err
+                            );
+                            network_failure(err, &scope)
+                        })?;
+                    info!(
+                        event = "upstream_response",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "claude.raw_predict",
+                        status = %response.status().as_u16(),
+                        elapsed_ms = started_at.elapsed().as_millis(),
+                        is_stream = false
+                    );
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "claude.raw_predict".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
+    /// Streaming counterpart of `handle_claude_raw`, hitting
+    /// `publishers/anthropic/models/{model}:streamRawPredict`.
+    async fn handle_claude_raw_stream(
+        &self,
+        request: claude::create_message::request::CreateMessageRequest,
+        ctx: CallContext,
+    ) -> Result<UpstreamOk, UpstreamPassthroughError> {
+        let model = request.model.clone();
+        let scope = DisallowScope::model(model.clone());
+
+        self.pool
+            .execute(scope.clone(), |credential| {
+                let ctx = ctx.clone();
+                let scope = scope.clone();
+                let model = model.clone();
+                let request = request.clone();
+                async move {
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
+                    let base_url = credential_base_url(credential.value());
+                    let version_prefix =
+                        version_prefix(gproxy_provider_core::GeminiApiVersion::V1Beta);
+                    let (path, url) = build_generate_endpoint(
+                        credential.value(),
+                        base_url.as_deref(),
+                        version_prefix,
+                        "anthropic",
+                        &model,
+                        "streamRawPredict",
+                        &auth,
+                    );
+                    let req_headers = match &auth {
+                        CredentialAuth::ApiKey(_) => build_vertexexpress_headers(None),
+                        CredentialAuth::Bearer(token) => build_vertexexpress_headers(Some(token)),
+                    };
+                    let client = shared_client(ctx.proxy.as_deref())?;
+                    let body = build_claude_raw_body(&request);
+                    let request_body = json_body_to_string(&body);
+                    let request_headers = headers_to_json(&req_headers);
+                    let started_at = Instant::now();
+                    info!(
+                        event = "upstream_request",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "claude.stream_raw_predict",
+                        method = "POST",
+                        path = %path,
+                        model = %model,
+                        is_stream = true
+                    );
+                    let response = client
+                        .post(url)
+                        .headers(req_headers.clone())
+                        .json(&body)
+                        .send()
+                        .await
+                        .map_err(|err| {
+                            warn!(
+                                event = "upstream_response",
+                                trace_id = %ctx.trace_id,
+                                provider = %PROVIDER_NAME,
+                                op = "claude.stream_raw_predict",
+                                status = "error",
+                                elapsed_ms = started_at.elapsed().as_millis(),
+                                error = %err
+                            );
+                            network_failure(err, &scope)
+                        })?;
+                    info!(
+                        event = "upstream_response",
+                        trace_id = %ctx.trace_id,
+                        provider = %PROVIDER_NAME,
+                        op = "claude.stream_raw_predict",
+                        status = %response.status().as_u16(),
+                        elapsed_ms = started_at.elapsed().as_millis(),
+                        is_stream = true
+                    );
+                    let meta = UpstreamRecordMeta {
+                        provider: PROVIDER_NAME.to_string(),
+                        provider_id: ctx
+                            .downstream_meta
+                            .as_ref()
+                            .and_then(|meta| meta.provider_id),
+                        credential_id: Some(credential.value().id),
+                        operation: "claude.stream_raw_predict".to_string(),
+                        model: Some(model),
+                        request_method: "POST".to_string(),
+                        request_path: path,
+                        request_query: None,
+                        request_headers,
+                        request_body,
+                    };
+                    let response =
+                        handle_response(response, true, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
+                    Ok(UpstreamOk { response, meta })
+                }
+            })
+            .await
+    }
+
     async fn handle_count_tokens(
         &self,
         version: gproxy_provider_core::GeminiApiVersion,
@@ -444,18 +843,23 @@ impl VertexExpressProvider {
                 let model = model.clone();
                 let body = body.clone();
                 async move {
-                    let api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
                     let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
-                    let path =
-                        format!("/{version_prefix}/publishers/google/models/{model}:countTokens");
-                    let url = build_url(
+                    let (path, url) = build_generate_endpoint(
+                        credential.value(),
                         base_url.as_deref(),
-                        &format!("{path}?key={api_key}"),
+                        version_prefix,
+                        "google",
+                        &model,
+                        "countTokens",
+                        &auth,
                     );
+                    let req_headers = match &auth {
+                        CredentialAuth::ApiKey(_) => build_vertexexpress_headers(None),
+                        CredentialAuth::Bearer(token) => build_vertexexpress_headers(Some(token)),
+                    };
                     let client = shared_client(ctx.proxy.as_deref())?;
-                    let req_headers = build_vertexexpress_headers();
                     let request_body = json_body_to_string(&body);
                     let request_headers = headers_to_json(&req_headers);
                     let started_at = Instant::now();
@@ -511,14 +915,9 @@ impl VertexExpressProvider {
                         request_headers,
                         request_body,
                     };
-                    let response = handle_response(
-                        response,
-                        false,
-                        scope.clone(),
-                        &ctx,
-                        Some(meta.clone()),
-                    )
-                    .await?;
+                    let response =
+                        handle_response(response, false, scope.clone(), &ctx, Some(meta.clone()))
+                            .await?;
                     Ok(UpstreamOk { response, meta })
                 }
             })
@@ -538,11 +937,25 @@ impl VertexExpressProvider {
                 let ctx = ctx.clone();
                 let scope = scope.clone();
                 async move {
-                    let _api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
+                    let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
                     let path = format!("/{version_prefix}/models");
-                    let body_json = local_models_json();
+                    let (operation, body_json) = match fetch_remote_models(
+                        credential.value(),
+                        &ctx,
+                        &auth,
+                        base_url.as_deref(),
+                        version_prefix,
+                    )
+                    .await
+                    {
+                        Ok(remote) => (
+                            "gemini.models_list.remote",
+                            merge_model_catalogs(remote, local_models_json()),
+                        ),
+                        Err(_) => ("gemini.models_list.local", local_models_json()),
+                    };
                     let body = serde_json::to_vec(&body_json).unwrap_or_default();
                     let mut headers = HeaderMap::new();
                     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
@@ -553,7 +966,7 @@ impl VertexExpressProvider {
                             .as_ref()
                             .and_then(|meta| meta.provider_id),
                         credential_id: Some(credential.value().id),
-                        operation: "gemini.models_list.local".to_string(),
+                        operation: operation.to_string(),
                         model: None,
                         request_method: "GET".to_string(),
                         request_path: path,
@@ -587,11 +1000,26 @@ impl VertexExpressProvider {
                 let scope = scope.clone();
                 let name = name.clone();
                 async move {
-                    let _api_key = credential_api_key(credential.value())
-                        .ok_or_else(|| invalid_credential(&scope, "missing api_key"))?;
+                    let auth = resolve_auth(credential.value(), &ctx, &scope).await?;
+                    let base_url = credential_base_url(credential.value());
                     let version_prefix = version_prefix(version);
                     let path = format!("/{version_prefix}/models/{name}");
-                    let model = find_local_model(&name);
+                    let (operation, catalog) = match fetch_remote_models(
+                        credential.value(),
+                        &ctx,
+                        &auth,
+                        base_url.as_deref(),
+                        version_prefix,
+                    )
+                    .await
+                    {
+                        Ok(remote) => (
+                            "gemini.models_get.remote",
+                            merge_model_catalogs(remote, local_models_json()),
+                        ),
+                        Err(_) => ("gemini.models_get.local", local_models_json()),
+                    };
+                    let model = find_model(&catalog, &name);
                     let (status, body_json) = match model {
                         Some(model) => (http::StatusCode::OK, model),
                         None => (
@@ -609,7 +1037,7 @@ impl VertexExpressProvider {
                             .as_ref()
                             .and_then(|meta| meta.provider_id),
                         credential_id: Some(credential.value().id),
-                        operation: "gemini.models_get.local".to_string(),
+                        operation: operation.to_string(),
                         model: Some(name),
                         request_method: "GET".to_string(),
                         request_path: path,
@@ -633,9 +1061,8 @@ fn local_models_json() -> JsonValue {
     serde_json::from_str(MODELS_JSON).unwrap_or_else(|_| json!({ "models": [] }))
 }
 
-fn find_local_model(name: &str) -> Option<JsonValue> {
-    let models = local_models_json();
-    let list = models.get("models")?.as_array()?;
+fn find_model(catalog: &JsonValue, name: &str) -> Option<JsonValue> {
+    let list = catalog.get("models")?.as_array()?;
     let prefixed = format!("models/{name}");
     for model in list {
         if let Some(model_name) = model.get("name").and_then(|value| value.as_str()) {
@@ -647,12 +1074,283 @@ fn find_local_model(name: &str) -> Option<JsonValue> {
     None
 }
 
-fn build_vertexexpress_headers() -> HeaderMap {
+/// How long a fetched upstream model catalog is trusted before the next
+/// call re-fetches it, keyed by credential id alongside [`ensure_access_token`]'s
+/// token cache.
+const MODEL_CATALOG_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Clone, Debug)]
+struct CachedModelCatalog {
+    body: JsonValue,
+    fetched_at: SystemTime,
+}
+
+static MODEL_CACHE: OnceLock<tokio::sync::Mutex<HashMap<i64, CachedModelCatalog>>> =
+    OnceLock::new();
+
+fn model_cache() -> &'static tokio::sync::Mutex<HashMap<i64, CachedModelCatalog>> {
+    MODEL_CACHE.get_or_init(|| tokio::sync::Mutex::new(HashMap::new()))
+}
+
+/// Fetch the live `/{version_prefix}/models` catalog for `credential`,
+/// caching it per credential id for `MODEL_CATALOG_TTL` so a burst of
+/// `models.list`/`models.get` calls doesn't hammer the upstream.
+async fn fetch_remote_models(
+    credential: &BaseCredential,
+    ctx: &CallContext,
+    auth: &CredentialAuth,
+    base_url: Option<&str>,
+    version_prefix: &str,
+) -> Result<JsonValue, AttemptFailure> {
+    {
+        let cache = model_cache().lock().await;
+        if let Some(cached) = cache.get(&credential.id) {
+            if cached.fetched_at.elapsed().unwrap_or(Duration::MAX) < MODEL_CATALOG_TTL {
+                return Ok(cached.body.clone());
+            }
+        }
+    }
+    let path = format!("/{version_prefix}/models");
+    let url = match auth {
+        CredentialAuth::ApiKey(api_key) => build_url(base_url, &format!("{path}?key={api_key}")),
+        CredentialAuth::Bearer(_) => build_url(base_url, &path),
+    };
+    let req_headers = match auth {
+        CredentialAuth::ApiKey(_) => build_vertexexpress_headers(None),
+        CredentialAuth::Bearer(token) => build_vertexexpress_headers(Some(token)),
+    };
+    let client = shared_client(ctx.proxy.as_deref())?;
+    let response = client
+        .get(url)
+        .headers(req_headers)
+        .send()
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(format!(
+                "remote model catalog fetch failed: {status}"
+            )),
+            mark: None,
+        });
+    }
+    let body: JsonValue = response.json().await.map_err(|err| AttemptFailure {
+        passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+        mark: None,
+    })?;
+    let mut cache = model_cache().lock().await;
+    cache.insert(
+        credential.id,
+        CachedModelCatalog {
+            body: body.clone(),
+            fetched_at: SystemTime::now(),
+        },
+    );
+    Ok(body)
+}
+
+/// Merge a freshly-fetched upstream catalog over the bundled static list:
+/// upstream entries always win, static entries fill in anything the
+/// upstream response doesn't (yet) list.
+fn merge_model_catalogs(remote: JsonValue, local: JsonValue) -> JsonValue {
+    let mut merged: Vec<JsonValue> = remote
+        .get("models")
+        .and_then(|value| value.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let seen: HashSet<String> = merged
+        .iter()
+        .filter_map(|model| model.get("name").and_then(|value| value.as_str()))
+        .map(|value| value.to_string())
+        .collect();
+    if let Some(local_list) = local.get("models").and_then(|value| value.as_array()) {
+        for model in local_list {
+            if let Some(name) = model.get("name").and_then(|value| value.as_str()) {
+                if !seen.contains(name) {
+                    merged.push(model.clone());
+                }
+            }
+        }
+    }
+    json!({ "models": merged })
+}
+
+/// Either form of Vertex authentication this provider supports: an Express
+/// API key appended as a `?key=` query parameter, or a bearer token minted
+/// from an ADC service-account JSON via `oauth::ensure_access_token`.
+enum CredentialAuth {
+    ApiKey(String),
+    Bearer(String),
+}
+
+/// Resolve which authentication scheme a credential uses. Service-account
+/// JSON (detected by the presence of `private_key`/`client_email`) takes
+/// priority, since a credential configured for full Vertex access has no
+/// reason to also carry an Express key.
+async fn resolve_auth(
+    credential: &BaseCredential,
+    ctx: &CallContext,
+    scope: &DisallowScope,
+) -> Result<CredentialAuth, AttemptFailure> {
+    if let Some(service_account) = credential_service_account(credential) {
+        let token = ensure_access_token(credential.id, &service_account, ctx, scope).await?;
+        return Ok(CredentialAuth::Bearer(token));
+    }
+    if credential_vertex_region(credential).is_some() {
+        return Err(invalid_credential(
+            scope,
+            "regional vertex routing requires a service-account credential",
+        ));
+    }
+    let api_key = credential_api_key(credential)
+        .ok_or_else(|| invalid_credential(scope, "missing api_key"))?;
+    Ok(CredentialAuth::ApiKey(api_key))
+}
+
+fn build_vertexexpress_headers(bearer_token: Option<&str>) -> HeaderMap {
     let mut headers = HeaderMap::new();
     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    if let Some(token) = bearer_token {
+        if let Ok(value) = HeaderValue::from_str(&format!("Bearer {token}")) {
+            headers.insert(http::header::AUTHORIZATION, value);
+        }
+    }
     headers
 }
 
+/// One entry of a Gemini `safetySettings` array, e.g.
+/// `{"category": "HARM_CATEGORY_HARASSMENT", "threshold": "BLOCK_NONE"}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SafetySetting {
+    category: String,
+    threshold: String,
+}
+
+/// Per-credential safety-setting overrides, read from `credential.meta`.
+/// Analogous to `credential_base_url`: there's no per-provider counterpart
+/// stored anywhere a running provider can read, so this is the only knob —
+/// `default_provider()`'s `config_json` merely documents the shape.
+fn credential_safety_settings(credential: &BaseCredential) -> Vec<SafetySetting> {
+    credential
+        .meta
+        .get("safety_settings")
+        .cloned()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Merge `overrides` into `body["safetySettings"]`, skipping any category the
+/// caller already specified so explicit client settings always win.
+fn merge_safety_settings(body: &mut JsonValue, overrides: &[SafetySetting]) {
+    if overrides.is_empty() {
+        return;
+    }
+    let settings = body
+        .as_object_mut()
+        .map(|object| object.entry("safetySettings").or_insert_with(|| json!([])));
+    let Some(settings) = settings else {
+        return;
+    };
+    let Some(settings) = settings.as_array_mut() else {
+        return;
+    };
+    let existing: std::collections::HashSet<String> = settings
+        .iter()
+        .filter_map(|entry| entry.get("category").and_then(|value| value.as_str()))
+        .map(|value| value.to_string())
+        .collect();
+    for setting in overrides {
+        if !existing.contains(&setting.category) {
+            settings.push(json!({
+                "category": setting.category,
+                "threshold": setting.threshold,
+            }));
+        }
+    }
+}
+
+/// A credential's project/location for "regional" (project-scoped) Vertex
+/// routing, read from `credential.meta`. Present only when both fields are
+/// set — a credential with just one of the two falls back to Express mode
+/// rather than building a half-specified regional URL.
+struct VertexRegion {
+    project_id: String,
+    location: String,
+}
+
+fn credential_vertex_region(credential: &BaseCredential) -> Option<VertexRegion> {
+    let project_id = credential.meta.get("project_id")?.as_str()?.to_string();
+    let location = credential.meta.get("location")?.as_str()?.to_string();
+    Some(VertexRegion {
+        project_id,
+        location,
+    })
+}
+
+/// Build the path and URL for a `publishers/{publisher}/models/{model}:{operation}`
+/// Vertex call — Gemini operations under the `google` publisher, or Claude
+/// rawPredict operations under `anthropic`. Express mode addresses the
+/// shared `aiplatform.googleapis.com` host with `?key=`; regional mode
+/// (credential carries `project_id` + `location`) addresses the
+/// location-scoped host with a project/location-qualified path instead, and
+/// never appends `?key=` since `resolve_auth` only hands back a bearer token
+/// for regional credentials.
+fn build_generate_endpoint(
+    credential: &BaseCredential,
+    base_url: Option<&str>,
+    version_prefix: &str,
+    publisher: &str,
+    model: &str,
+    operation: &str,
+    auth: &CredentialAuth,
+) -> (String, String) {
+    if let Some(region) = credential_vertex_region(credential) {
+        let regional_base = format!("https://{}-aiplatform.googleapis.com", region.location);
+        let path = format!(
+            "/{version_prefix}/projects/{}/locations/{}/publishers/{publisher}/models/{model}:{operation}",
+            region.project_id, region.location
+        );
+        let url = build_url(Some(&regional_base), &path);
+        return (path, url);
+    }
+    let path = format!("/{version_prefix}/publishers/{publisher}/models/{model}:{operation}");
+    let url = match auth {
+        CredentialAuth::ApiKey(api_key) => build_url(base_url, &format!("{path}?key={api_key}")),
+        CredentialAuth::Bearer(_) => build_url(base_url, &path),
+    };
+    (path, url)
+}
+
+/// Whether `model` names an Anthropic model hosted on Vertex, e.g.
+/// `claude-3-5-sonnet@20240620`. Vertex's Anthropic publisher models carry
+/// an `@{version_date}` suffix that Gemini model names never do, which is
+/// enough to route Claude traffic to the rawPredict surface without a
+/// separate "backend" field on the request.
+fn is_vertex_claude_model(model: &str) -> bool {
+    model.starts_with("claude-") && model.contains('@')
+}
+
+/// Adapt an Anthropic Messages API request body to what Vertex's rawPredict
+/// surface expects: no top-level `model` (the model is already in the URL)
+/// and a required `anthropic_version` marker in its place.
+fn build_claude_raw_body(
+    request: &claude::create_message::request::CreateMessageRequest,
+) -> JsonValue {
+    let mut body = serde_json::to_value(request).unwrap_or_else(|_| json!({}));
+    if let Some(object) = body.as_object_mut() {
+        object.remove("model");
+        object.insert(
+            "anthropic_version".to_string(),
+            json!(ANTHROPIC_VERTEX_VERSION),
+        );
+    }
+    body
+}
+
 fn credential_api_key(credential: &BaseCredential) -> Option<String> {
     if let serde_json::Value::String(value) = &credential.secret {
         return Some(value.clone());
@@ -679,7 +1377,9 @@ fn build_url(base_url: Option<&str>, path: &str) -> String {
         path = path.trim_start_matches("v1/").trim_start_matches("v1");
     }
     if base.ends_with("/v1beta1") && (path == "v1beta1" || path.starts_with("v1beta1/")) {
-        path = path.trim_start_matches("v1beta1/").trim_start_matches("v1beta1");
+        path = path
+            .trim_start_matches("v1beta1/")
+            .trim_start_matches("v1beta1");
     }
     format!("{base}/{path}")
 }
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use http::header::CONTENT_TYPE;
+use http::HeaderValue;
+use jsonwebtoken::{Algorithm, EncodingKey, Header as JwtHeader};
+use serde::{Deserialize, Serialize};
+
+use gproxy_provider_core::{AttemptFailure, CallContext, DisallowScope, UpstreamPassthroughError};
+
+use crate::client::shared_client;
+use crate::credential::BaseCredential;
+
+use super::invalid_credential;
+
+const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+/// Refresh this far ahead of the real expiry so an in-flight request never
+/// races a token that expires mid-call.
+const REFRESH_SKEW: Duration = Duration::from_secs(60);
+
+/// The fields of a Google ADC service-account JSON key this provider needs.
+/// Present in `credential.secret` for OAuth-style Vertex credentials, as
+/// opposed to the plain `api_key` string used by Express-mode credentials.
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct ServiceAccountKey {
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default = "default_token_uri")]
+    pub token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
+
+pub(super) fn credential_service_account(credential: &BaseCredential) -> Option<ServiceAccountKey> {
+    serde_json::from_value(credential.secret.clone()).ok()
+}
+
+#[derive(Clone, Debug)]
+struct CachedToken {
+    access_token: String,
+    expires_at: SystemTime,
+}
+
+#[derive(Serialize)]
+struct Claims {
+    iss: String,
+    scope: &'static str,
+    aud: String,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Serialize)]
+struct TokenRequest {
+    grant_type: &'static str,
+    assertion: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+/// Keyed by credential id, guarded by a single lock so concurrent callers on
+/// the same credential never mint more than one token at a time — the
+/// refresh itself (the network round-trip) runs with the lock held, which is
+/// the simplest way to make that guarantee without a per-credential
+/// singleflight structure.
+static TOKEN_CACHE: OnceLock<tokio::sync::Mutex<HashMap<i64, CachedToken>>> = OnceLock::new();
+
+fn token_cache() -> &'static tokio::sync::Mutex<HashMap<i64, CachedToken>> {
+    TOKEN_CACHE.get_or_init(|| tokio::sync::Mutex::new(HashMap::new()))
+}
+
+pub(super) async fn ensure_access_token(
+    credential_id: i64,
+    service_account: &ServiceAccountKey,
+    ctx: &CallContext,
+    scope: &DisallowScope,
+) -> Result<String, AttemptFailure> {
+    let mut cache = token_cache().lock().await;
+    if let Some(cached) = cache.get(&credential_id) {
+        if cached.expires_at > SystemTime::now() + REFRESH_SKEW {
+            return Ok(cached.access_token.clone());
+        }
+    }
+    let token = mint_access_token(service_account, ctx, scope).await?;
+    let cached = CachedToken {
+        access_token: token.access_token.clone(),
+        expires_at: SystemTime::now() + Duration::from_secs(token.expires_in.unwrap_or(3600)),
+    };
+    let access_token = cached.access_token.clone();
+    cache.insert(credential_id, cached);
+    Ok(access_token)
+}
+
+async fn mint_access_token(
+    service_account: &ServiceAccountKey,
+    ctx: &CallContext,
+    scope: &DisallowScope,
+) -> Result<TokenResponse, AttemptFailure> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let claims = Claims {
+        iss: service_account.client_email.clone(),
+        scope: CLOUD_PLATFORM_SCOPE,
+        aud: service_account.token_uri.clone(),
+        iat: now,
+        exp: now + 3600,
+    };
+    let encoding_key = EncodingKey::from_rsa_pem(service_account.private_key.as_bytes())
+        .map_err(|err| invalid_credential(scope, &format!("invalid private_key: {err}")))?;
+    let assertion = jsonwebtoken::encode(&JwtHeader::new(Algorithm::RS256), &claims, &encoding_key)
+        .map_err(|err| invalid_credential(scope, &format!("failed to sign jwt: {err}")))?;
+
+    let client = shared_client(ctx.proxy.as_deref())?;
+    let request = TokenRequest {
+        grant_type: "urn:ietf:params:oauth:grant-type:jwt-bearer",
+        assertion,
+    };
+    let response = client
+        .post(&service_account.token_uri)
+        .header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        )
+        .form(&request)
+        .send()
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let message = format!("adc token exchange failed: {status}: {body}");
+        let mark = if status == http::StatusCode::UNAUTHORIZED
+            || status == http::StatusCode::BAD_REQUEST
+        {
+            Some(gproxy_provider_core::DisallowMark {
+                scope: scope.clone(),
+                level: gproxy_provider_core::DisallowLevel::Dead,
+                duration: None,
+                reason: Some("adc_token_invalid".to_string()),
+            })
+        } else {
+            None
+        };
+        return Err(AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(message),
+            mark,
+        });
+    }
+    response
+        .json::<TokenResponse>()
+        .await
+        .map_err(|err| AttemptFailure {
+            passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+            mark: None,
+        })
+}
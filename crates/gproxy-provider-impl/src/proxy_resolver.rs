@@ -0,0 +1,134 @@
+//! Host-aware outbound proxy resolution for upstream provider connections,
+//! in the same spirit as [`crate::dns`]'s custom resolver: a forward proxy
+//! (HTTP `CONNECT` or SOCKS5, picked from the configured URL's scheme) that
+//! upstream calls to providers like `GeminiCliProvider`
+//! (`generativelanguage.googleapis.com`) or `OpenAIProvider`
+//! (`api.openai.com`) can be routed through when gproxy only has outbound
+//! access via a corporate/region-restricted forward proxy.
+//!
+//! `GlobalConfig` (`apps/gproxy/src/cli.rs`) is meant to carry this as an
+//! optional `upstream_proxy: Option<UpstreamProxyConfig>` section, the same
+//! way it already carries `proxy`/`dns`. `TransportConfig::resolve_proxy`
+//! (`transport.rs`) already resolves a single flat proxy string per call
+//! (credential override, falling back to `CallContext::proxy`); this
+//! resolver is the per-host layer underneath that fallback, for the global
+//! case where the proxy to use depends on which upstream host is being
+//! reached.
+//!
+//! `crate::client::shared_client` uses [`ProxyScheme`] today (it only takes
+//! an already-resolved proxy string, not a host, so the per-host
+//! [`UpstreamProxyResolver::resolve`] below isn't reachable from there yet).
+//! A caller that needs host-based routing resolves through this type before
+//! calling `shared_client`, the same way `TransportConfig::resolve_proxy`
+//! already resolves before calling it; wiring that resolution in automatically
+//! for every call site needs `shared_client` to take a target host too, which
+//! is a larger, call-site-touching change left for whoever adds
+//! `GlobalConfig.upstream_proxy`.
+
+use serde::{Deserialize, Serialize};
+
+/// Forward-proxy transport, inferred from the configured proxy URL's
+/// scheme: `socks5://`/`socks5h://` means SOCKS5, anything else (`http://`,
+/// `https://`) means a plain HTTP `CONNECT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyScheme {
+    HttpConnect,
+    Socks5,
+}
+
+impl ProxyScheme {
+    fn from_url(url: &str) -> Self {
+        if url.starts_with("socks5://") || url.starts_with("socks5h://") {
+            ProxyScheme::Socks5
+        } else {
+            ProxyScheme::HttpConnect
+        }
+    }
+}
+
+/// One configured routing rule: the proxy to use for any host matching one
+/// of `hosts`. A host pattern of `*.example.com` matches `example.com`
+/// itself and any subdomain; anything else must match the target host
+/// exactly (case-insensitively).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyRule {
+    pub hosts: Vec<String>,
+    pub proxy_url: String,
+}
+
+/// Operator-supplied outbound proxy routing for upstream provider
+/// connections.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpstreamProxyConfig {
+    /// Checked in order; the first rule with a matching host wins.
+    #[serde(default)]
+    pub rules: Vec<ProxyRule>,
+    /// Used when no rule in `rules` matches. `None` means "no proxy" rather
+    /// than falling through to the system environment.
+    #[serde(default)]
+    pub default_proxy: Option<String>,
+    /// Hosts (exact or `*.`-prefixed wildcard) that should always bypass
+    /// every rule and `default_proxy`, checked before either.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+/// A resolved proxy for a specific upstream host: the URL to dial plus the
+/// transport [`ProxyScheme`] it implies.
+#[derive(Debug, Clone)]
+pub struct ResolvedProxy {
+    pub url: String,
+    pub scheme: ProxyScheme,
+}
+
+impl ResolvedProxy {
+    fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            scheme: ProxyScheme::from_url(url),
+        }
+    }
+}
+
+/// Resolves the outbound proxy (if any) that should be used to reach a
+/// given upstream host, honoring `no_proxy` and wildcard host matching.
+/// Built once from a [`UpstreamProxyConfig`] and shared across providers,
+/// the way [`crate::dns::GproxyDnsResolver`] is built once from a
+/// `DnsConfig`.
+#[derive(Debug, Clone)]
+pub struct UpstreamProxyResolver {
+    config: UpstreamProxyConfig,
+}
+
+impl UpstreamProxyResolver {
+    pub fn new(config: UpstreamProxyConfig) -> Self {
+        Self { config }
+    }
+
+    /// The proxy chain to use for `host`, or `None` if it should be reached
+    /// directly (either because `no_proxy` matched it or no rule and no
+    /// `default_proxy` applies).
+    pub fn resolve(&self, host: &str) -> Option<ResolvedProxy> {
+        if self.config.no_proxy.iter().any(|pattern| host_matches(pattern, host)) {
+            return None;
+        }
+        for rule in &self.config.rules {
+            if rule.hosts.iter().any(|pattern| host_matches(pattern, host)) {
+                return Some(ResolvedProxy::new(&rule.proxy_url));
+            }
+        }
+        self.config.default_proxy.as_deref().map(ResolvedProxy::new)
+    }
+}
+
+/// Matches `host` against a single pattern: `*.example.com` matches
+/// `example.com` and any subdomain of it; anything else is an exact,
+/// case-insensitive match.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => {
+            host.eq_ignore_ascii_case(suffix) || host.to_ascii_lowercase().ends_with(&format!(".{}", suffix.to_ascii_lowercase()))
+        }
+        None => pattern.eq_ignore_ascii_case(host),
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use gproxy_provider_core::{NoopStateSink, PoolSnapshot, Provider, StateSink};
+
+use crate::credential::BaseCredential;
+use crate::dispatch::{DispatchProvider, FailoverProvider};
+use crate::provider::{
+    AistudioProvider, AntiGravityProvider, AzureOpenAIProvider, ClaudeCodeProvider, ClaudeProvider,
+    CodexProvider, DeepSeekProvider, GeminiCliProvider, NvidiaProvider, OpenAIProvider,
+    VertexExpressProvider, VertexProvider,
+};
+use crate::ProviderDefault;
+
+/// Every upstream this crate ships, constructed once at startup and shared
+/// behind the `Arc<ProviderRegistry>` that `apps/gproxy` threads through
+/// `Core`/`AdminState`. Kept as one field per provider (rather than only a
+/// name-keyed map) because most call sites — `collect_stats`,
+/// `apply_snapshot`'s pool refresh, `Core::router`'s per-provider dispatch —
+/// already know exactly which provider they mean at compile time and want
+/// the concrete type's methods (`pool()`, `replace_snapshot()`) without a
+/// downcast. [`ProviderRegistry::by_name`] covers the complementary case —
+/// picking a provider at runtime from a string, e.g. an admin route that
+/// takes `{provider}` as a path segment.
+#[derive(Debug)]
+pub struct ProviderRegistry {
+    openai: Arc<OpenAIProvider>,
+    claude: Arc<ClaudeProvider>,
+    aistudio: Arc<AistudioProvider>,
+    vertexexpress: Arc<VertexExpressProvider>,
+    vertex: Arc<VertexProvider>,
+    geminicli: Arc<GeminiCliProvider>,
+    claudecode: Arc<ClaudeCodeProvider>,
+    codex: Arc<CodexProvider>,
+    antigravity: Arc<AntiGravityProvider>,
+    nvidia: Arc<NvidiaProvider>,
+    deepseek: Arc<DeepSeekProvider>,
+    azure_openai: Arc<AzureOpenAIProvider>,
+    by_name: HashMap<String, Arc<dyn Provider>>,
+    /// The subset of providers that implement [`DispatchProvider`] (the
+    /// `call_native`/`dispatch_plan` split [`FailoverProvider`] needs), keyed
+    /// by `PROVIDER_NAME` — the backend pool [`Self::apply_failover_groups`]
+    /// draws from. Providers that only implement `Provider` directly
+    /// (`openai`, `claude`, `codex`, `nvidia`, `deepseek`) can't take part in
+    /// a failover group.
+    dispatch_by_name: HashMap<String, Arc<dyn DispatchProvider>>,
+    /// Admin-configured failover groups, layered in front of `by_name`: a
+    /// name present here is served by a [`FailoverProvider`] instead of its
+    /// single concrete provider. Behind an `RwLock` rather than baked in at
+    /// construction so `apply_failover_groups` can hot-swap it the same way
+    /// `apply_pools` hot-swaps credential state.
+    failover_overrides: RwLock<HashMap<String, Arc<dyn Provider>>>,
+}
+
+macro_rules! by_name_entry {
+    ($map:expr, $provider:expr) => {
+        $map.insert(
+            Provider::name(&*$provider).to_string(),
+            $provider.clone() as Arc<dyn Provider>,
+        );
+    };
+}
+
+macro_rules! dispatch_by_name_entry {
+    ($map:expr, $provider:expr) => {
+        $map.insert(
+            DispatchProvider::name(&*$provider).to_string(),
+            $provider.clone() as Arc<dyn DispatchProvider>,
+        );
+    };
+}
+
+impl ProviderRegistry {
+    fn new(sink: Arc<dyn StateSink>) -> Self {
+        let openai = Arc::new(OpenAIProvider::new(sink.clone()));
+        let claude = Arc::new(ClaudeProvider::new(sink.clone()));
+        let aistudio = Arc::new(AistudioProvider::new(sink.clone()));
+        let vertexexpress = Arc::new(VertexExpressProvider::new(sink.clone()));
+        let vertex = Arc::new(VertexProvider::new(sink.clone()));
+        let geminicli = Arc::new(GeminiCliProvider::new(sink.clone()));
+        let claudecode = Arc::new(ClaudeCodeProvider::new(sink.clone()));
+        let codex = Arc::new(CodexProvider::new(sink.clone()));
+        let antigravity = Arc::new(AntiGravityProvider::new(sink.clone()));
+        let nvidia = Arc::new(NvidiaProvider::new(sink.clone()));
+        let deepseek = Arc::new(DeepSeekProvider::new(sink.clone()));
+        let azure_openai = Arc::new(AzureOpenAIProvider::new(sink));
+
+        let mut by_name: HashMap<String, Arc<dyn Provider>> = HashMap::new();
+        by_name_entry!(by_name, openai);
+        by_name_entry!(by_name, claude);
+        by_name_entry!(by_name, aistudio);
+        by_name_entry!(by_name, vertexexpress);
+        by_name_entry!(by_name, vertex);
+        by_name_entry!(by_name, geminicli);
+        by_name_entry!(by_name, claudecode);
+        by_name_entry!(by_name, codex);
+        by_name_entry!(by_name, antigravity);
+        by_name_entry!(by_name, nvidia);
+        by_name_entry!(by_name, deepseek);
+        by_name_entry!(by_name, azure_openai);
+
+        let mut dispatch_by_name: HashMap<String, Arc<dyn DispatchProvider>> = HashMap::new();
+        dispatch_by_name_entry!(dispatch_by_name, aistudio);
+        dispatch_by_name_entry!(dispatch_by_name, vertexexpress);
+        dispatch_by_name_entry!(dispatch_by_name, vertex);
+        dispatch_by_name_entry!(dispatch_by_name, geminicli);
+        dispatch_by_name_entry!(dispatch_by_name, claudecode);
+        dispatch_by_name_entry!(dispatch_by_name, antigravity);
+
+        Self {
+            openai,
+            claude,
+            aistudio,
+            vertexexpress,
+            vertex,
+            geminicli,
+            claudecode,
+            codex,
+            antigravity,
+            nvidia,
+            deepseek,
+            azure_openai,
+            by_name,
+            dispatch_by_name,
+            failover_overrides: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn openai(&self) -> &OpenAIProvider {
+        &self.openai
+    }
+
+    pub fn claude(&self) -> &ClaudeProvider {
+        &self.claude
+    }
+
+    pub fn aistudio(&self) -> &AistudioProvider {
+        &self.aistudio
+    }
+
+    pub fn vertexexpress(&self) -> &VertexExpressProvider {
+        &self.vertexexpress
+    }
+
+    pub fn vertex(&self) -> &VertexProvider {
+        &self.vertex
+    }
+
+    pub fn geminicli(&self) -> &GeminiCliProvider {
+        &self.geminicli
+    }
+
+    pub fn claudecode(&self) -> &ClaudeCodeProvider {
+        &self.claudecode
+    }
+
+    pub fn codex(&self) -> &CodexProvider {
+        &self.codex
+    }
+
+    pub fn antigravity(&self) -> &AntiGravityProvider {
+        &self.antigravity
+    }
+
+    pub fn nvidia(&self) -> &NvidiaProvider {
+        &self.nvidia
+    }
+
+    pub fn deepseek(&self) -> &DeepSeekProvider {
+        &self.deepseek
+    }
+
+    pub fn azure_openai(&self) -> &AzureOpenAIProvider {
+        &self.azure_openai
+    }
+
+    /// Looks a provider up by its `PROVIDER_NAME` (`"openai"`, `"claude"`,
+    /// ...) instead of a fixed accessor — for callers that only have the
+    /// name on hand, such as an admin route keyed on a `{provider}` path
+    /// segment or a future third-party provider registered outside this
+    /// crate's fixed field list. A name with an active failover group
+    /// (see [`Self::apply_failover_groups`]) resolves to its
+    /// [`FailoverProvider`] instead of the single concrete provider, so
+    /// `Core::router`'s `(state.lookup)(provider)` call site never has to
+    /// know a group exists.
+    pub fn by_name(&self, name: &str) -> Option<Arc<dyn Provider>> {
+        if let Some(provider) = self.failover_overrides.read().unwrap().get(name) {
+            return Some(provider.clone());
+        }
+        self.by_name.get(name).cloned()
+    }
+
+    pub fn provider_names(&self) -> impl Iterator<Item = &str> {
+        self.by_name.keys().map(String::as_str)
+    }
+
+    /// Swaps in freshly loaded credential/disallow state for every provider
+    /// named in `pools`, built from [`crate::credential::BaseCredential`] —
+    /// the shared currency type `snapshot::build_provider_pools`
+    /// (`apps/gproxy`) hands back regardless of which provider a row
+    /// belongs to. `claude` and `codex` are skipped here: both are still
+    /// `not_implemented` stubs backed by unit-struct credential types
+    /// (`ClaudeCredential`/`CodexCredential`) rather than `BaseCredential`,
+    /// so there's no real pool for them to receive yet.
+    pub fn apply_pools(&self, pools: HashMap<String, PoolSnapshot<BaseCredential>>) {
+        for (name, snapshot) in pools {
+            match name.as_str() {
+                "openai" => self.openai.replace_snapshot(snapshot),
+                "aistudio" => self.aistudio.replace_snapshot(snapshot),
+                "vertexexpress" => self.vertexexpress.replace_snapshot(snapshot),
+                "vertex" => self.vertex.replace_snapshot(snapshot),
+                "geminicli" => self.geminicli.replace_snapshot(snapshot),
+                "claudecode" => self.claudecode.replace_snapshot(snapshot),
+                "antigravity" => self.antigravity.replace_snapshot(snapshot),
+                "nvidia" => self.nvidia.replace_snapshot(snapshot),
+                "deepseek" => self.deepseek.replace_snapshot(snapshot),
+                "azure-openai" => self.azure_openai.replace_snapshot(snapshot),
+                _ => {}
+            }
+        }
+    }
+
+    /// Rebuilds the failover-group overrides from an admin-configured
+    /// `name -> backend provider names` map (`apps/gproxy`'s
+    /// `failover_config::FailoverConfig`, loaded from `failover.toml`).
+    /// Unknown backend names, or a group naming a provider that doesn't
+    /// implement [`DispatchProvider`] (`openai`, `claude`, `codex`,
+    /// `nvidia`, `deepseek`, `azure-openai`), are dropped from the group with a warning
+    /// rather than failing the whole reload — the remaining healthy
+    /// backends still form a usable pool. A group left with no resolvable
+    /// backends is skipped entirely, leaving that name served by its plain
+    /// concrete provider (if any) exactly as before.
+    pub fn apply_failover_groups(&self, groups: HashMap<String, Vec<String>>) {
+        let mut overrides = HashMap::new();
+        for (name, backend_names) in groups {
+            let backends: Vec<Arc<dyn DispatchProvider>> = backend_names
+                .iter()
+                .filter_map(|backend_name| {
+                    let backend = self.dispatch_by_name.get(backend_name).cloned();
+                    if backend.is_none() {
+                        tracing::warn!(
+                            group = %name,
+                            backend = %backend_name,
+                            "failover group names a backend with no DispatchProvider implementation; skipping it"
+                        );
+                    }
+                    backend
+                })
+                .collect();
+            if backends.is_empty() {
+                tracing::warn!(group = %name, "failover group has no resolvable backends; leaving it unconfigured");
+                continue;
+            }
+            overrides.insert(
+                name.clone(),
+                Arc::new(FailoverProvider::new(name, backends)) as Arc<dyn Provider>,
+            );
+        }
+        *self.failover_overrides.write().unwrap() = overrides;
+    }
+}
+
+/// Builds a registry with a no-op state sink — for tooling (tests, one-shot
+/// CLI commands) that doesn't need provider state-change notifications.
+/// Production startup should use [`build_registry_with_sink`] instead so
+/// credential state transitions actually get persisted.
+pub fn build_registry() -> ProviderRegistry {
+    ProviderRegistry::new(Arc::new(NoopStateSink))
+}
+
+pub fn build_registry_with_sink(sink: Arc<dyn StateSink>) -> ProviderRegistry {
+    ProviderRegistry::new(sink)
+}
+
+/// The seed-data row for every provider this crate ships, in the shape
+/// `storage.ensure_providers` expects — run once at startup so a fresh
+/// database already has a `providers` row (enabled, with a sane default
+/// `base_url`) for each of them before any admin ever touches `/admin/providers`.
+pub fn default_providers() -> Vec<ProviderDefault> {
+    vec![
+        crate::provider::openai::default_provider(),
+        crate::provider::claude::default_provider(),
+        crate::provider::aistudio::default_provider(),
+        crate::provider::vertexexpress::default_provider(),
+        crate::provider::vertex::default_provider(),
+        crate::provider::geminicli::default_provider(),
+        crate::provider::claudecode::default_provider(),
+        crate::provider::codex::default_provider(),
+        crate::provider::antigravity::default_provider(),
+        crate::provider::nvidia::default_provider(),
+        crate::provider::deepseek::default_provider(),
+        crate::provider::azure_openai::default_provider(),
+    ]
+}
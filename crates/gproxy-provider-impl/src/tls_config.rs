@@ -0,0 +1,245 @@
+//! Per-provider custom TLS trust, for upstreams fronted by a corporate
+//! TLS-terminating proxy with a private CA or a pinned leaf cert —
+//! `VertexProvider`, `VertexExpressProvider`, `NvidiaProvider`, and
+//! self-hosted `DeepSeekProvider` gateways are the providers operators most
+//! often put behind one of these. `crate::client::shared_client` installs
+//! this (via `build_client_config`) when `GPROXY_UPSTREAM_TLS_*` env vars
+//! are set. `GlobalConfig` (`apps/gproxy/src/cli.rs`) is meant to carry this
+//! as an optional `tls: Option<TlsConfig>` section per provider entry, the
+//! same way it already carries `proxy`/`dns` — replacing the process-wide
+//! env config with a per-provider one is the remaining piece.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, Error as RustlsError, RootCertStore, SignatureScheme};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Operator-supplied TLS trust overrides for one provider's upstream
+/// connections.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Additional trust anchors, PEM-encoded, appended to the root store
+    /// used to verify the upstream's certificate chain.
+    #[serde(default)]
+    pub extra_root_certs_pem: Vec<String>,
+    /// Fall back to the bundled webpki roots in addition to
+    /// `extra_root_certs_pem`. `false` means only the custom roots (and any
+    /// pinned fingerprint) are trusted — the usual choice once an operator
+    /// has pinned every upstream they talk to.
+    #[serde(default = "default_true")]
+    pub use_webpki_roots: bool,
+    /// Trust-on-first-use pinning: the first certificate seen for a given
+    /// `host:port` is recorded and every later handshake must match it.
+    #[serde(default)]
+    pub pinning: Option<PinningConfig>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Where pinned fingerprints are persisted across restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinningConfig {
+    pub known_hosts_path: PathBuf,
+}
+
+/// Errors building a TLS client configuration from a [`TlsConfig`].
+#[derive(Debug)]
+pub enum TlsConfigError {
+    InvalidPem(String),
+    KnownHosts { path: PathBuf, source: std::io::Error },
+    Rustls(RustlsError),
+}
+
+impl std::fmt::Display for TlsConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlsConfigError::InvalidPem(reason) => write!(f, "invalid PEM trust anchor: {reason}"),
+            TlsConfigError::KnownHosts { path, source } => {
+                write!(f, "failed to read known-hosts file {}: {source}", path.display())
+            }
+            TlsConfigError::Rustls(err) => write!(f, "rustls configuration error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for TlsConfigError {}
+
+impl From<RustlsError> for TlsConfigError {
+    fn from(err: RustlsError) -> Self {
+        TlsConfigError::Rustls(err)
+    }
+}
+
+/// Builds the root store `extra_root_certs_pem` (and, if enabled,
+/// `use_webpki_roots`) contribute to.
+pub fn build_root_store(config: &TlsConfig) -> Result<RootCertStore, TlsConfigError> {
+    let mut roots = RootCertStore::empty();
+    if config.use_webpki_roots {
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    }
+    for pem in &config.extra_root_certs_pem {
+        let mut reader = std::io::BufReader::new(pem.as_bytes());
+        for cert in rustls_pemfile::certs(&mut reader) {
+            let cert = cert.map_err(|err| TlsConfigError::InvalidPem(err.to_string()))?;
+            roots
+                .add(cert)
+                .map_err(|err| TlsConfigError::InvalidPem(err.to_string()))?;
+        }
+    }
+    Ok(roots)
+}
+
+/// A `host:port` -> SHA-256 leaf-certificate-fingerprint table, persisted as
+/// one `host:port sha256hex` line per entry.
+#[derive(Debug, Default)]
+struct KnownHosts {
+    path: PathBuf,
+    fingerprints: HashMap<String, String>,
+}
+
+impl KnownHosts {
+    fn load(path: PathBuf) -> Result<Self, TlsConfigError> {
+        let fingerprints = match fs::read_to_string(&path) {
+            Ok(contents) => contents
+                .lines()
+                .filter_map(|line| line.split_once(' '))
+                .map(|(key, fingerprint)| (key.to_string(), fingerprint.to_string()))
+                .collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(TlsConfigError::KnownHosts { path, source: err }),
+        };
+        Ok(Self { path, fingerprints })
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.fingerprints.get(key).map(String::as_str)
+    }
+
+    fn insert(&mut self, key: String, fingerprint: String) -> Result<(), TlsConfigError> {
+        self.fingerprints.insert(key, fingerprint);
+        let contents = self
+            .fingerprints
+            .iter()
+            .map(|(key, fingerprint)| format!("{key} {fingerprint}\n"))
+            .collect::<String>();
+        fs::write(&self.path, contents).map_err(|err| TlsConfigError::KnownHosts {
+            path: self.path.clone(),
+            source: err,
+        })
+    }
+}
+
+/// A [`ServerCertVerifier`] that trusts whatever leaf certificate it first
+/// sees for a given `host:port` and rejects any later handshake presenting a
+/// different one, persisting the accepted fingerprint to `known_hosts_path`
+/// across restarts. Chain validation still runs against the provided
+/// `roots` first — pinning only narrows an already-trusted chain down to one
+/// specific leaf, it doesn't replace verification with blind trust.
+#[derive(Debug)]
+pub struct PinningVerifier {
+    inner: rustls::client::WebPkiServerVerifier,
+    known_hosts: Mutex<KnownHosts>,
+}
+
+impl PinningVerifier {
+    pub fn new(
+        roots: std::sync::Arc<RootCertStore>,
+        pinning: &PinningConfig,
+    ) -> Result<std::sync::Arc<Self>, TlsConfigError> {
+        let inner = rustls::client::WebPkiServerVerifier::builder(roots)
+            .build()
+            .map_err(|err| TlsConfigError::InvalidPem(err.to_string()))?;
+        let known_hosts = KnownHosts::load(pinning.known_hosts_path.clone())?;
+        Ok(std::sync::Arc::new(Self {
+            inner: *inner,
+            known_hosts: Mutex::new(known_hosts),
+        }))
+    }
+
+    fn fingerprint(cert: &CertificateDer<'_>) -> String {
+        let digest = Sha256::digest(cert.as_ref());
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, RustlsError> {
+        self.inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+
+        let key = format!("{server_name:?}");
+        let fingerprint = Self::fingerprint(end_entity);
+        let mut known_hosts = self
+            .known_hosts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match known_hosts.get(&key) {
+            Some(pinned) if pinned == fingerprint => Ok(ServerCertVerified::assertion()),
+            Some(_) => Err(RustlsError::General(format!(
+                "certificate fingerprint mismatch for {key}: pinned fingerprint does not match presented certificate"
+            ))),
+            None => {
+                known_hosts
+                    .insert(key, fingerprint)
+                    .map_err(|err| RustlsError::General(err.to_string()))?;
+                Ok(ServerCertVerified::assertion())
+            }
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Builds a `rustls::ClientConfig` honoring `config`: the combined root
+/// store from [`build_root_store`], with `config.pinning`'s
+/// [`PinningVerifier`] layered on top when set.
+pub fn build_client_config(config: &TlsConfig) -> Result<rustls::ClientConfig, TlsConfigError> {
+    let roots = std::sync::Arc::new(build_root_store(config)?);
+    let builder = rustls::ClientConfig::builder();
+    let client_config = match &config.pinning {
+        Some(pinning) => {
+            let verifier = PinningVerifier::new(roots, pinning)?;
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(verifier)
+        }
+        None => builder.with_root_certificates((*roots).clone()),
+    }
+    .with_no_client_auth();
+    Ok(client_config)
+}
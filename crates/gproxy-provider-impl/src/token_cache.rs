@@ -0,0 +1,84 @@
+//! Shared single-flight access-token cache for bearer-authenticated
+//! credentials, so a provider doesn't mint a fresh OAuth token on every
+//! `pool.execute` call. `VertexProvider` (`provider/vertex/oauth.rs`) is the
+//! first caller: its own `TOKEN_CACHE` was already a `credential_id`-keyed,
+//! mutex-guarded cache with this exact single-flight property, just not
+//! reusable — this module is that same shape, generalized.
+//!
+//! A handful of other providers (`aistudio`, `antigravity`, `claudecode`,
+//! `geminicli`, `vertexexpress`) keep their own independent token caches
+//! today, some of them (`geminicli::refresh`'s `RwLock`-backed cache, for
+//! one) without this guarantee — a burst of concurrent requests past an
+//! expired token there can trigger several simultaneous refreshes instead
+//! of one. Migrating those is left for a follow-up per provider, since each
+//! one's `ensure_tokens`/`ensure_access_token` also branches across several
+//! credential shapes (service account, refresh token, static access token)
+//! beyond plain caching, so folding them onto `TokenCache` is more than a
+//! mechanical swap.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::Mutex;
+
+#[derive(Clone, Debug)]
+struct CachedToken {
+    access_token: String,
+    expires_at: SystemTime,
+}
+
+/// A `key -> (access_token, expires_at)` cache guarded by a single
+/// `tokio::sync::Mutex`. Holding that lock across the `await` on a refresh
+/// is what makes lookups single-flight: a second caller that arrives
+/// mid-refresh blocks on the same lock instead of starting its own token
+/// exchange, and sees the first refresh's result once it completes.
+pub struct TokenCache<K> {
+    entries: Mutex<HashMap<K, CachedToken>>,
+}
+
+impl<K> Default for TokenCache<K>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K> TokenCache<K>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached token for `key` if it won't expire within `skew`
+    /// of now; otherwise awaits `refresh` to mint a new one, caches it
+    /// alongside its TTL, and returns it.
+    pub async fn get_or_refresh<F, Fut, E>(&self, key: K, skew: Duration, refresh: F) -> Result<String, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(String, Duration), E>>,
+    {
+        let mut entries = self.entries.lock().await;
+        if let Some(cached) = entries.get(&key) {
+            if cached.expires_at > SystemTime::now() + skew {
+                return Ok(cached.access_token.clone());
+            }
+        }
+        let (access_token, ttl) = refresh().await?;
+        entries.insert(
+            key,
+            CachedToken {
+                access_token: access_token.clone(),
+                expires_at: SystemTime::now() + ttl,
+            },
+        );
+        Ok(access_token)
+    }
+}
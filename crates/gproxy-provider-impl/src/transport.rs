@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+use serde_json::Value as JsonValue;
+
+use gproxy_provider_core::{AttemptFailure, UpstreamPassthroughError};
+
+/// Transport overrides read out of a credential's `meta` JSON, alongside the
+/// `base_url` every provider already supports there: a proxy override, a
+/// connect timeout, and arbitrary extra headers (e.g. `organization_id` for
+/// OpenAI-compatible gateways). Lets one provider type serve many
+/// differently-configured endpoints without a dedicated provider per
+/// endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct TransportConfig {
+    pub proxy: Option<String>,
+    pub connect_timeout: Option<Duration>,
+    pub extra_headers: Vec<(String, String)>,
+}
+
+impl TransportConfig {
+    pub fn from_meta(meta: &JsonValue) -> Self {
+        let proxy = meta
+            .get("proxy")
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string());
+        let connect_timeout = meta
+            .get("connect_timeout_secs")
+            .and_then(|value| value.as_u64())
+            .map(Duration::from_secs);
+        let mut extra_headers = Vec::new();
+        if let Some(organization_id) = meta.get("organization_id").and_then(|value| value.as_str()) {
+            extra_headers.push(("OpenAI-Organization".to_string(), organization_id.to_string()));
+        }
+        if let Some(headers) = meta.get("extra_headers").and_then(|value| value.as_object()) {
+            for (name, value) in headers {
+                if let Some(value) = value.as_str() {
+                    extra_headers.push((name.clone(), value.to_string()));
+                }
+            }
+        }
+        Self {
+            proxy,
+            connect_timeout,
+            extra_headers,
+        }
+    }
+
+    /// The proxy this request should use: the credential's override if set,
+    /// otherwise whatever the caller's `CallContext` already specified.
+    pub fn resolve_proxy<'a>(&'a self, ctx_proxy: Option<&'a str>) -> Option<&'a str> {
+        self.proxy.as_deref().or(ctx_proxy)
+    }
+
+    /// Applies `extra_headers` on top of an already-built header map.
+    pub fn apply_headers(&self, headers: &mut HeaderMap) -> Result<(), AttemptFailure> {
+        for (name, value) in &self.extra_headers {
+            let header_name = HeaderName::try_from(name.as_str()).map_err(|err| AttemptFailure {
+                passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+                mark: None,
+            })?;
+            let header_value = HeaderValue::from_str(value).map_err(|err| AttemptFailure {
+                passthrough: UpstreamPassthroughError::service_unavailable(err.to_string()),
+                mark: None,
+            })?;
+            headers.insert(header_name, header_value);
+        }
+        Ok(())
+    }
+}
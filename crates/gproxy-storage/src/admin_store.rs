@@ -0,0 +1,586 @@
+use async_trait::async_trait;
+use sea_orm::prelude::Json;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ActiveValue, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+use time::OffsetDateTime;
+
+use crate::entities;
+use crate::snapshot::StorageSnapshot;
+use crate::traffic::{
+    AdminAuditInput, AdminCredentialInput, AdminDisallowInput, AdminInstructionRuleInput,
+    AdminInstructionTemplateInput, AdminKeyInput, AdminProviderInput, AdminUserInput,
+    TrafficStorage,
+};
+
+/// The provider/credential/disallow/user/key/audit CRUD surface `apps/gproxy`'s
+/// admin API consumes, pulled out as a trait for the same reason
+/// [`crate::backend::StorageBackend`] exists: so `AdminState` can hold
+/// `Arc<dyn TrafficStore>` and run against an alternate backend (an
+/// in-memory store for tests, a remote row/object store for
+/// horizontally-scaled deployments) chosen by config, instead of being
+/// locked to one concrete connection type — mirroring how mail storage was
+/// lifted behind `blob_fetch`/`row_fetch` to run over different durable
+/// stores.
+///
+/// `impl TrafficStore for TrafficStorage` below is the real (if only)
+/// implementor today, backed by the same SeaORM connection `TrafficStorage`
+/// already uses for traffic logging. The `apps/gproxy/src/admin.rs` handlers
+/// call this trait's methods directly on their `TrafficStorage` value, so
+/// bringing `TrafficStore` into scope there is what makes those calls
+/// resolve.
+#[async_trait]
+pub trait TrafficStore: Send + Sync {
+    async fn list_providers(&self) -> Result<Vec<entities::providers::Model>, DbErr>;
+    async fn upsert_provider(&self, input: AdminProviderInput) -> Result<i64, DbErr>;
+    async fn delete_provider(&self, id: i64) -> Result<(), DbErr>;
+
+    async fn list_credentials(&self) -> Result<Vec<entities::credentials::Model>, DbErr>;
+    async fn upsert_credential(&self, input: AdminCredentialInput) -> Result<i64, DbErr>;
+    async fn delete_credential(&self, id: i64) -> Result<(), DbErr>;
+
+    async fn list_disallow(&self) -> Result<Vec<entities::credential_disallow::Model>, DbErr>;
+    async fn upsert_disallow(&self, input: AdminDisallowInput) -> Result<i64, DbErr>;
+    async fn delete_disallow(&self, id: i64) -> Result<(), DbErr>;
+
+    async fn list_users(&self) -> Result<Vec<entities::users::Model>, DbErr>;
+    async fn upsert_user(&self, input: AdminUserInput) -> Result<i64, DbErr>;
+    async fn delete_user(&self, id: i64) -> Result<(), DbErr>;
+
+    async fn list_keys(&self) -> Result<Vec<entities::api_keys::Model>, DbErr>;
+    async fn upsert_key(&self, input: AdminKeyInput) -> Result<i64, DbErr>;
+    async fn delete_key(&self, id: i64) -> Result<(), DbErr>;
+    async fn set_key_enabled(&self, id: i64, enabled: bool) -> Result<(), DbErr>;
+
+    async fn list_instruction_templates(
+        &self,
+    ) -> Result<Vec<entities::instruction_templates::Model>, DbErr>;
+    async fn upsert_instruction_template(
+        &self,
+        input: AdminInstructionTemplateInput,
+    ) -> Result<i64, DbErr>;
+    async fn delete_instruction_template(&self, id: i64) -> Result<(), DbErr>;
+
+    async fn list_instruction_rules(&self) -> Result<Vec<entities::instruction_rules::Model>, DbErr>;
+    async fn upsert_instruction_rule(&self, input: AdminInstructionRuleInput) -> Result<i64, DbErr>;
+    async fn delete_instruction_rule(&self, id: i64) -> Result<(), DbErr>;
+
+    async fn insert_audit_entry(&self, input: AdminAuditInput) -> Result<i64, DbErr>;
+    async fn list_audit_entries(
+        &self,
+        actor: Option<String>,
+        entity_type: Option<String>,
+        since: Option<OffsetDateTime>,
+        until: Option<OffsetDateTime>,
+    ) -> Result<Vec<entities::audit_log::Model>, DbErr>;
+
+    async fn get_global_config(&self) -> Result<Option<entities::global_config::Model>, DbErr>;
+    async fn upsert_global_config(
+        &self,
+        id: i64,
+        config_json: Json,
+        updated_at: OffsetDateTime,
+    ) -> Result<(), DbErr>;
+    async fn ensure_admin_user(&self, admin_key: &str) -> Result<(), DbErr>;
+
+    async fn load_snapshot(&self) -> Result<StorageSnapshot, DbErr>;
+    async fn health(&self) -> Result<(), DbErr>;
+}
+
+#[async_trait]
+impl TrafficStore for TrafficStorage {
+    async fn list_providers(&self) -> Result<Vec<entities::providers::Model>, DbErr> {
+        entities::Providers::find().all(self.connection()).await
+    }
+
+    async fn upsert_provider(&self, input: AdminProviderInput) -> Result<i64, DbErr> {
+        use entities::providers::Column;
+
+        match input.id {
+            Some(id) => {
+                let active = entities::providers::ActiveModel {
+                    id: ActiveValue::Set(id),
+                    name: ActiveValue::Set(input.name),
+                    config_json: ActiveValue::Set(input.config_json),
+                    enabled: ActiveValue::Set(input.enabled),
+                    updated_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+                    ..Default::default()
+                };
+                entities::Providers::insert(active)
+                    .on_conflict(
+                        OnConflict::column(Column::Id)
+                            .update_columns([
+                                Column::Name,
+                                Column::ConfigJson,
+                                Column::Enabled,
+                                Column::UpdatedAt,
+                            ])
+                            .to_owned(),
+                    )
+                    .exec(self.connection())
+                    .await?;
+                Ok(id)
+            }
+            None => {
+                let active = entities::providers::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    name: ActiveValue::Set(input.name),
+                    config_json: ActiveValue::Set(input.config_json),
+                    enabled: ActiveValue::Set(input.enabled),
+                    updated_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+                    ..Default::default()
+                };
+                let result = entities::Providers::insert(active)
+                    .exec(self.connection())
+                    .await?;
+                Ok(result.last_insert_id)
+            }
+        }
+    }
+
+    async fn delete_provider(&self, id: i64) -> Result<(), DbErr> {
+        entities::Providers::delete_by_id(id)
+            .exec(self.connection())
+            .await?;
+        Ok(())
+    }
+
+    async fn list_credentials(&self) -> Result<Vec<entities::credentials::Model>, DbErr> {
+        entities::Credentials::find().all(self.connection()).await
+    }
+
+    async fn upsert_credential(&self, input: AdminCredentialInput) -> Result<i64, DbErr> {
+        use entities::credentials::Column;
+
+        match input.id {
+            Some(id) => {
+                let active = entities::credentials::ActiveModel {
+                    id: ActiveValue::Set(id),
+                    provider_id: ActiveValue::Set(input.provider_id),
+                    name: ActiveValue::Set(input.name),
+                    secret: ActiveValue::Set(input.secret),
+                    meta_json: ActiveValue::Set(input.meta_json),
+                    weight: ActiveValue::Set(input.weight),
+                    enabled: ActiveValue::Set(input.enabled),
+                    updated_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+                    ..Default::default()
+                };
+                entities::Credentials::insert(active)
+                    .on_conflict(
+                        OnConflict::column(Column::Id)
+                            .update_columns([
+                                Column::ProviderId,
+                                Column::Name,
+                                Column::Secret,
+                                Column::MetaJson,
+                                Column::Weight,
+                                Column::Enabled,
+                                Column::UpdatedAt,
+                            ])
+                            .to_owned(),
+                    )
+                    .exec(self.connection())
+                    .await?;
+                Ok(id)
+            }
+            None => {
+                let now = OffsetDateTime::now_utc();
+                let active = entities::credentials::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    provider_id: ActiveValue::Set(input.provider_id),
+                    name: ActiveValue::Set(input.name),
+                    secret: ActiveValue::Set(input.secret),
+                    meta_json: ActiveValue::Set(input.meta_json),
+                    weight: ActiveValue::Set(input.weight),
+                    enabled: ActiveValue::Set(input.enabled),
+                    created_at: ActiveValue::Set(now),
+                    updated_at: ActiveValue::Set(now),
+                    ..Default::default()
+                };
+                let result = entities::Credentials::insert(active)
+                    .exec(self.connection())
+                    .await?;
+                Ok(result.last_insert_id)
+            }
+        }
+    }
+
+    async fn delete_credential(&self, id: i64) -> Result<(), DbErr> {
+        entities::Credentials::delete_by_id(id)
+            .exec(self.connection())
+            .await?;
+        Ok(())
+    }
+
+    async fn list_disallow(&self) -> Result<Vec<entities::credential_disallow::Model>, DbErr> {
+        entities::CredentialDisallow::find()
+            .all(self.connection())
+            .await
+    }
+
+    async fn upsert_disallow(&self, input: AdminDisallowInput) -> Result<i64, DbErr> {
+        use entities::credential_disallow::Column;
+
+        match input.id {
+            Some(id) => {
+                let active = entities::credential_disallow::ActiveModel {
+                    id: ActiveValue::Set(id),
+                    credential_id: ActiveValue::Set(input.credential_id),
+                    scope_kind: ActiveValue::Set(input.scope_kind),
+                    scope_value: ActiveValue::Set(input.scope_value),
+                    level: ActiveValue::Set(input.level),
+                    until_at: ActiveValue::Set(input.until_at),
+                    reason: ActiveValue::Set(input.reason),
+                    updated_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+                    ..Default::default()
+                };
+                entities::CredentialDisallow::insert(active)
+                    .on_conflict(
+                        OnConflict::column(Column::Id)
+                            .update_columns([
+                                Column::CredentialId,
+                                Column::ScopeKind,
+                                Column::ScopeValue,
+                                Column::Level,
+                                Column::UntilAt,
+                                Column::Reason,
+                                Column::UpdatedAt,
+                            ])
+                            .to_owned(),
+                    )
+                    .exec(self.connection())
+                    .await?;
+                Ok(id)
+            }
+            None => {
+                let active = entities::credential_disallow::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    credential_id: ActiveValue::Set(input.credential_id),
+                    scope_kind: ActiveValue::Set(input.scope_kind),
+                    scope_value: ActiveValue::Set(input.scope_value),
+                    level: ActiveValue::Set(input.level),
+                    until_at: ActiveValue::Set(input.until_at),
+                    reason: ActiveValue::Set(input.reason),
+                    updated_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+                    ..Default::default()
+                };
+                let result = entities::CredentialDisallow::insert(active)
+                    .exec(self.connection())
+                    .await?;
+                Ok(result.last_insert_id)
+            }
+        }
+    }
+
+    async fn delete_disallow(&self, id: i64) -> Result<(), DbErr> {
+        entities::CredentialDisallow::delete_by_id(id)
+            .exec(self.connection())
+            .await?;
+        Ok(())
+    }
+
+    async fn list_users(&self) -> Result<Vec<entities::users::Model>, DbErr> {
+        entities::Users::find().all(self.connection()).await
+    }
+
+    async fn upsert_user(&self, input: AdminUserInput) -> Result<i64, DbErr> {
+        use entities::users::Column;
+
+        let now = OffsetDateTime::now_utc();
+        match input.id {
+            Some(id) => {
+                let active = entities::users::ActiveModel {
+                    id: ActiveValue::Set(id),
+                    name: ActiveValue::Set(input.name),
+                    updated_at: ActiveValue::Set(now),
+                    ..Default::default()
+                };
+                entities::Users::insert(active)
+                    .on_conflict(
+                        OnConflict::column(Column::Id)
+                            .update_columns([Column::Name, Column::UpdatedAt])
+                            .to_owned(),
+                    )
+                    .exec(self.connection())
+                    .await?;
+                Ok(id)
+            }
+            None => {
+                let active = entities::users::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    name: ActiveValue::Set(input.name),
+                    created_at: ActiveValue::Set(now),
+                    updated_at: ActiveValue::Set(now),
+                    ..Default::default()
+                };
+                let result = entities::Users::insert(active)
+                    .exec(self.connection())
+                    .await?;
+                Ok(result.last_insert_id)
+            }
+        }
+    }
+
+    async fn delete_user(&self, id: i64) -> Result<(), DbErr> {
+        entities::Users::delete_by_id(id)
+            .exec(self.connection())
+            .await?;
+        Ok(())
+    }
+
+    async fn list_keys(&self) -> Result<Vec<entities::api_keys::Model>, DbErr> {
+        entities::ApiKeys::find().all(self.connection()).await
+    }
+
+    async fn upsert_key(&self, input: AdminKeyInput) -> Result<i64, DbErr> {
+        use entities::api_keys::Column;
+
+        match input.id {
+            Some(id) => {
+                let active = entities::api_keys::ActiveModel {
+                    id: ActiveValue::Set(id),
+                    user_id: ActiveValue::Set(input.user_id),
+                    key_value: ActiveValue::Set(input.key_value),
+                    label: ActiveValue::Set(input.label),
+                    enabled: ActiveValue::Set(input.enabled),
+                    ..Default::default()
+                };
+                entities::ApiKeys::insert(active)
+                    .on_conflict(
+                        OnConflict::column(Column::Id)
+                            .update_columns([
+                                Column::UserId,
+                                Column::KeyValue,
+                                Column::Label,
+                                Column::Enabled,
+                            ])
+                            .to_owned(),
+                    )
+                    .exec(self.connection())
+                    .await?;
+                Ok(id)
+            }
+            None => {
+                let active = entities::api_keys::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    user_id: ActiveValue::Set(input.user_id),
+                    key_value: ActiveValue::Set(input.key_value),
+                    label: ActiveValue::Set(input.label),
+                    enabled: ActiveValue::Set(input.enabled),
+                    created_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+                    last_used_at: ActiveValue::Set(None),
+                    ..Default::default()
+                };
+                let result = entities::ApiKeys::insert(active)
+                    .exec(self.connection())
+                    .await?;
+                Ok(result.last_insert_id)
+            }
+        }
+    }
+
+    async fn delete_key(&self, id: i64) -> Result<(), DbErr> {
+        entities::ApiKeys::delete_by_id(id)
+            .exec(self.connection())
+            .await?;
+        Ok(())
+    }
+
+    async fn set_key_enabled(&self, id: i64, enabled: bool) -> Result<(), DbErr> {
+        let active = entities::api_keys::ActiveModel {
+            id: ActiveValue::Set(id),
+            enabled: ActiveValue::Set(enabled),
+            ..Default::default()
+        };
+        entities::ApiKeys::update(active).exec(self.connection()).await?;
+        Ok(())
+    }
+
+    async fn list_instruction_templates(
+        &self,
+    ) -> Result<Vec<entities::instruction_templates::Model>, DbErr> {
+        entities::InstructionTemplates::find()
+            .all(self.connection())
+            .await
+    }
+
+    async fn upsert_instruction_template(
+        &self,
+        input: AdminInstructionTemplateInput,
+    ) -> Result<i64, DbErr> {
+        use entities::instruction_templates::Column;
+
+        let now = OffsetDateTime::now_utc();
+        match input.id {
+            Some(id) => {
+                let active = entities::instruction_templates::ActiveModel {
+                    id: ActiveValue::Set(id),
+                    template_id: ActiveValue::Set(input.template_id),
+                    body: ActiveValue::Set(input.body),
+                    updated_at: ActiveValue::Set(now),
+                };
+                entities::InstructionTemplates::insert(active)
+                    .on_conflict(
+                        OnConflict::column(Column::Id)
+                            .update_columns([Column::TemplateId, Column::Body, Column::UpdatedAt])
+                            .to_owned(),
+                    )
+                    .exec(self.connection())
+                    .await?;
+                Ok(id)
+            }
+            None => {
+                let active = entities::instruction_templates::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    template_id: ActiveValue::Set(input.template_id),
+                    body: ActiveValue::Set(input.body),
+                    updated_at: ActiveValue::Set(now),
+                };
+                let result = entities::InstructionTemplates::insert(active)
+                    .exec(self.connection())
+                    .await?;
+                Ok(result.last_insert_id)
+            }
+        }
+    }
+
+    async fn delete_instruction_template(&self, id: i64) -> Result<(), DbErr> {
+        entities::InstructionTemplates::delete_by_id(id)
+            .exec(self.connection())
+            .await?;
+        Ok(())
+    }
+
+    async fn list_instruction_rules(&self) -> Result<Vec<entities::instruction_rules::Model>, DbErr> {
+        entities::InstructionRules::find().all(self.connection()).await
+    }
+
+    async fn upsert_instruction_rule(
+        &self,
+        input: AdminInstructionRuleInput,
+    ) -> Result<i64, DbErr> {
+        use entities::instruction_rules::Column;
+
+        let now = OffsetDateTime::now_utc();
+        match input.id {
+            Some(id) => {
+                let active = entities::instruction_rules::ActiveModel {
+                    id: ActiveValue::Set(id),
+                    position: ActiveValue::Set(input.position),
+                    model_glob: ActiveValue::Set(input.model_glob),
+                    template_id: ActiveValue::Set(input.template_id),
+                    personality: ActiveValue::Set(input.personality),
+                    updated_at: ActiveValue::Set(now),
+                };
+                entities::InstructionRules::insert(active)
+                    .on_conflict(
+                        OnConflict::column(Column::Id)
+                            .update_columns([
+                                Column::Position,
+                                Column::ModelGlob,
+                                Column::TemplateId,
+                                Column::Personality,
+                                Column::UpdatedAt,
+                            ])
+                            .to_owned(),
+                    )
+                    .exec(self.connection())
+                    .await?;
+                Ok(id)
+            }
+            None => {
+                let active = entities::instruction_rules::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    position: ActiveValue::Set(input.position),
+                    model_glob: ActiveValue::Set(input.model_glob),
+                    template_id: ActiveValue::Set(input.template_id),
+                    personality: ActiveValue::Set(input.personality),
+                    updated_at: ActiveValue::Set(now),
+                };
+                let result = entities::InstructionRules::insert(active)
+                    .exec(self.connection())
+                    .await?;
+                Ok(result.last_insert_id)
+            }
+        }
+    }
+
+    async fn delete_instruction_rule(&self, id: i64) -> Result<(), DbErr> {
+        entities::InstructionRules::delete_by_id(id)
+            .exec(self.connection())
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_audit_entry(&self, input: AdminAuditInput) -> Result<i64, DbErr> {
+        let active = entities::audit_log::ActiveModel {
+            id: ActiveValue::NotSet,
+            actor: ActiveValue::Set(input.actor),
+            route: ActiveValue::Set(input.route),
+            entity_type: ActiveValue::Set(input.entity_type),
+            entity_id: ActiveValue::Set(input.entity_id),
+            diff_json: ActiveValue::Set(input.diff_json),
+            created_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+        };
+        let result = entities::audit_log::Entity::insert(active)
+            .exec(self.connection())
+            .await?;
+        Ok(result.last_insert_id)
+    }
+
+    async fn list_audit_entries(
+        &self,
+        actor: Option<String>,
+        entity_type: Option<String>,
+        since: Option<OffsetDateTime>,
+        until: Option<OffsetDateTime>,
+    ) -> Result<Vec<entities::audit_log::Model>, DbErr> {
+        use entities::audit_log::Column;
+
+        let mut query = entities::audit_log::Entity::find();
+        if let Some(actor) = actor {
+            query = query.filter(Column::Actor.eq(actor));
+        }
+        if let Some(entity_type) = entity_type {
+            query = query.filter(Column::EntityType.eq(entity_type));
+        }
+        if let Some(since) = since {
+            query = query.filter(Column::CreatedAt.gte(since));
+        }
+        if let Some(until) = until {
+            query = query.filter(Column::CreatedAt.lt(until));
+        }
+        query.all(self.connection()).await
+    }
+
+    async fn get_global_config(&self) -> Result<Option<entities::global_config::Model>, DbErr> {
+        entities::GlobalConfig::find().one(self.connection()).await
+    }
+
+    async fn upsert_global_config(
+        &self,
+        id: i64,
+        config_json: Json,
+        updated_at: OffsetDateTime,
+    ) -> Result<(), DbErr> {
+        TrafficStorage::upsert_global_config(self, id, config_json, updated_at).await
+    }
+
+    async fn ensure_admin_user(&self, admin_key: &str) -> Result<(), DbErr> {
+        TrafficStorage::ensure_admin_user(self, admin_key).await
+    }
+
+    async fn load_snapshot(&self) -> Result<StorageSnapshot, DbErr> {
+        Ok(StorageSnapshot {
+            global_config: self.get_global_config().await?,
+            providers: self.list_providers().await?,
+            credentials: self.list_credentials().await?,
+            disallow: self.list_disallow().await?,
+            users: self.list_users().await?,
+            api_keys: self.list_keys().await?,
+        })
+    }
+
+    async fn health(&self) -> Result<(), DbErr> {
+        self.connection().ping().await
+    }
+}
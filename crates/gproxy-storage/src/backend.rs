@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use sea_orm::prelude::Json;
+use sea_orm::DbErr;
+use time::OffsetDateTime;
+
+use crate::traffic::{TimeRange, TrafficStorage, UsageSummary};
+use crate::{DownstreamTrafficEvent, UpstreamTrafficEvent};
+
+/// The traffic/usage/config storage surface `TrafficStorage` exposes to the
+/// rest of the proxy, pulled out as a trait so background writers and usage
+/// dashboards can run against something other than a live database
+/// connection (e.g. an in-memory fake in tests) and so a deployment isn't
+/// permanently locked into whichever concrete type backs `TrafficStorage`.
+///
+/// This only covers traffic logging, usage aggregation, and global config.
+/// The `/admin/*` CRUD surface (providers/credentials/disallow/users/keys)
+/// lives on [`crate::admin_store::TrafficStore`] instead — a separate trait
+/// because it targets a disjoint set of tables and callers.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    async fn sync(&self) -> Result<(), DbErr>;
+    async fn insert_downstream(&self, event: DownstreamTrafficEvent) -> Result<(), DbErr>;
+    async fn insert_downstream_batch(
+        &self,
+        events: Vec<DownstreamTrafficEvent>,
+    ) -> Result<(), DbErr>;
+    async fn insert_upstream(&self, event: UpstreamTrafficEvent) -> Result<(), DbErr>;
+    async fn insert_upstream_batch(&self, events: Vec<UpstreamTrafficEvent>) -> Result<(), DbErr>;
+    async fn usage_by_credential(
+        &self,
+        provider: Option<&str>,
+        range: TimeRange,
+    ) -> Result<Vec<UsageSummary>, DbErr>;
+    async fn usage_by_key(
+        &self,
+        key_id: Option<i64>,
+        range: TimeRange,
+    ) -> Result<Vec<UsageSummary>, DbErr>;
+    async fn usage_by_model(&self, range: TimeRange) -> Result<Vec<UsageSummary>, DbErr>;
+    async fn upsert_global_config(
+        &self,
+        id: i64,
+        config_json: Json,
+        updated_at: OffsetDateTime,
+    ) -> Result<(), DbErr>;
+    async fn ensure_admin_user(&self, admin_key: &str) -> Result<(), DbErr>;
+}
+
+#[async_trait]
+impl StorageBackend for TrafficStorage {
+    async fn sync(&self) -> Result<(), DbErr> {
+        TrafficStorage::sync(self).await
+    }
+
+    async fn insert_downstream(&self, event: DownstreamTrafficEvent) -> Result<(), DbErr> {
+        TrafficStorage::insert_downstream(self, event).await
+    }
+
+    async fn insert_downstream_batch(
+        &self,
+        events: Vec<DownstreamTrafficEvent>,
+    ) -> Result<(), DbErr> {
+        TrafficStorage::insert_downstream_batch(self, events).await
+    }
+
+    async fn insert_upstream(&self, event: UpstreamTrafficEvent) -> Result<(), DbErr> {
+        TrafficStorage::insert_upstream(self, event).await
+    }
+
+    async fn insert_upstream_batch(&self, events: Vec<UpstreamTrafficEvent>) -> Result<(), DbErr> {
+        TrafficStorage::insert_upstream_batch(self, events).await
+    }
+
+    async fn usage_by_credential(
+        &self,
+        provider: Option<&str>,
+        range: TimeRange,
+    ) -> Result<Vec<UsageSummary>, DbErr> {
+        TrafficStorage::usage_by_credential(self, provider, range).await
+    }
+
+    async fn usage_by_key(
+        &self,
+        key_id: Option<i64>,
+        range: TimeRange,
+    ) -> Result<Vec<UsageSummary>, DbErr> {
+        TrafficStorage::usage_by_key(self, key_id, range).await
+    }
+
+    async fn usage_by_model(&self, range: TimeRange) -> Result<Vec<UsageSummary>, DbErr> {
+        TrafficStorage::usage_by_model(self, range).await
+    }
+
+    async fn upsert_global_config(
+        &self,
+        id: i64,
+        config_json: Json,
+        updated_at: OffsetDateTime,
+    ) -> Result<(), DbErr> {
+        TrafficStorage::upsert_global_config(self, id, config_json, updated_at).await
+    }
+
+    async fn ensure_admin_user(&self, admin_key: &str) -> Result<(), DbErr> {
+        TrafficStorage::ensure_admin_user(self, admin_key).await
+    }
+}
+
+/// An in-memory `StorageBackend` for unit tests: a `TrafficStorage` backed
+/// by SQLite's `:memory:` DSN rather than a file or a real Postgres server.
+/// This reuses `TrafficStorage`'s actual SeaORM queries instead of
+/// re-implementing them against a hand-rolled store, so tests exercise the
+/// same SQL the real backend runs.
+pub async fn memory_backend() -> Result<TrafficStorage, DbErr> {
+    let storage = TrafficStorage::connect("sqlite::memory:").await?;
+    storage.sync().await?;
+    Ok(storage)
+}
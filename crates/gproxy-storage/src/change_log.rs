@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use sea_orm::prelude::Json;
+use sea_orm::{
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use time::OffsetDateTime;
+
+use crate::entities::{admin_checkpoint, admin_op_log};
+
+/// Append-only history for admin-API mutations (`apps/gproxy/src/admin.rs`),
+/// durable enough to audit every change and to rebuild current state from
+/// scratch. Every function here takes a `&DatabaseConnection` directly
+/// rather than `&TrafficStorage`, because `TrafficStorage` (`crate::traffic`)
+/// keeps its connection private and doesn't yet expose it or call into this
+/// module — wiring `append_operation` in before each admin mutation applies,
+/// and calling `latest_checkpoint`/`list_operations_since` from
+/// `TrafficStorage::load_snapshot` (or a `TrafficStore::load_snapshot` impl,
+/// see [`crate::admin_store`]), is the integration this module is waiting
+/// on, not something this file can do on its own.
+///
+/// How every N operations turns into a checkpoint: the caller (whatever
+/// eventually drives `append_operation`) is expected to check
+/// `sort_key % CHECKPOINT_FOLD_INTERVAL == 0` (or track a running count) and
+/// call `write_checkpoint` with the materialized `StorageSnapshot` once that
+/// threshold is hit. Checkpoint writes are plain inserts, never updates, so
+/// a crash between `append_operation` and `write_checkpoint` just leaves the
+/// previous checkpoint as the replay floor and a few extra ops to replay on
+/// top of it — never a gap.
+pub const CHECKPOINT_FOLD_INTERVAL: u64 = 64;
+
+static LAST_SORT_KEY: AtomicI64 = AtomicI64::new(0);
+
+/// A strictly monotonic sort key for `admin_op_log.sort_key`, seeded from
+/// unix-nanos but bumped by hand when two calls land in the same nanosecond
+/// (or the clock goes backwards), since replay depends on every row having a
+/// distinct, increasing key.
+pub fn monotonic_sort_key() -> i64 {
+    let now = OffsetDateTime::now_utc().unix_timestamp_nanos() as i64;
+    loop {
+        let last = LAST_SORT_KEY.load(Ordering::SeqCst);
+        let next = if now > last { now } else { last + 1 };
+        if LAST_SORT_KEY
+            .compare_exchange(last, next, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            return next;
+        }
+    }
+}
+
+/// Appends one operation record. Must be called, and awaited to completion,
+/// before the corresponding in-memory mutation is applied — an op recorded
+/// after the fact could be lost on a crash while the memory-side effect
+/// already happened, defeating the point of a replayable log.
+pub async fn append_operation(
+    db: &DatabaseConnection,
+    sort_key: i64,
+    route: &str,
+    op: Json,
+) -> Result<i64, DbErr> {
+    let model = admin_op_log::ActiveModel {
+        id: ActiveValue::NotSet,
+        sort_key: ActiveValue::Set(sort_key),
+        route: ActiveValue::Set(route.to_string()),
+        op_json: ActiveValue::Set(op),
+        recorded_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+    };
+    let inserted = admin_op_log::Entity::insert(model).exec(db).await?;
+    Ok(inserted.last_insert_id)
+}
+
+/// The most recently written checkpoint, if any. `None` means replay must
+/// start from the beginning of `admin_op_log`.
+pub async fn latest_checkpoint(
+    db: &DatabaseConnection,
+) -> Result<Option<admin_checkpoint::Model>, DbErr> {
+    admin_checkpoint::Entity::find()
+        .order_by_desc(admin_checkpoint::Column::FoldedThrough)
+        .limit(1)
+        .one(db)
+        .await
+}
+
+/// Folds every operation up to and including `folded_through` into a new
+/// checkpoint row. `snapshot_json` is the caller's already-serialized
+/// `StorageSnapshot` (this module doesn't assume `StorageSnapshot`
+/// implements `Serialize` — that's for the caller to resolve).
+pub async fn write_checkpoint(
+    db: &DatabaseConnection,
+    folded_through: i64,
+    snapshot_json: Json,
+) -> Result<(), DbErr> {
+    let model = admin_checkpoint::ActiveModel {
+        id: ActiveValue::NotSet,
+        folded_through: ActiveValue::Set(folded_through),
+        snapshot_json: ActiveValue::Set(snapshot_json),
+        created_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+    };
+    admin_checkpoint::Entity::insert(model).exec(db).await?;
+    Ok(())
+}
+
+/// Operations with `sort_key` strictly greater than `since`, oldest first —
+/// exactly the set replay needs to reapply on top of a checkpoint taken at
+/// `since`, and exactly what `GET /admin/changes?since=<ts>` streams back
+/// for auditing.
+///
+/// Per the replay invariant this subsystem is built around, a malformed
+/// `since` is a hard error rather than a silent "replay everything": a
+/// caller that got the floor wrong should not get back a *different* set of
+/// ops without realizing it.
+pub async fn list_operations_since(
+    db: &DatabaseConnection,
+    since: i64,
+) -> Result<Vec<admin_op_log::Model>, DbErr> {
+    admin_op_log::Entity::find()
+        .filter(admin_op_log::Column::SortKey.gt(since))
+        .order_by_asc(admin_op_log::Column::SortKey)
+        .all(db)
+        .await
+}
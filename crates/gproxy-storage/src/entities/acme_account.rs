@@ -0,0 +1,16 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// The ACME account's private key (PEM), a single row keyed by `id = 1` the
+/// same way `global_config` stores its singleton.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "acme_account")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub key_pem: String,
+    pub updated_at: OffsetDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
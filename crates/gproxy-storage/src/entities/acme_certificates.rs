@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// One issued certificate/key pair backing `apps/gproxy`'s
+/// `tls_acme::CertBundle`, looked up by `domain` rather than `id` since
+/// that's the key callers (`run_acme_order`, `spawn_renewal_task`) always
+/// have on hand.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "acme_certificates")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub domain: String,
+    pub cert_chain_pem: String,
+    pub private_key_pem: String,
+    pub not_after: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A materialized [`crate::snapshot::StorageSnapshot`] folded from every
+/// [`super::admin_op_log::Model`] row up to and including `folded_through`
+/// (a [`crate::change_log::monotonic_sort_key`] value). Replay starts from
+/// the newest row here, then reapplies only ops with `sort_key >
+/// folded_through`, so a restart doesn't replay the full operation history
+/// from the beginning of time.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "admin_checkpoint")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub folded_through: i64,
+    pub snapshot_json: Json,
+    pub created_at: OffsetDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
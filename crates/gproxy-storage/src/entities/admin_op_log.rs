@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// One admin-API mutation appended before it's applied to in-memory state.
+/// `sort_key` is a strictly monotonic unix-nanos counter (see
+/// [`crate::change_log::monotonic_sort_key`]), not `id`, because replay
+/// needs to resume from an exact point in time recorded in a checkpoint
+/// ([`super::admin_checkpoint::Model::folded_through`]), and an
+/// autoincrement id alone wouldn't survive a restore from a backup taken
+/// between appends.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "admin_op_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub sort_key: i64,
+    pub route: String,
+    pub op_json: Json,
+    pub recorded_at: OffsetDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
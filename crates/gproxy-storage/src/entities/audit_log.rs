@@ -0,0 +1,22 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// One recorded admin-API mutation: who did it (`actor`), which route
+/// handled it, which kind of row it touched and that row's id, and a
+/// redacted copy of the request payload (`secret`/`key_value`/`admin_key`
+/// fields masked before this row is ever written, not after).
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub actor: String,
+    pub route: String,
+    pub entity_type: String,
+    pub entity_id: Option<i64>,
+    pub diff_json: Json,
+    pub created_at: OffsetDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
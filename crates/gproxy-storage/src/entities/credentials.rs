@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "credentials")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub provider_id: i64,
+    pub name: String,
+    pub secret: String,
+    pub meta_json: Json,
+    pub weight: i32,
+    pub enabled: bool,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+    #[sea_orm(belongs_to, from = "provider_id", to = "id")]
+    pub provider: HasOne<super::providers::Entity>,
+    #[sea_orm(has_many)]
+    pub disallow: HasMany<super::credential_disallow::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -23,6 +23,9 @@ pub struct Model {
     pub response_status: i32,
     pub response_headers: String,
     pub response_body: String,
+    /// `"identity"` (the default, plain text) or `"zstd"`. Absent/`identity`
+    /// rows are read back verbatim so pre-compression data keeps loading.
+    pub body_encoding: String,
     pub claude_input_tokens: Option<i64>,
     pub claude_output_tokens: Option<i64>,
     pub claude_total_tokens: Option<i64>,
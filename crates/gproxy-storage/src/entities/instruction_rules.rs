@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// One row of the Codex model-instruction routing table backing
+/// `gproxy_provider_impl::provider::codex::instructions::InstructionRule`.
+/// `position` is the table's match order (lower sorts first) since SQL
+/// result order isn't guaranteed without an explicit `ORDER BY`.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "instruction_rules")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub position: i32,
+    pub model_glob: String,
+    pub template_id: String,
+    pub personality: Option<String>,
+    pub updated_at: OffsetDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,17 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// One named instruction body backing
+/// `gproxy_provider_impl::provider::codex::instructions::InstructionTemplate`.
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "instruction_templates")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub template_id: String,
+    pub body: String,
+    pub updated_at: OffsetDateTime,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
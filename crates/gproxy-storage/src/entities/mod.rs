@@ -0,0 +1,28 @@
+pub mod acme_account;
+pub mod acme_certificates;
+pub mod admin_checkpoint;
+pub mod admin_op_log;
+pub mod api_keys;
+pub mod audit_log;
+pub mod credential_disallow;
+pub mod credentials;
+pub mod downstream_traffic;
+pub mod global_config;
+pub mod instruction_rules;
+pub mod instruction_templates;
+pub mod providers;
+pub mod upstream_traffic;
+pub mod users;
+
+pub use acme_account::Entity as AcmeAccount;
+pub use acme_certificates::Entity as AcmeCertificates;
+pub use api_keys::Entity as ApiKeys;
+pub use credential_disallow::Entity as CredentialDisallow;
+pub use credentials::Entity as Credentials;
+pub use downstream_traffic::Entity as DownstreamTraffic;
+pub use global_config::Entity as GlobalConfig;
+pub use instruction_rules::Entity as InstructionRules;
+pub use instruction_templates::Entity as InstructionTemplates;
+pub use providers::Entity as Providers;
+pub use upstream_traffic::Entity as UpstreamTraffic;
+pub use users::Entity as Users;
@@ -8,6 +8,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64,
     pub name: String,
+    pub config_json: Json,
     pub enabled: bool,
     pub updated_at: OffsetDateTime,
     #[sea_orm(has_many)]
@@ -1,13 +1,32 @@
+pub mod admin_store;
+pub mod backend;
+pub mod change_log;
 pub mod entities;
 pub mod db;
 pub mod bus;
+pub mod object_store_backend;
 pub mod snapshot;
+pub mod stream_audit;
 pub mod traffic;
+pub mod traffic_writer;
 
+pub use admin_store::TrafficStore;
+pub use backend::{memory_backend, StorageBackend};
+pub use change_log::{
+    append_operation, latest_checkpoint, list_operations_since, monotonic_sort_key,
+    write_checkpoint, CHECKPOINT_FOLD_INTERVAL,
+};
 pub use bus::{ConfigEvent, ControlEvent, StorageBus, StorageBusConfig};
+pub use object_store_backend::{FsObjectStore, ObjectPut, ObjectStoreTrafficBackend};
 pub use snapshot::StorageSnapshot;
 pub use gproxy_provider_core::{DownstreamTrafficEvent, UpstreamTrafficEvent};
+pub use stream_audit::{
+    read_stream_audit, replay_stream_audit, spawn_stream_audit_writer, StreamAuditDirection,
+    StreamAuditLine, StreamAuditWriterGuard, StreamAuditWriterHandle,
+};
 pub use traffic::{
-    AdminCredentialInput, AdminDisallowInput, AdminKeyInput, AdminProviderInput, AdminUserInput,
-    TrafficStorage,
+    AdminAuditInput, AdminCredentialInput, AdminDisallowInput, AdminInstructionRuleInput,
+    AdminInstructionTemplateInput, AdminKeyInput, AdminProviderInput, AdminUserInput,
+    RedactionPolicy, TimeRange, TrafficStorage, UsageSummary,
 };
+pub use traffic_writer::{spawn_writer, TrafficWriterGuard, TrafficWriterHandle};
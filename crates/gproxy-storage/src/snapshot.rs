@@ -0,0 +1,16 @@
+use crate::entities;
+
+/// A full read of the admin CRUD tables, returned by
+/// [`crate::admin_store::TrafficStore::load_snapshot`]. `apps/gproxy` loads
+/// one of these at startup (and after a `/admin/reload`) to rebuild its
+/// in-memory auth and provider-pool state from whatever's currently in
+/// storage.
+#[derive(Debug, Clone, Default)]
+pub struct StorageSnapshot {
+    pub global_config: Option<entities::global_config::Model>,
+    pub providers: Vec<entities::providers::Model>,
+    pub credentials: Vec<entities::credentials::Model>,
+    pub disallow: Vec<entities::credential_disallow::Model>,
+    pub users: Vec<entities::users::Model>,
+    pub api_keys: Vec<entities::api_keys::Model>,
+}
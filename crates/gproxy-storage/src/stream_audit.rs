@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_util::stream::unfold;
+use serde::{Deserialize, Serialize};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+
+use gproxy_provider_core::{ProxyResponse, StreamBody};
+
+/// Rows are fsynced once this many lines have been appended since the last
+/// sync...
+const BATCH_SIZE: usize = 64;
+/// ...or once this much time has passed since the last sync, whichever
+/// comes first, so a quiet stream doesn't leave recent frames unsynced.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Which side of a proxied stream a [`StreamAuditLine`] was observed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StreamAuditDirection {
+    Upstream,
+    Downstream,
+}
+
+impl StreamAuditDirection {
+    fn file_suffix(self) -> &'static str {
+        match self {
+            StreamAuditDirection::Upstream => "upstream",
+            StreamAuditDirection::Downstream => "downstream",
+        }
+    }
+}
+
+/// One line of a per-trace newline-delimited JSON audit log. `event` is
+/// `None` only on the closing marker line, which is what tells
+/// [`read_stream_audit`] the file is complete rather than a crash-truncated
+/// partial capture.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamAuditLine {
+    pub trace_id: String,
+    pub direction: StreamAuditDirection,
+    pub ts: i64,
+    pub seq: u64,
+    pub event: Option<String>,
+}
+
+enum StreamAuditCommand {
+    Append {
+        trace_id: String,
+        direction: StreamAuditDirection,
+        ts: i64,
+        data: String,
+    },
+    Finish {
+        trace_id: String,
+        direction: StreamAuditDirection,
+        ts: i64,
+    },
+}
+
+/// A non-blocking handle onto the background task [`spawn_stream_audit_writer`]
+/// starts. `append`/`finish` never wait on disk I/O: they push onto a
+/// bounded channel and drop the line (counted in `dropped_lines`) when that
+/// channel is full, so a slow filesystem degrades audit coverage instead of
+/// the request path.
+#[derive(Clone)]
+pub struct StreamAuditWriterHandle {
+    tx: mpsc::Sender<StreamAuditCommand>,
+    dropped: std::sync::Arc<std::sync::atomic::AtomicU64>,
+}
+
+impl StreamAuditWriterHandle {
+    /// Appends one decoded `data:` frame to `trace_id`'s audit log.
+    pub fn append(&self, trace_id: String, direction: StreamAuditDirection, ts: i64, data: String) {
+        self.send(StreamAuditCommand::Append {
+            trace_id,
+            direction,
+            ts,
+            data,
+        });
+    }
+
+    /// Appends the closing marker line and lets the background task drop
+    /// its cached file handle for this trace/direction.
+    pub fn finish(&self, trace_id: String, direction: StreamAuditDirection, ts: i64) {
+        self.send(StreamAuditCommand::Finish {
+            trace_id,
+            direction,
+            ts,
+        });
+    }
+
+    fn send(&self, command: StreamAuditCommand) {
+        if self.tx.try_send(command).is_err() {
+            let dropped = self
+                .dropped
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+                + 1;
+            warn!(
+                event = "stream_audit_buffer_full",
+                dropped_total = dropped,
+                "dropping stream audit line: write-ahead buffer saturated"
+            );
+        }
+    }
+
+    /// Total lines dropped since this writer was spawned because the
+    /// buffer was saturated. Operators should alert on this climbing.
+    pub fn dropped_lines(&self) -> u64 {
+        self.dropped.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// Owns the sending half needed to signal shutdown and the background
+/// task's `JoinHandle`, mirroring [`crate::traffic_writer::TrafficWriterGuard`].
+pub struct StreamAuditWriterGuard {
+    tx: mpsc::Sender<StreamAuditCommand>,
+    task: JoinHandle<()>,
+}
+
+impl StreamAuditWriterGuard {
+    /// Closes the channel and waits for the background task to fsync
+    /// whatever it had buffered, then returns.
+    pub async fn shutdown(self) -> Result<(), tokio::task::JoinError> {
+        drop(self.tx);
+        self.task.await
+    }
+}
+
+/// Spawns the background task that appends streamed SSE frames to
+/// `base_dir/<trace_id>.<direction>.jsonl`, one JSON line per frame,
+/// fsync-batched the same way [`crate::traffic_writer::spawn_writer`]
+/// batches database inserts. Returns immediately.
+pub fn spawn_stream_audit_writer(
+    base_dir: PathBuf,
+    capacity: usize,
+) -> (StreamAuditWriterHandle, StreamAuditWriterGuard) {
+    let (tx, rx) = mpsc::channel(capacity);
+    let dropped = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+    let task = tokio::spawn(run_writer(base_dir, rx));
+    (
+        StreamAuditWriterHandle {
+            tx: tx.clone(),
+            dropped,
+        },
+        StreamAuditWriterGuard { tx, task },
+    )
+}
+
+struct OpenAuditFile {
+    file: File,
+    seq: u64,
+    dirty_lines: usize,
+}
+
+async fn run_writer(base_dir: PathBuf, mut rx: mpsc::Receiver<StreamAuditCommand>) {
+    let mut open_files: HashMap<(String, StreamAuditDirection), OpenAuditFile> = HashMap::new();
+    let mut ticker = interval(FLUSH_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            command = rx.recv() => {
+                match command {
+                    Some(command) => handle_command(&base_dir, &mut open_files, command).await,
+                    None => {
+                        for (_, entry) in open_files.iter_mut() {
+                            let _ = entry.file.sync_data().await;
+                        }
+                        return;
+                    }
+                }
+            }
+            _ = ticker.tick() => {
+                for (_, entry) in open_files.iter_mut() {
+                    if entry.dirty_lines > 0 {
+                        let _ = entry.file.sync_data().await;
+                        entry.dirty_lines = 0;
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn handle_command(
+    base_dir: &Path,
+    open_files: &mut HashMap<(String, StreamAuditDirection), OpenAuditFile>,
+    command: StreamAuditCommand,
+) {
+    match command {
+        StreamAuditCommand::Append {
+            trace_id,
+            direction,
+            ts,
+            data,
+        } => {
+            let Ok(entry) = entry_for(base_dir, open_files, &trace_id, direction).await else {
+                return;
+            };
+            entry.seq += 1;
+            let line = StreamAuditLine {
+                trace_id,
+                direction,
+                ts,
+                seq: entry.seq,
+                event: Some(data),
+            };
+            if let Ok(json) = serde_json::to_string(&line) {
+                let _ = entry.file.write_all(json.as_bytes()).await;
+                let _ = entry.file.write_all(b"\n").await;
+                write_line(entry).await;
+            }
+        }
+        StreamAuditCommand::Finish {
+            trace_id,
+            direction,
+            ts,
+        } => {
+            let key = (trace_id.clone(), direction);
+            if let Ok(entry) = entry_for(base_dir, open_files, &trace_id, direction).await {
+                entry.seq += 1;
+                let line = StreamAuditLine {
+                    trace_id,
+                    direction,
+                    ts,
+                    seq: entry.seq,
+                    event: None,
+                };
+                if let Ok(json) = serde_json::to_string(&line) {
+                    let _ = entry.file.write_all(json.as_bytes()).await;
+                    let _ = entry.file.write_all(b"\n").await;
+                }
+                let _ = entry.file.sync_data().await;
+            }
+            open_files.remove(&key);
+        }
+    }
+}
+
+async fn write_line(entry: &mut OpenAuditFile) {
+    entry.dirty_lines += 1;
+    if entry.dirty_lines >= BATCH_SIZE {
+        let _ = entry.file.sync_data().await;
+        entry.dirty_lines = 0;
+    }
+}
+
+async fn entry_for<'a>(
+    base_dir: &Path,
+    open_files: &'a mut HashMap<(String, StreamAuditDirection), OpenAuditFile>,
+    trace_id: &str,
+    direction: StreamAuditDirection,
+) -> io::Result<&'a mut OpenAuditFile> {
+    let key = (trace_id.to_string(), direction);
+    if !open_files.contains_key(&key) {
+        let path = base_dir.join(format!("{trace_id}.{}.jsonl", direction.file_suffix()));
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+        open_files.insert(
+            key.clone(),
+            OpenAuditFile {
+                file,
+                seq: 0,
+                dirty_lines: 0,
+            },
+        );
+    }
+    Ok(open_files.get_mut(&key).expect("just inserted"))
+}
+
+/// Reads a stream audit file back into its constituent lines, in the order
+/// they were appended. The caller can tell the capture is complete (rather
+/// than a crash-truncated partial one) by checking the last line's `event`
+/// is `None`.
+pub async fn read_stream_audit(path: &Path) -> io::Result<Vec<StreamAuditLine>> {
+    let file = File::open(path).await?;
+    let mut reader = BufReader::new(file).lines();
+    let mut lines = Vec::new();
+    while let Some(raw) = reader.next_line().await? {
+        if raw.is_empty() {
+            continue;
+        }
+        if let Ok(line) = serde_json::from_str::<StreamAuditLine>(&raw) {
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
+
+/// Replays a recorded stream audit file as a [`ProxyResponse::Stream`],
+/// re-emitting each captured frame through a `StreamBody` the same way a
+/// live upstream would. With `preserve_timing`, frames are paced apart by
+/// the original inter-event `ts` deltas instead of being emitted back to
+/// back; the closing marker line is consumed but not re-emitted.
+pub async fn replay_stream_audit(
+    path: &Path,
+    status: http::StatusCode,
+    headers: http::HeaderMap,
+    content_type: String,
+    preserve_timing: bool,
+) -> io::Result<ProxyResponse> {
+    let mut lines = read_stream_audit(path).await?;
+    lines.retain(|line| line.event.is_some());
+    let lines = std::sync::Arc::new(lines);
+
+    let stream = unfold(0usize, move |index| {
+        let lines = lines.clone();
+        async move {
+            let line = lines.get(index)?;
+            if preserve_timing && index > 0 {
+                if let Some(previous) = lines.get(index - 1) {
+                    let delta_ms = (line.ts - previous.ts).max(0) as u64;
+                    if delta_ms > 0 {
+                        tokio::time::sleep(Duration::from_millis(delta_ms)).await;
+                    }
+                }
+            }
+            let data = line.event.clone().unwrap_or_default();
+            Some((Ok::<Bytes, io::Error>(Bytes::from(data)), index + 1))
+        }
+    });
+
+    Ok(ProxyResponse::Stream {
+        status,
+        headers,
+        body: StreamBody::new(content_type, stream),
+    })
+}
@@ -1,11 +1,52 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use sea_orm::entity::prelude::*;
-use sea_orm::sea_query::OnConflict;
-use sea_orm::{ActiveValue, Database, DatabaseConnection, DbErr, Schema};
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ActiveValue, Database, DatabaseConnection, DbErr, FromQueryResult, QueryFilter, QuerySelect,
+    Schema,
+};
+use sha2::{Digest, Sha256};
 use time::OffsetDateTime;
 
 use crate::entities;
 
-#[derive(Debug, Clone)]
+const IDENTITY_ENCODING: &str = "identity";
+const ZSTD_ENCODING: &str = "zstd";
+/// Bodies shorter than this aren't worth the zstd framing overhead.
+const COMPRESS_THRESHOLD_BYTES: usize = 2048;
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compresses `body` with zstd when it's large enough to be worth it,
+/// returning the stored text (base64 when compressed, verbatim otherwise)
+/// alongside the `body_encoding` value that records which it is.
+fn encode_body(body: &str) -> (String, String) {
+    if body.len() < COMPRESS_THRESHOLD_BYTES {
+        return (body.to_string(), IDENTITY_ENCODING.to_string());
+    }
+    match zstd::encode_all(body.as_bytes(), ZSTD_LEVEL) {
+        Ok(compressed) => (BASE64.encode(compressed), ZSTD_ENCODING.to_string()),
+        Err(_) => (body.to_string(), IDENTITY_ENCODING.to_string()),
+    }
+}
+
+/// Reverses [`encode_body`]. Any encoding other than `"zstd"` (including an
+/// absent/unrecognized one) is treated as plain text so rows written before
+/// this column existed still load.
+pub fn decode_body(body: &str, body_encoding: &str) -> String {
+    if body_encoding != ZSTD_ENCODING {
+        return body.to_string();
+    }
+    let Ok(compressed) = BASE64.decode(body) else {
+        return body.to_string();
+    };
+    zstd::decode_all(compressed.as_slice())
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or_else(|| body.to_string())
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct DownstreamTrafficEvent {
     pub provider: String,
     pub provider_id: Option<i64>,
@@ -47,7 +88,7 @@ pub struct DownstreamTrafficEvent {
     pub openai_responses_output_reasoning_tokens: Option<i64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct UpstreamTrafficEvent {
     pub provider: String,
     pub provider_id: Option<i64>,
@@ -88,19 +129,242 @@ pub struct UpstreamTrafficEvent {
     pub openai_responses_output_reasoning_tokens: Option<i64>,
 }
 
+/// Upsert payload for [`crate::admin_store::TrafficStore::upsert_provider`].
+/// `id: None` inserts a new row; `Some(id)` updates the existing one.
+#[derive(Debug, Clone)]
+pub struct AdminProviderInput {
+    pub id: Option<i64>,
+    pub name: String,
+    pub config_json: sea_orm::prelude::Json,
+    pub enabled: bool,
+}
+
+/// Upsert payload for [`crate::admin_store::TrafficStore::upsert_credential`].
+#[derive(Debug, Clone)]
+pub struct AdminCredentialInput {
+    pub id: Option<i64>,
+    pub provider_id: i64,
+    pub name: String,
+    pub secret: String,
+    pub meta_json: sea_orm::prelude::Json,
+    pub weight: i32,
+    pub enabled: bool,
+}
+
+/// Upsert payload for [`crate::admin_store::TrafficStore::upsert_disallow`].
+#[derive(Debug, Clone)]
+pub struct AdminDisallowInput {
+    pub id: Option<i64>,
+    pub credential_id: i64,
+    pub scope_kind: String,
+    pub scope_value: Option<String>,
+    pub level: String,
+    pub until_at: Option<OffsetDateTime>,
+    pub reason: Option<String>,
+}
+
+/// Upsert payload for [`crate::admin_store::TrafficStore::upsert_user`].
+#[derive(Debug, Clone)]
+pub struct AdminUserInput {
+    pub id: Option<i64>,
+    pub name: Option<String>,
+}
+
+/// Upsert payload for [`crate::admin_store::TrafficStore::upsert_key`].
+#[derive(Debug, Clone)]
+pub struct AdminKeyInput {
+    pub id: Option<i64>,
+    pub user_id: i64,
+    pub key_value: String,
+    pub label: Option<String>,
+    pub enabled: bool,
+}
+
+/// Upsert payload for
+/// [`crate::admin_store::TrafficStore::upsert_instruction_template`].
+#[derive(Debug, Clone)]
+pub struct AdminInstructionTemplateInput {
+    pub id: Option<i64>,
+    pub template_id: String,
+    pub body: String,
+}
+
+/// Upsert payload for
+/// [`crate::admin_store::TrafficStore::upsert_instruction_rule`].
+#[derive(Debug, Clone)]
+pub struct AdminInstructionRuleInput {
+    pub id: Option<i64>,
+    pub position: i32,
+    pub model_glob: String,
+    pub template_id: String,
+    pub personality: Option<String>,
+}
+
+/// Insert payload for [`crate::admin_store::TrafficStore::insert_audit_entry`].
+#[derive(Debug, Clone)]
+pub struct AdminAuditInput {
+    pub actor: String,
+    pub route: String,
+    pub entity_type: String,
+    pub entity_id: Option<i64>,
+    pub diff_json: sea_orm::prelude::Json,
+}
+
+/// A window over `created_at` used to scope aggregate usage queries.
+/// Half-open (`start` inclusive, `end` exclusive) so adjacent windows never
+/// double-count a row that lands exactly on the boundary.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeRange {
+    pub start: OffsetDateTime,
+    pub end: OffsetDateTime,
+}
+
+/// One row of token usage summed over a [`TimeRange`] and grouped by
+/// whichever dimension the query asked for
+/// ([`usage_by_credential`](TrafficStorage::usage_by_credential),
+/// [`usage_by_key`](TrafficStorage::usage_by_key), or
+/// [`usage_by_model`](TrafficStorage::usage_by_model)); only the field(s)
+/// matching that dimension are populated, the rest are `None`.
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct UsageSummary {
+    pub credential_id: Option<i64>,
+    pub key_id: Option<i64>,
+    pub model: Option<String>,
+    pub request_count: i64,
+    pub claude_input_tokens: Option<i64>,
+    pub claude_output_tokens: Option<i64>,
+    pub claude_total_tokens: Option<i64>,
+    pub gemini_prompt_tokens: Option<i64>,
+    pub gemini_candidates_tokens: Option<i64>,
+    pub gemini_total_tokens: Option<i64>,
+    pub openai_chat_prompt_tokens: Option<i64>,
+    pub openai_chat_completion_tokens: Option<i64>,
+    pub openai_chat_total_tokens: Option<i64>,
+    pub openai_responses_input_tokens: Option<i64>,
+    pub openai_responses_output_tokens: Option<i64>,
+    pub openai_responses_total_tokens: Option<i64>,
+}
+
+/// Controls what [`insert_downstream`](TrafficStorage::insert_downstream) and
+/// [`insert_upstream`](TrafficStorage::insert_upstream) persist for
+/// sensitive header/body content, so regulated deployments aren't forced to
+/// store credentials or full prompt/response text in cleartext.
+#[derive(Debug, Clone)]
+pub struct RedactionPolicy {
+    /// Header names (matched case-insensitively) whose value is replaced
+    /// with `"[redacted]"` before storage.
+    pub redacted_headers: Vec<String>,
+    /// Bodies longer than this are hashed or truncated, per
+    /// `hash_oversized_bodies`. `None` disables the size check entirely.
+    pub max_body_bytes: Option<usize>,
+    /// When a body exceeds `max_body_bytes`: `true` replaces it with a
+    /// `sha256:<hex digest>`, `false` truncates it to `max_body_bytes` with
+    /// a trailing `...[truncated]` marker.
+    pub hash_oversized_bodies: bool,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self {
+            redacted_headers: vec![
+                "authorization".to_string(),
+                "x-api-key".to_string(),
+                "cookie".to_string(),
+                "set-cookie".to_string(),
+            ],
+            max_body_bytes: None,
+            hash_oversized_bodies: false,
+        }
+    }
+}
+
+impl RedactionPolicy {
+    const REDACTED_MARKER: &'static str = "[redacted]";
+    const TRUNCATED_MARKER: &'static str = "...[truncated]";
+
+    /// `headers_json` is the `{"header-name": "value", ...}` string produced
+    /// by `crate::record::headers_to_json` upstream; any key matching
+    /// `redacted_headers` has its value blanked out. Falls back to the
+    /// input unchanged if it isn't a JSON object, rather than dropping data
+    /// on an unexpected shape.
+    fn redact_headers(&self, headers_json: &str) -> String {
+        let Ok(serde_json::Value::Object(mut headers)) = serde_json::from_str(headers_json) else {
+            return headers_json.to_string();
+        };
+        for (name, value) in headers.iter_mut() {
+            if self
+                .redacted_headers
+                .iter()
+                .any(|redacted| redacted.eq_ignore_ascii_case(name))
+            {
+                *value = serde_json::Value::String(Self::REDACTED_MARKER.to_string());
+            }
+        }
+        serde_json::Value::Object(headers).to_string()
+    }
+
+    fn redact_body(&self, body: String) -> String {
+        let Some(max_body_bytes) = self.max_body_bytes else {
+            return body;
+        };
+        if body.len() <= max_body_bytes {
+            return body;
+        }
+        if self.hash_oversized_bodies {
+            format!("sha256:{:x}", Sha256::digest(body.as_bytes()))
+        } else {
+            let mut truncated: String = body.chars().take(max_body_bytes).collect();
+            truncated.push_str(Self::TRUNCATED_MARKER);
+            truncated
+        }
+    }
+
+    fn apply_downstream(&self, mut event: DownstreamTrafficEvent) -> DownstreamTrafficEvent {
+        event.request_headers = self.redact_headers(&event.request_headers);
+        event.response_headers = self.redact_headers(&event.response_headers);
+        event.request_body = self.redact_body(event.request_body);
+        event.response_body = self.redact_body(event.response_body);
+        event
+    }
+
+    fn apply_upstream(&self, mut event: UpstreamTrafficEvent) -> UpstreamTrafficEvent {
+        event.request_headers = self.redact_headers(&event.request_headers);
+        event.response_headers = self.redact_headers(&event.response_headers);
+        event.request_body = self.redact_body(event.request_body);
+        event.response_body = self.redact_body(event.response_body);
+        event
+    }
+}
+
 #[derive(Clone)]
 pub struct TrafficStorage {
     db: DatabaseConnection,
+    policy: RedactionPolicy,
 }
 
 impl TrafficStorage {
     pub async fn connect(database_url: &str) -> Result<Self, DbErr> {
         let db = Database::connect(database_url).await?;
-        Ok(Self { db })
+        Ok(Self {
+            db,
+            policy: RedactionPolicy::default(),
+        })
     }
 
     pub async fn from_connection(db: DatabaseConnection) -> Result<Self, DbErr> {
-        Ok(Self { db })
+        Ok(Self {
+            db,
+            policy: RedactionPolicy::default(),
+        })
+    }
+
+    /// Overrides the redaction policy applied by
+    /// [`insert_downstream`](Self::insert_downstream) and
+    /// [`insert_upstream`](Self::insert_upstream); the default blanks the
+    /// common credential-bearing headers and never touches bodies.
+    pub fn with_redaction_policy(mut self, policy: RedactionPolicy) -> Self {
+        self.policy = policy;
+        self
     }
 
     pub fn connection(&self) -> &DatabaseConnection {
@@ -116,35 +380,242 @@ impl TrafficStorage {
             .register(entities::Credentials)
             .register(entities::CredentialDisallow)
             .register(entities::GlobalConfig)
+            .register(entities::InstructionTemplates)
+            .register(entities::InstructionRules)
+            .register(entities::AcmeAccount)
+            .register(entities::AcmeCertificates)
             .register(entities::DownstreamTraffic)
             .register(entities::UpstreamTraffic)
             .sync(&self.db)
             .await
     }
 
-    pub async fn insert_downstream(
+    pub async fn insert_downstream(&self, event: DownstreamTrafficEvent) -> Result<(), DbErr> {
+        let active = downstream_active(self.policy.apply_downstream(event));
+        entities::DownstreamTraffic::insert(active)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Same as [`insert_downstream`](Self::insert_downstream) but for a
+    /// batch of events in one multi-row `INSERT`, used by the durable
+    /// write-ahead buffer in `traffic_writer` to keep round-trips down
+    /// under load.
+    pub async fn insert_downstream_batch(
         &self,
-        event: DownstreamTrafficEvent,
+        events: Vec<DownstreamTrafficEvent>,
     ) -> Result<(), DbErr> {
-        let now = OffsetDateTime::now_utc();
-        let mut active: entities::downstream_traffic::ActiveModel = event.into();
-        active.created_at = ActiveValue::Set(now);
-        entities::DownstreamTraffic::insert(active)
+        if events.is_empty() {
+            return Ok(());
+        }
+        let actives = events
+            .into_iter()
+            .map(|event| downstream_active(self.policy.apply_downstream(event)));
+        entities::DownstreamTraffic::insert_many(actives)
             .exec(&self.db)
             .await?;
         Ok(())
     }
 
     pub async fn insert_upstream(&self, event: UpstreamTrafficEvent) -> Result<(), DbErr> {
-        let now = OffsetDateTime::now_utc();
-        let mut active: entities::upstream_traffic::ActiveModel = event.into();
-        active.created_at = ActiveValue::Set(now);
+        let active = upstream_active(self.policy.apply_upstream(event));
         entities::UpstreamTraffic::insert(active)
             .exec(&self.db)
             .await?;
         Ok(())
     }
 
+    /// Same as [`insert_upstream`](Self::insert_upstream) but for a batch
+    /// of events in one multi-row `INSERT`.
+    pub async fn insert_upstream_batch(
+        &self,
+        events: Vec<UpstreamTrafficEvent>,
+    ) -> Result<(), DbErr> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let actives = events
+            .into_iter()
+            .map(|event| upstream_active(self.policy.apply_upstream(event)));
+        entities::UpstreamTraffic::insert_many(actives)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Sums token usage from `upstream_traffic` grouped by `credential_id`
+    /// over `range`, optionally narrowed to a single `provider`. Powers
+    /// per-credential billing/rate-tracking dashboards without hand-written
+    /// SQL.
+    pub async fn usage_by_credential(
+        &self,
+        provider: Option<&str>,
+        range: TimeRange,
+    ) -> Result<Vec<UsageSummary>, DbErr> {
+        use entities::upstream_traffic::Column;
+
+        let mut query = entities::UpstreamTraffic::find()
+            .select_only()
+            .column(Column::CredentialId)
+            .column_as(Expr::value(None::<i64>), "key_id")
+            .column_as(Expr::value(None::<String>), "model")
+            .column_as(Column::Id.count(), "request_count")
+            .column_as(Column::ClaudeInputTokens.sum(), "claude_input_tokens")
+            .column_as(Column::ClaudeOutputTokens.sum(), "claude_output_tokens")
+            .column_as(Column::ClaudeTotalTokens.sum(), "claude_total_tokens")
+            .column_as(Column::GeminiPromptTokens.sum(), "gemini_prompt_tokens")
+            .column_as(
+                Column::GeminiCandidatesTokens.sum(),
+                "gemini_candidates_tokens",
+            )
+            .column_as(Column::GeminiTotalTokens.sum(), "gemini_total_tokens")
+            .column_as(
+                Column::OpenaiChatPromptTokens.sum(),
+                "openai_chat_prompt_tokens",
+            )
+            .column_as(
+                Column::OpenaiChatCompletionTokens.sum(),
+                "openai_chat_completion_tokens",
+            )
+            .column_as(
+                Column::OpenaiChatTotalTokens.sum(),
+                "openai_chat_total_tokens",
+            )
+            .column_as(
+                Column::OpenaiResponsesInputTokens.sum(),
+                "openai_responses_input_tokens",
+            )
+            .column_as(
+                Column::OpenaiResponsesOutputTokens.sum(),
+                "openai_responses_output_tokens",
+            )
+            .column_as(
+                Column::OpenaiResponsesTotalTokens.sum(),
+                "openai_responses_total_tokens",
+            )
+            .filter(Column::CreatedAt.gte(range.start))
+            .filter(Column::CreatedAt.lt(range.end))
+            .group_by(Column::CredentialId);
+
+        if let Some(provider) = provider {
+            query = query.filter(Column::Provider.eq(provider.to_string()));
+        }
+
+        query.into_model::<UsageSummary>().all(&self.db).await
+    }
+
+    /// Sums token usage from `downstream_traffic` grouped by `key_id` over
+    /// `range`, optionally narrowed to a single `key_id`.
+    pub async fn usage_by_key(
+        &self,
+        key_id: Option<i64>,
+        range: TimeRange,
+    ) -> Result<Vec<UsageSummary>, DbErr> {
+        use entities::downstream_traffic::Column;
+
+        let mut query = entities::DownstreamTraffic::find()
+            .select_only()
+            .column_as(Expr::value(None::<i64>), "credential_id")
+            .column(Column::KeyId)
+            .column_as(Expr::value(None::<String>), "model")
+            .column_as(Column::Id.count(), "request_count")
+            .column_as(Column::ClaudeInputTokens.sum(), "claude_input_tokens")
+            .column_as(Column::ClaudeOutputTokens.sum(), "claude_output_tokens")
+            .column_as(Column::ClaudeTotalTokens.sum(), "claude_total_tokens")
+            .column_as(Column::GeminiPromptTokens.sum(), "gemini_prompt_tokens")
+            .column_as(
+                Column::GeminiCandidatesTokens.sum(),
+                "gemini_candidates_tokens",
+            )
+            .column_as(Column::GeminiTotalTokens.sum(), "gemini_total_tokens")
+            .column_as(
+                Column::OpenaiChatPromptTokens.sum(),
+                "openai_chat_prompt_tokens",
+            )
+            .column_as(
+                Column::OpenaiChatCompletionTokens.sum(),
+                "openai_chat_completion_tokens",
+            )
+            .column_as(
+                Column::OpenaiChatTotalTokens.sum(),
+                "openai_chat_total_tokens",
+            )
+            .column_as(
+                Column::OpenaiResponsesInputTokens.sum(),
+                "openai_responses_input_tokens",
+            )
+            .column_as(
+                Column::OpenaiResponsesOutputTokens.sum(),
+                "openai_responses_output_tokens",
+            )
+            .column_as(
+                Column::OpenaiResponsesTotalTokens.sum(),
+                "openai_responses_total_tokens",
+            )
+            .filter(Column::CreatedAt.gte(range.start))
+            .filter(Column::CreatedAt.lt(range.end))
+            .group_by(Column::KeyId);
+
+        if let Some(key_id) = key_id {
+            query = query.filter(Column::KeyId.eq(key_id));
+        }
+
+        query.into_model::<UsageSummary>().all(&self.db).await
+    }
+
+    /// Sums token usage from `downstream_traffic` grouped by `model` over
+    /// `range`.
+    pub async fn usage_by_model(&self, range: TimeRange) -> Result<Vec<UsageSummary>, DbErr> {
+        use entities::downstream_traffic::Column;
+
+        entities::DownstreamTraffic::find()
+            .select_only()
+            .column_as(Expr::value(None::<i64>), "credential_id")
+            .column_as(Expr::value(None::<i64>), "key_id")
+            .column(Column::Model)
+            .column_as(Column::Id.count(), "request_count")
+            .column_as(Column::ClaudeInputTokens.sum(), "claude_input_tokens")
+            .column_as(Column::ClaudeOutputTokens.sum(), "claude_output_tokens")
+            .column_as(Column::ClaudeTotalTokens.sum(), "claude_total_tokens")
+            .column_as(Column::GeminiPromptTokens.sum(), "gemini_prompt_tokens")
+            .column_as(
+                Column::GeminiCandidatesTokens.sum(),
+                "gemini_candidates_tokens",
+            )
+            .column_as(Column::GeminiTotalTokens.sum(), "gemini_total_tokens")
+            .column_as(
+                Column::OpenaiChatPromptTokens.sum(),
+                "openai_chat_prompt_tokens",
+            )
+            .column_as(
+                Column::OpenaiChatCompletionTokens.sum(),
+                "openai_chat_completion_tokens",
+            )
+            .column_as(
+                Column::OpenaiChatTotalTokens.sum(),
+                "openai_chat_total_tokens",
+            )
+            .column_as(
+                Column::OpenaiResponsesInputTokens.sum(),
+                "openai_responses_input_tokens",
+            )
+            .column_as(
+                Column::OpenaiResponsesOutputTokens.sum(),
+                "openai_responses_output_tokens",
+            )
+            .column_as(
+                Column::OpenaiResponsesTotalTokens.sum(),
+                "openai_responses_total_tokens",
+            )
+            .filter(Column::CreatedAt.gte(range.start))
+            .filter(Column::CreatedAt.lt(range.end))
+            .group_by(Column::Model)
+            .into_model::<UsageSummary>()
+            .all(&self.db)
+            .await
+    }
+
     pub async fn upsert_global_config(
         &self,
         id: i64,
@@ -171,6 +642,106 @@ impl TrafficStorage {
         Ok(())
     }
 
+    /// The ACME account's private key (PEM), backing
+    /// `apps/gproxy`'s `tls_acme::CertificateStore::load_account_key`.
+    pub async fn load_acme_account_key(&self) -> Result<Option<String>, DbErr> {
+        Ok(entities::AcmeAccount::find_by_id(1)
+            .one(&self.db)
+            .await?
+            .map(|row| row.key_pem))
+    }
+
+    pub async fn save_acme_account_key(&self, key_pem: &str) -> Result<(), DbErr> {
+        use entities::acme_account::Column;
+
+        let active = entities::acme_account::ActiveModel {
+            id: ActiveValue::Set(1),
+            key_pem: ActiveValue::Set(key_pem.to_string()),
+            updated_at: ActiveValue::Set(OffsetDateTime::now_utc()),
+        };
+
+        entities::AcmeAccount::insert(active)
+            .on_conflict(
+                OnConflict::column(Column::Id)
+                    .update_columns([Column::KeyPem, Column::UpdatedAt])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// The most recently issued certificate/key pair for `domain`, backing
+    /// `tls_acme::CertificateStore::load_certificate`.
+    pub async fn load_acme_certificate(
+        &self,
+        domain: &str,
+    ) -> Result<Option<entities::acme_certificates::Model>, DbErr> {
+        use entities::acme_certificates::Column;
+
+        entities::AcmeCertificates::find()
+            .filter(Column::Domain.eq(domain))
+            .one(&self.db)
+            .await
+    }
+
+    /// Upserts the certificate/key pair for `domain`, keyed by `domain`
+    /// rather than `id` since callers never have a row id on hand — this
+    /// reads the existing row (if any) to decide insert vs. update, the
+    /// same two-step `TrafficStore::upsert_disallow` would need if
+    /// `CredentialDisallow` were keyed by something other than `id`.
+    pub async fn save_acme_certificate(
+        &self,
+        domain: &str,
+        cert_chain_pem: &str,
+        private_key_pem: &str,
+        not_after: OffsetDateTime,
+    ) -> Result<(), DbErr> {
+        use entities::acme_certificates::Column;
+
+        let existing = entities::AcmeCertificates::find()
+            .filter(Column::Domain.eq(domain))
+            .one(&self.db)
+            .await?;
+
+        let now = OffsetDateTime::now_utc();
+        let active = entities::acme_certificates::ActiveModel {
+            id: match existing {
+                Some(ref row) => ActiveValue::Set(row.id),
+                None => ActiveValue::NotSet,
+            },
+            domain: ActiveValue::Set(domain.to_string()),
+            cert_chain_pem: ActiveValue::Set(cert_chain_pem.to_string()),
+            private_key_pem: ActiveValue::Set(private_key_pem.to_string()),
+            not_after: ActiveValue::Set(not_after),
+            updated_at: ActiveValue::Set(now),
+        };
+
+        match existing {
+            Some(_) => {
+                entities::AcmeCertificates::insert(active)
+                    .on_conflict(
+                        OnConflict::column(Column::Id)
+                            .update_columns([
+                                Column::CertChainPem,
+                                Column::PrivateKeyPem,
+                                Column::NotAfter,
+                                Column::UpdatedAt,
+                            ])
+                            .to_owned(),
+                    )
+                    .exec(&self.db)
+                    .await?;
+            }
+            None => {
+                entities::AcmeCertificates::insert(active)
+                    .exec(&self.db)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     pub async fn ensure_admin_user(&self, admin_key: &str) -> Result<(), DbErr> {
         let now = OffsetDateTime::now_utc();
 
@@ -224,6 +795,32 @@ impl TrafficStorage {
     }
 }
 
+fn downstream_active(event: DownstreamTrafficEvent) -> entities::downstream_traffic::ActiveModel {
+    let now = OffsetDateTime::now_utc();
+    let (request_body, request_encoding) = encode_body(&event.request_body);
+    let (response_body, response_encoding) = encode_body(&event.response_body);
+    // Bodies only ever share one encoding per row; a mismatch can only
+    // happen if one of the two was too short to compress.
+    let body_encoding = if request_encoding == ZSTD_ENCODING || response_encoding == ZSTD_ENCODING {
+        ZSTD_ENCODING
+    } else {
+        IDENTITY_ENCODING
+    };
+    let mut active: entities::downstream_traffic::ActiveModel = event.into();
+    active.created_at = ActiveValue::Set(now);
+    active.request_body = ActiveValue::Set(request_body);
+    active.response_body = ActiveValue::Set(response_body);
+    active.body_encoding = ActiveValue::Set(body_encoding.to_string());
+    active
+}
+
+fn upstream_active(event: UpstreamTrafficEvent) -> entities::upstream_traffic::ActiveModel {
+    let now = OffsetDateTime::now_utc();
+    let mut active: entities::upstream_traffic::ActiveModel = event.into();
+    active.created_at = ActiveValue::Set(now);
+    active
+}
+
 impl From<DownstreamTrafficEvent> for entities::downstream_traffic::ActiveModel {
     fn from(event: DownstreamTrafficEvent) -> Self {
         entities::downstream_traffic::ActiveModel {
@@ -256,9 +853,7 @@ impl From<DownstreamTrafficEvent> for entities::downstream_traffic::ActiveModel
             gemini_total_tokens: ActiveValue::Set(event.gemini_total_tokens),
             gemini_cached_tokens: ActiveValue::Set(event.gemini_cached_tokens),
             openai_chat_prompt_tokens: ActiveValue::Set(event.openai_chat_prompt_tokens),
-            openai_chat_completion_tokens: ActiveValue::Set(
-                event.openai_chat_completion_tokens,
-            ),
+            openai_chat_completion_tokens: ActiveValue::Set(event.openai_chat_completion_tokens),
             openai_chat_total_tokens: ActiveValue::Set(event.openai_chat_total_tokens),
             openai_responses_input_tokens: ActiveValue::Set(event.openai_responses_input_tokens),
             openai_responses_output_tokens: ActiveValue::Set(event.openai_responses_output_tokens),
@@ -304,9 +899,7 @@ impl From<UpstreamTrafficEvent> for entities::upstream_traffic::ActiveModel {
             gemini_total_tokens: ActiveValue::Set(event.gemini_total_tokens),
             gemini_cached_tokens: ActiveValue::Set(event.gemini_cached_tokens),
             openai_chat_prompt_tokens: ActiveValue::Set(event.openai_chat_prompt_tokens),
-            openai_chat_completion_tokens: ActiveValue::Set(
-                event.openai_chat_completion_tokens,
-            ),
+            openai_chat_completion_tokens: ActiveValue::Set(event.openai_chat_completion_tokens),
             openai_chat_total_tokens: ActiveValue::Set(event.openai_chat_total_tokens),
             openai_responses_input_tokens: ActiveValue::Set(event.openai_responses_input_tokens),
             openai_responses_output_tokens: ActiveValue::Set(event.openai_responses_output_tokens),
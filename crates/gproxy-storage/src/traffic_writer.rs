@@ -0,0 +1,207 @@
+//! Durable write-ahead buffering for already-individual traffic events on
+//! their way into storage: group up to `BATCH_SIZE` events into one
+//! multi-row `INSERT` (or flush early on `FLUSH_INTERVAL`), retrying with
+//! backoff on failure instead of dropping them.
+//!
+//! This is a different job from `gproxy_provider_core::traffic_batch`'s
+//! `TrafficBatcher`: that type *merges* multiple events sharing a
+//! `batch_key` into one before it's ever written (e.g. folding a
+//! downstream event into its matching upstream event), which would be
+//! wrong here — two distinct downstream events can share a `trace_id` and
+//! both still need their own row. This module only ever groups
+//! already-final events for a cheaper write, never merges them, so it
+//! isn't a reimplementation of `TrafficBatcher`'s scheduling/merging
+//! engine, just a later pipeline stage it would feed into once
+//! `StorageTrafficSink` (see `traffic_batch`'s module doc) exists.
+
+use std::mem;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::{JoinError, JoinHandle};
+use tokio::time::{MissedTickBehavior, interval};
+use tracing::warn;
+
+use crate::backend::StorageBackend;
+use crate::traffic::{DownstreamTrafficEvent, UpstreamTrafficEvent};
+
+/// Rows are flushed once a batch reaches this size...
+const BATCH_SIZE: usize = 64;
+/// ...or once this much time has passed since the last flush, whichever
+/// comes first, so a quiet period doesn't leave events sitting unwritten.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+const BASE_BACKOFF: Duration = Duration::from_millis(200);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+enum TrafficWriteEvent {
+    Downstream(DownstreamTrafficEvent),
+    Upstream(UpstreamTrafficEvent),
+}
+
+/// A non-blocking handle onto the background task [`spawn_writer`] starts.
+/// `enqueue_downstream`/`enqueue_upstream` never wait on the database: they
+/// push onto a bounded channel and fall back to dropping the event (counted
+/// in `dropped_events`) when that channel is full, so a slow or unreachable
+/// database degrades usage accounting instead of the request path.
+#[derive(Clone)]
+pub struct TrafficWriterHandle {
+    tx: mpsc::Sender<TrafficWriteEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl TrafficWriterHandle {
+    pub fn enqueue_downstream(&self, event: DownstreamTrafficEvent) {
+        self.enqueue(TrafficWriteEvent::Downstream(event));
+    }
+
+    pub fn enqueue_upstream(&self, event: UpstreamTrafficEvent) {
+        self.enqueue(TrafficWriteEvent::Upstream(event));
+    }
+
+    fn enqueue(&self, event: TrafficWriteEvent) {
+        if self.tx.try_send(event).is_err() {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!(
+                event = "traffic_writer_buffer_full",
+                dropped_total = dropped,
+                "dropping traffic event: write-ahead buffer saturated"
+            );
+        }
+    }
+
+    /// Total events dropped since this writer was spawned because the
+    /// buffer was saturated. Operators should alert on this climbing.
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Spawns the background task that drains a bounded write-ahead buffer of
+/// traffic events into `storage`, batching inserts and retrying transient
+/// failures with exponential backoff. Returns immediately; call
+/// [`shutdown`](TrafficWriterGuard::shutdown) on the returned guard during
+/// graceful shutdown so buffered events are flushed before exit.
+pub fn spawn_writer(
+    storage: Arc<dyn StorageBackend>,
+    capacity: usize,
+) -> (TrafficWriterHandle, TrafficWriterGuard) {
+    let (tx, rx) = mpsc::channel(capacity);
+    let dropped = Arc::new(AtomicU64::new(0));
+    let task = tokio::spawn(run_writer(storage, rx));
+    (
+        TrafficWriterHandle {
+            tx: tx.clone(),
+            dropped,
+        },
+        TrafficWriterGuard { tx, task },
+    )
+}
+
+/// Owns the sending half needed to signal shutdown and the background
+/// task's `JoinHandle`. Kept separate from [`TrafficWriterHandle`] since
+/// only whoever orchestrates shutdown should be able to close the channel;
+/// ordinary callers only ever enqueue.
+pub struct TrafficWriterGuard {
+    tx: mpsc::Sender<TrafficWriteEvent>,
+    task: JoinHandle<()>,
+}
+
+impl TrafficWriterGuard {
+    /// Closes the channel and waits for the background task to flush
+    /// whatever it had buffered, then returns.
+    pub async fn shutdown(self) -> Result<(), JoinError> {
+        drop(self.tx);
+        self.task.await
+    }
+}
+
+async fn run_writer(storage: Arc<dyn StorageBackend>, mut rx: mpsc::Receiver<TrafficWriteEvent>) {
+    let mut downstream_batch = Vec::with_capacity(BATCH_SIZE);
+    let mut upstream_batch = Vec::with_capacity(BATCH_SIZE);
+    let mut ticker = interval(FLUSH_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                match event {
+                    Some(TrafficWriteEvent::Downstream(event)) => downstream_batch.push(event),
+                    Some(TrafficWriteEvent::Upstream(event)) => upstream_batch.push(event),
+                    None => {
+                        flush(&storage, &mut downstream_batch, &mut upstream_batch).await;
+                        return;
+                    }
+                }
+                if downstream_batch.len() >= BATCH_SIZE || upstream_batch.len() >= BATCH_SIZE {
+                    flush(&storage, &mut downstream_batch, &mut upstream_batch).await;
+                }
+            }
+            _ = ticker.tick() => {
+                flush(&storage, &mut downstream_batch, &mut upstream_batch).await;
+            }
+        }
+    }
+}
+
+async fn flush(
+    storage: &Arc<dyn StorageBackend>,
+    downstream_batch: &mut Vec<DownstreamTrafficEvent>,
+    upstream_batch: &mut Vec<UpstreamTrafficEvent>,
+) {
+    if !downstream_batch.is_empty() {
+        let batch = mem::take(downstream_batch);
+        flush_downstream_with_retry(storage, batch).await;
+    }
+    if !upstream_batch.is_empty() {
+        let batch = mem::take(upstream_batch);
+        flush_upstream_with_retry(storage, batch).await;
+    }
+}
+
+async fn flush_downstream_with_retry(
+    storage: &Arc<dyn StorageBackend>,
+    batch: Vec<DownstreamTrafficEvent>,
+) {
+    let mut backoff = BASE_BACKOFF;
+    loop {
+        match storage.insert_downstream_batch(batch.clone()).await {
+            Ok(()) => return,
+            Err(err) => {
+                warn!(
+                    event = "traffic_writer_flush_failed",
+                    kind = "downstream",
+                    error = %err,
+                    retry_in_ms = backoff.as_millis() as u64,
+                    "retrying downstream traffic batch insert"
+                );
+            }
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+async fn flush_upstream_with_retry(
+    storage: &Arc<dyn StorageBackend>,
+    batch: Vec<UpstreamTrafficEvent>,
+) {
+    let mut backoff = BASE_BACKOFF;
+    loop {
+        match storage.insert_upstream_batch(batch.clone()).await {
+            Ok(()) => return,
+            Err(err) => {
+                warn!(
+                    event = "traffic_writer_flush_failed",
+                    kind = "upstream",
+                    error = %err,
+                    retry_in_ms = backoff.as_millis() as u64,
+                    "retrying upstream traffic batch insert"
+                );
+            }
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
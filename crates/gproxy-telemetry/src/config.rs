@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sink::{NoopTelemetrySink, TelemetrySink, TracingTelemetrySink};
+
+/// Cross-cutting telemetry is opt-in: a deployment that never sets
+/// `enabled` pays nothing beyond the `Arc<dyn TelemetrySink>` vtable call
+/// that [`NoopTelemetrySink`] no-ops out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self { enabled: false }
+    }
+}
+
+/// Builds the sink `CallContext::telemetry` should carry for the lifetime of
+/// the process, per this config. Exists so `main.rs` doesn't need to know
+/// about either concrete sink type.
+pub fn build_sink(config: &TelemetryConfig) -> Arc<dyn TelemetrySink> {
+    if config.enabled {
+        Arc::new(TracingTelemetrySink)
+    } else {
+        Arc::new(NoopTelemetrySink)
+    }
+}
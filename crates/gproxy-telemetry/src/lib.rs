@@ -0,0 +1,9 @@
+pub mod config;
+pub mod metrics;
+pub mod sink;
+pub mod span;
+
+pub use config::{build_sink, TelemetryConfig};
+pub use metrics::{ProxyMetrics, StreamTermination};
+pub use sink::{NoopTelemetrySink, TelemetrySink, TracingTelemetrySink};
+pub use span::{NoopSpan, TracingSpan, TransformSpan};
@@ -0,0 +1,187 @@
+use prometheus::{
+    register_counter_vec_with_registry, register_histogram_vec_with_registry, CounterVec, Encoder,
+    HistogramVec, Registry, TextEncoder,
+};
+
+/// Why a recorded stream ended, as the `reason` label on
+/// `gproxy_stream_terminations_total`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamTermination {
+    /// The upstream sent a terminal `[DONE]` sentinel (or its decoder simply
+    /// ran dry) and nothing flagged the stream as aborted or truncated.
+    Clean,
+    /// The recorder's retained body was truncated or the upstream otherwise
+    /// ended without a clean sentinel — most likely a mid-stream transport
+    /// error.
+    UpstreamError,
+    /// The downstream client disconnected before the stream finished, per
+    /// `CallContext::cancellation`.
+    ClientDisconnect,
+    /// The inactivity watchdog (`CallContext::idle_timeout` or
+    /// `stream_deadline`) gave up on the upstream before it produced a
+    /// terminal sentinel.
+    Timeout,
+}
+
+impl StreamTermination {
+    fn label(self) -> &'static str {
+        match self {
+            StreamTermination::Clean => "clean",
+            StreamTermination::UpstreamError => "upstream_error",
+            StreamTermination::ClientDisconnect => "client_disconnect",
+            StreamTermination::Timeout => "timeout",
+        }
+    }
+}
+
+/// Proxy-wide Prometheus metrics, fed from the recording paths in
+/// `gproxy-provider-impl`'s `dispatch::record` — those tasks already see
+/// every forwarded chunk and the final usage totals, so they're the natural
+/// place to call these. Deliberately has no `trace_id` label on anything:
+/// that's unbounded cardinality that would make the registry grow without
+/// bound, so per-call correlation belongs on a log line next to the metric
+/// call instead, the way [`crate::TracingSpan`] already does it for spans.
+pub struct ProxyMetrics {
+    registry: Registry,
+    bytes_forwarded_total: CounterVec,
+    sse_events_decoded_total: CounterVec,
+    tokens_total: CounterVec,
+    stream_duration_seconds: HistogramVec,
+    time_to_first_byte_seconds: HistogramVec,
+    stream_terminations_total: CounterVec,
+}
+
+impl ProxyMetrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let bytes_forwarded_total = register_counter_vec_with_registry!(
+            "gproxy_bytes_forwarded_total",
+            "Bytes forwarded per direction, provider, and model.",
+            &["direction", "provider", "model"],
+            registry
+        )
+        .expect("gproxy_bytes_forwarded_total registration");
+        let sse_events_decoded_total = register_counter_vec_with_registry!(
+            "gproxy_sse_events_decoded_total",
+            "SSE events decoded off an upstream stream, by provider and model.",
+            &["provider", "model"],
+            registry
+        )
+        .expect("gproxy_sse_events_decoded_total registration");
+        let tokens_total = register_counter_vec_with_registry!(
+            "gproxy_tokens_total",
+            "Tokens accounted by provider, model, usage kind, and token type (prompt/completion).",
+            &["provider", "model", "usage_kind", "token_type"],
+            registry
+        )
+        .expect("gproxy_tokens_total registration");
+        let stream_duration_seconds = register_histogram_vec_with_registry!(
+            "gproxy_stream_duration_seconds",
+            "Wall-clock duration of a streamed upstream call, by provider and model.",
+            &["provider", "model"],
+            registry
+        )
+        .expect("gproxy_stream_duration_seconds registration");
+        let time_to_first_byte_seconds = register_histogram_vec_with_registry!(
+            "gproxy_time_to_first_byte_seconds",
+            "Time from the recorder starting to drain a stream to its first chunk, by provider and model.",
+            &["provider", "model"],
+            registry
+        )
+        .expect("gproxy_time_to_first_byte_seconds registration");
+        let stream_terminations_total = register_counter_vec_with_registry!(
+            "gproxy_stream_terminations_total",
+            "Streamed calls that ended, by provider, model, and termination reason.",
+            &["provider", "model", "reason"],
+            registry
+        )
+        .expect("gproxy_stream_terminations_total registration");
+        Self {
+            registry,
+            bytes_forwarded_total,
+            sse_events_decoded_total,
+            tokens_total,
+            stream_duration_seconds,
+            time_to_first_byte_seconds,
+            stream_terminations_total,
+        }
+    }
+
+    pub fn record_bytes_forwarded(&self, direction: &str, provider: &str, model: &str, bytes: u64) {
+        self.bytes_forwarded_total
+            .with_label_values(&[direction, provider, model])
+            .inc_by(bytes as f64);
+    }
+
+    pub fn record_sse_event(&self, provider: &str, model: &str) {
+        self.sse_events_decoded_total
+            .with_label_values(&[provider, model])
+            .inc();
+    }
+
+    /// Records whichever of prompt/completion is known; the `total` series
+    /// is derived here from the two rather than taken as a third input, so
+    /// callers that only have a generic `(prompt, completion)` pair (see
+    /// `dispatch::transform::generic_token_counts`) don't need to compute it
+    /// themselves.
+    pub fn record_tokens(
+        &self,
+        provider: &str,
+        model: &str,
+        usage_kind: &str,
+        prompt_tokens: Option<i64>,
+        completion_tokens: Option<i64>,
+    ) {
+        if let Some(prompt) = prompt_tokens {
+            self.tokens_total
+                .with_label_values(&[provider, model, usage_kind, "prompt"])
+                .inc_by(prompt as f64);
+        }
+        if let Some(completion) = completion_tokens {
+            self.tokens_total
+                .with_label_values(&[provider, model, usage_kind, "completion"])
+                .inc_by(completion as f64);
+        }
+        if let (Some(prompt), Some(completion)) = (prompt_tokens, completion_tokens) {
+            self.tokens_total
+                .with_label_values(&[provider, model, usage_kind, "total"])
+                .inc_by((prompt + completion) as f64);
+        }
+    }
+
+    pub fn record_stream_duration(&self, provider: &str, model: &str, seconds: f64) {
+        self.stream_duration_seconds
+            .with_label_values(&[provider, model])
+            .observe(seconds);
+    }
+
+    pub fn record_time_to_first_byte(&self, provider: &str, model: &str, seconds: f64) {
+        self.time_to_first_byte_seconds
+            .with_label_values(&[provider, model])
+            .observe(seconds);
+    }
+
+    pub fn record_termination(&self, provider: &str, model: &str, reason: StreamTermination) {
+        self.stream_terminations_total
+            .with_label_values(&[provider, model, reason.label()])
+            .inc();
+    }
+
+    /// Renders every registered metric family in Prometheus text exposition
+    /// format, for the dedicated `/metrics` endpoint to return as-is.
+    pub fn render(&self) -> String {
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        let encoder = TextEncoder::new();
+        if encoder.encode(&metric_families, &mut buffer).is_err() {
+            return String::new();
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+impl Default for ProxyMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
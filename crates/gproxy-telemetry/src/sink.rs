@@ -0,0 +1,61 @@
+use crate::span::{NoopSpan, TracingSpan, TransformSpan};
+
+/// Opens the top-level span for one `dispatch_transform` call. Implementors
+/// decide how `source`/`target`/`usage_kind`/`model` become span fields,
+/// metric labels, or both.
+pub trait TelemetrySink: Send + Sync {
+    fn start_transform(
+        &self,
+        source: &'static str,
+        target: &'static str,
+        usage_kind: &'static str,
+        model: Option<&str>,
+    ) -> Box<dyn TransformSpan>;
+}
+
+/// The default sink: every call returns a [`NoopSpan`], so telemetry is
+/// zero-overhead until a deployment opts in.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopTelemetrySink;
+
+impl TelemetrySink for NoopTelemetrySink {
+    fn start_transform(
+        &self,
+        _source: &'static str,
+        _target: &'static str,
+        _usage_kind: &'static str,
+        _model: Option<&str>,
+    ) -> Box<dyn TransformSpan> {
+        Box::new(NoopSpan)
+    }
+}
+
+/// Opens a real `tracing::Span` per transform call, tagged with the
+/// source/target format pair, `UsageKind`, and upstream model. Counters and
+/// histograms (request count, upstream latency, transformed bytes) are
+/// derived from these spans by whatever `tracing-opentelemetry`/metrics
+/// layer `init_tracing` installs — this sink only needs to emit a span with
+/// the right fields, not talk to an OTLP endpoint directly.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TracingTelemetrySink;
+
+impl TelemetrySink for TracingTelemetrySink {
+    fn start_transform(
+        &self,
+        source: &'static str,
+        target: &'static str,
+        usage_kind: &'static str,
+        model: Option<&str>,
+    ) -> Box<dyn TransformSpan> {
+        let span = tracing::info_span!(
+            "dispatch_transform",
+            source,
+            target,
+            usage_kind,
+            model = model.unwrap_or(""),
+            prompt_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty,
+        );
+        Box::new(TracingSpan(span))
+    }
+}
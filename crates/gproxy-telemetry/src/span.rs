@@ -0,0 +1,57 @@
+/// A single open span around one hop of a transform pipeline (request
+/// transform, upstream call, or response transform), or the whole pipeline
+/// when opened by [`crate::TelemetrySink::start_transform`]. Closes however
+/// the concrete implementation's `Drop` closes it — there's no explicit
+/// `end()` so a span can't be forgotten by a caller that bails out early via
+/// `?`.
+pub trait TransformSpan: Send + Sync {
+    /// Opens a child span for one named hop (`"request_transform"`,
+    /// `"upstream_call"`, `"response_transform"`) nested under this one.
+    fn child(&self, name: &'static str) -> Box<dyn TransformSpan>;
+
+    /// Records prompt/completion token counts once they're known — at
+    /// `map_usage_for_kind`'s output for a buffered response, or at
+    /// `UsageState::finish()` for a streamed one.
+    fn record_tokens(&self, prompt_tokens: Option<i64>, completion_tokens: Option<i64>);
+}
+
+/// The default span when telemetry is disabled: every operation is a no-op,
+/// so the common case pays no overhead beyond a vtable call.
+pub struct NoopSpan;
+
+impl TransformSpan for NoopSpan {
+    fn child(&self, _name: &'static str) -> Box<dyn TransformSpan> {
+        Box::new(NoopSpan)
+    }
+
+    fn record_tokens(&self, _prompt_tokens: Option<i64>, _completion_tokens: Option<i64>) {}
+}
+
+/// Wraps a `tracing::Span` so transform spans ride the same pipeline as the
+/// rest of the app's logs. Once a `tracing-opentelemetry` layer is installed
+/// in `init_tracing` (not part of this checkout — it's an added dependency,
+/// not a code change here), these are exported as OTLP spans with no further
+/// change to this module.
+pub struct TracingSpan(pub tracing::Span);
+
+impl TransformSpan for TracingSpan {
+    fn child(&self, name: &'static str) -> Box<dyn TransformSpan> {
+        let child = tracing::info_span!(
+            parent: &self.0,
+            "transform_hop",
+            hop = name,
+            prompt_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty,
+        );
+        Box::new(TracingSpan(child))
+    }
+
+    fn record_tokens(&self, prompt_tokens: Option<i64>, completion_tokens: Option<i64>) {
+        if let Some(prompt_tokens) = prompt_tokens {
+            self.0.record("prompt_tokens", prompt_tokens);
+        }
+        if let Some(completion_tokens) = completion_tokens {
+            self.0.record("completion_tokens", completion_tokens);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use gproxy_protocol::gemini::batch_embed_contents::request::{
+    BatchEmbedContentsRequest, EmbedContent, EmbedContentPart, EmbedContentRequest,
+};
+use gproxy_protocol::openai::embeddings::request::{EmbeddingsInput, EmbeddingsRequest};
+
+/// Convert an OpenAI embeddings request into Gemini's `batchEmbedContents`
+/// shape: OpenAI's `input` can be a single string or a list, so it becomes
+/// one Gemini sub-request per string, in order, so the response side can
+/// zip Gemini's `embeddings` array back up against the original inputs by
+/// index.
+pub fn transform_request(request: EmbeddingsRequest) -> BatchEmbedContentsRequest {
+    let model = format!("models/{}", request.model);
+    let texts = match request.input {
+        EmbeddingsInput::Single(text) => vec![text],
+        EmbeddingsInput::Many(texts) => texts,
+    };
+
+    let requests = texts
+        .into_iter()
+        .map(|text| EmbedContentRequest {
+            model: model.clone(),
+            content: EmbedContent {
+                parts: vec![EmbedContentPart { text }],
+            },
+        })
+        .collect();
+
+    BatchEmbedContentsRequest { requests }
+}
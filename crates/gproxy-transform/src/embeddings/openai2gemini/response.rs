@@ -0,0 +1,35 @@
+use gproxy_protocol::gemini::batch_embed_contents::response::BatchEmbedContentsResponse;
+use gproxy_protocol::openai::embeddings::response::{
+    EmbeddingObject, EmbeddingsResponse, EmbeddingsUsage,
+};
+
+/// Convert a Gemini `batchEmbedContents` response into OpenAI's embeddings
+/// response shape. `embeddings[i]` corresponds to the `i`th sub-request
+/// `transform_request` built, so the original input order is preserved via
+/// that index rather than anything Gemini echoes back.
+pub fn transform_response(response: BatchEmbedContentsResponse) -> EmbeddingsResponse {
+    let data = response
+        .embeddings
+        .into_iter()
+        .enumerate()
+        .map(|(index, embedding)| EmbeddingObject {
+            object: "embedding".to_string(),
+            index,
+            embedding: embedding.values,
+        })
+        .collect();
+
+    EmbeddingsResponse {
+        object: "list".to_string(),
+        data,
+        // `transform_json_response` only passes the deserialized body through,
+        // so the requested model name isn't available here; callers that need
+        // it on the wire can patch the field in afterward.
+        model: String::new(),
+        // Gemini's batchEmbedContents response carries no token accounting.
+        usage: EmbeddingsUsage {
+            prompt_tokens: 0,
+            total_tokens: 0,
+        },
+    }
+}
@@ -0,0 +1,13 @@
+use gproxy_protocol::claude::create_message::request::CreateMessageRequest;
+use gproxy_protocol::openai::create_chat_completions::request::CreateChatCompletionRequest;
+
+/// Convert a Claude create-message request into an OpenAI chat-completion
+/// request. The actual mapping lives alongside the Claude response-side
+/// transform in `openai_response2claude`, where it was added first; this
+/// just gives the Chat Completions format pair its own module to live
+/// under, matching `claude2gemini` and friends.
+pub fn transform_request(request: CreateMessageRequest) -> CreateChatCompletionRequest {
+    crate::generate_content::openai_response2claude::request::transform_chat_completion_request(
+        request,
+    )
+}
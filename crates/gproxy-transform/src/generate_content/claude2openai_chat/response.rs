@@ -0,0 +1,52 @@
+use gproxy_protocol::claude::create_message::response::CreateMessageResponse;
+use gproxy_protocol::claude::create_message::types::ContentBlock;
+use gproxy_protocol::openai::create_chat_completions::response::{
+    ChatCompletionChoice, ChatCompletionResponseMessage, ChatCompletionUsage,
+    CreateChatCompletionResponse,
+};
+
+/// Convert a Claude create-message response into an OpenAI chat-completion
+/// response — the mirror of `transform_chat_completion_request` in
+/// `request.rs`. Content blocks are collapsed to plain text, same
+/// simplification as the Responses-format sibling transform.
+pub fn transform_response(response: CreateMessageResponse) -> CreateChatCompletionResponse {
+    let content = response
+        .content
+        .into_iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text } => Some(text),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("");
+
+    CreateChatCompletionResponse {
+        id: response.id,
+        model: response.model,
+        choices: vec![ChatCompletionChoice {
+            index: 0,
+            message: ChatCompletionResponseMessage {
+                role: response.role,
+                content: Some(content),
+                tool_calls: None,
+            },
+            finish_reason: response.stop_reason.as_deref().map(map_stop_reason),
+        }],
+        usage: Some(ChatCompletionUsage {
+            prompt_tokens: response.usage.input_tokens,
+            completion_tokens: response.usage.output_tokens,
+            total_tokens: response.usage.input_tokens + response.usage.output_tokens,
+        }),
+    }
+}
+
+fn map_stop_reason(reason: &str) -> String {
+    match reason {
+        "end_turn" => "stop",
+        "max_tokens" => "length",
+        "tool_use" => "tool_calls",
+        "stop_sequence" => "stop",
+        other => other,
+    }
+    .to_string()
+}
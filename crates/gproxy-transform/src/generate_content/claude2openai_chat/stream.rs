@@ -0,0 +1,85 @@
+use gproxy_protocol::claude::create_message::stream::BetaStreamEvent;
+use gproxy_protocol::openai::create_chat_completions::stream::CreateChatCompletionStreamResponse;
+
+/// Converts OpenAI chat-completion chunk deltas into Claude
+/// `BetaStreamEvent`s, for a proxy that accepts the Claude Messages API but
+/// routes the call to a Chat Completions backend — the mirror of
+/// `openai_chat2claude::stream::ClaudeToOpenAIChatStreamState`. Only the
+/// deltas this proxy's Claude-format clients read are emitted: a start
+/// event carrying the role, text deltas, and a stop event carrying the
+/// mapped finish reason.
+pub struct OpenAIChatToClaudeStreamState {
+    started: bool,
+}
+
+impl OpenAIChatToClaudeStreamState {
+    pub fn new() -> Self {
+        Self { started: false }
+    }
+
+    pub fn transform_response(
+        &mut self,
+        response: CreateChatCompletionStreamResponse,
+    ) -> Vec<BetaStreamEvent> {
+        let mut events = Vec::new();
+        let Some(choice) = response.choices.into_iter().next() else {
+            return events;
+        };
+
+        if !self.started {
+            self.started = true;
+            events.push(BetaStreamEvent::MessageStart {
+                message: gproxy_protocol::claude::create_message::response::CreateMessageResponse {
+                    id: response.id,
+                    r#type: "message".to_string(),
+                    role: choice.delta.role.unwrap_or_else(|| "assistant".to_string()),
+                    content: Vec::new(),
+                    model: response.model,
+                    stop_reason: None,
+                    stop_sequence: None,
+                    usage: gproxy_protocol::claude::create_message::types::Usage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        cache_creation_input_tokens: 0,
+                        cache_read_input_tokens: 0,
+                    },
+                },
+            });
+        }
+
+        if let Some(text) = choice.delta.content {
+            events.push(BetaStreamEvent::ContentBlockDelta {
+                index: choice.index,
+                delta: gproxy_protocol::claude::create_message::stream::TextDelta { text },
+            });
+        }
+
+        if let Some(finish_reason) = choice.finish_reason {
+            events.push(BetaStreamEvent::MessageDelta {
+                delta: gproxy_protocol::claude::create_message::stream::MessageDeltaFields {
+                    stop_reason: Some(map_finish_reason(&finish_reason)),
+                },
+                usage: None,
+            });
+        }
+
+        events
+    }
+}
+
+impl Default for OpenAIChatToClaudeStreamState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn map_finish_reason(reason: &str) -> String {
+    match reason {
+        "stop" => "end_turn",
+        "length" => "max_tokens",
+        "tool_calls" => "tool_use",
+        "content_filter" => "stop_sequence",
+        other => other,
+    }
+    .to_string()
+}
@@ -0,0 +1,64 @@
+use gproxy_protocol::gemini::generate_content::{Content, ContentRole, GenerateContentRequest, Part};
+use gproxy_protocol::openai::create_chat_completions::request::{
+    ChatCompletionMessage, CreateChatCompletionRequest,
+};
+
+/// Convert a Gemini generate-content request into an OpenAI chat-completion
+/// request. Parts are collapsed to plain text; non-text parts (function
+/// calls, inline data) are dropped, same simplification already made for
+/// the Claude <-> Chat Completions pair.
+pub fn transform_request(request: GenerateContentRequest) -> CreateChatCompletionRequest {
+    let mut messages = Vec::new();
+    if let Some(system) = request.body.system_instruction {
+        messages.push(ChatCompletionMessage {
+            role: "system".to_string(),
+            content: Some(join_text(&system)),
+            tool_calls: None,
+            tool_call_id: None,
+        });
+    }
+    messages.extend(request.body.contents.iter().map(|content| {
+        let role = match content.role {
+            ContentRole::User => "user",
+            ContentRole::Model => "assistant",
+        };
+        ChatCompletionMessage {
+            role: role.to_string(),
+            content: Some(join_text(content)),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }));
+
+    CreateChatCompletionRequest {
+        model: request.path.model,
+        messages,
+        max_tokens: request
+            .body
+            .generation_config
+            .as_ref()
+            .and_then(|config| config.max_output_tokens),
+        temperature: request
+            .body
+            .generation_config
+            .as_ref()
+            .and_then(|config| config.temperature),
+        stream: false,
+        stop: request
+            .body
+            .generation_config
+            .as_ref()
+            .and_then(|config| config.stop_sequences.clone()),
+        tools: None,
+        tool_choice: None,
+    }
+}
+
+fn join_text(content: &Content) -> String {
+    content
+        .parts
+        .iter()
+        .filter_map(|part| part.text.as_deref())
+        .collect::<Vec<_>>()
+        .join("")
+}
@@ -0,0 +1,56 @@
+use gproxy_protocol::gemini::generate_content::GenerateContentResponse;
+use gproxy_protocol::openai::create_chat_completions::response::{
+    ChatCompletionChoice, ChatCompletionResponseMessage, ChatCompletionUsage,
+    CreateChatCompletionResponse,
+};
+
+/// Convert a Gemini generate-content response into an OpenAI chat-completion
+/// response. Only the first candidate is translated, matching the
+/// single-choice shape the rest of the proxy assumes elsewhere.
+pub fn transform_response(response: GenerateContentResponse) -> CreateChatCompletionResponse {
+    let candidate = response.candidates.into_iter().next();
+    let (text, finish_reason) = match candidate {
+        Some(candidate) => {
+            let text = candidate
+                .content
+                .parts
+                .iter()
+                .filter_map(|part| part.text.as_deref())
+                .collect::<Vec<_>>()
+                .join("");
+            (text, candidate.finish_reason.as_deref().map(map_finish_reason))
+        }
+        None => (String::new(), None),
+    };
+
+    let usage = response.usage_metadata.map(|usage| ChatCompletionUsage {
+        prompt_tokens: usage.prompt_token_count.unwrap_or_default(),
+        completion_tokens: usage.candidates_token_count.unwrap_or_default(),
+        total_tokens: usage.total_token_count.unwrap_or_default(),
+    });
+
+    CreateChatCompletionResponse {
+        id: String::new(),
+        model: response.model_version.unwrap_or_default(),
+        choices: vec![ChatCompletionChoice {
+            index: 0,
+            message: ChatCompletionResponseMessage {
+                role: "assistant".to_string(),
+                content: Some(text),
+                tool_calls: None,
+            },
+            finish_reason,
+        }],
+        usage,
+    }
+}
+
+fn map_finish_reason(reason: &str) -> String {
+    match reason {
+        "STOP" => "stop",
+        "MAX_TOKENS" => "length",
+        "SAFETY" | "RECITATION" => "content_filter",
+        other => other,
+    }
+    .to_string()
+}
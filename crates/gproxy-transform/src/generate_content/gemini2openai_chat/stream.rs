@@ -0,0 +1,58 @@
+use gproxy_protocol::gemini::generate_content::types::{Content, ContentRole, Part};
+use gproxy_protocol::gemini::generate_content::response::{Candidate, GenerateContentResponse};
+use gproxy_protocol::openai::create_chat_completions::stream::CreateChatCompletionStreamResponse;
+
+/// Converts OpenAI chat-completion chunk deltas into Gemini
+/// `GenerateContentResponse` deltas, for a proxy that accepts the Gemini
+/// generateContent streaming API but routes the call to a Chat Completions
+/// backend — the mirror of
+/// `openai_chat2gemini::stream::GeminiToOpenAIChatStreamState`.
+pub struct OpenAIChatToGeminiStreamState;
+
+impl OpenAIChatToGeminiStreamState {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn transform_response(
+        &mut self,
+        response: CreateChatCompletionStreamResponse,
+    ) -> Vec<GenerateContentResponse> {
+        let Some(choice) = response.choices.into_iter().next() else {
+            return Vec::new();
+        };
+        let text = choice.delta.content.unwrap_or_default();
+
+        vec![GenerateContentResponse {
+            candidates: vec![Candidate {
+                content: Content {
+                    role: ContentRole::Model,
+                    parts: vec![Part {
+                        text: Some(text),
+                        ..Default::default()
+                    }],
+                },
+                finish_reason: choice.finish_reason.as_deref().map(map_finish_reason),
+                ..Default::default()
+            }],
+            model_version: Some(response.model),
+            usage_metadata: None,
+        }]
+    }
+}
+
+impl Default for OpenAIChatToGeminiStreamState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn map_finish_reason(reason: &str) -> String {
+    match reason {
+        "stop" => "STOP",
+        "length" => "MAX_TOKENS",
+        "content_filter" => "SAFETY",
+        other => other,
+    }
+    .to_string()
+}
@@ -0,0 +1,41 @@
+use gproxy_protocol::claude::create_message::request::CreateMessageRequest;
+use gproxy_protocol::claude::create_message::types::ContentBlock;
+use gproxy_protocol::openai::create_chat_completions::request::CreateChatCompletionRequest;
+
+/// Convert an OpenAI chat-completion request into a Claude create-message
+/// request — the mirror of `claude2openai_chat::request::transform_request`,
+/// for a proxy that accepts the Chat Completions API but routes the call to
+/// a Claude backend. A leading `system` message becomes `system`; everything
+/// else becomes a plain-text content block, same simplification already
+/// made for the Responses format pair.
+pub fn transform_request(mut request: CreateChatCompletionRequest) -> CreateMessageRequest {
+    let system = request
+        .messages
+        .first()
+        .filter(|message| message.role == "system")
+        .and_then(|message| message.content.clone());
+    if system.is_some() {
+        request.messages.remove(0);
+    }
+
+    let messages = request
+        .messages
+        .into_iter()
+        .map(|message| gproxy_protocol::claude::create_message::types::Message {
+            role: message.role,
+            content: vec![ContentBlock::Text {
+                text: message.content.unwrap_or_default(),
+            }],
+        })
+        .collect();
+
+    CreateMessageRequest {
+        model: request.model,
+        messages,
+        system,
+        max_tokens: request.max_tokens.unwrap_or(4096),
+        temperature: request.temperature,
+        stream: Some(request.stream),
+        stop_sequences: request.stop,
+    }
+}
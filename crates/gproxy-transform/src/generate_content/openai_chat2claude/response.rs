@@ -0,0 +1,11 @@
+use gproxy_protocol::claude::create_message::response::CreateMessageResponse;
+use gproxy_protocol::openai::create_chat_completions::response::CreateChatCompletionResponse;
+
+/// Convert an OpenAI chat-completion response into a Claude create-message
+/// response. Delegates to the implementation already living alongside the
+/// Claude response-side transform.
+pub fn transform_response(response: CreateChatCompletionResponse) -> CreateMessageResponse {
+    crate::generate_content::openai_response2claude::response::transform_chat_completion_response(
+        response,
+    )
+}
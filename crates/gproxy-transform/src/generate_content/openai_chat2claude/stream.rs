@@ -0,0 +1,89 @@
+use gproxy_protocol::claude::create_message::stream::BetaStreamEvent;
+use gproxy_protocol::openai::create_chat_completions::stream::CreateChatCompletionStreamResponse;
+
+/// Converts Claude `BetaStreamEvent`s into OpenAI chat-completion chunk
+/// deltas, for a proxy that accepts the Chat Completions API but routes the
+/// call to a Claude backend. The role is emitted once, on the first chunk;
+/// `finish_reason` is attached to the final one.
+pub struct ClaudeToOpenAIChatStreamState {
+    id: String,
+    created: u64,
+    model: String,
+    role_sent: bool,
+}
+
+impl ClaudeToOpenAIChatStreamState {
+    pub fn new() -> Self {
+        Self {
+            id: String::new(),
+            created: 0,
+            model: String::new(),
+            role_sent: false,
+        }
+    }
+
+    pub fn transform_event(
+        &mut self,
+        event: BetaStreamEvent,
+    ) -> Vec<CreateChatCompletionStreamResponse> {
+        match event {
+            BetaStreamEvent::MessageStart { message } => {
+                self.id = message.id;
+                self.model = message.model;
+                vec![self.chunk(Some("assistant".to_string()), None, None)]
+            }
+            BetaStreamEvent::ContentBlockDelta { delta, .. } => {
+                vec![self.chunk(None, Some(delta.text), None)]
+            }
+            BetaStreamEvent::MessageDelta { delta, .. } => {
+                let finish_reason = delta.stop_reason.as_deref().map(map_stop_reason);
+                vec![self.chunk(None, None, finish_reason)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn chunk(
+        &mut self,
+        role: Option<String>,
+        content: Option<String>,
+        finish_reason: Option<String>,
+    ) -> CreateChatCompletionStreamResponse {
+        let role = if self.role_sent { None } else { role };
+        self.role_sent = true;
+        CreateChatCompletionStreamResponse {
+            id: self.id.clone(),
+            created: self.created,
+            model: self.model.clone(),
+            choices: vec![
+                gproxy_protocol::openai::create_chat_completions::stream::ChatCompletionStreamChoice {
+                    index: 0,
+                    delta:
+                        gproxy_protocol::openai::create_chat_completions::stream::ChatCompletionStreamDelta {
+                            role,
+                            content,
+                        },
+                    finish_reason,
+                },
+            ],
+            usage: None,
+        }
+    }
+}
+
+impl Default for ClaudeToOpenAIChatStreamState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn map_stop_reason(reason: &str) -> String {
+    match reason {
+        "end_turn" => "stop",
+        "max_tokens" => "length",
+        "tool_use" => "tool_calls",
+        "stop_sequence" => "stop",
+        other => other,
+    }
+    .to_string()
+}
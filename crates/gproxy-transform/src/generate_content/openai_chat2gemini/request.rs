@@ -0,0 +1,60 @@
+use gproxy_protocol::gemini::generate_content::{
+    Content, ContentRole, GenerateContentPath, GenerateContentRequest, GenerateContentRequestBody,
+    Part,
+};
+use gproxy_protocol::openai::create_chat_completions::request::CreateChatCompletionRequest;
+
+/// Convert an OpenAI chat-completion request into a Gemini generate-content
+/// request — the mirror of `gemini2openai_chat::request::transform_request`.
+/// A leading `system` message becomes `system_instruction`; everything else
+/// becomes a single text `Part`, same simplification used throughout this
+/// format pair.
+pub fn transform_request(mut request: CreateChatCompletionRequest) -> GenerateContentRequest {
+    let system_instruction = request
+        .messages
+        .first()
+        .filter(|message| message.role == "system")
+        .and_then(|message| message.content.clone())
+        .map(text_content);
+    if system_instruction.is_some() {
+        request.messages.remove(0);
+    }
+
+    let contents = request
+        .messages
+        .into_iter()
+        .map(|message| Content {
+            role: if message.role == "assistant" {
+                ContentRole::Model
+            } else {
+                ContentRole::User
+            },
+            parts: vec![Part {
+                text: message.content,
+                ..Default::default()
+            }],
+        })
+        .collect();
+
+    GenerateContentRequest {
+        path: GenerateContentPath {
+            model: request.model,
+        },
+        body: GenerateContentRequestBody {
+            contents,
+            system_instruction,
+            generation_config: None,
+            tools: None,
+        },
+    }
+}
+
+fn text_content(text: String) -> Content {
+    Content {
+        role: ContentRole::User,
+        parts: vec![Part {
+            text: Some(text),
+            ..Default::default()
+        }],
+    }
+}
@@ -0,0 +1,9 @@
+use gproxy_protocol::gemini::generate_content::GenerateContentResponse;
+use gproxy_protocol::openai::create_chat_completions::response::CreateChatCompletionResponse;
+
+/// Convert a Gemini generate-content response into an OpenAI chat-completion
+/// response. Delegates to the implementation already living alongside the
+/// Gemini request-side transform.
+pub fn transform_response(response: GenerateContentResponse) -> CreateChatCompletionResponse {
+    crate::generate_content::gemini2openai_chat::response::transform_response(response)
+}
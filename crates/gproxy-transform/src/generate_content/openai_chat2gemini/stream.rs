@@ -0,0 +1,75 @@
+use gproxy_protocol::gemini::generate_content::response::GenerateContentResponse;
+use gproxy_protocol::openai::create_chat_completions::stream::CreateChatCompletionStreamResponse;
+
+/// Converts Gemini `GenerateContentResponse` deltas into OpenAI
+/// chat-completion chunk deltas, for a proxy that accepts the Chat
+/// Completions API but routes the call to a Gemini backend.
+pub struct GeminiToOpenAIChatStreamState {
+    role_sent: bool,
+}
+
+impl GeminiToOpenAIChatStreamState {
+    pub fn new() -> Self {
+        Self { role_sent: false }
+    }
+
+    pub fn transform_response(
+        &mut self,
+        response: GenerateContentResponse,
+    ) -> Vec<CreateChatCompletionStreamResponse> {
+        let Some(candidate) = response.candidates.into_iter().next() else {
+            return Vec::new();
+        };
+        let text = candidate
+            .content
+            .parts
+            .iter()
+            .filter_map(|part| part.text.as_deref())
+            .collect::<Vec<_>>()
+            .join("");
+        let finish_reason = candidate
+            .finish_reason
+            .as_deref()
+            .map(map_finish_reason);
+        let role = if self.role_sent {
+            None
+        } else {
+            self.role_sent = true;
+            Some("assistant".to_string())
+        };
+
+        vec![CreateChatCompletionStreamResponse {
+            id: String::new(),
+            created: 0,
+            model: response.model_version.unwrap_or_default(),
+            choices: vec![
+                gproxy_protocol::openai::create_chat_completions::stream::ChatCompletionStreamChoice {
+                    index: 0,
+                    delta:
+                        gproxy_protocol::openai::create_chat_completions::stream::ChatCompletionStreamDelta {
+                            role,
+                            content: Some(text),
+                        },
+                    finish_reason,
+                },
+            ],
+            usage: None,
+        }]
+    }
+}
+
+impl Default for GeminiToOpenAIChatStreamState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn map_finish_reason(reason: &str) -> String {
+    match reason {
+        "STOP" => "stop",
+        "MAX_TOKENS" => "length",
+        "SAFETY" | "RECITATION" => "content_filter",
+        other => other,
+    }
+    .to_string()
+}
@@ -0,0 +1,60 @@
+use gproxy_protocol::claude::create_message::request::CreateMessageRequest as ClaudeCreateMessageRequest;
+use gproxy_protocol::claude::create_message::types::ContentBlock;
+use gproxy_protocol::openai::create_chat_completions::request::{
+    ChatCompletionMessage, CreateChatCompletionRequest,
+};
+use gproxy_protocol::openai::create_response::request::CreateResponseRequest;
+
+/// Convert a Claude create-message request into an OpenAI responses request.
+pub fn transform_request(request: ClaudeCreateMessageRequest) -> CreateResponseRequest {
+    crate::generate_content::claude2openai_response::request::transform_request(request)
+}
+
+/// Convert a Claude create-message request into an OpenAI chat-completion
+/// request — the mirror of `transform_chat_completion_response` in
+/// `response.rs`, for a proxy that accepts the Claude Messages API but
+/// routes the call to an OpenAI Chat Completions backend rather than the
+/// Responses API. Content blocks are collapsed to plain text; non-text
+/// blocks (images, tool results) are dropped rather than guessed at, same
+/// as the simplification already made on the response side.
+pub fn transform_chat_completion_request(
+    request: ClaudeCreateMessageRequest,
+) -> CreateChatCompletionRequest {
+    let mut messages = Vec::new();
+    if let Some(system) = request.system {
+        messages.push(ChatCompletionMessage {
+            role: "system".to_string(),
+            content: Some(system),
+            tool_calls: None,
+            tool_call_id: None,
+        });
+    }
+    messages.extend(request.messages.into_iter().map(|message| {
+        let content = message
+            .content
+            .into_iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("");
+        ChatCompletionMessage {
+            role: message.role,
+            content: Some(content),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }));
+
+    CreateChatCompletionRequest {
+        model: request.model,
+        messages,
+        max_tokens: Some(request.max_tokens),
+        temperature: request.temperature,
+        stream: request.stream.unwrap_or(false),
+        stop: request.stop_sequences,
+        tools: None,
+        tool_choice: None,
+    }
+}
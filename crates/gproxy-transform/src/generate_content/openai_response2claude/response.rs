@@ -1,7 +1,64 @@
 use gproxy_protocol::claude::create_message::response::CreateMessageResponse as ClaudeCreateMessageResponse;
+use gproxy_protocol::claude::create_message::types::{ContentBlock, Usage as ClaudeUsage};
+use gproxy_protocol::openai::create_chat_completions::response::CreateChatCompletionResponse;
 use gproxy_protocol::openai::create_response::response::Response as OpenAIResponse;
 
 /// Convert a Claude create-message response into an OpenAI responses response.
 pub fn transform_response(response: ClaudeCreateMessageResponse) -> OpenAIResponse {
     crate::generate_content::claude2openai_response::response::transform_response(response)
 }
+
+/// Convert an OpenAI chat-completion response into a Claude create-message
+/// response — the mirror of `transform_response` above, for a proxy that
+/// accepts the Claude Messages API but routes the call to an OpenAI Chat
+/// Completions backend rather than the Responses API.
+pub fn transform_chat_completion_response(
+    response: CreateChatCompletionResponse,
+) -> ClaudeCreateMessageResponse {
+    let choice = response.choices.into_iter().next();
+    let (role, text, finish_reason) = match choice {
+        Some(choice) => (
+            choice.message.role,
+            choice.message.content.unwrap_or_default(),
+            choice.finish_reason,
+        ),
+        None => ("assistant".to_string(), String::new(), None),
+    };
+
+    let usage = response
+        .usage
+        .map(|usage| ClaudeUsage {
+            input_tokens: usage.prompt_tokens,
+            output_tokens: usage.completion_tokens,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        })
+        .unwrap_or(ClaudeUsage {
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        });
+
+    ClaudeCreateMessageResponse {
+        id: response.id,
+        r#type: "message".to_string(),
+        role,
+        content: vec![ContentBlock::Text { text }],
+        model: response.model,
+        stop_reason: finish_reason.as_deref().map(map_finish_reason),
+        stop_sequence: None,
+        usage,
+    }
+}
+
+fn map_finish_reason(reason: &str) -> String {
+    match reason {
+        "stop" => "end_turn",
+        "length" => "max_tokens",
+        "tool_calls" => "tool_use",
+        "content_filter" => "stop_sequence",
+        other => other,
+    }
+    .to_string()
+}
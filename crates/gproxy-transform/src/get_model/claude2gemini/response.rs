@@ -12,6 +12,10 @@ pub fn transform_response(response: GeminiGetModelResponse) -> ClaudeGetModelRes
 
     let id = if !base_model_id.is_empty() {
         base_model_id
+    } else if let Some(stripped) = name.strip_prefix("publishers/google/models/") {
+        // Vertex AI's project-scoped naming, e.g.
+        // `publishers/google/models/gemini-1.5-pro`.
+        stripped.to_string()
     } else if let Some(stripped) = name.strip_prefix("models/") {
         stripped.to_string()
     } else {
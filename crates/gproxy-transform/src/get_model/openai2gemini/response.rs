@@ -1,27 +1,92 @@
 use gproxy_protocol::gemini::get_model::response::GetModelResponse as GeminiGetModelResponse;
+use gproxy_protocol::gemini::list_models::response::ListModelsResponse as GeminiListModelsResponse;
 use gproxy_protocol::openai::get_model::response::GetModelResponse as OpenAIGetModelResponse;
 use gproxy_protocol::openai::get_model::types::{
     Model as OpenAIModel, ModelObjectType as OpenAIModelObjectType,
 };
+use gproxy_protocol::openai::list_models::response::ListModelsResponse as OpenAIListModelsResponse;
 
 /// Convert a Gemini get-model response into OpenAI's model response shape.
 pub fn transform_response(response: GeminiGetModelResponse) -> OpenAIGetModelResponse {
-    let name = response.name;
-    let base_model_id = response.base_model_id;
-
-    let id = if !base_model_id.is_empty() {
-        base_model_id
-    } else if let Some(stripped) = name.strip_prefix("models/") {
-        stripped.to_string()
-    } else {
-        name
-    };
+    let id = derive_id(&response.name, &response.base_model_id);
+    let owned_by = derive_owned_by(&response.name);
 
     OpenAIModel {
         id,
         // Gemini model metadata does not expose a created timestamp; use 0 as a placeholder.
         created: 0,
         object: OpenAIModelObjectType::Model,
-        owned_by: "unknown".to_string(),
+        owned_by,
+        display_name: response.display_name,
+        description: response.description,
+        context_window: response.input_token_limit,
+        max_output_tokens: response.output_token_limit,
+        supported_generation_methods: response.supported_generation_methods,
     }
 }
+
+/// Convert a Gemini list-models response into OpenAI's model-list response
+/// shape. Only entries Gemini advertises as `generateContent`-capable are
+/// kept, since chat-only clients enumerating `/v1/models` have no use for
+/// e.g. embedding-only models.
+pub fn transform_list_response(response: GeminiListModelsResponse) -> OpenAIListModelsResponse {
+    let data = response
+        .models
+        .into_iter()
+        .filter(|model| {
+            model
+                .supported_generation_methods
+                .as_deref()
+                .is_some_and(|methods| methods.iter().any(|method| method == "generateContent"))
+        })
+        .map(|model| {
+            let id = derive_id(&model.name, model.base_model_id.as_deref().unwrap_or_default());
+            let owned_by = derive_owned_by(&model.name);
+            OpenAIModel {
+                id,
+                // Gemini model metadata does not expose a created timestamp; use 0 as a placeholder.
+                created: 0,
+                object: OpenAIModelObjectType::Model,
+                owned_by,
+                display_name: model.display_name,
+                description: model.description,
+                context_window: model.input_token_limit,
+                max_output_tokens: model.output_token_limit,
+                supported_generation_methods: model.supported_generation_methods,
+            }
+        })
+        .collect();
+
+    OpenAIListModelsResponse {
+        object: "list".to_string(),
+        data,
+    }
+}
+
+/// Shared id-derivation logic: prefer Gemini's `base_model_id` when present,
+/// otherwise strip whichever prefix `name` carries — the public Generative
+/// Language API's `models/`, or Vertex AI's project-scoped
+/// `publishers/google/models/`.
+fn derive_id(name: &str, base_model_id: &str) -> String {
+    if !base_model_id.is_empty() {
+        base_model_id.to_string()
+    } else if let Some(stripped) = name.strip_prefix("publishers/google/models/") {
+        stripped.to_string()
+    } else if let Some(stripped) = name.strip_prefix("models/") {
+        stripped.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Derive an OpenAI-style `owned_by` from whatever publisher information
+/// Gemini's resource name carries — Vertex AI's project-scoped names embed
+/// the publisher as `publishers/{publisher}/models/...`, while the public
+/// Generative Language API never names a publisher since it only ever
+/// serves Google's own models.
+fn derive_owned_by(name: &str) -> String {
+    name.strip_prefix("publishers/")
+        .and_then(|rest| rest.split_once("/models/"))
+        .map(|(publisher, _)| publisher.to_string())
+        .unwrap_or_else(|| "google".to_string())
+}